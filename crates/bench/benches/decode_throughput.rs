@@ -0,0 +1,160 @@
+//! Decode throughput benchmarks, run with `cargo bench -p glaciers-bench`.
+//!
+//! Every benchmark uses criterion's default of 100 samples. The 1m rows decode only runs when `GLACIERS_BENCH_LARGE` is set, to keep the CI runs short.
+//! Inputs are built once, outside the measured code, and cloned for each iteration, since the decoder functions take their DataFrames by value.
+
+use std::collections::HashMap;
+use std::env;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use glaciers::abi_reader::read_new_abi_folder;
+use glaciers::configger::get_config;
+use glaciers::decoder::{decode_df_with_abi_df, DecoderType};
+use glaciers::matcher::match_logs_by_topic0;
+use glaciers_bench::{synthetic_abi_df, synthetic_logs_df, write_synthetic_abi_folder};
+use polars::prelude::*;
+use tokio::runtime::Runtime;
+
+fn bench_decode_logs(c: &mut Criterion, num_rows: usize) {
+    let runtime = Runtime::new().unwrap();
+    let logs_df = synthetic_logs_df(num_rows);
+    let abi_df = synthetic_abi_df(1_000);
+
+    let mut group = c.benchmark_group("decode_logs");
+    group.throughput(Throughput::Elements(num_rows as u64));
+    group.bench_function(format!("{}_rows", num_rows), |b| {
+        b.iter_batched(
+            || (logs_df.clone(), abi_df.clone()),
+            |(logs_df, abi_df)| runtime.block_on(decode_df_with_abi_df(logs_df, abi_df, DecoderType::Log)).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn bench_decode_logs_1k_rows(c: &mut Criterion) {
+    bench_decode_logs(c, 1_000);
+}
+
+fn bench_decode_logs_100k_rows(c: &mut Criterion) {
+    bench_decode_logs(c, 100_000);
+}
+
+fn bench_decode_logs_1m_rows(c: &mut Criterion) {
+    if env::var_os("GLACIERS_BENCH_LARGE").is_none() {
+        return;
+    }
+    bench_decode_logs(c, 1_000_000);
+}
+
+fn bench_match_by_topic0(c: &mut Criterion, name: &str, num_abi_items: usize) {
+    let logs_df = synthetic_logs_df(10_000);
+    let abi_df = synthetic_abi_df(num_abi_items);
+
+    c.bench_function(name, |b| {
+        b.iter_batched(
+            || (logs_df.clone(), abi_df.clone()),
+            |(logs_df, abi_df)| match_logs_by_topic0(logs_df, abi_df).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn bench_match_by_topic0_small_abi(c: &mut Criterion) {
+    bench_match_by_topic0(c, "match_by_topic0_small_abi", 1_000);
+}
+
+fn bench_match_by_topic0_large_abi(c: &mut Criterion) {
+    bench_match_by_topic0(c, "match_by_topic0_large_abi", 100_000);
+}
+
+fn bench_read_abi_folder_100_files(c: &mut Criterion) {
+    let folder_path = env::temp_dir().join("glaciers_bench_abi_folder");
+    write_synthetic_abi_folder(&folder_path, 100).unwrap();
+    let folder_path = folder_path.to_str().unwrap().to_string();
+
+    c.bench_function("read_abi_folder_100_files", |b| {
+        b.iter(|| read_new_abi_folder(&folder_path).unwrap())
+    });
+}
+
+/// Topic0 lookup with an in-memory index (hash -> full_signature), the baseline for `bench_polars_join_lookup`
+fn bench_abi_index_lookup(c: &mut Criterion) {
+    let config = get_config();
+    let logs_df = synthetic_logs_df(100_000);
+    let abi_df = synthetic_abi_df(100_000);
+
+    let hashes = abi_df.column(&config.abi_reader.hash_column_name).unwrap().binary().unwrap().clone();
+    let signatures = abi_df.column("full_signature").unwrap().str().unwrap().clone();
+    let mut index: HashMap<&[u8], &str> = HashMap::new();
+    for (hash, signature) in hashes.into_iter().zip(&signatures) {
+        if let (Some(hash), Some(signature)) = (hash, signature) {
+            index.entry(hash).or_insert(signature);
+        }
+    }
+    let topic0 = logs_df
+        .column(&config.log_decoder.log_schema.log_alias.topic0)
+        .unwrap()
+        .binary()
+        .unwrap()
+        .clone();
+
+    c.bench_function("abi_index_lookup", |b| {
+        b.iter(|| {
+            topic0
+                .into_iter()
+                .map(|hash| hash.and_then(|hash| index.get(hash).copied()))
+                .collect::<Vec<Option<&str>>>()
+        })
+    });
+}
+
+/// Topic0 lookup with a Polars left join, as done by the matcher
+fn bench_polars_join_lookup(c: &mut Criterion) {
+    let config = get_config();
+    let logs_df = synthetic_logs_df(100_000);
+    let abi_df = synthetic_abi_df(100_000);
+    let hash_column = config.abi_reader.hash_column_name;
+    let topic0_alias = config.log_decoder.log_schema.log_alias.topic0;
+
+    let signatures_df = abi_df
+        .lazy()
+        .select([col(&hash_column), col("full_signature")])
+        .unique(Some(vec![hash_column.clone()]), UniqueKeepStrategy::First)
+        .collect()
+        .unwrap();
+
+    c.bench_function("polars_join_lookup", |b| {
+        b.iter_batched(
+            || (logs_df.clone(), signatures_df.clone()),
+            |(logs_df, signatures_df)| {
+                logs_df
+                    .lazy()
+                    .join(
+                        signatures_df.lazy(),
+                        [col(&topic0_alias)],
+                        [col(&hash_column)],
+                        JoinArgs::new(JoinType::Left),
+                    )
+                    .collect()
+                    .unwrap()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets =
+        bench_decode_logs_1k_rows,
+        bench_decode_logs_100k_rows,
+        bench_decode_logs_1m_rows,
+        bench_match_by_topic0_small_abi,
+        bench_match_by_topic0_large_abi,
+        bench_read_abi_folder_100_files,
+        bench_abi_index_lookup,
+        bench_polars_join_lookup
+}
+criterion_main!(benches);
@@ -0,0 +1,102 @@
+//! Synthetic data used by the Glaciers benchmarks.
+//!
+//! The benchmarks don't read the example files in `/data`, so input sizes can be set freely.
+//! Logs are ERC20 `Transfer` events emitted by `TOKEN_ADDRESS`, and ABI DataFrames contain the `Transfer`
+//! event plus filler events, each one with its own signature and contract address.
+
+use std::fs;
+use std::path::Path;
+
+use alloy::primitives::{address, keccak256, Address, B256, U256};
+use glaciers::abi_reader::AbiDfBuilder;
+use glaciers::log_decoder::{build_logs_df, RawBytes};
+use polars::prelude::*;
+
+/// Contract address of all the synthetic logs
+pub const TOKEN_ADDRESS: Address = address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+
+/// Signature of the event emitted by all the synthetic logs
+pub const TRANSFER_SIGNATURE: &str = "event Transfer(address indexed from, address indexed to, uint256 value)";
+
+/// Builds an address from a number, so each synthetic row or ABI item gets a different one
+pub fn address_from_index(index: u64) -> Address {
+    let mut bytes = [0u8; 20];
+    bytes[12..].copy_from_slice(&index.to_be_bytes());
+    Address::from(bytes)
+}
+
+/// Builds a raw logs DataFrame with `num_rows` `Transfer` logs, with different senders, receivers and values
+pub fn synthetic_logs_df(num_rows: usize) -> DataFrame {
+    let topic0 = transfer_topic0();
+    let addresses = vec![RawBytes::Bytes(TOKEN_ADDRESS.to_vec()); num_rows];
+    let topics = (0..num_rows as u64)
+        .map(|i| {
+            vec![
+                Some(RawBytes::Bytes(topic0.to_vec())),
+                Some(RawBytes::Bytes(address_from_index(i).into_word().to_vec())),
+                Some(RawBytes::Bytes(address_from_index(i + 1).into_word().to_vec())),
+                None,
+            ]
+        })
+        .collect();
+    let data = (0..num_rows as u64)
+        .map(|i| RawBytes::Bytes(U256::from(i).to_be_bytes::<32>().to_vec()))
+        .collect();
+
+    build_logs_df(addresses, topics, data, None, None).expect("synthetic logs are well formed")
+}
+
+/// Builds an ABI DataFrame with `num_items` items: the `Transfer` event of `TOKEN_ADDRESS`, and filler events
+pub fn synthetic_abi_df(num_items: usize) -> DataFrame {
+    let mut builder = AbiDfBuilder::new()
+        .add_event(TRANSFER_SIGNATURE, TOKEN_ADDRESS)
+        .expect("Transfer signature is valid");
+    for i in 1..num_items as u64 {
+        let signature = format!("event Synthetic{}(address indexed account, uint256 value)", i);
+        builder = builder
+            .add_event(&signature, address_from_index(i))
+            .expect("synthetic signature is valid");
+    }
+    builder.build().expect("synthetic ABI items are well formed")
+}
+
+/// Writes `num_files` ABI files in `folder_path`, named after their contract address, as read by `read_new_abi_folder`
+///
+/// Each file contains the `Transfer` event and a filler event, so files produce different ABI items.
+pub fn write_synthetic_abi_folder(folder_path: &Path, num_files: usize) -> std::io::Result<()> {
+    if folder_path.exists() {
+        fs::remove_dir_all(folder_path)?;
+    }
+    fs::create_dir_all(folder_path)?;
+    for i in 0..num_files as u64 {
+        let abi = serde_json::json!([
+            {
+                "type": "event",
+                "name": "Transfer",
+                "anonymous": false,
+                "inputs": [
+                    {"name": "from", "type": "address", "indexed": true},
+                    {"name": "to", "type": "address", "indexed": true},
+                    {"name": "value", "type": "uint256", "indexed": false}
+                ]
+            },
+            {
+                "type": "event",
+                "name": format!("Synthetic{}", i),
+                "anonymous": false,
+                "inputs": [
+                    {"name": "account", "type": "address", "indexed": true},
+                    {"name": "value", "type": "uint256", "indexed": false}
+                ]
+            }
+        ]);
+        let file_path = folder_path.join(format!("{}.json", address_from_index(i + 1)));
+        fs::write(file_path, abi.to_string())?;
+    }
+    Ok(())
+}
+
+/// Topic0 of the `Transfer` event, as stored in the hash column of the ABI DataFrame
+pub fn transfer_topic0() -> B256 {
+    keccak256("Transfer(address,address,uint256)")
+}
@@ -1,7 +1,13 @@
-use clap::{Parser, Subcommand};
+mod shell;
+
+use alloy::primitives::Address;
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand};
 use glaciers::{abi_reader, configger};
 use glaciers::decoder::{self, DecoderType};
+use glaciers::{matcher, utils};
 use std::path::PathBuf;
+use std::str::FromStr;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -14,6 +20,8 @@ enum AppError {
     DecoderError(#[from] decoder::DecoderError),
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+    #[error("Shell error: {0}")]
+    ShellError(String),
 }
 
 #[derive(Parser, Debug)]
@@ -31,6 +39,69 @@ struct Cli {
     command: Commands,
 }
 
+/// Filters of the files of a folder to decode, and the dry-run flag, shared by decode-logs and decode-traces
+#[derive(Args, Debug)]
+struct PlanArgs {
+    /// Print the files of the folder that would be decoded, with their decoded file paths, without decoding them
+    #[arg(long)]
+    dry_run: bool,
+    /// Only decode the folder files with this extension (i.e: parquet). It can be repeated
+    #[arg(long = "extension", action = clap::ArgAction::Append)]
+    extensions: Vec<String>,
+    /// Only decode the folder files modified after this RFC 3339 time (i.e: 2024-01-01T00:00:00Z)
+    #[arg(long)]
+    modified_since: Option<String>,
+    /// Only decode the folder files whose block range, inferred from the file name (i.e: "..._00018000000_to_00018099999.parquet"), overlaps this inclusive range
+    #[arg(long, value_names = ["FROM_BLOCK", "TO_BLOCK"], num_args = 2)]
+    block_range: Vec<u64>,
+}
+
+impl PlanArgs {
+    fn has_filters(&self) -> bool {
+        self.dry_run || !self.extensions.is_empty() || self.modified_since.is_some() || !self.block_range.is_empty()
+    }
+
+    fn options(&self) -> Result<decoder::FilePlanOptions, AppError> {
+        let modified_since = self.modified_since
+            .as_ref()
+            .map(|time| DateTime::parse_from_rfc3339(time)
+                .map(|time| time.with_timezone(&Utc))
+                .map_err(|e| AppError::InvalidInput(format!("Invalid --modified-since {}: {}", time, e))))
+            .transpose()?;
+        Ok(decoder::FilePlanOptions {
+            extensions: self.extensions.clone(),
+            modified_since,
+            block_range: (self.block_range.len() == 2).then(|| (self.block_range[0], self.block_range[1])),
+        })
+    }
+}
+
+/// Decodes a raw logs/traces file, or the planned files of a folder, or prints the folder plan with --dry-run
+async fn decode_path(path: PathBuf, abi_db_path: Vec<String>, decoder_type: DecoderType, plan: PlanArgs) -> Result<(), AppError> {
+    if !path.exists() {
+        return Err(AppError::InvalidInput(format!("Path does not exist: {}", path.display())));
+    }
+
+    if path.is_dir() {
+        let options = plan.options()?;
+        if plan.dry_run {
+            let planned_files = decoder::plan_folder(&path, &decoder_type, &options)?;
+            for file in &planned_files {
+                println!("{} -> {}", file.input_path.display(), file.output_path.display());
+            }
+            let total_bytes: u64 = planned_files.iter().map(|file| file.size_bytes).sum();
+            println!("{} files planned ({} bytes), nothing decoded (--dry-run)", planned_files.len(), total_bytes);
+        } else {
+            decoder::decode_folder_with_options(path.to_string_lossy().into_owned(), abi_db_path, decoder_type, options).await?;
+        }
+    } else if plan.has_filters() {
+        return Err(AppError::InvalidInput("--dry-run and the file filters only apply to folders".to_string()));
+    } else {
+        decoder::decode_file(path, abi_db_path, decoder_type).await?;
+    }
+    Ok(())
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Read ABI file or folder, or update an existing ABI database
@@ -38,9 +109,15 @@ enum Commands {
         /// Path to ABI database file (or the path to create a new file). Optional, default: events_abi_db_file_path in config file
         #[arg(short='d', long = "db")]
         abi_db_path: Option<String>,
-        /// Path to ABI file or folder. Optional, default: config file
+        /// Path to ABI file or folder, or "-" to read a single ABI JSON from stdin. Optional, default: config file
         #[arg(short, long="abi")]
-        abi_path: Option<String>
+        abi_path: Option<String>,
+        /// Contract address of the ABI read from stdin. Required with --abi -
+        #[arg(long)]
+        address: Option<String>,
+        /// Check that the num_indexed_args of the ABI database events match their signatures, without reading new ABIs
+        #[arg(long)]
+        validate: bool,
     },
     
     /// Decode Ethereum logs
@@ -48,9 +125,14 @@ enum Commands {
         /// Path to log file or folder to decode. Optional, default: raw_logs_folder_path in config file
         #[arg(short, long="log")]
         log_path: Option<String>,
-        /// Path to ABI database file. Optional, default: events_abi_db_file_path in config file
-        #[arg(short, long="db")]
-        abi_db_path: Option<String>
+        /// Path to ABI database file. It can be repeated to use multiple ABI databases. Optional, default: events_abi_db_file_path in config file
+        #[arg(short, long="db", action = clap::ArgAction::Append)]
+        abi_db_path: Vec<String>,
+        /// Overwrite decoded files written by another glaciers version or config (sets decoder.overwrite_mismatched_outputs)
+        #[arg(long)]
+        force: bool,
+        #[command(flatten)]
+        plan: PlanArgs,
     },
 
     /// Decode Ethereum traces
@@ -58,10 +140,77 @@ enum Commands {
         /// Path to trace file or folder to decode. Optional, default: raw_traces_folder_path in config file
         #[arg(short, long="trace")]
         trace_path: Option<String>,
-        /// Path to ABI database file. Optional, default: functions_abi_db_file_path in config file
-        #[arg(short, long="db")]
-        abi_db_path: Option<String>
+        /// Path to ABI database file. It can be repeated to use multiple ABI databases. Optional, default: functions_abi_db_file_path in config file
+        #[arg(short, long="db", action = clap::ArgAction::Append)]
+        abi_db_path: Vec<String>,
+        /// Overwrite decoded files written by another glaciers version or config (sets decoder.overwrite_mismatched_outputs)
+        #[arg(long)]
+        force: bool,
+        #[command(flatten)]
+        plan: PlanArgs,
+    },
+
+    /// Decode a single logs/traces partition file into an explicit output path, printing a JSON summary. It's skipped if
+    /// the output already exists with the same config
+    DecodePartition {
+        /// Path to the raw logs/traces file
+        #[arg(short, long="input")]
+        input_path: String,
+        /// Path of the decoded file. Its extension sets the format (csv, parquet or ndjson_pretty)
+        #[arg(short, long="output")]
+        output_path: String,
+        /// Type of the raw data, allowed values = ["log", "trace"]. Optional, default: log
+        #[arg(short='y', long="type", default_value = "log")]
+        decoder_type: String,
+        /// Path to ABI database file. It can be repeated to use multiple ABI databases. Optional, default: events_abi_db_file_path or functions_abi_db_file_path in config file
+        #[arg(short, long="db", action = clap::ArgAction::Append)]
+        abi_db_path: Vec<String>,
+        /// Overwrite an output written by another glaciers version or config (sets decoder.overwrite_mismatched_outputs)
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Label a file of 4 bytes function selectors with their names and signatures, without decoding calldata
+    Label {
+        /// Path to the selectors file (csv or parquet). The selector column name is trace_decoder.trace_schema.trace_alias.selector in config file
+        #[arg(short, long="selectors")]
+        selectors_path: String,
+        /// Path to ABI database file. It can be repeated to use multiple ABI databases. Optional, default: functions_abi_db_file_path in config file
+        #[arg(short, long="db", action = clap::ArgAction::Append)]
+        abi_db_path: Vec<String>,
+        /// Path to save the labeled file. Optional, default: "labeled_" + selectors file name, in the same folder
+        #[arg(short, long="output")]
+        output_path: Option<String>,
+    },
+
+    /// Report how many rows of a raw logs/traces file have a signature in the ABI database, without decoding
+    Coverage {
+        /// Path to the raw logs/traces file (csv or parquet)
+        #[arg(short, long="file")]
+        file_path: String,
+        /// Type of the raw data, allowed values = ["log", "trace"]. Optional, default: log
+        #[arg(short='y', long="type", default_value = "log")]
+        decoder_type: String,
+        /// Path to ABI database file. It can be repeated to use multiple ABI databases. Optional, default: events_abi_db_file_path or functions_abi_db_file_path in config file
+        #[arg(short, long="db", action = clap::ArgAction::Append)]
+        abi_db_path: Vec<String>,
+        /// Print the matched, missing and indexed args mismatch hashes, with counts and example signatures
+        #[arg(long)]
+        detailed: bool,
     },
+
+    /// Summarize a decoded logs/traces file or folder by contract and event/function name, with counts, first/last block and match status
+    Summarize {
+        /// Path to the decoded file or folder (csv or parquet files). Folders are read lazily
+        #[arg(short, long="path")]
+        path: String,
+        /// Path to save the summary (csv or parquet). Optional, default: print the summary
+        #[arg(short, long="output")]
+        output_path: Option<String>,
+    },
+
+    /// Start an interactive shell to explore an ABI database and decode sample logs
+    Shell,
 }
 
 #[tokio::main]
@@ -91,59 +240,197 @@ async fn async_main() -> Result<(), AppError> {
     }
 
     match cli.command {
-        Commands::Abi { abi_db_path, abi_path } => {
+        Commands::Abi { abi_db_path, abi_path, address, validate } => {
             let abi_db_path = abi_db_path.unwrap_or_else(|| configger::get_config().main.events_abi_db_file_path);
+            if validate {
+                let abi_df = abi_reader::read_abi_db_file(&PathBuf::from(&abi_db_path)).map_err(abi_reader::AbiReaderError::from)?;
+                abi_reader::validate_num_indexed_args(&abi_df)?;
+                println!("num_indexed_args of the {} ABI items in {} match their signatures", abi_df.height(), abi_db_path);
+                return Ok(());
+            }
             let abi_path = abi_path.unwrap_or_else(|| configger::get_config().main.abi_folder_path);
 
-            abi_reader::update_abi_db(abi_db_path, abi_path)?;
+            if abi_path == "-" {
+                let address = address
+                    .ok_or_else(|| AppError::InvalidInput("--address is required to read an ABI from stdin".to_string()))?;
+                let address = Address::from_str(&address)
+                    .map_err(|e| AppError::InvalidInput(format!("Invalid address {}: {}", address, e)))?;
+                abi_reader::update_abi_db_from_reader(abi_db_path, std::io::stdin().lock(), address)?;
+            } else {
+                abi_reader::update_abi_db(abi_db_path, abi_path)?;
+            }
         },
         
-        Commands::DecodeLogs { log_path, abi_db_path } => {
+        Commands::DecodeLogs { log_path, abi_db_path, force, plan } => {
+            if force {
+                configger::set_config("decoder.overwrite_mismatched_outputs", configger::ConfigValue::Boolean(true))?;
+            }
             let log_path = log_path.unwrap_or_else(|| configger::get_config().main.raw_logs_folder_path);
-            let abi_db_path = abi_db_path.unwrap_or_else(|| configger::get_config().main.events_abi_db_file_path);
+            let abi_db_path = if abi_db_path.is_empty() {
+                vec![configger::get_config().main.events_abi_db_file_path]
+            } else {
+                abi_db_path
+            };
 
-            let log_path = PathBuf::from(log_path);
+            decode_path(PathBuf::from(log_path), abi_db_path, DecoderType::Log, plan).await?;
+        }
 
-            if !log_path.exists() {
-                return Err(AppError::InvalidInput(format!("Path does not exist: {}", log_path.display())));
+        Commands::DecodeTraces { trace_path, abi_db_path, force, plan } => {
+            if force {
+                configger::set_config("decoder.overwrite_mismatched_outputs", configger::ConfigValue::Boolean(true))?;
             }
+            let trace_path = trace_path.unwrap_or_else(|| configger::get_config().main.raw_traces_folder_path);
+            let abi_db_path = if abi_db_path.is_empty() {
+                vec![configger::get_config().main.functions_abi_db_file_path]
+            } else {
+                abi_db_path
+            };
+            
+            decode_path(PathBuf::from(trace_path), abi_db_path, DecoderType::Trace, plan).await?;
+        }
 
-            if log_path.is_dir() {
-                decoder::decode_folder(log_path.to_string_lossy().into_owned(), abi_db_path, DecoderType::Log).await?;
+        Commands::DecodePartition { input_path, output_path, decoder_type, abi_db_path, force } => {
+            if force {
+                configger::set_config("decoder.overwrite_mismatched_outputs", configger::ConfigValue::Boolean(true))?;
+            }
+            let decoder_type = match decoder_type.to_lowercase().as_str() {
+                "log" => DecoderType::Log,
+                "trace" => DecoderType::Trace,
+                _ => return Err(AppError::InvalidInput(format!("Invalid decoder type: {}, allowed values = [log, trace]", decoder_type))),
+            };
+            let abi_db_path = if abi_db_path.is_empty() {
+                match decoder_type {
+                    DecoderType::Log => vec![configger::get_config().main.events_abi_db_file_path],
+                    DecoderType::Trace => vec![configger::get_config().main.functions_abi_db_file_path],
+                }
             } else {
-                decoder::decode_file(log_path, abi_db_path, DecoderType::Log).await?;
+                abi_db_path
+            };
+
+            let input_path = PathBuf::from(input_path);
+            if !input_path.is_file() {
+                return Err(AppError::InvalidInput(format!("File does not exist: {}", input_path.display())));
             }
+
+            let summary = decoder::decode_partition(input_path, abi_db_path, PathBuf::from(output_path), decoder_type).await?;
+            let summary = serde_json::to_string_pretty(&summary)
+                .map_err(|e| AppError::InvalidInput(format!("Invalid partition summary: {}", e)))?;
+            println!("{}", summary);
         }
 
-        Commands::DecodeTraces { trace_path, abi_db_path } => {
-            let trace_path = trace_path.unwrap_or_else(|| configger::get_config().main.raw_traces_folder_path);
-            let abi_db_path = abi_db_path.unwrap_or_else(|| configger::get_config().main.functions_abi_db_file_path);
-            
-            let trace_path = PathBuf::from(trace_path);
+        Commands::Label { selectors_path, abi_db_path, output_path } => {
+            let abi_db_path = if abi_db_path.is_empty() {
+                vec![configger::get_config().main.functions_abi_db_file_path]
+            } else {
+                abi_db_path
+            };
 
-            if !trace_path.exists() {
-                return Err(AppError::InvalidInput(format!("Path does not exist: {}", trace_path.display())));
+            let selectors_path = PathBuf::from(selectors_path);
+            if !selectors_path.is_file() {
+                return Err(AppError::InvalidInput(format!("File does not exist: {}", selectors_path.display())));
             }
+            let output_path = match output_path {
+                Some(output_path) => PathBuf::from(output_path),
+                None => {
+                    let file_name = selectors_path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                    selectors_path
+                        .with_file_name(format!("labeled_{}", file_name))
+                        .with_extension(configger::get_config().decoder.output_file_format)
+                }
+            };
 
-            if trace_path.is_dir() {
-                decoder::decode_folder(trace_path.to_string_lossy().into_owned(), abi_db_path, DecoderType::Trace).await?;
+            let selectors_df = utils::read_df_file(&selectors_path).map_err(decoder::DecoderError::from)?;
+            let mut labeled_df = decoder::label_selectors(selectors_df, abi_db_path)?;
+            utils::write_df_file(&mut labeled_df, &output_path).map_err(decoder::DecoderError::from)?;
+            println!("Labeled {} selectors, saved to: {}", labeled_df.height(), output_path.display());
+        }
+
+        Commands::Coverage { file_path, decoder_type, abi_db_path, detailed } => {
+            let decoder_type = match decoder_type.to_lowercase().as_str() {
+                "log" => DecoderType::Log,
+                "trace" => DecoderType::Trace,
+                _ => return Err(AppError::InvalidInput(format!("Invalid decoder type: {}, allowed values = [log, trace]", decoder_type))),
+            };
+            let abi_db_path = if abi_db_path.is_empty() {
+                match decoder_type {
+                    DecoderType::Log => vec![configger::get_config().main.events_abi_db_file_path],
+                    DecoderType::Trace => vec![configger::get_config().main.functions_abi_db_file_path],
+                }
             } else {
-                decoder::decode_file(trace_path, abi_db_path, DecoderType::Trace).await?;
+                abi_db_path
+            };
+
+            let file_path = PathBuf::from(file_path);
+            if !file_path.is_file() {
+                return Err(AppError::InvalidInput(format!("File does not exist: {}", file_path.display())));
             }
+
+            let df = utils::read_df_file(&file_path).map_err(decoder::DecoderError::from)?;
+            let abi_df = utils::read_abi_db_files(&abi_db_path).map_err(decoder::DecoderError::from)?;
+            let report = matcher::compatibility_report(df, abi_df, decoder_type).map_err(decoder::DecoderError::from)?;
+            print_coverage(&report, detailed)?;
         }
+
+        Commands::Summarize { path, output_path } => {
+            let path = PathBuf::from(path);
+            if !path.exists() {
+                return Err(AppError::InvalidInput(format!("Path does not exist: {}", path.display())));
+            }
+
+            let decoded_lf = utils::scan_df_path(&path).map_err(decoder::DecoderError::from)?;
+            let summary = decoder::summarize_decoded_lazy(decoded_lf)?
+                .collect()
+                .map_err(decoder::DecoderError::from)?;
+            let mut summary = utils::binary_columns_to_hex_string(summary).map_err(decoder::DecoderError::from)?;
+            match output_path {
+                Some(output_path) => {
+                    let output_path = PathBuf::from(output_path);
+                    utils::write_df_file(&mut summary, &output_path).map_err(decoder::DecoderError::from)?;
+                    println!("Summarized {} contract/name groups, saved to: {}", summary.height(), output_path.display());
+                }
+                None => println!("{}", summary),
+            }
+        }
+
+        Commands::Shell => shell::run_shell().await?,
     }
 
     Ok(())
 }
 
+/// Prints the match rate of a compatibility report, and the report itself if detailed is set
+fn print_coverage(report: &polars::prelude::DataFrame, detailed: bool) -> Result<(), AppError> {
+    let categories = report.column("category").and_then(|s| s.str().cloned());
+    let row_counts = report.column("row_count").and_then(|s| s.u64().cloned());
+    let (categories, row_counts) = match (categories, row_counts) {
+        (Ok(categories), Ok(row_counts)) => (categories, row_counts),
+        _ => return Err(AppError::InvalidInput("Invalid compatibility report".to_string())),
+    };
+
+    let total_rows: u64 = row_counts.into_iter().flatten().sum();
+    let matched_rows: u64 = categories
+        .into_iter()
+        .zip(&row_counts)
+        .filter(|(category, _)| *category == Some("matched"))
+        .filter_map(|(_, count)| count)
+        .sum();
+    let match_rate = if total_rows == 0 { 0.0 } else { matched_rows as f64 / total_rows as f64 * 100.0 };
+    println!("Matched {} of {} rows ({:.2}%)", matched_rows, total_rows, match_rate);
+
+    if detailed {
+        println!("{}", report);
+    }
+    Ok(())
+}
+
 fn parse_config_value(value: &str) ->configger::ConfigValue {
 
     let value = match value.to_lowercase().as_str() {
         // Boolean values
         "true" => configger::ConfigValue::Boolean(true),
         "false" => configger::ConfigValue::Boolean(false),
-        // Numeric values
-        _ if value.parse::<usize>().is_ok() => configger::ConfigValue::Number(value.parse().unwrap()),
+        // Numeric values, also with underscore separators or k/m/g suffixes (i.e: "1_000_000" or "500k")
+        _ if configger::parse_human_number(value).is_some() => configger::ConfigValue::Number(configger::parse_human_number(value).unwrap()),
         // List values
         _ if value.contains(',') => configger::ConfigValue::List(value.replace("[", "").replace("]", "").split(',').map(|s| s.trim().to_string()).collect()),
         // String values
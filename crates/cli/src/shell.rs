@@ -0,0 +1,152 @@
+//! Interactive shell for exploring an ABI DB and decoding sample logs.
+//!
+//! Available commands:
+//!  - load abi <path>: Loads an ABI DB file (csv or parquet)
+//!  - search <text>: Searches the loaded ABI DB items by name or full signature
+//!  - decode-log <topic0> [topic1] [topic2] [topic3] <data>: Decodes a single log using the loaded ABI DB
+//!  - show config: Prints the current configuration
+//!  - help: Prints the available commands
+//!  - exit: Exits the shell
+
+use std::path::Path;
+use alloy::hex;
+use glaciers::configger;
+use glaciers::decoder::{self, DecoderType};
+use glaciers::log_decoder::{self, RawBytes};
+use glaciers::utils;
+use polars::prelude::*;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use crate::AppError;
+
+const HELP: &str = "\
+Available commands:
+  load abi <path>                                       Load an ABI DB file (csv or parquet)
+  search <text>                                         Search the loaded ABI DB items by name or full signature
+  decode-log <topic0> [topic1] [topic2] [topic3] <data> Decode a single log (hex strings) using the loaded ABI DB
+  show config                                           Print the current configuration
+  help                                                  Print this message
+  exit                                                  Exit the shell";
+
+/// Starts the interactive shell, reading commands until the user exits
+pub async fn run_shell() -> Result<(), AppError> {
+    let mut editor = DefaultEditor::new().map_err(|e| AppError::ShellError(e.to_string()))?;
+    let mut abi_df: Option<DataFrame> = None;
+
+    println!("Glaciers shell. Type 'help' to list the available commands.");
+    loop {
+        let line = match editor.readline("glaciers> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(AppError::ShellError(e.to_string())),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        let args: Vec<&str> = line.split_whitespace().collect();
+        // Errors in a command are printed, and don't stop the shell
+        let result = match args.as_slice() {
+            ["exit"] | ["quit"] => break,
+            ["help"] => {
+                println!("{}", HELP);
+                Ok(())
+            }
+            ["load", "abi", path] => load_abi(path).map(|df| {
+                println!("Loaded {} ABI items from {}", df.height(), path);
+                abi_df = Some(df);
+            }),
+            ["search", text @ ..] if !text.is_empty() => with_abi_df(&abi_df, |df| search(df, &text.join(" "))),
+            ["decode-log", hex_args @ ..] if (2..=5).contains(&hex_args.len()) => match &abi_df {
+                Some(df) => decode_log(df.clone(), hex_args).await,
+                None => Err(AppError::InvalidInput("No ABI DB loaded, use 'load abi <path>' first".to_string())),
+            },
+            ["show", "config"] => toml::to_string_pretty(&configger::get_config())
+                .map(|config| println!("{}", config))
+                .map_err(|e| AppError::ShellError(e.to_string())),
+            _ => Err(AppError::InvalidInput(format!("Unknown command '{}'. Type 'help' to list the available commands.", line))),
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs a command that needs the loaded ABI DB, or returns an error if no ABI DB was loaded
+fn with_abi_df(abi_df: &Option<DataFrame>, command: impl FnOnce(&DataFrame) -> Result<(), AppError>) -> Result<(), AppError> {
+    match abi_df {
+        Some(df) => command(df),
+        None => Err(AppError::InvalidInput("No ABI DB loaded, use 'load abi <path>' first".to_string())),
+    }
+}
+
+/// Reads an ABI DB file, normalizing the hash and address columns to binary
+fn load_abi(path: &str) -> Result<DataFrame, AppError> {
+    let df = utils::read_df_file(Path::new(path)).map_err(|e| AppError::ShellError(e.to_string()))?;
+    utils::abi_df_hex_string_columns_to_binary(df).map_err(|e| AppError::ShellError(e.to_string()))
+}
+
+/// Prints the ABI DB items whose name or full signature contains the searched text (case insensitive)
+fn search(df: &DataFrame, text: &str) -> Result<(), AppError> {
+    let text = text.to_lowercase();
+    let names = df.column("name").and_then(|s| s.str().cloned()).map_err(|e| AppError::ShellError(e.to_string()))?;
+    let signatures = df.column("full_signature").and_then(|s| s.str().cloned()).map_err(|e| AppError::ShellError(e.to_string()))?;
+    let mask: BooleanChunked = names
+        .into_iter()
+        .zip(&signatures)
+        .map(|(name, signature)| {
+            Some(
+                name.is_some_and(|n| n.to_lowercase().contains(&text))
+                    || signature.is_some_and(|s| s.to_lowercase().contains(&text)),
+            )
+        })
+        .collect();
+    let result = df
+        .filter(&mask)
+        .and_then(|df| df.select(["full_signature", "hash", "address"]))
+        .map_err(|e| AppError::ShellError(e.to_string()))?;
+    let result = utils::binary_columns_to_hex_string(result).map_err(|e| AppError::ShellError(e.to_string()))?;
+
+    println!("Found {} ABI items:", result.height());
+    let signatures = result.column("full_signature").and_then(|s| s.str().cloned()).map_err(|e| AppError::ShellError(e.to_string()))?;
+    let hashes = result.column("hash").and_then(|s| s.str().cloned()).map_err(|e| AppError::ShellError(e.to_string()))?;
+    let addresses = result.column("address").and_then(|s| s.str().cloned()).map_err(|e| AppError::ShellError(e.to_string()))?;
+    for ((signature, hash), address) in signatures.into_iter().zip(&hashes).zip(&addresses) {
+        println!("  {} | {} | {}", signature.unwrap_or(""), hash.unwrap_or(""), address.unwrap_or(""));
+    }
+    Ok(())
+}
+
+/// Decodes a single log with the loaded ABI DB. The last argument is the data, the previous ones are the topics.
+async fn decode_log(abi_df: DataFrame, hex_args: &[&str]) -> Result<(), AppError> {
+    let (data, topics) = hex_args.split_last().ok_or(AppError::InvalidInput("Missing log data".to_string()))?;
+    let topics: Vec<Option<RawBytes>> = topics.iter().map(|t| Some(RawBytes::HexString(t.to_string()))).collect();
+    // The log address isn't known, so the zero address is used, and the ABI item is matched by topic0
+    let logs_df = log_decoder::build_logs_df(
+        vec![RawBytes::HexString(hex::encode_prefixed([0u8; 20]))],
+        vec![topics],
+        vec![RawBytes::HexString(data.to_string())],
+        None,
+        None,
+    )
+    .map_err(|e| AppError::InvalidInput(e.to_string()))?;
+
+    let decoded_df = decoder::decode_df_with_abi_df(logs_df, abi_df, DecoderType::Log).await?;
+    let get_str = |name: &str| -> Option<String> {
+        decoded_df.column(name).ok()?.str().ok()?.get(0).map(|v| v.trim().to_string())
+    };
+    match get_str("full_signature") {
+        Some(signature) => {
+            println!("Signature: {}", signature);
+            println!("Keys:      {}", get_str("event_keys").unwrap_or_default());
+            println!("Values:    {}", get_str("event_values").unwrap_or_default());
+        }
+        None => println!("No matching ABI item found for this log"),
+    }
+    Ok(())
+}
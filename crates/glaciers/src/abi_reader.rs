@@ -1,22 +1,34 @@
 //! Module for reading and processing EVM ABIs for Glaciers.
 //! 
 //! This module provides functions to:
-//! - Maintain a database of ABI signatures
+//! - Maintain a database of ABI signatures, with progress and row counts of each update
 //! - Read through ABI files in a directory
-//! - Read a single ABI file
+//! - Read the subfolders of a directory in parallel, each subfolder atomically
+//! - Read a single ABI file, or a single ABI JSON from a reader (i.e: stdin), optionally with comments and trailing commas
 //! - Parse through the JSON ABI
-//! - Extract function and event signatures
+//! - Extract function and event signatures, including receive() and fallback() functions
 //! - Convert ABI data into a structured DataFrame format
+//! - Scope ABI items to a chain, with the chain_id column
+//! - Describe the schema of the ABI DataFrames produced under the current config
+//! - Build an ABI DataFrame programmatically from signatures
+//! - Deduplicate an ABI DataFrame using a chosen strategy
+//! - Validate the num_indexed_args of an ABI DataFrame against its event signatures
+//! - Convert an ABI DB file between binary and hex string encodings
+//! - Read ABI DB files, including ABI DBs saved as a JSON array of objects
 
+use std::collections::{BTreeSet, HashSet};
 use std::path::PathBuf;
-use std::{str::FromStr, path::Path};
+use std::{ffi::OsStr, str::FromStr, path::Path};
 use std::fs;
-use alloy::{json_abi::JsonAbi, primitives::{Address, FixedBytes}};
-use polars::prelude::*;
+use std::io::{BufRead, BufReader, Read};
+use alloy::{json_abi::{Event, Function, JsonAbi}, primitives::{Address, FixedBytes}};
+use polars::{error::ErrString, prelude::*};
 use chrono::Local;
+use rayon::prelude::*;
 use thiserror::Error;
 
 use crate::configger::{self, get_config}; 
+use crate::miscellaneous;
 use crate::utils;
 
 /// Errors that can occur during ABI reading and processing
@@ -32,7 +44,38 @@ pub enum AbiReaderError {
     InvalidAbiDf(String),
     #[error("Invalid configs: {0}")]
     InvalidConfig(String),
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+    #[error("Duplicated ABI items: {0}")]
+    DuplicatedAbiItems(String),
+    #[error("Inconsistent num_indexed_args: {0}")]
+    InconsistentNumIndexedArgs(String),
 }
+
+/// Errors of a subfolder skipped by `read_new_abi_folder_parallel_with_errors`
+///
+/// # Fields
+/// * `subfolder` - The path of the skipped subfolder
+/// * `errors` - The errors of each ABI file of the subfolder that couldn't be read
+#[derive(Debug)]
+pub struct SubfolderErrors {
+    pub subfolder: PathBuf,
+    pub errors: Vec<AbiReaderError>,
+}
+
+/// Counts of an ABI DB update, returned by `update_abi_db_with_progress`
+///
+/// # Fields
+/// * `files_read` - ABI files read successfully
+/// * `files_skipped` - Files skipped, i.e: not a .json, the file name isn't an address, or the ABI couldn't be parsed
+/// * `rows_added` - New items added to the ABI DB
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AbiDbUpdateStats {
+    pub files_read: usize,
+    pub files_skipped: usize,
+    pub rows_added: usize,
+}
+
 /// Represents a row in the ABI database containing function or event information.
 /// 
 /// # Fields
@@ -44,6 +87,7 @@ pub enum AbiReaderError {
 /// * `num_indexed_args` - (Only for events) The number of indexed arguments.
 /// * `state_mutability` - (Only for functions) The state mutability of the function.
 /// * `id` - The unique identifier for the function or event
+/// * `chain_id` - The chain where the contract is deployed. None if the item isn't scoped to a chain.
 #[derive(Debug, Clone)]
 pub struct AbiItemRow {
     address: FixedBytes<20>,
@@ -54,8 +98,25 @@ pub struct AbiItemRow {
     num_indexed_args: Option<usize>,
     state_mutability : Option<String>,
     id: String,
+    chain_id: Option<u64>,
+    raw_inputs_json: Option<String>,
 }
 
+impl AbiItemRow {
+    /// Returns the ABI item type of the row: "event" or "function", also for the receive() and fallback() functions
+    fn item_type(&self) -> &str {
+        match self.hash {
+            Hash::Hash32(_) => "event",
+            Hash::Hash4(_) => "function",
+        }
+    }
+}
+
+/// Sentinel hash for receive() functions, which don't have a selector
+const RECEIVE_HASH: [u8; 4] = [0x00; 4];
+/// Sentinel hash for fallback() functions, which don't have a selector
+const FALLBACK_HASH: [u8; 4] = [0xff; 4];
+
 /// Internal representation of function/event hashes
 #[derive(Debug, Clone)]
 enum Hash {
@@ -72,6 +133,88 @@ impl Hash {
     }
 }
 
+/// Builder to construct an ABI DataFrame programmatically, without reading ABI files.
+///
+/// Each added item is parsed from its human-readable signature and converted into the same
+/// row format produced by the ABI reader, so the resulting DataFrame can be passed directly
+/// to `decode_df_with_abi_df`.
+///
+/// # Example
+/// ```
+/// use alloy::primitives::{address, b256, hex};
+/// use glaciers::abi_reader::AbiDfBuilder;
+/// use glaciers::decoder::{decode_df_with_abi_df, DecoderType};
+/// use polars::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let contract = address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+///     let abi_df = AbiDfBuilder::new()
+///         .add_event("event Transfer(address indexed from, address indexed to, uint256 value)", contract)?
+///         .build()?;
+///
+///     let logs_df = df!(
+///         "topic0" => [b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").to_vec()],
+///         "topic1" => [b256!("000000000000000000000000eedff72a683058f8ff531e8c98575f920430fdc5").to_vec()],
+///         "topic2" => [b256!("0000000000000000000000007a250d5630b4cf539739df2c5dacb4c659f2488d").to_vec()],
+///         "topic3" => Series::full_null("topic3", 1, &DataType::Binary),
+///         "data" => [hex!("0000000000000000000000000000000000000000000000000000000000000064").to_vec()],
+///         "address" => [contract.to_vec()]
+///     )?;
+///
+///     let decoded_df = decode_df_with_abi_df(logs_df, abi_df, DecoderType::Log).await?;
+///     assert_eq!(decoded_df.column("name")?.str()?.get(0), Some("Transfer"));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AbiDfBuilder {
+    rows: Vec<AbiItemRow>,
+}
+
+impl AbiDfBuilder {
+    /// Creates an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an event item to the ABI DataFrame
+    ///
+    /// # Arguments
+    /// * `signature` - Event signature, i.e: "event Transfer(address indexed from, address indexed to, uint256 value)"
+    /// * `address` - Contract address associated with the event
+    ///
+    /// # Returns
+    /// The builder with the new event, or an error if the signature can't be parsed
+    pub fn add_event(mut self, signature: &str, address: Address) -> Result<Self, AbiReaderError> {
+        let event = Event::parse(signature).map_err(|e| AbiReaderError::InvalidSignature(e.to_string()))?;
+        self.rows.push(create_event_row(&event, address));
+        Ok(self)
+    }
+
+    /// Adds a function item to the ABI DataFrame
+    ///
+    /// # Arguments
+    /// * `signature` - Function signature, i.e: "function transfer(address to, uint256 amount) returns (bool)"
+    /// * `address` - Contract address associated with the function
+    ///
+    /// # Returns
+    /// The builder with the new function, or an error if the signature can't be parsed
+    pub fn add_function(mut self, signature: &str, address: Address) -> Result<Self, AbiReaderError> {
+        let function = Function::parse(signature).map_err(|e| AbiReaderError::InvalidSignature(e.to_string()))?;
+        self.rows.push(create_function_row(&function, address));
+        Ok(self)
+    }
+
+    /// Converts all added items into an ABI DataFrame
+    ///
+    /// # Notes
+    /// The output format (binary/hex) of some columns is determined by configuration
+    pub fn build(self) -> Result<DataFrame, AbiReaderError> {
+        create_dataframe_from_rows(self.rows)
+    }
+}
+
 /// Updates or creates an ABI database by processing new ABI files in a folder.
 ///
 /// # Arguments
@@ -91,9 +234,86 @@ impl Hash {
 /// );
 /// ```
 pub fn update_abi_db(abi_db_path: String, abi_folder_path: String) -> Result<DataFrame, AbiReaderError> {
+    update_abi_db_with_progress(abi_db_path, abi_folder_path, |_, _| {}).map(|(abi_df, _)| abi_df)
+}
+
+/// Updates or creates an ABI database by processing new ABI files in a folder, reporting the progress and counts.
+///
+/// # Arguments
+/// * `abi_db_path` - Path to the existing or new ABI database file
+/// * `abi_folder_path` - Path to the folder containing ABI files to process
+/// * `progress` - Called after each file with the number of files processed and the total number of files
+///
+/// # Returns
+/// Returns a DataFrame containing only unique ABI information, like `update_abi_db`, and the counts of the update.
+///
+/// # Examples
+/// ```no_run
+/// use glaciers::abi_reader::update_abi_db_with_progress;
+///
+/// let (abi_df, stats) = update_abi_db_with_progress(
+///     "path/to/abi_db.parquet".to_string(),
+///     "path/to/abi/folder".to_string(),
+///     |done, total| println!("{}/{} ABI files", done, total),
+/// ).unwrap();
+/// println!("{} files skipped, {} items added", stats.files_skipped, stats.rows_added);
+/// ```
+pub fn update_abi_db_with_progress(
+    abi_db_path: String,
+    abi_folder_path: String,
+    progress: impl FnMut(usize, usize),
+) -> Result<(DataFrame, AbiDbUpdateStats), AbiReaderError> {
     let path = Path::new(&abi_db_path);
+    let (new_df, files_read, files_skipped) = read_abi_folder_files(&abi_folder_path, progress)?;
+    let (abi_df, rows_added) = merge_into_abi_db(path, new_df)?;
+    Ok((abi_df, AbiDbUpdateStats { files_read, files_skipped, rows_added }))
+}
+
+/// Updates or creates an ABI database with a single ABI read from a reader, i.e: piped from stdin
+///
+/// # Arguments
+/// * `abi_db_path` - Path to the existing or new ABI database file
+/// * `reader` - Buffered reader of the ABI JSON
+/// * `address` - Contract address associated with the ABI
+///
+/// # Returns
+/// Returns a DataFrame containing only unique ABI information, based on the unique_key in the config.
+///
+/// # Notes
+/// The ABI JSON is parsed while it's read, see `read_new_abi_reader`.
+pub fn update_abi_db_from_reader<R: BufRead>(abi_db_path: String, reader: R, address: Address) -> Result<DataFrame, AbiReaderError> {
+    let new_df = read_new_abi_reader(reader, address)?;
+    merge_into_abi_db(Path::new(&abi_db_path), new_df).map(|(abi_df, _)| abi_df)
+}
+
+/// Reads an ABI database file to append new items to it, with the dtypes of the new items
+///
+/// ABI DBs created by older versions don't have the chain_id column, and ABI DBs saved as csv or json are read
+/// with hex strings and inferred dtypes, so they are converted back like the new items.
+pub(crate) fn read_existing_abi_db(path: &Path) -> Result<DataFrame, AbiReaderError> {
+    let existing_df = add_missing_chain_id(read_abi_db_file(path)?)?;
+    if !is_text_abi_db(path) {
+        Ok(existing_df)
+    } else if get_config().abi_reader.output_hex_string_encoding {
+        Ok(utils::restore_abi_df_dtypes(existing_df)?)
+    } else {
+        Ok(utils::abi_df_hex_string_columns_to_binary(existing_df)?)
+    }
+}
+
+/// Returns true for ABI DB files saved as csv or json, which can't store binary columns
+fn is_text_abi_db(path: &Path) -> bool {
+    matches!(path.extension().and_then(OsStr::to_str), Some("csv") | Some("json"))
+}
+
+/// Adds the items of a new ABI DataFrame missing from the ABI database, and writes the database file
+///
+/// # Returns
+/// The ABI database DataFrame, and the number of items added to it
+fn merge_into_abi_db(path: &Path, new_df: DataFrame) -> Result<(DataFrame, usize), AbiReaderError> {
+    let old_file_size = file_size(path);
     let existing_df = if path.exists() {
-        utils::read_df_file(path)?
+        read_existing_abi_db(path)?
     } else {
         // Create a empty dataframe with a schema so joins don't fail for missing id field.
         DataFrame::new(vec![
@@ -105,24 +325,26 @@ pub fn update_abi_db(abi_db_path: String, abi_folder_path: String) -> Result<Dat
             Series::new_empty("num_indexed_args", &DataType::Int8),
             Series::new_empty("state_mutability", &DataType::String),
             Series::new_empty("id", &DataType::String),
+            Series::new_empty("chain_id", &DataType::UInt64),
         ])?
     };
 
-    let new_df = read_new_abi_folder(&abi_folder_path)?;
     let diff_df = new_df.clone().join(
         &existing_df,
         ["id"],
         ["id"],
         JoinArgs::new(JoinType::Anti))?;
-    if diff_df.height() == 0 {
+    let rows_added = diff_df.height();
+    if rows_added == 0 {
         println!(
             "[{}] No new event signatures found in the scanned files.",
             Local::now().format("%Y-%m-%d %H:%M:%S"),
         );
     } else {
         println!(
-            "[{}] New event signatures found found. 10 new lines example: {}",
+            "[{}] {} new event signatures found. 10 new lines example: {}",
             Local::now().format("%Y-%m-%d %H:%M:%S"),
+            rows_added,
             diff_df
         );
     }
@@ -135,8 +357,38 @@ pub fn update_abi_db(abi_db_path: String, abi_folder_path: String) -> Result<Dat
     };
 
     utils::write_df_file(&mut combined_df, path)?;
+    println!(
+        "[{}] ABI DB saved to {}: {} -> {} on disk, {} in memory. {} rows added, {} rows in total.",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        path.display(),
+        human_readable_size(old_file_size),
+        human_readable_size(file_size(path)),
+        human_readable_size(combined_df.estimated_size() as u64),
+        rows_added,
+        combined_df.height()
+    );
 
-    Ok(combined_df)
+    Ok((combined_df, rows_added))
+}
+
+/// Size of a file in bytes, 0 if it doesn't exist
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0)
+}
+
+/// Formats a size in bytes with binary units, i.e: 1536 -> "1.5 KB"
+fn human_readable_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes)
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
 }
 
 /// Processes all ABI files in a folder and combines them into a single DataFrame
@@ -150,32 +402,48 @@ pub fn update_abi_db(abi_db_path: String, abi_folder_path: String) -> Result<Dat
 /// # Errors
 /// Returns an error if the path doesn't exist or if there are issues reading the files
 pub fn read_new_abi_folder(abi_folder_path: &str) -> Result<DataFrame, AbiReaderError> {
+    read_abi_folder_files(abi_folder_path, |_, _| {}).map(|(combined_df, _, _)| combined_df)
+}
+
+/// Processes all ABI files in a folder like `read_new_abi_folder`, calling `progress` after each file with the
+/// number of files processed and the total number of files.
+///
+/// # Returns
+/// The combined DataFrame, and the number of files read and skipped
+fn read_abi_folder_files(
+    abi_folder_path: &str,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<(DataFrame, usize, usize), AbiReaderError> {
     let abi_folder_path  = Path::new(abi_folder_path);
     if !abi_folder_path.exists() {
         return Err(AbiReaderError::InvalidPath(format!("Path does not exist: {}", abi_folder_path.display())));
     }
 
     let combined_df = if abi_folder_path.is_dir() {
-        let paths = fs::read_dir(abi_folder_path)
-            .map_err(|e| AbiReaderError::InvalidPath(e.to_string()))?;
+        let paths: Vec<PathBuf> = fs::read_dir(abi_folder_path)
+            .map_err(|e| AbiReaderError::InvalidPath(e.to_string()))?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| !path.is_dir())
+            .collect();
+        let total_files = paths.len();
         
         // Process each file and collect successful results
         let processed_frames: Vec<DataFrame> = paths
-            .filter_map(|entry| {
-                let path = entry.ok()?.path();
-                if path.is_dir() {
-                    return None;
-                }
-                match read_new_abi_file(path) {
-                    Ok(df) => Some(df),
-                    Err(_) => None,  // Silently skip invalid files
-                }
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, path)| {
+                // Silently skip invalid files
+                let abi_df = read_new_abi_file(path).ok();
+                progress(i + 1, total_files);
+                abi_df
             })
             .collect();
+        let files_read = processed_frames.len();
+        let files_skipped = total_files - files_read;
         
         // Handle case where no valid files were processed
         if processed_frames.is_empty() {
-            return Ok(DataFrame::new(vec![
+            return Ok((DataFrame::new(vec![
                 Series::new_empty("address", &DataType::Binary),
                 Series::new_empty("hash", &DataType::Binary),
                 Series::new_empty("full_signature", &DataType::String),
@@ -183,7 +451,8 @@ pub fn read_new_abi_folder(abi_folder_path: &str) -> Result<DataFrame, AbiReader
                 Series::new_empty("anonymous", &DataType::Boolean),
                 Series::new_empty("state_mutability", &DataType::String),
                 Series::new_empty("id", &DataType::String),
-            ])?);
+                Series::new_empty("chain_id", &DataType::UInt64),
+            ])?, 0, files_skipped));
         }
         
         // Combine all DataFrames
@@ -192,14 +461,137 @@ pub fn read_new_abi_folder(abi_folder_path: &str) -> Result<DataFrame, AbiReader
             // concatenate each file dataframe
             combined_df = combined_df.vstack(&df).map_err(AbiReaderError::PolarsError)?;
         }
-        combined_df
+        (combined_df, files_read, files_skipped)
     } else {
-        read_new_abi_file(abi_folder_path.to_path_buf())?
+        let abi_df = read_new_abi_file(abi_folder_path.to_path_buf())?;
+        progress(1, 1);
+        (abi_df, 1, 0)
     };
 
     Ok(combined_df)
 }
 
+/// Processes each immediate subfolder of a root folder in parallel and combines them into a single DataFrame
+///
+/// # Arguments
+/// * `root_path` - Path to the root folder, i.e: with the ABI files organized in subfolders by protocol
+///
+/// # Returns
+/// Returns a DataFrame containing the processed ABI information of all the subfolders read successfully
+///
+/// # Errors
+/// Returns an error if the root path isn't a folder or can't be read
+///
+/// # Notes
+/// See `read_new_abi_folder_parallel_with_errors`. The errors of the skipped subfolders are printed.
+pub fn read_new_abi_folder_parallel(root_path: &str) -> Result<DataFrame, AbiReaderError> {
+    let (combined_df, subfolder_errors) = read_new_abi_folder_parallel_with_errors(root_path)?;
+    for subfolder_error in subfolder_errors {
+        println!(
+            "[{}] Skipping ABI subfolder: {:?}. {} ABI files couldn't be read: {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            subfolder_error.subfolder,
+            subfolder_error.errors.len(),
+            subfolder_error.errors.iter().map(|e| e.to_string()).collect::<Vec<String>>().join("; ")
+        );
+    }
+    Ok(combined_df)
+}
+
+/// Processes each immediate subfolder of a root folder in parallel, returning the errors of the skipped subfolders
+///
+/// # Arguments
+/// * `root_path` - Path to the root folder, i.e: with the ABI files organized in subfolders by protocol
+///
+/// # Returns
+/// Returns a DataFrame containing the processed ABI information of all the subfolders read successfully,
+/// and the errors of each skipped subfolder
+///
+/// # Errors
+/// Returns an error if the root path isn't a folder or can't be read
+///
+/// # Notes
+/// Each subfolder (including its nested folders) is processed atomically in a rayon thread: if any ABI file
+/// in it can't be read, the whole subfolder is skipped and all its errors are collected, without blocking the other subfolders.
+/// Files directly in the root folder are processed as one more unit. Files that aren't ABI files (not a .json,
+/// or the filename isn't an address) are skipped, like in `read_new_abi_folder`.
+/// The number of threads is abi_reader.max_concurrent_abi_folders from the config.
+pub fn read_new_abi_folder_parallel_with_errors(root_path: &str) -> Result<(DataFrame, Vec<SubfolderErrors>), AbiReaderError> {
+    let root_path = Path::new(root_path);
+    if !root_path.is_dir() {
+        return Err(AbiReaderError::InvalidPath(format!("Path is not a folder: {}", root_path.display())));
+    }
+
+    // Each unit is the list of ABI files of a subfolder, the files directly in the root folder are one more unit
+    let mut units: Vec<(PathBuf, Vec<PathBuf>)> = vec![(root_path.to_path_buf(), Vec::new())];
+    for entry in fs::read_dir(root_path).map_err(|e| AbiReaderError::InvalidPath(e.to_string()))? {
+        let path = entry.map_err(|e| AbiReaderError::InvalidPath(e.to_string()))?.path();
+        if path.is_dir() {
+            let files = collect_abi_files(&path)?;
+            units.push((path, files));
+        } else if extract_address_from_path(&path).is_some() {
+            units[0].1.push(path);
+        }
+    }
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(get_config().abi_reader.max_concurrent_abi_folders)
+        .build()
+        .map_err(|e| AbiReaderError::InvalidConfig(e.to_string()))?;
+    let results: Vec<Result<Vec<DataFrame>, SubfolderErrors>> = pool.install(|| {
+        units
+            .into_par_iter()
+            .map(|(subfolder, files)| {
+                let (frames, errors): (Vec<_>, Vec<_>) = files
+                    .into_iter()
+                    .map(read_new_abi_file)
+                    .partition(|result| result.is_ok());
+                if errors.is_empty() {
+                    Ok(frames.into_iter().map(Result::unwrap).collect())
+                } else {
+                    Err(SubfolderErrors { subfolder, errors: errors.into_iter().map(Result::unwrap_err).collect() })
+                }
+            })
+            .collect()
+    });
+
+    let mut frames = Vec::new();
+    let mut subfolder_errors = Vec::new();
+    for result in results {
+        match result {
+            Ok(subfolder_frames) => frames.extend(subfolder_frames.into_iter().map(|df| df.lazy())),
+            Err(e) => subfolder_errors.push(e),
+        }
+    }
+
+    let combined_df = if frames.is_empty() {
+        DataFrame::from(&output_schema())
+    } else {
+        concat(frames, UnionArgs::default())?.collect()?
+    };
+    Ok((combined_df, subfolder_errors))
+}
+
+/// Auxiliary function to recursively collect the ABI files of a folder
+///
+/// # Arguments
+/// * `folder_path` - Path to the folder
+///
+/// # Returns
+/// Returns the paths of the files that are ABI files (a .json with an address as filename)
+fn collect_abi_files(folder_path: &Path) -> Result<Vec<PathBuf>, AbiReaderError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(folder_path).map_err(|e| AbiReaderError::InvalidPath(e.to_string()))? {
+        let path = entry.map_err(|e| AbiReaderError::InvalidPath(e.to_string()))?.path();
+        if path.is_dir() {
+            files.extend(collect_abi_files(&path)?);
+        } else if extract_address_from_path(&path).is_some() {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
 /// Reads and processes a single ABI file
 ///
 /// # Arguments
@@ -220,10 +612,9 @@ pub fn read_new_abi_file(path: PathBuf) -> Result<DataFrame, AbiReaderError> {
             path
         );
 
-        let json = fs::read_to_string(&path).map_err(|e| AbiReaderError::InvalidAbiFile(e.to_string()))?;
-        let abi: JsonAbi = serde_json::from_str(&json).map_err(|e| AbiReaderError::InvalidAbiFile(e.to_string()))?;
-        // let a = Some(abi.events().map(|event| create_event_row(event)).collect());
-        read_new_abi_json(abi, address)
+        let file = fs::File::open(&path).map_err(|e| AbiReaderError::InvalidAbiFile(e.to_string()))?;
+        let abi_df = read_new_abi_reader(BufReader::new(file), address)?;
+        Ok(miscellaneous::with_abi_source(abi_df, &miscellaneous::AbiSource::Local(path))?)
     } else {
         //skip file if it's not a .json or couldn't be parsed into an address by the extract_address_from_path function
         println!(
@@ -237,6 +628,31 @@ pub fn read_new_abi_file(path: PathBuf) -> Result<DataFrame, AbiReaderError> {
     }
 }
 
+/// Reads and processes a single ABI JSON from a reader, i.e: piped from stdin
+///
+/// # Arguments
+/// * `reader` - Buffered reader of the ABI JSON
+/// * `address` - Contract address associated with the ABI
+///
+/// # Returns
+/// Returns a DataFrame containing the processed ABI information
+///
+/// # Notes
+/// The JSON is parsed while it's read, without buffering the whole input in a string first.
+/// If abi_reader.relaxed_json is set, the input is read in a string and parsed as JSON5, so `//` and `/* */` comments,
+/// trailing commas and the other JSON5 extensions (i.e: unquoted keys) are accepted.
+pub fn read_new_abi_reader<R: BufRead>(mut reader: R, address: Address) -> Result<DataFrame, AbiReaderError> {
+    let abi: JsonAbi = if get_config().abi_reader.relaxed_json {
+        let mut json = String::new();
+        reader.read_to_string(&mut json).map_err(|e| AbiReaderError::InvalidAbiFile(e.to_string()))?;
+        let value: serde_json::Value = json5::from_str(&json).map_err(|e| AbiReaderError::InvalidAbiFile(e.to_string()))?;
+        serde_json::from_value(value)
+    } else {
+        serde_json::from_reader(reader)
+    }.map_err(|e| AbiReaderError::InvalidAbiFile(e.to_string()))?;
+    read_new_abi_json(abi, address)
+}
+
 /// Processes a parsed ABI JSON structure into a DataFrame
 ///
 /// # Arguments
@@ -248,11 +664,47 @@ pub fn read_new_abi_file(path: PathBuf) -> Result<DataFrame, AbiReaderError> {
 /// 
 /// # Notes
 /// This function gets the abi_read_mode from the config and uses it to filter the items to read.
+/// receive() and fallback() functions are read as functions, with sentinel hashes (0x00000000 and 0xffffffff)
+/// and "receive" or "fallback" in the state_mutability column.
+/// Items repeated in the ABI (i.e: malformed ABIs listing the same overload twice) are read once, keeping the first
+/// row of each id, built from the unique_key config.
+///
+/// # Example
+/// ```
+/// use alloy::{json_abi::JsonAbi, primitives::Address};
+/// use glaciers::abi_reader::read_new_abi_json;
+///
+/// let transfer = r#"{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}"#;
+/// let abi: JsonAbi = serde_json::from_str(&format!("[{transfer}, {transfer}]")).unwrap();
+/// assert_eq!(abi.events().count(), 2);
+/// assert_eq!(read_new_abi_json(abi, Address::ZERO).unwrap().height(), 1);
+/// ```
 pub fn read_new_abi_json(abi: JsonAbi, address: Address) -> Result<DataFrame, AbiReaderError>{
+    read_new_abi_json_with_chain_id(abi, address, None)
+}
+
+/// Processes a parsed ABI JSON structure into a DataFrame, scoping its items to a chain
+///
+/// # Arguments
+/// * `abi` - Parsed JsonAbi structure
+/// * `address` - Contract address associated with the ABI
+/// * `chain_id` - Chain where the contract is deployed, stored in the chain_id column. None if the items aren't scoped to a chain.
+///
+/// # Returns
+/// Returns a DataFrame containing function and/or event signatures.
+/// 
+/// # Notes
+/// Same as `read_new_abi_json`, but with the chain_id column filled. When "chain_id" is part of the unique_key config,
+/// the same ABI item of a contract deployed in different chains is stored once for each chain.
+pub fn read_new_abi_json_with_chain_id(abi: JsonAbi, address: Address, chain_id: Option<u64>) -> Result<DataFrame, AbiReaderError>{
     let abi_read_mode = get_config().abi_reader.abi_read_mode;
     // inverted logic because we want to read all items except the ones specified in the abi_read_mode
     let function_rows: Vec<AbiItemRow> = if abi_read_mode != configger::AbiReadMode::Events {
-        abi.functions().map(|function| create_function_row(function, address)).collect()
+        let mut function_rows: Vec<AbiItemRow> = abi.functions().map(|function| create_function_row(function, address)).collect();
+        // receive() and fallback() aren't returned by abi.functions()
+        function_rows.extend(abi.receive.map(|_| create_special_function_row("receive", RECEIVE_HASH, address)));
+        function_rows.extend(abi.fallback.map(|_| create_special_function_row("fallback", FALLBACK_HASH, address)));
+        function_rows
     } else {
         vec![]
     };
@@ -261,11 +713,338 @@ pub fn read_new_abi_json(abi: JsonAbi, address: Address) -> Result<DataFrame, Ab
     } else {
         vec![]
     };
-    let abi_rows = [function_rows, event_rows].concat();
+    // Duplicated items of the same ABI are removed before creating the rows, keeping the first one of each id
+    let mut ids = HashSet::new();
+    let abi_rows = [function_rows, event_rows]
+        .concat()
+        .into_iter()
+        .map(|row| with_chain_id(row, chain_id))
+        .filter(|row| ids.insert(row.id.clone()))
+        .collect();
     
     create_dataframe_from_rows(abi_rows)
 }
 
+/// Returns the schema of the ABI DataFrames produced by the ABI reader, under the current config.
+///
+/// # Returns
+/// Returns the column names and dtypes, in the same order as the ABI reader output.
+///
+/// # Notes
+/// The address and hash columns are Binary, or String (0x prefixed hex) if `abi_reader.output_hex_string_encoding` is set.
+///
+/// # Example
+/// ```
+/// use alloy::primitives::address;
+/// use glaciers::abi_reader::{output_schema, AbiDfBuilder};
+///
+/// let abi_df = AbiDfBuilder::new()
+///     .add_event("event Transfer(address indexed from, address indexed to, uint256 value)", address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"))
+///     .unwrap()
+///     .build()
+///     .unwrap();
+/// assert_eq!(abi_df.schema(), output_schema());
+/// ```
+pub fn output_schema() -> Schema {
+    let bytes_dtype = if get_config().abi_reader.output_hex_string_encoding {
+        DataType::String
+    } else {
+        DataType::Binary
+    };
+    Schema::from_iter([
+        Field::new("address", bytes_dtype.clone()),
+        Field::new("hash", bytes_dtype),
+        Field::new("full_signature", DataType::String),
+        Field::new("name", DataType::String),
+        Field::new("anonymous", DataType::Boolean),
+        Field::new("num_indexed_args", DataType::UInt32),
+        Field::new("state_mutability", DataType::String),
+        Field::new("id", DataType::String),
+        Field::new("chain_id", DataType::UInt64),
+    ])
+}
+
+/// The ABI DB schema of the items saved in text files (csv or json), with the hash and address as hex strings
+fn text_abi_db_schema() -> Schema {
+    Schema::from_iter(output_schema().iter_fields().map(|field| match field.data_type() {
+        DataType::Binary => Field::new(field.name(), DataType::String),
+        _ => field,
+    }))
+}
+
+/// Reads an ABI DB file, saved as parquet, csv or json.
+///
+/// # Arguments
+/// * `path` - The path to the ABI DB file
+///
+/// # Returns
+/// * If successful, a DataFrame with the ABI items, with hex string hash and address columns for csv and json files.
+///
+/// # Notes
+/// * Parquet and csv files are read with `utils::read_df_file`, and json files with `read_abi_db_json`.
+pub fn read_abi_db_file(path: &Path) -> Result<DataFrame, PolarsError> {
+    if path.extension() == Some(OsStr::new("json")) {
+        read_abi_db_json(BufReader::new(utils::open_file(path)?))
+    } else {
+        utils::read_df_file(path)
+    }
+}
+
+/// Reads an ABI DB saved as a JSON array of objects, i.e: `[{"address": "0x...", "hash": "0x...", "full_signature": ...}]`.
+/// 
+/// # Arguments
+/// * `reader` - The reader of the JSON array
+/// 
+/// # Returns
+/// * If successful, a DataFrame with a column for each object key. Keys missing in some objects are read as nulls.
+///   An empty array is read as an empty DataFrame with the ABI DB columns of `output_schema`, with hex string hash
+///   and address columns.
+/// 
+/// # Notes
+/// * The ABI DB columns are ordered like `output_schema`, followed by the other keys in alphabetical order.
+/// * Like ABI DBs saved as csv, the hash and address columns are read as 0x prefixed hex strings, lowercased and
+///   with the 0x prefix added if missing, to be converted to binary with `abi_df_hex_string_columns_to_binary`.
+///   The num_indexed_args and chain_id dtypes are restored, see `restore_abi_df_dtypes`.
+pub fn read_abi_db_json<R: Read>(reader: R) -> Result<DataFrame, PolarsError> {
+    let rows: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_reader(reader)
+        .map_err(|e| PolarsError::ComputeError(ErrString::from(format!("Expected a JSON array of objects: {}", e))))?;
+
+    if rows.is_empty() {
+        return Ok(DataFrame::from(&text_abi_db_schema()));
+    }
+
+    let abi_columns: Vec<String> = output_schema().iter_names().map(|name| name.to_string()).collect();
+    let mut names: Vec<&String> = rows.iter().flat_map(|row| row.keys()).collect::<BTreeSet<&String>>().into_iter().collect();
+    names.sort_by_key(|name| abi_columns.iter().position(|abi_column| abi_column == *name).unwrap_or(abi_columns.len()));
+    let columns: Vec<Series> = names
+        .into_iter()
+        .map(|name| json_values_to_series(name, rows.iter().map(|row| row.get(name).unwrap_or(&serde_json::Value::Null)).collect()))
+        .collect();
+    let abi_df = DataFrame::new(columns)?;
+
+    let hash_column = get_config().abi_reader.hash_column_name;
+    let hex_exprs: Vec<Expr> = [hash_column.as_str(), "address"]
+        .into_iter()
+        .filter(|name| abi_df.column(name).is_ok_and(|s| s.dtype() == &DataType::String))
+        .map(|name| concat_str([lit("0x"), col(name).str().to_lowercase().str().strip_prefix(lit("0x"))], "", false).alias(name))
+        .collect();
+    utils::restore_abi_df_dtypes(abi_df.lazy().with_columns(hex_exprs).collect()?)
+}
+
+/// Converts the values of a JSON key into a Series, used by `read_abi_db_json`.
+/// 
+/// Booleans and integers keep their dtype (Boolean, Int64, or UInt64 above the Int64 range), and other numbers are read
+/// as Float64. Strings, and keys mixing value types, are read as String, with the non string values as their JSON text.
+/// Keys with only nulls are read as String.
+fn json_values_to_series(name: &str, values: Vec<&serde_json::Value>) -> Series {
+    let non_null = || values.iter().filter(|value| !value.is_null());
+    if non_null().count() > 0 && non_null().all(|value| value.is_boolean()) {
+        Series::new(name, values.iter().map(|value| value.as_bool()).collect::<Vec<Option<bool>>>())
+    } else if non_null().count() > 0 && non_null().all(|value| value.is_i64()) {
+        Series::new(name, values.iter().map(|value| value.as_i64()).collect::<Vec<Option<i64>>>())
+    } else if non_null().count() > 0 && non_null().all(|value| value.is_u64()) {
+        Series::new(name, values.iter().map(|value| value.as_u64()).collect::<Vec<Option<u64>>>())
+    } else if non_null().count() > 0 && non_null().all(|value| value.is_number()) {
+        Series::new(name, values.iter().map(|value| value.as_f64()).collect::<Vec<Option<f64>>>())
+    } else {
+        let strings = values.iter().map(|value| match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        });
+        Series::new(name, strings.collect::<Vec<Option<String>>>())
+    }
+}
+
+/// Strategies to deduplicate ABI items with the same id
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupStrategy {
+    /// Keep the first occurrence of each id
+    First,
+    /// Keep the last occurrence of each id
+    Last,
+    /// Prefer items with a non-zero address over items with the zero address
+    MostSpecific,
+    /// Return an error if any duplicated id exists
+    Fail,
+}
+
+/// Deduplicates an ABI DataFrame by its id column, using the chosen strategy.
+///
+/// # Arguments
+/// * `df` - ABI DataFrame, i.e: received from an external source
+/// * `strategy` - Strategy used to choose which item to keep when ids are duplicated
+///
+/// # Returns
+/// Returns a DataFrame with unique ids, keeping the original row order.
+///
+/// # Notes
+/// The id is built from the unique_key config when the ABI items are read. `MostSpecific` only has effect
+/// when the address isn't part of the unique_key, otherwise items with different addresses have different ids.
+///
+/// # Examples
+/// ```no_run
+/// use glaciers::abi_reader::{deduplicate_abi_df, DedupStrategy};
+/// use glaciers::utils::read_df_file;
+/// use std::path::Path;
+///
+/// let abi_df = read_df_file(Path::new("path/to/abi_db.parquet")).unwrap();
+/// let deduplicated_df = deduplicate_abi_df(abi_df, DedupStrategy::MostSpecific);
+/// ```
+pub fn deduplicate_abi_df(df: DataFrame, strategy: DedupStrategy) -> Result<DataFrame, AbiReaderError> {
+    let id = vec!["id".to_string()];
+    let df = match strategy {
+        DedupStrategy::First => df.lazy().unique_stable(Some(id), UniqueKeepStrategy::First).collect()?,
+        DedupStrategy::Last => df.lazy().unique_stable(Some(id), UniqueKeepStrategy::Last).collect()?,
+        DedupStrategy::MostSpecific => {
+            // The address column can be binary or hex string, depending on the output_hex_string_encoding config
+            let zero_address = match df.column("address")?.dtype() {
+                DataType::String => lit(Address::ZERO.to_string()),
+                _ => lit(Address::ZERO.as_slice()),
+            };
+            df.lazy()
+                .with_row_index("row_index", None)
+                .with_columns([col("address").eq(zero_address).fill_null(lit(true)).alias("is_zero_address")])
+                .sort_by_exprs([col("is_zero_address"), col("row_index")], [false, false], false, true)
+                .unique_stable(Some(id), UniqueKeepStrategy::First)
+                .sort("row_index", Default::default())
+                .select([col("*").exclude(["row_index", "is_zero_address"])])
+                .collect()?
+        }
+        DedupStrategy::Fail => {
+            let duplicated_ids = df.clone()
+                .lazy()
+                .group_by_stable([col("id")])
+                .agg([len().alias("count")])
+                .filter(col("count").gt(lit(1)))
+                .collect()?;
+            if duplicated_ids.height() > 0 {
+                return Err(AbiReaderError::DuplicatedAbiItems(format!(
+                    "{} ids are duplicated, i.e: {}", duplicated_ids.height(), duplicated_ids.column("id")?.get(0)?
+                )));
+            }
+            df
+        }
+    };
+    Ok(df)
+}
+
+/// Maximum number of inconsistent rows listed in the `validate_num_indexed_args` error
+const MAX_INCONSISTENT_ROWS_LISTED: usize = 5;
+
+/// Checks that the num_indexed_args of each event in an ABI DataFrame matches its full_signature.
+///
+/// # Arguments
+/// * `df` - ABI DataFrame, i.e: an ABI DB loaded from an older format
+///
+/// # Returns
+/// Returns `Ok(())` if all the events are consistent, or `AbiReaderError::InconsistentNumIndexedArgs` with the number
+/// of inconsistent rows and the first ones (row index, full_signature, stored and expected values).
+///
+/// # Notes
+/// The expected value is recomputed like when the ABI items are read, with `Event::num_topics`: the number of indexed
+/// params, plus the topic0 for non anonymous events. The anonymous column is used when present, since it isn't part of
+/// the full_signature. Rows with a null num_indexed_args (functions) are skipped, and events with a full_signature that
+/// can't be parsed are reported as inconsistent.
+///
+/// # Examples
+/// ```
+/// use alloy::primitives::Address;
+/// use glaciers::abi_reader::{validate_num_indexed_args, AbiDfBuilder};
+/// use polars::prelude::*;
+///
+/// let abi_df = AbiDfBuilder::new()
+///     .add_event("event Transfer(address indexed from, address indexed to, uint256 value)", Address::ZERO).unwrap()
+///     .build().unwrap();
+/// assert!(validate_num_indexed_args(&abi_df).is_ok());
+///
+/// // An older format storing only the number of indexed params, without the topic0
+/// let old_df = abi_df.lazy().with_column(lit(2u32).alias("num_indexed_args")).collect().unwrap();
+/// assert!(validate_num_indexed_args(&old_df).is_err());
+/// ```
+pub fn validate_num_indexed_args(df: &DataFrame) -> Result<(), AbiReaderError> {
+    let num_indexed_args = df.column("num_indexed_args")?.cast(&DataType::UInt32)?;
+    let num_indexed_args = num_indexed_args.u32()?;
+    let full_signatures = df.column("full_signature")?.str()?;
+    let anonymous = match df.column("anonymous") {
+        Ok(anonymous) => Some(anonymous.cast(&DataType::Boolean)?),
+        Err(_) => None,
+    };
+    let anonymous = anonymous.as_ref().map(|anonymous| anonymous.bool()).transpose()?;
+
+    let mut inconsistent_rows = Vec::new();
+    for (row, (stored, full_signature)) in num_indexed_args.into_iter().zip(full_signatures).enumerate() {
+        let Some(stored) = stored else {
+            continue;
+        };
+        let full_signature = full_signature.unwrap_or_default();
+        let event = Event::parse(full_signature)
+            .or_else(|_| Event::parse(&utils::strip_tuple_component_names(full_signature)));
+        let expected = event.map(|mut event| {
+            if let Some(anonymous) = anonymous.and_then(|anonymous| anonymous.get(row)) {
+                event.anonymous = anonymous;
+            }
+            event.num_topics()
+        });
+        match expected {
+            Ok(expected) if expected == stored as usize => {},
+            Ok(expected) => inconsistent_rows.push(format!(
+                "row {} '{}' has {}, expected {}", row, full_signature, stored, expected
+            )),
+            Err(e) => inconsistent_rows.push(format!(
+                "row {} '{}' has {}, but its signature can't be parsed: {}", row, full_signature, stored, e
+            )),
+        }
+    }
+
+    if inconsistent_rows.is_empty() {
+        return Ok(());
+    }
+    let total = inconsistent_rows.len();
+    inconsistent_rows.truncate(MAX_INCONSISTENT_ROWS_LISTED);
+    Err(AbiReaderError::InconsistentNumIndexedArgs(format!(
+        "{} rows don't match their full_signature, i.e: {}", total, inconsistent_rows.join("; ")
+    )))
+}
+
+/// Converts the hash and address columns of an ABI DB file between binary and hex string encodings, rewriting the file.
+///
+/// # Arguments
+/// * `abi_db_path` - Path to the ABI database file
+/// * `to_hex` - Convert the columns to 0x prefixed hex strings if true, or to binary if false
+///
+/// # Returns
+/// Returns the converted ABI DataFrame
+///
+/// # Notes
+/// The columns are first normalized to binary, so DBs with one column in each encoding are also converted.
+/// The hash column name is taken from the `abi_reader.hash_column_name` config. CSV and JSON files can't store binary
+/// columns, so converting a CSV or JSON ABI DB to binary returns an error without rewriting the file.
+///
+/// # Examples
+/// ```no_run
+/// use glaciers::abi_reader::convert_abi_db_encoding;
+///
+/// let abi_df = convert_abi_db_encoding("path/to/abi_db.parquet".to_string(), true).unwrap();
+/// ```
+pub fn convert_abi_db_encoding(abi_db_path: String, to_hex: bool) -> Result<DataFrame, AbiReaderError> {
+    let path = Path::new(&abi_db_path);
+    if !to_hex && is_text_abi_db(path) {
+        let extension = path.extension().and_then(OsStr::to_str).unwrap_or_default();
+        return Err(AbiReaderError::InvalidAbiDf(format!("{} is a {} file, binary columns can only be saved in parquet files", abi_db_path, extension)));
+    }
+
+    let abi_df = utils::abi_df_hex_string_columns_to_binary(read_abi_db_file(path)?)?;
+    let mut abi_df = if to_hex {
+        utils::binary_columns_to_hex_string(abi_df)?
+    } else {
+        abi_df
+    };
+    utils::write_df_file(&mut abi_df, path)?;
+
+    Ok(abi_df)
+}
+
 /// Auxiliary function to extract an Ethereum address from a file path
 ///
 /// # Arguments
@@ -293,7 +1072,7 @@ fn extract_address_from_path(path: &Path) -> Option<Address> {
 /// # Notes
 /// The function takes the unique_key from the config and uses it to create the id. 
 /// Later on, the id is used to filter unique entries in the database.
-fn create_event_row(event: &alloy::json_abi::Event, address: Address) -> AbiItemRow {
+fn create_event_row(event: &Event, address: Address) -> AbiItemRow {
     let unique_key = get_config().abi_reader.unique_key;
     let mut id = event.selector().to_string();
     if unique_key.contains(&"full_signature".to_string()) {
@@ -302,7 +1081,8 @@ fn create_event_row(event: &alloy::json_abi::Event, address: Address) -> AbiItem
     if unique_key.contains(&"address".to_string()) {
         id = id + " - " + address.to_string().as_str();
     }
-    let event_row = AbiItemRow {
+
+    AbiItemRow {
         address: address.0,
         hash: Hash::Hash32(event.selector()),
         full_signature: event.full_signature(),
@@ -310,9 +1090,10 @@ fn create_event_row(event: &alloy::json_abi::Event, address: Address) -> AbiItem
         anonymous: Some(event.anonymous),
         num_indexed_args: Some(event.num_topics()),
         state_mutability: None,
-        id: id,
-    };
-    event_row
+        id,
+        chain_id: None,
+        raw_inputs_json: raw_inputs_json(&event.inputs),
+    }
 }
 
 /// Creates an AbiItemRow from a Function
@@ -327,7 +1108,7 @@ fn create_event_row(event: &alloy::json_abi::Event, address: Address) -> AbiItem
 /// # Notes
 /// The function takes the unique_key from the config and uses it to create the id. 
 /// Later on, the id is used to filter unique entries in the database.
-fn create_function_row(function: &alloy::json_abi::Function, address: Address) -> AbiItemRow {
+fn create_function_row(function: &Function, address: Address) -> AbiItemRow {
     let state_mutability = match function.state_mutability {
         alloy::json_abi::StateMutability::Pure => "pure".to_owned(),
         alloy::json_abi::StateMutability::View => "view".to_owned(),
@@ -344,7 +1125,7 @@ fn create_function_row(function: &alloy::json_abi::Function, address: Address) -
         id = id + " - " + address.to_string().as_str();
     }
 
-    let function_row = AbiItemRow {
+    AbiItemRow {
         address: address.0,
         hash: Hash::Hash4(function.selector()),
         full_signature: function.full_signature(),
@@ -352,9 +1133,97 @@ fn create_function_row(function: &alloy::json_abi::Function, address: Address) -
         anonymous: None,
         num_indexed_args: None,
         state_mutability: Some(state_mutability),
-        id: id
-    };
-    function_row
+        id,
+        chain_id: None,
+        raw_inputs_json: raw_inputs_json(&function.inputs),
+    }
+}
+
+/// Creates an AbiItemRow for a receive() or fallback() function
+///
+/// # Arguments
+/// * `kind` - "receive" or "fallback"
+/// * `hash` - Sentinel hash used for the function, since it doesn't have a selector
+/// * `address` - Contract address associated with the function
+///
+/// # Returns
+/// Returns an AbiItemRow with "{kind}()" as the full signature and name, and the kind in the state_mutability column
+/// 
+/// # Notes
+/// The function takes the unique_key from the config and uses it to create the id. 
+/// Later on, the id is used to filter unique entries in the database.
+fn create_special_function_row(kind: &str, hash: [u8; 4], address: Address) -> AbiItemRow {
+    let hash = FixedBytes::<4>::from(hash);
+    let full_signature = format!("{}()", kind);
+
+    let unique_key = get_config().abi_reader.unique_key;
+    let mut id = hash.to_string();
+    if unique_key.contains(&"full_signature".to_string()) {
+        id = id + " - " + full_signature.as_str();
+    }
+    if unique_key.contains(&"address".to_string()) {
+        id = id + " - " + address.to_string().as_str();
+    }
+
+    AbiItemRow {
+        address: address.0,
+        hash: Hash::Hash4(hash),
+        full_signature,
+        name: kind.to_string(),
+        anonymous: None,
+        num_indexed_args: None,
+        state_mutability: Some(kind.to_string()),
+        id,
+        chain_id: None,
+        raw_inputs_json: None,
+    }
+}
+
+/// Serializes the inputs of an event/function to JSON, if the abi_reader.store_raw_inputs config is set
+///
+/// # Arguments
+/// * `inputs` - The alloy event or function params
+///
+/// # Returns
+/// Returns the JSON array of the inputs (names, types, indexed flags and components), or None if the config isn't set
+fn raw_inputs_json<T: serde::Serialize>(inputs: &[T]) -> Option<String> {
+    if !get_config().abi_reader.store_raw_inputs {
+        return None;
+    }
+    serde_json::to_string(inputs).ok()
+}
+
+/// Scopes an AbiItemRow to a chain, adding the chain_id to its id when "chain_id" is part of the unique_key config
+///
+/// # Arguments
+/// * `row` - AbiItemRow created without a chain
+/// * `chain_id` - Chain where the contract is deployed, or None to keep the row unscoped
+///
+/// # Returns
+/// Returns the AbiItemRow with the chain_id set
+fn with_chain_id(mut row: AbiItemRow, chain_id: Option<u64>) -> AbiItemRow {
+    if let Some(chain_id) = chain_id {
+        if get_config().abi_reader.unique_key.contains(&"chain_id".to_string()) {
+            row.id = row.id + " - " + chain_id.to_string().as_str();
+        }
+    }
+    row.chain_id = chain_id;
+    row
+}
+
+/// Adds a null chain_id column to ABI DataFrames that don't have it (i.e: created by older versions),
+/// so they can be concatenated with new ABI items
+///
+/// # Arguments
+/// * `df` - ABI DataFrame
+///
+/// # Returns
+/// Returns the ABI DataFrame with a chain_id column
+pub(crate) fn add_missing_chain_id(df: DataFrame) -> Result<DataFrame, AbiReaderError> {
+    if df.get_column_names().contains(&"chain_id") {
+        return Ok(df);
+    }
+    Ok(df.lazy().with_column(lit(Null {}).cast(DataType::UInt64).alias("chain_id")).collect()?)
 }
 
 /// Converts a vector of AbiItemRows into a DataFrame
@@ -366,18 +1235,29 @@ fn create_function_row(function: &alloy::json_abi::Function, address: Address) -
 /// Returns a DataFrame containing the ABI information
 ///
 /// # Notes
-/// The output format (binary/hex) of some columns is determined by configuration
+/// The output format (binary/hex) of some columns is determined by configuration.
+/// Rows of the item types in the abi_reader.skip_item_types config are skipped.
+/// The raw_inputs_json column is only added if the abi_reader.store_raw_inputs config is set.
 fn create_dataframe_from_rows(rows: Vec<AbiItemRow>) -> Result<DataFrame, AbiReaderError> {
-    let columns = vec![
-        Series::new("address".into(), rows.iter().map(|r| r.address.as_slice().to_vec()).collect::<Vec<Vec<u8>>>()),
-        Series::new("hash".into(), rows.iter().map(|r| r.hash.as_bytes()).collect::<Vec<Vec<u8>>>()),
-        Series::new("full_signature".into(), rows.iter().map(|r| r.full_signature.clone()).collect::<Vec<String>>()),
-        Series::new("name".into(), rows.iter().map(|r| r.name.clone()).collect::<Vec<String>>()),
-        Series::new("anonymous".into(), rows.iter().map(|r| r.anonymous).collect::<Vec<Option<bool>>>()),
-        Series::new("num_indexed_args".into(), rows.iter().map(|r| r.num_indexed_args.map(|n| n as u32)).collect::<Vec<Option<u32>>>()),
-        Series::new("state_mutability".into(), rows.iter().map(|r| r.state_mutability.clone()).collect::<Vec<Option<String>>>()),
-        Series::new("id".into(), rows.iter().map(|r| r.id.clone()).collect::<Vec<String>>()),
+    let skip_item_types = get_config().abi_reader.skip_item_types;
+    let rows: Vec<AbiItemRow> = rows
+        .into_iter()
+        .filter(|row| !skip_item_types.iter().any(|item_type| item_type == row.item_type()))
+        .collect();
+    let mut columns = vec![
+        Series::new("address", rows.iter().map(|r| r.address.as_slice().to_vec()).collect::<Vec<Vec<u8>>>()),
+        Series::new("hash", rows.iter().map(|r| r.hash.as_bytes()).collect::<Vec<Vec<u8>>>()),
+        Series::new("full_signature", rows.iter().map(|r| r.full_signature.clone()).collect::<Vec<String>>()),
+        Series::new("name", rows.iter().map(|r| r.name.clone()).collect::<Vec<String>>()),
+        Series::new("anonymous", rows.iter().map(|r| r.anonymous).collect::<Vec<Option<bool>>>()),
+        Series::new("num_indexed_args", rows.iter().map(|r| r.num_indexed_args.map(|n| n as u32)).collect::<Vec<Option<u32>>>()),
+        Series::new("state_mutability", rows.iter().map(|r| r.state_mutability.clone()).collect::<Vec<Option<String>>>()),
+        Series::new("id", rows.iter().map(|r| r.id.clone()).collect::<Vec<String>>()),
+        Series::new("chain_id", rows.iter().map(|r| r.chain_id).collect::<Vec<Option<u64>>>()),
     ];
+    if get_config().abi_reader.store_raw_inputs {
+        columns.push(Series::new("raw_inputs_json", rows.iter().map(|r| r.raw_inputs_json.clone()).collect::<Vec<Option<String>>>()));
+    }
 
     let df = DataFrame::new(columns).map_err(AbiReaderError::PolarsError)?;
     Ok(if get_config().abi_reader.output_hex_string_encoding {
@@ -395,7 +1275,7 @@ fn create_dataframe_from_rows(rows: Vec<AbiItemRow>) -> Result<DataFrame, AbiRea
 /// # Returns
 /// Returns a combined DataFrame with duplicate IDs removed
 fn concat_dataframes(dfs: Vec<LazyFrame>) -> Result<DataFrame, AbiReaderError> {
-    let df = concat(dfs, UnionArgs::default())?;
-    let df = df.unique(Some(vec!["id".to_string()]), UniqueKeepStrategy::First).collect();
-    df.map_err(AbiReaderError::PolarsError)
+    // diagonal, since only the ABIs read with abi_reader.store_abi_source (or store_raw_inputs) have the abi_source (or raw_inputs_json) column
+    let df = concat_lf_diagonal(dfs, UnionArgs::default())?.collect()?;
+    deduplicate_abi_df(df, DedupStrategy::First)
 }
\ No newline at end of file
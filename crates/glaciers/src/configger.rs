@@ -35,6 +35,16 @@ pub struct Config {
     pub decoder: DecoderConfig,
     pub log_decoder: LogDecoderConfig,
     pub trace_decoder: TraceDecoderConfig,
+    pub io: IoConfig,
+}
+
+/// Configuration for reading the raw input files
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct IoConfig {
+    pub csv_delimiter: String,
+    pub csv_has_header: bool,
+    pub csv_quote_char: String,
+    pub csv_alias_columns_as_string: bool,
 }
 
 /// Configuration for the Glaciers component
@@ -67,6 +77,19 @@ pub struct AbiReaderConfig {
     pub abi_read_mode: AbiReadMode,
     pub unique_key: Vec<String>,
     pub output_hex_string_encoding: bool,
+    pub sourcify_url: String,
+    pub etherscan_url: String,
+    pub max_concurrent_abi_downloads: usize,
+    pub hash_column_name: String,
+    pub max_concurrent_abi_folders: usize,
+    pub store_abi_source: bool,
+    pub skip_item_types: Vec<String>,
+    pub relaxed_json: bool,
+    pub store_raw_inputs: bool,
+    pub http_timeout_secs: usize,
+    pub http_retry_count: usize,
+    pub http_retry_backoff_ms: usize,
+    pub http_user_agent: String,
 }
 
 /// Enum for the different modes of reading ABIs
@@ -86,6 +109,38 @@ pub struct DecoderConfig {
     pub max_concurrent_files_decoding: usize,
     pub max_chunk_threads_per_file: usize,
     pub decoded_chunk_size: usize,
+    pub max_total_chunk_threads: usize,
+    pub max_in_flight_bytes: usize,
+    pub chunk_retry_count: usize,
+    pub io_max_retries: usize,
+    pub drop_raw_columns: bool,
+    pub passthrough_columns: Vec<String>,
+    pub signature_allowlist: Vec<String>,
+    pub signature_denylist: Vec<String>,
+    pub decode_all_candidates: bool,
+    pub dataset_mode: bool,
+    pub abi_id_column: String,
+    pub abi_passthrough_columns: Vec<String>,
+    pub overwrite_mismatched_outputs: bool,
+    pub write_run_manifest: bool,
+    pub write_watermark_file: bool,
+    pub add_source_file_column: bool,
+    pub positional_names_when_unnamed: bool,
+    pub max_value_length: usize,
+    pub bytes_encoding: BytesEncoding,
+    pub timestamp_column: Option<String>,
+    pub timestamp_timezone: String,
+}
+
+/// Enum for the different encodings of the dynamic bytes values in the decoded columns
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BytesEncoding {
+    /// 0x prefixed lowercase hex
+    Hex,
+    /// Standard base64, with padding
+    Base64,
+    /// UTF-8 text, i.e: for revert strings. Bytes that aren't valid UTF-8 are kept as hex, prefixed with "hex:"
+    Utf8Lossy,
 }
 
 /// Enum for the different algorithms of decoding
@@ -99,6 +154,21 @@ pub enum DecoderAlgorithm {
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct LogDecoderConfig {
     pub log_schema: LogSchemaConfig,
+    pub missing_topics_mode: MissingTopicsMode,
+    pub adjust_indexed_args: bool,
+    pub include_num_indexed_args: bool,
+    pub topics_list_column: String,
+    pub topics_list_last_is_data: bool,
+    pub indexed_only: bool,
+}
+
+/// Enum for the different ways of handling null topics when decoding logs
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum MissingTopicsMode {
+    /// Null topics are replaced by zero filled topics
+    Compatible,
+    /// Only the present topics are decoded, and the number of topics is validated against the signature
+    Strict
 }
 
 /// Schema configuration for log data
@@ -149,6 +219,7 @@ impl LogDatatypeConfig {
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct TraceDecoderConfig {
     pub trace_schema: TraceSchemaConfig,
+    pub parse_hex_numeric_columns: bool,
 }
 
 /// Schema configuration for trace data
@@ -165,6 +236,11 @@ pub struct TraceAliasConfig {
     pub action_input: String,
     pub result_output: String,
     pub action_to: String,
+    pub gas: String,
+    pub gas_used: String,
+    pub value: String,
+    /// Optional column with the traceAddress array of each call, used to compute the call_depth column
+    pub trace_address: Option<String>,
 }
 
 /// Returns only the column names used for trace decoding as an array
@@ -197,6 +273,13 @@ pub enum DataType {
     HexString
 }
 
+/// Number of threads the machine can run in parallel, used to size the decoder and ABI reader concurrency defaults.
+///
+/// Falls back to 8 if the available parallelism can't be detected.
+pub static AVAILABLE_PARALLELISM: LazyLock<usize> = LazyLock::new(|| {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(8)
+});
+
 /// Static configuration for the Glaciers component 
 /// 
 /// This is the default configuration for Glaciers.
@@ -204,6 +287,8 @@ pub enum DataType {
 /// It is a lazy lock to ensure that the configuration is thread safe.
 /// 
 /// It is initialized with the default values for all the configuration fields.
+/// The decoder concurrency defaults are sized from the available parallelism: max_concurrent_files_decoding
+/// is twice the available parallelism and max_chunk_threads_per_file is the available parallelism.
 /// 
 pub static GLACIERS_CONFIG: LazyLock<RwLock<Config>> = LazyLock::new(|| {
     RwLock::new(Config {
@@ -222,14 +307,48 @@ pub static GLACIERS_CONFIG: LazyLock<RwLock<Config>> = LazyLock::new(|| {
             abi_read_mode: AbiReadMode::Events,
             output_hex_string_encoding: false,
             unique_key: vec![String::from("hash"), String::from("full_signature"), String::from("address")],
+            sourcify_url: String::from("https://repo.sourcify.dev"),
+            etherscan_url: String::from("https://api.etherscan.io/v2/api"),
+            max_concurrent_abi_downloads: 8,
+            hash_column_name: String::from("hash"),
+            max_concurrent_abi_folders: *AVAILABLE_PARALLELISM,
+            store_abi_source: false,
+            skip_item_types: Vec::new(),
+            relaxed_json: false,
+            store_raw_inputs: false,
+            http_timeout_secs: 30,
+            http_retry_count: 2,
+            http_retry_backoff_ms: 500,
+            http_user_agent: format!("glaciers/{}", env!("CARGO_PKG_VERSION")),
         },
         decoder: DecoderConfig {
             algorithm: DecoderAlgorithm::Hash,
             output_hex_string_encoding: false,
             output_file_format: String::from("parquet"),
-            max_concurrent_files_decoding: 16,
-            max_chunk_threads_per_file: 16,
+            max_concurrent_files_decoding: *AVAILABLE_PARALLELISM * 2,
+            max_chunk_threads_per_file: *AVAILABLE_PARALLELISM,
             decoded_chunk_size: 500_000,
+            max_total_chunk_threads: 0,
+            max_in_flight_bytes: 0,
+            chunk_retry_count: 0,
+            io_max_retries: 2,
+            drop_raw_columns: false,
+            passthrough_columns: vec![String::from("*")],
+            signature_allowlist: Vec::new(),
+            signature_denylist: Vec::new(),
+            decode_all_candidates: false,
+            dataset_mode: false,
+            abi_id_column: String::from("id"),
+            abi_passthrough_columns: Vec::new(),
+            overwrite_mismatched_outputs: false,
+            write_run_manifest: false,
+            write_watermark_file: false,
+            add_source_file_column: false,
+            positional_names_when_unnamed: true,
+            max_value_length: 0,
+            bytes_encoding: BytesEncoding::Hex,
+            timestamp_column: None,
+            timestamp_timezone: String::from("UTC"),
         },
         log_decoder: LogDecoderConfig {
             log_schema: LogSchemaConfig {
@@ -250,6 +369,12 @@ pub static GLACIERS_CONFIG: LazyLock<RwLock<Config>> = LazyLock::new(|| {
                     address: DataType::Binary,
                 }
             },
+            missing_topics_mode: MissingTopicsMode::Compatible,
+            adjust_indexed_args: false,
+            include_num_indexed_args: false,
+            topics_list_column: String::new(),
+            topics_list_last_is_data: false,
+            indexed_only: false,
         },
         trace_decoder: TraceDecoderConfig {
             trace_schema: TraceSchemaConfig {
@@ -258,6 +383,10 @@ pub static GLACIERS_CONFIG: LazyLock<RwLock<Config>> = LazyLock::new(|| {
                     action_input: String::from("action_input"),
                     result_output: String::from("result_output"),
                     action_to: String::from("action_to"),
+                    gas: String::from("action_gas"),
+                    gas_used: String::from("result_gas_used"),
+                    value: String::from("action_value"),
+                    trace_address: None,
                 },
                 trace_datatype: TraceDatatypeConfig {
                     selector: DataType::Binary,
@@ -266,6 +395,13 @@ pub static GLACIERS_CONFIG: LazyLock<RwLock<Config>> = LazyLock::new(|| {
                     action_to: DataType::Binary,
                 }
             },
+            parse_hex_numeric_columns: false,
+        },
+        io: IoConfig {
+            csv_delimiter: String::from(","),
+            csv_has_header: true,
+            csv_quote_char: String::from("\""),
+            csv_alias_columns_as_string: false,
         },
     })
 });
@@ -333,6 +469,8 @@ impl From<bool> for ConfigValue {
 /// * `Config` - A struct with all the current configurations (like a dictionary)
 /// # Example
 /// ```rust
+/// use glaciers::configger::get_config;
+///
 /// let config = get_config();
 /// ```
 pub fn get_config() -> Config {
@@ -348,12 +486,56 @@ pub fn get_config() -> Config {
 /// # Notes
 /// * Some items can receive different types of values (i.e: output_hex_string_encoding can be False/True, 1/0)
 /// * It also does some light transformations to the value, like converting the string to lowercase, for less error prone code.
+/// * Numeric items also accept human-readable strings, like "1_000_000" or "500k" (see `parse_human_number`).
 pub fn set_config(config_path: &str, value: impl Into<ConfigValue>) -> Result<(), ConfiggerError> {
+    let value = value.into();
+    match (set_config_value(config_path, value.clone()), value) {
+        // Strings are retried as numbers, only if the item doesn't accept the string itself
+        (Err(e), ConfigValue::String(v)) => match parse_human_number(&v) {
+            Some(n) => set_config_value(config_path, ConfigValue::Number(n)).map_err(|_| e),
+            None => Err(e),
+        },
+        (result, _) => result,
+    }
+}
+
+/// Parses a human-readable number: digits with optional underscore separators and an optional k (thousand),
+/// m (million) or g (billion) suffix, case insensitive.
+///
+/// # Returns
+/// The number, or None if the string isn't a human-readable number or it overflows
+///
+/// # Example
+/// ```rust
+/// use glaciers::configger::parse_human_number;
+///
+/// assert_eq!(parse_human_number("1_000_000"), Some(1_000_000));
+/// assert_eq!(parse_human_number("500k"), Some(500_000));
+/// assert_eq!(parse_human_number("1M"), Some(1_000_000));
+/// assert_eq!(parse_human_number("500000"), Some(500_000));
+/// assert_eq!(parse_human_number("k"), None);
+/// assert_eq!(parse_human_number("1.5M"), None);
+/// ```
+pub fn parse_human_number(value: &str) -> Option<usize> {
+    let value = value.trim().replace('_', "");
+    let (digits, multiplier) = match value.chars().last()?.to_ascii_lowercase() {
+        'k' => (&value[..value.len() - 1], 1_000),
+        'm' => (&value[..value.len() - 1], 1_000_000),
+        'g' => (&value[..value.len() - 1], 1_000_000_000),
+        _ => (value.as_str(), 1),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse::<usize>().ok()?.checked_mul(multiplier)
+}
+
+/// Sets a configuration item with a value of the type it expects, see `set_config`.
+fn set_config_value(config_path: &str, value: ConfigValue) -> Result<(), ConfiggerError> {
     let mut config = GLACIERS_CONFIG.write().unwrap();
     
     // Breaks the config_path into sections, fields and subfields.
-    let value = value.into();
-    let section = config_path.split(".").nth(0).ok_or(ConfiggerError::InvalidFieldOrValue(format!("Section missing in field: {}", config_path.to_string())))?;
+    let section = config_path.split(".").next().ok_or(ConfiggerError::InvalidFieldOrValue(format!("Section missing in field: {}", config_path)))?;
     let field = config_path.split(".").nth(1);
     let subfield = config_path.split(".").nth(2);
     let schema_field = config_path.split(".").nth(3);
@@ -416,6 +598,49 @@ pub fn set_config(config_path: &str, value: impl Into<ConfigValue>) -> Result<()
                 validate_unique_key(&v)?;
                 config.abi_reader.unique_key = v;
             },
+            (Some("relaxed_json"), ConfigValue::Boolean(v)) => config.abi_reader.relaxed_json = v,
+            (Some("relaxed_json"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.abi_reader.relaxed_json = true,
+                    0 => config.abi_reader.relaxed_json = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("store_raw_inputs"), ConfigValue::Boolean(v)) => config.abi_reader.store_raw_inputs = v,
+            (Some("store_raw_inputs"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.abi_reader.store_raw_inputs = true,
+                    0 => config.abi_reader.store_raw_inputs = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("skip_item_types"), ConfigValue::List(v)) => {
+                let v = v.iter().map(|s| s.to_lowercase()).collect();
+                validate_skip_item_types(&v)?;
+                config.abi_reader.skip_item_types = v;
+            },
+            (Some("skip_item_types"), ConfigValue::String(v)) => {
+                let v = vec![v.to_lowercase()];
+                validate_skip_item_types(&v)?;
+                config.abi_reader.skip_item_types = v;
+            },
+            (Some("sourcify_url"), ConfigValue::String(v)) => config.abi_reader.sourcify_url = v.trim_end_matches('/').to_string(),
+            (Some("etherscan_url"), ConfigValue::String(v)) => config.abi_reader.etherscan_url = v.trim_end_matches('/').to_string(),
+            (Some("max_concurrent_abi_downloads"), ConfigValue::Number(v)) => config.abi_reader.max_concurrent_abi_downloads = v,
+            (Some("http_timeout_secs"), ConfigValue::Number(v)) => config.abi_reader.http_timeout_secs = v,
+            (Some("http_retry_count"), ConfigValue::Number(v)) => config.abi_reader.http_retry_count = v,
+            (Some("http_retry_backoff_ms"), ConfigValue::Number(v)) => config.abi_reader.http_retry_backoff_ms = v,
+            (Some("http_user_agent"), ConfigValue::String(v)) => config.abi_reader.http_user_agent = v,
+            (Some("max_concurrent_abi_folders"), ConfigValue::Number(v)) => config.abi_reader.max_concurrent_abi_folders = v,
+            (Some("hash_column_name"), ConfigValue::String(v)) => config.abi_reader.hash_column_name = v,
+            (Some("store_abi_source"), ConfigValue::Boolean(v)) => config.abi_reader.store_abi_source = v,
+            (Some("store_abi_source"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.abi_reader.store_abi_source = true,
+                    0 => config.abi_reader.store_abi_source = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
             _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
         },
         
@@ -443,21 +668,137 @@ pub fn set_config(config_path: &str, value: impl Into<ConfigValue>) -> Result<()
             (Some("max_concurrent_files_decoding"), ConfigValue::Number(v)) => config.decoder.max_concurrent_files_decoding = v,
             (Some("max_chunk_threads_per_file"), ConfigValue::Number(v)) => config.decoder.max_chunk_threads_per_file = v,
             (Some("decoded_chunk_size"), ConfigValue::Number(v)) => config.decoder.decoded_chunk_size = v,
+            (Some("max_total_chunk_threads"), ConfigValue::Number(v)) => config.decoder.max_total_chunk_threads = v,
+            (Some("max_in_flight_bytes"), ConfigValue::Number(v)) => config.decoder.max_in_flight_bytes = v,
+            (Some("chunk_retry_count"), ConfigValue::Number(v)) => config.decoder.chunk_retry_count = v,
+            (Some("io_max_retries"), ConfigValue::Number(v)) => config.decoder.io_max_retries = v,
+            (Some("max_value_length"), ConfigValue::Number(v)) => config.decoder.max_value_length = v,
+            (Some("bytes_encoding"), ConfigValue::String(v)) => {
+                match v.to_lowercase().as_str() {
+                    "hex" => config.decoder.bytes_encoding = BytesEncoding::Hex,
+                    "base64" => config.decoder.bytes_encoding = BytesEncoding::Base64,
+                    "utf8_lossy" | "utf8lossy" => config.decoder.bytes_encoding = BytesEncoding::Utf8Lossy,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("timestamp_column"), ConfigValue::String(v)) => config.decoder.timestamp_column = Some(v).filter(|v| !v.is_empty()),
+            (Some("timestamp_timezone"), ConfigValue::String(v)) => {
+                validate_timezone(&v)?;
+                config.decoder.timestamp_timezone = v;
+            },
+            (Some("drop_raw_columns"), ConfigValue::Boolean(v)) => config.decoder.drop_raw_columns = v,
+            (Some("drop_raw_columns"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.decoder.drop_raw_columns = true,
+                    0 => config.decoder.drop_raw_columns = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("dataset_mode"), ConfigValue::Boolean(v)) => config.decoder.dataset_mode = v,
+            (Some("dataset_mode"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.decoder.dataset_mode = true,
+                    0 => config.decoder.dataset_mode = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("overwrite_mismatched_outputs"), ConfigValue::Boolean(v)) => config.decoder.overwrite_mismatched_outputs = v,
+            (Some("overwrite_mismatched_outputs"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.decoder.overwrite_mismatched_outputs = true,
+                    0 => config.decoder.overwrite_mismatched_outputs = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("write_run_manifest"), ConfigValue::Boolean(v)) => config.decoder.write_run_manifest = v,
+            (Some("write_run_manifest"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.decoder.write_run_manifest = true,
+                    0 => config.decoder.write_run_manifest = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("write_watermark_file"), ConfigValue::Boolean(v)) => config.decoder.write_watermark_file = v,
+            (Some("write_watermark_file"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.decoder.write_watermark_file = true,
+                    0 => config.decoder.write_watermark_file = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("decode_all_candidates"), ConfigValue::Boolean(v)) => config.decoder.decode_all_candidates = v,
+            (Some("decode_all_candidates"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.decoder.decode_all_candidates = true,
+                    0 => config.decoder.decode_all_candidates = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("add_source_file_column"), ConfigValue::Boolean(v)) => config.decoder.add_source_file_column = v,
+            (Some("add_source_file_column"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.decoder.add_source_file_column = true,
+                    0 => config.decoder.add_source_file_column = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("positional_names_when_unnamed"), ConfigValue::Boolean(v)) => config.decoder.positional_names_when_unnamed = v,
+            (Some("positional_names_when_unnamed"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.decoder.positional_names_when_unnamed = true,
+                    0 => config.decoder.positional_names_when_unnamed = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("abi_id_column"), ConfigValue::String(v)) => {
+                if v.is_empty() {
+                    return Err(ConfiggerError::InvalidFieldOrValue("abi_id_column = ''. It must be a column name".to_string()));
+                }
+                config.decoder.abi_id_column = v;
+            },
+            (Some("abi_passthrough_columns"), ConfigValue::List(v)) => config.decoder.abi_passthrough_columns = v,
+            (Some("abi_passthrough_columns"), ConfigValue::String(v)) => config.decoder.abi_passthrough_columns = vec![v],
+            (Some("passthrough_columns"), ConfigValue::List(v)) => config.decoder.passthrough_columns = v,
+            (Some("passthrough_columns"), ConfigValue::String(v)) => config.decoder.passthrough_columns = vec![v],
+            (Some("signature_allowlist"), ConfigValue::List(v)) => {
+                validate_signature_list(&v)?;
+                config.decoder.signature_allowlist = v;
+            },
+            (Some("signature_allowlist"), ConfigValue::String(v)) => {
+                let v = vec![v];
+                validate_signature_list(&v)?;
+                config.decoder.signature_allowlist = v;
+            },
+            (Some("signature_denylist"), ConfigValue::List(v)) => {
+                validate_signature_list(&v)?;
+                config.decoder.signature_denylist = v;
+            },
+            (Some("signature_denylist"), ConfigValue::String(v)) => {
+                let v = vec![v];
+                validate_signature_list(&v)?;
+                config.decoder.signature_denylist = v;
+            },
             _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
         },
         
         "log_decoder" => match (field, value) {
             (Some("log_schema"), value) => match (subfield, value) {
                 (Some("log_alias"), ConfigValue::String(v)) => {
+                    // The change is applied to a copy, so a duplicate alias leaves the config unchanged
+                    let mut log_alias = config.log_decoder.log_schema.log_alias.clone();
                     match schema_field {
-                        Some("topic0") => config.log_decoder.log_schema.log_alias.topic0 = v,
-                        Some("topic1") => config.log_decoder.log_schema.log_alias.topic1 = v,
-                        Some("topic2") => config.log_decoder.log_schema.log_alias.topic2 = v,
-                        Some("topic3") => config.log_decoder.log_schema.log_alias.topic3 = v,
-                        Some("data") => config.log_decoder.log_schema.log_alias.data = v,
-                        Some("address") => config.log_decoder.log_schema.log_alias.address = v,
+                        Some("topic0") => log_alias.topic0 = v,
+                        Some("topic1") => log_alias.topic1 = v,
+                        Some("topic2") => log_alias.topic2 = v,
+                        Some("topic3") => log_alias.topic3 = v,
+                        Some("data") => log_alias.data = v,
+                        Some("address") => log_alias.address = v,
                         _ => return Err(ConfiggerError::InvalidFieldOrValue(schema_field.unwrap_or("").to_string()))
                     }
+                    let mut aliases = log_alias.as_array();
+                    aliases.push(log_alias.address.clone());
+                    validate_unique_aliases("log_alias", &aliases)?;
+                    config.log_decoder.log_schema.log_alias = log_alias;
                 },
                 (Some("log_datatype"), ConfigValue::String(v)) => {
                     match schema_field {
@@ -496,19 +837,78 @@ pub fn set_config(config_path: &str, value: impl Into<ConfigValue>) -> Result<()
                 },
                 _ => return Err(ConfiggerError::InvalidFieldOrValue(subfield.unwrap_or("").to_string()))
             },
+            (Some("missing_topics_mode"), ConfigValue::String(v)) => {
+                match v.to_lowercase().as_str() {
+                    "compatible" => config.log_decoder.missing_topics_mode = MissingTopicsMode::Compatible,
+                    "strict" => config.log_decoder.missing_topics_mode = MissingTopicsMode::Strict,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("adjust_indexed_args"), ConfigValue::Boolean(v)) => config.log_decoder.adjust_indexed_args = v,
+            (Some("adjust_indexed_args"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.log_decoder.adjust_indexed_args = true,
+                    0 => config.log_decoder.adjust_indexed_args = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("include_num_indexed_args"), ConfigValue::Boolean(v)) => config.log_decoder.include_num_indexed_args = v,
+            (Some("include_num_indexed_args"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.log_decoder.include_num_indexed_args = true,
+                    0 => config.log_decoder.include_num_indexed_args = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("topics_list_column"), ConfigValue::String(v)) => config.log_decoder.topics_list_column = v,
+            (Some("topics_list_last_is_data"), ConfigValue::Boolean(v)) => config.log_decoder.topics_list_last_is_data = v,
+            (Some("topics_list_last_is_data"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.log_decoder.topics_list_last_is_data = true,
+                    0 => config.log_decoder.topics_list_last_is_data = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("indexed_only"), ConfigValue::Boolean(v)) => config.log_decoder.indexed_only = v,
+            (Some("indexed_only"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.log_decoder.indexed_only = true,
+                    0 => config.log_decoder.indexed_only = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
             _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
         },
         
         "trace_decoder" => match (field, value) {
             (Some("trace_schema"), value) => match (subfield, value) {
                 (Some("trace_alias"), ConfigValue::String(v)) => {
+                    // The change is applied to a copy, so a duplicate alias leaves the config unchanged
+                    let mut trace_alias = config.trace_decoder.trace_schema.trace_alias.clone();
                     match schema_field {
-                        Some("selector") => config.trace_decoder.trace_schema.trace_alias.selector = v,
-                        Some("action_input") => config.trace_decoder.trace_schema.trace_alias.action_input = v,
-                        Some("result_output") => config.trace_decoder.trace_schema.trace_alias.result_output = v,
-                        Some("action_to") => config.trace_decoder.trace_schema.trace_alias.action_to = v,
+                        Some("selector") => trace_alias.selector = v,
+                        Some("action_input") => trace_alias.action_input = v,
+                        Some("result_output") => trace_alias.result_output = v,
+                        Some("action_to") => trace_alias.action_to = v,
+                        Some("gas") => trace_alias.gas = v,
+                        Some("gas_used") => trace_alias.gas_used = v,
+                        Some("value") => trace_alias.value = v,
+                        // An empty string unsets the column
+                        Some("trace_address") => trace_alias.trace_address = Some(v).filter(|v| !v.is_empty()),
                         _ => return Err(ConfiggerError::InvalidFieldOrValue(schema_field.unwrap_or("").to_string()))
                     }
+                    let mut aliases = vec![
+                        trace_alias.selector.clone(),
+                        trace_alias.action_input.clone(),
+                        trace_alias.result_output.clone(),
+                        trace_alias.action_to.clone(),
+                        trace_alias.gas.clone(),
+                        trace_alias.gas_used.clone(),
+                        trace_alias.value.clone(),
+                    ];
+                    aliases.extend(trace_alias.trace_address.clone());
+                    validate_unique_aliases("trace_alias", &aliases)?;
+                    config.trace_decoder.trace_schema.trace_alias = trace_alias;
                 },
                 (Some("trace_datatype"), ConfigValue::String(v)) => {
                     match schema_field {
@@ -537,6 +937,42 @@ pub fn set_config(config_path: &str, value: impl Into<ConfigValue>) -> Result<()
                 },
                 _ => return Err(ConfiggerError::InvalidFieldOrValue(subfield.unwrap_or("").to_string()))
             },
+            (Some("parse_hex_numeric_columns"), ConfigValue::Boolean(v)) => config.trace_decoder.parse_hex_numeric_columns = v,
+            (Some("parse_hex_numeric_columns"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.trace_decoder.parse_hex_numeric_columns = true,
+                    0 => config.trace_decoder.parse_hex_numeric_columns = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+        },
+
+        "io" => match (field, value) {
+            (Some("csv_delimiter"), ConfigValue::String(v)) => {
+                validate_csv_char("csv_delimiter", &v, false)?;
+                config.io.csv_delimiter = v;
+            },
+            (Some("csv_has_header"), ConfigValue::Boolean(v)) => config.io.csv_has_header = v,
+            (Some("csv_has_header"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.io.csv_has_header = true,
+                    0 => config.io.csv_has_header = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
+            (Some("csv_quote_char"), ConfigValue::String(v)) => {
+                validate_csv_char("csv_quote_char", &v, true)?;
+                config.io.csv_quote_char = v;
+            },
+            (Some("csv_alias_columns_as_string"), ConfigValue::Boolean(v)) => config.io.csv_alias_columns_as_string = v,
+            (Some("csv_alias_columns_as_string"), ConfigValue::Number(v)) => {
+                match v {
+                    1 => config.io.csv_alias_columns_as_string = true,
+                    0 => config.io.csv_alias_columns_as_string = false,
+                    _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
+                }
+            },
             _ => return Err(ConfiggerError::InvalidFieldOrValue(field.unwrap_or("").to_string()))
         },
         _ => return Err(ConfiggerError::InvalidFieldOrValue(section.to_string()))
@@ -619,7 +1055,7 @@ pub fn set_config_toml(file_path: &str) -> Result<(), ConfiggerError> {
  /// # Arguments
  /// * `unique_key` - The unique_key to validate
  fn validate_unique_key(unique_key: &Vec<String>) -> Result<(), ConfiggerError> {
-    let allowed_keys = ["hash", "full_signature", "address"];
+    let allowed_keys = ["hash", "full_signature", "address", "chain_id"];
     for key in unique_key {
         if !allowed_keys.contains(&key.as_str()) {
             return Err(ConfiggerError::InvalidFieldOrValue(format!("unique_key = '{}'. Allowed values are: {:?}", key, allowed_keys)));
@@ -633,9 +1069,78 @@ pub fn set_config_toml(file_path: &str) -> Result<(), ConfiggerError> {
  /// # Arguments
  /// * `output_file_format` - The output_file_format to validate
  fn validate_output_file_format(output_file_format: &String) -> Result<(), ConfiggerError> {
-    let allowed_formats = ["csv", "parquet"];
+    let allowed_formats = ["csv", "parquet", "ndjson_pretty"];
     if !allowed_formats.contains(&output_file_format.as_str()) {
         return Err(ConfiggerError::InvalidFieldOrValue(format!("output_file_format = '{}'. Allowed values are: {:?}", output_file_format, allowed_formats)));
     }
     Ok(())
- }
\ No newline at end of file
+ }
+
+ /// Validates the timestamp_timezone field, that must be a timezone of the IANA database (i.e: "UTC", "America/New_York")
+ /// 
+ /// # Arguments
+ /// * `timezone` - The timezone to validate
+ pub(crate) fn validate_timezone(timezone: &str) -> Result<(), ConfiggerError> {
+    timezone
+        .parse::<chrono_tz::Tz>()
+        .map(|_| ())
+        .map_err(|_| ConfiggerError::InvalidFieldOrValue(format!("timestamp_timezone = '{}'. It must be a timezone of the IANA database, i.e: 'UTC'", timezone)))
+ }
+
+ /// Validates the signature_allowlist and signature_denylist fields. Entries starting with "0x" must be hex hashes.
+ /// 
+ /// # Arguments
+ /// * `signatures` - The signature list to validate
+ fn validate_signature_list(signatures: &Vec<String>) -> Result<(), ConfiggerError> {
+    for signature in signatures {
+        let signature = signature.trim();
+        if let Some(hash) = signature.strip_prefix("0x").or_else(|| signature.strip_prefix("0X")) {
+            if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(ConfiggerError::InvalidFieldOrValue(format!("signature list entry '{}' is not a valid hex hash", signature)));
+            }
+        }
+    }
+    Ok(())
+ }
+
+ /// Validates the skip_item_types field.
+ /// 
+ /// # Arguments
+ /// * `item_types` - The ABI item types to validate
+ fn validate_skip_item_types(item_types: &Vec<String>) -> Result<(), ConfiggerError> {
+    let allowed_item_types = ["function", "event"];
+    for item_type in item_types {
+        if !allowed_item_types.contains(&item_type.as_str()) {
+            return Err(ConfiggerError::InvalidFieldOrValue(format!("skip_item_types = '{}'. Allowed values are: {:?}", item_type, allowed_item_types)));
+        }
+    }
+    Ok(())
+ }
+
+ /// Validates the csv_delimiter and csv_quote_char fields, which must be a single ASCII character.
+ /// 
+ /// # Arguments
+ /// * `field` - The name of the field being validated
+ /// * `value` - The value to validate
+ /// * `allow_empty` - Whether an empty value is allowed (i.e: to disable quoting)
+ fn validate_csv_char(field: &str, value: &str, allow_empty: bool) -> Result<(), ConfiggerError> {
+    let is_valid = (allow_empty && value.is_empty()) || (value.len() == 1 && value.is_ascii());
+    if !is_valid {
+        return Err(ConfiggerError::InvalidFieldOrValue(format!("{} = '{}'. It must be a single ASCII character", field, value)));
+    }
+    Ok(())
+ }
+
+ /// Validates that the column aliases of a schema are distinct, so two logical columns don't map to the same physical column.
+ /// 
+ /// # Arguments
+ /// * `field` - The name of the alias section being validated
+ /// * `aliases` - The column aliases of the section
+ fn validate_unique_aliases(field: &str, aliases: &[String]) -> Result<(), ConfiggerError> {
+    for (i, alias) in aliases.iter().enumerate() {
+        if aliases[..i].contains(alias) {
+            return Err(ConfiggerError::InvalidFieldOrValue(format!("{} = '{}'. The column is already used by another alias", field, alias)));
+        }
+    }
+    Ok(())
+ }
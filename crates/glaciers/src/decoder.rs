@@ -1,22 +1,53 @@
 //! Module for the high level processing and decoding blockchain data.
 //! 
 //! This module provides functionality to:
-//! - Decode a folder of logs/traces
+//! - Decode a folder of logs/traces, file by file or as a single partitioned dataset, with a manifest of the run
 //! - Decode a single log/trace file
-//! - Decode a DataFrame of logs/traces using an ABI database file path
+//! - Decode a single partition file into an explicit output path, atomically and idempotently, for orchestrators
+//! - Decode the parquet members of a .tar.gz archive of logs/traces, without unpacking it to disk
+//! - Decode a DataFrame of logs/traces using one or more ABI database file paths
 //! - Decode a DataFrame of logs/traces using a pre-loaded ABI DataFrame
+//! - Decode a batch of DataFrames of logs/traces using a pre-loaded ABI DataFrame, as a stream of results
+//! - Estimate the decoding throughput of this machine on a sample of logs/traces
+//! - Count how many logs/traces of a DataFrame match and decode, without building the decoded columns
+//! - Decode a DataFrame of logs/traces using a pre-loaded ABI DataFrame and a user-provided matcher
+//! - Decode a DataFrame of logs/traces using a pre-loaded ABI DataFrame and ordered candidate signatures for some hashes
+//! - Decode a DataFrame of logs/traces using a pre-loaded ABI DataFrame and signatures overriding the matched ones for some hashes
+//! - Build a LazyFrame plan decoding logs/traces, i.e: for the Polars streaming engine
+//! - Decode arrow-rs record batches of logs/traces, behind the `arrow-interop` feature
+//! - Decode a DataFrame of logs/traces adding constant context metadata columns
+//! - Decode a DataFrame of logs/traces into a wide DataFrame, with one column per param of each signature
+//! - Label a DataFrame of function selectors with their names and signatures, without decoding calldata
+//! - Decode the logs and traces of a transaction bundle in a single call
+//! - Validate the number of decoded rows of a signature against a reference count
+//! - Summarize a decoded DataFrame by contract and event/function name
 //! - Split logs/traces DF in chunks, decode logs/traces, collect and union results and save in the decoded folder
 
-use chrono::Local;
+use alloy::dyn_abi::DynSolValue;
+use alloy::json_abi::{Event, Function, Param};
+use alloy::primitives::{keccak256, Signed};
+use chrono::{DateTime, Local, Utc};
+#[cfg(feature = "arrow-interop")]
+use polars::export::arrow::{array::StructArray, chunk::Chunk, ffi};
+use flate2::read::GzDecoder;
+use futures::stream::{self, Stream, StreamExt};
 use polars::prelude::*;
-use serde::Serialize;
-use std::fs;
+use serde::de::{Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{Cursor, Read};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tokio::sync::{mpsc, Mutex, Semaphore};
+use tokio::sync::{mpsc, Mutex, Semaphore, SemaphorePermit};
 use tokio::task;
 
-use crate::configger::{get_config, DecoderAlgorithm};
+use crate::configger::{get_config, validate_timezone, BytesEncoding, Config, DecoderAlgorithm};
 use crate::matcher;
 use crate::utils;
 use crate::log_decoder;
@@ -34,19 +65,273 @@ pub enum DecoderError {
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
     #[error("Join error: {0}")]
-    JoinError(#[from] tokio::task::JoinError)
+    JoinError(#[from] tokio::task::JoinError),
+    #[error("Config error: {0}")]
+    ConfigError(String),
+    #[error("Event count mismatch for {signature}: expected {expected}, found {actual}")]
+    EventCountMismatch { signature: String, expected: usize, actual: usize },
+    #[error("Refusing to overwrite {path}, written by another glaciers version or config ({differences}). Set decoder.overwrite_mismatched_outputs (--force in the CLI) to overwrite it")]
+    OutputMismatch { path: String, differences: String },
 }
 
 /// Represents a structured parameter from decoded data
 /// 
 /// Contains the name, position, type and value of a decoded parameter
 /// This is each item of event_json (logs) or input_json/output_json (traces)
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct StructuredParam {
     pub name: String,
     pub index: u32,
     pub value_type: String,
-    pub value: String,
+    pub value: ParamValue,
+}
+
+/// Value of a StructuredParam, keeping the structure of arrays and tuples in the JSON columns
+///
+/// Serialized as a JSON string for the other values, a JSON array for arrays, and a JSON object of the component
+/// names and values for tuples, in the ABI order (serde_json::Map sorts the keys).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamValue {
+    Value(String),
+    Array(Vec<ParamValue>),
+    Tuple(Vec<(String, ParamValue)>),
+}
+
+impl Serialize for ParamValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ParamValue::Value(value) => serializer.serialize_str(value),
+            ParamValue::Array(values) => serializer.collect_seq(values),
+            ParamValue::Tuple(components) => {
+                let mut map = serializer.serialize_map(Some(components.len()))?;
+                for (name, value) in components {
+                    map.serialize_entry(name, value)?;
+                }
+                map.end()
+            },
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ParamValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ParamValueVisitor)
+    }
+}
+
+struct ParamValueVisitor;
+
+impl<'de> Visitor<'de> for ParamValueVisitor {
+    type Value = ParamValue;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a string, an array or an object of param values")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<ParamValue, E> {
+        Ok(ParamValue::Value(value.to_string()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ParamValue, A::Error> {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(ParamValue::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<ParamValue, A::Error> {
+        let mut components = Vec::new();
+        while let Some(component) = map.next_entry()? {
+            components.push(component);
+        }
+        Ok(ParamValue::Tuple(components))
+    }
+}
+
+impl std::fmt::Display for ParamValue {
+    /// The value itself for the other values, and the JSON of arrays and tuples
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParamValue::Value(value) => f.write_str(value),
+            _ => f.write_str(&serde_json::to_string(self).map_err(|_| std::fmt::Error)?),
+        }
+    }
+}
+
+/// How the decoded params are named and converted to strings, read from the decoder config once for each decoded chunk
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ParamFormat {
+    /// Name the unnamed params by their index (decoder.positional_names_when_unnamed)
+    pub positional_names: bool,
+    /// Maximum length of each value string, 0 for unlimited (decoder.max_value_length)
+    pub max_value_length: usize,
+    /// Encoding of the dynamic bytes values (decoder.bytes_encoding)
+    pub bytes_encoding: BytesEncoding,
+}
+
+impl ParamFormat {
+    pub(crate) fn from_config(config: &Config) -> Self {
+        ParamFormat {
+            positional_names: config.decoder.positional_names_when_unnamed,
+            max_value_length: config.decoder.max_value_length,
+            bytes_encoding: config.decoder.bytes_encoding,
+        }
+    }
+
+    /// Returns the name of the param at `index`, see `utils::param_name_or_positional`
+    pub(crate) fn param_name(&self, name: &str, index: usize) -> String {
+        utils::param_name_or_positional(name, index, self.positional_names)
+    }
+
+    /// Converts a decoded value to its string, truncated to max_value_length, and whether it was truncated
+    pub(crate) fn value_string(&self, value: &DynSolValue) -> (String, bool) {
+        let value = utils::StrDynSolValue::from(value.clone()).to_string_with(&self.bytes_encoding).unwrap_or_else(|| "None".to_string());
+        utils::truncate_value(value, self.max_value_length)
+    }
+
+    /// Converts a decoded value to its JSON value: arrays and tuples keep their structure, recursively, with the tuple
+    /// fields named by the `components` of the ABI param (by their index, `arg0`, `arg1`, ..., if unnamed). The other
+    /// values are converted like in `value_string`.
+    pub(crate) fn param_value(&self, value: &DynSolValue, components: &[Param]) -> ParamValue {
+        match value {
+            // The items of an array have the components of the array param
+            DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+                ParamValue::Array(values.iter().map(|value| self.param_value(value, components)).collect())
+            },
+            DynSolValue::Tuple(values) => ParamValue::Tuple(
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        let component = components.get(i);
+                        // Object keys must be unique, so unnamed fields are always named by their index
+                        let name = utils::param_name_or_positional(component.map_or("", |c| c.name.as_str()), i, true);
+                        (name, self.param_value(value, component.map_or(&[], |c| c.components.as_slice())))
+                    })
+                    .collect(),
+            ),
+            _ => ParamValue::Value(self.value_string(value).0),
+        }
+    }
+}
+
+/// Separator between the parts of the UDF output string (ASCII Unit Separator).
+///
+/// A control character is used because it can't appear in the decoded parts: values and keys are Debug formatted
+/// and the json parts are serialized by serde_json, both escaping control characters.
+pub(crate) const UDF_SEPARATOR: &str = "\u{001F}";
+
+/// Prefix marking a UDF output string as a decoding error message, instead of the decoded parts
+pub(crate) const DECODING_ERROR_PREFIX: &str = "decoding_error:";
+
+/// Hive partition folder value of null partition values
+const HIVE_NULL_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Delay before the first retry of a failed chunk, doubled on each following retry
+const CHUNK_RETRY_BASE_DELAY_MS: u64 = 100;
+/// Delay before the first retry of a failed raw file read, doubled on each following retry
+const IO_RETRY_BASE_DELAY_MS: u64 = 100;
+
+/// Parquet metadata keys of the decoded files written by `decode_file`: the glaciers version, and the config
+/// (flattened to a JSON object of dotted keys) with its keccak256 hash
+const OUTPUT_VERSION_KEY: &str = "glaciers_version";
+const OUTPUT_CONFIG_KEY: &str = "glaciers_config";
+const OUTPUT_CONFIG_HASH_KEY: &str = "glaciers_config_hash";
+/// Parquet metadata key of the `PartitionSummary` JSON stored in the output files of `decode_partition`
+const OUTPUT_SUMMARY_KEY: &str = "glaciers_summary";
+
+/// Config sections and fields left out of the decoded files metadata, since they don't change the decoded output:
+/// paths, concurrency and HTTP settings (some defaults depend on the CPU cores) and the overwrite override itself
+const OUTPUT_CONFIG_IGNORED_KEYS: [&str; 17] = [
+    "main",
+    "abi_reader.max_concurrent_abi_downloads",
+    "abi_reader.http_timeout_secs",
+    "abi_reader.http_retry_count",
+    "abi_reader.http_retry_backoff_ms",
+    "abi_reader.http_user_agent",
+    "abi_reader.max_concurrent_abi_folders",
+    "decoder.max_concurrent_files_decoding",
+    "decoder.max_chunk_threads_per_file",
+    "decoder.decoded_chunk_size",
+    "decoder.max_total_chunk_threads",
+    "decoder.max_in_flight_bytes",
+    "decoder.chunk_retry_count",
+    "decoder.io_max_retries",
+    "decoder.overwrite_mismatched_outputs",
+    "decoder.write_run_manifest",
+    "decoder.write_watermark_file",
+];
+/// Name of the run manifest file written by `decode_folder` in the decoded folder, if decoder.write_run_manifest is set
+pub const RUN_MANIFEST_FILE_NAME: &str = "manifest.json";
+/// Name of the watermark file written by `decode_folder_incremental` in the decoded folder, if decoder.write_watermark_file is set
+pub const WATERMARK_FILE_NAME: &str = "decode_watermark.json";
+
+tokio::task_local! {
+    /// Semaphore shared by the chunks of all files decoded in a decode_folder/decode_archive run, limiting them to
+    /// decoder.max_total_chunk_threads. Unset outside these runs, or if the limit is disabled.
+    static TOTAL_CHUNK_SEMAPHORE: Option<Arc<Semaphore>>;
+}
+
+/// Creates the semaphore shared by all chunks of a multi-file run, None if decoder.max_total_chunk_threads is 0
+fn new_total_chunk_semaphore() -> Option<Arc<Semaphore>> {
+    match get_config().decoder.max_total_chunk_threads {
+        0 => None,
+        max_total_chunk_threads => Some(Arc::new(Semaphore::new(max_total_chunk_threads))),
+    }
+}
+
+/// Bytes per permit of the in-flight bytes budget, since a semaphore can't acquire more than u32::MAX permits at once
+const IN_FLIGHT_BYTES_PER_PERMIT: u64 = 1024;
+
+/// Budget of bytes of the files decoded at the same time by decode_folder, see decoder.max_in_flight_bytes
+struct InFlightBytesBudget {
+    semaphore: Arc<Semaphore>,
+    permits: u32,
+}
+
+impl InFlightBytesBudget {
+    /// Creates the budget of a decode_folder run, None if decoder.max_in_flight_bytes is 0
+    fn new() -> Option<Arc<Self>> {
+        match get_config().decoder.max_in_flight_bytes as u64 {
+            0 => None,
+            max_in_flight_bytes => {
+                let permits = max_in_flight_bytes.div_ceil(IN_FLIGHT_BYTES_PER_PERMIT).min(u32::MAX as u64) as u32;
+                Some(Arc::new(InFlightBytesBudget { semaphore: Arc::new(Semaphore::new(permits as usize)), permits }))
+            }
+        }
+    }
+
+    /// Waits until the size of a file is available in the budget, and takes it until the returned permit is dropped.
+    /// Files bigger than the whole budget take all of it, so they are decoded alone instead of waiting forever.
+    async fn acquire(&self, file_path: &Path) -> SemaphorePermit<'_> {
+        let file_bytes = fs::metadata(file_path).map(|metadata| metadata.len()).unwrap_or(0);
+        let file_permits = file_bytes.div_ceil(IN_FLIGHT_BYTES_PER_PERMIT).clamp(1, self.permits as u64) as u32;
+        self.semaphore.acquire_many(file_permits).await.expect("Semaphore should not be closed")
+    }
+}
+
+/// Moves the decoding errors of a UDF output column into a `decoding_error` column.
+///
+/// Rows that failed to decode have the error message, prefixed by `DECODING_ERROR_PREFIX`, in the UDF output column.
+/// The message is moved to `decoding_error` and the UDF output is set to null, so the decoded columns are null for these rows.
+/// Rows that decoded successfully or weren't decoded (without a matched signature) have a null `decoding_error`.
+///
+/// # Arguments
+/// * `lf` - LazyFrame with the UDF output column
+/// * `udf_column` - Name of the UDF output column (decoded_log or decoded_trace)
+pub(crate) fn split_decoding_error(lf: LazyFrame, udf_column: &str) -> LazyFrame {
+    let is_error = col(udf_column).str().starts_with(lit(DECODING_ERROR_PREFIX));
+    lf.with_columns([
+        when(is_error.clone())
+            .then(col(udf_column).str().strip_prefix(lit(DECODING_ERROR_PREFIX)))
+            .otherwise(lit(Null {}).cast(DataType::String))
+            .alias("decoding_error"),
+        when(is_error)
+            .then(lit(Null {}).cast(DataType::String))
+            .otherwise(col(udf_column))
+            .alias(udf_column),
+    ])
 }
 
 /// Specifies the source type of blockchain data to decode
@@ -58,237 +343,2970 @@ pub enum DecoderType {
     Trace,
 }
 
-/// Decodes all files in a folder. It spawns a task for each file to parallelize the decoding process.
-/// Decoded files are saved in a "decoded" folder, in the parent folder of the raw data.
-/// The file name is the same as the raw file name, but with the "logs" or "traces" replaced with "decoded_logs" or "decoded_traces".
+/// Decoded logs and traces of a transaction bundle, returned by `decode_tx_bundle`
+#[derive(Debug, Clone)]
+pub struct DecodedTxBundle {
+    /// Decoded logs, with the same schema as `decode_df` with `DecoderType::Log`
+    pub logs: DataFrame,
+    /// Decoded traces, with the same schema as `decode_df` with `DecoderType::Trace`
+    pub traces: DataFrame,
+}
+
+/// Row counts of a dry decode, returned by `can_decode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DecodeStats {
+    /// Number of logs/traces
+    pub total: usize,
+    /// Logs/traces matched with an ABI item
+    pub matched: usize,
+    /// Matched logs/traces that decode without errors
+    pub decoded: usize,
+    /// Logs/traces that fail to decode, i.e: they would have a decoding_error. It includes unmatched rows with
+    /// malformed fields (i.e: a trace input shorter than a selector), so it can exceed `matched - decoded`
+    pub failed: usize,
+}
+
+/// Record of a `decode_folder` run, returned by it and saved as `RUN_MANIFEST_FILE_NAME` if decoder.write_run_manifest is set
+#[derive(Debug, Clone, Serialize)]
+pub struct RunManifest {
+    /// Folder of raw logs/traces
+    pub input_folder: String,
+    /// "log" or "trace"
+    pub decoder_type: String,
+    /// ABI DB file paths
+    pub abi_db_paths: Vec<String>,
+    /// Number of items of the ABI DB files, only counted if decoder.write_run_manifest is set
+    pub abi_db_rows: Option<usize>,
+    /// Version of glaciers of the run
+    pub glaciers_version: String,
+    /// Local start time of the run, in RFC 3339 format
+    pub started_at: String,
+    /// Config of the run
+    pub config: Config,
+    /// Result of each file, sorted by input path. In dataset mode, a single entry for the whole dataset
+    pub files: Vec<FileManifest>,
+    /// Totals of all files
+    pub totals: RunTotals,
+}
+
+/// Result of a file of a `decode_folder` run
+#[derive(Debug, Clone, Serialize)]
+pub struct FileManifest {
+    /// Raw file path, or dataset folder path in dataset mode
+    pub input_path: String,
+    /// Decoded file path, or decoded dataset folder path in dataset mode. None if the file failed
+    pub output_path: Option<String>,
+    pub status: FileStatus,
+    /// Error message of a failed file
+    pub error: Option<String>,
+    /// Raw logs/traces read
+    pub rows_in: usize,
+    /// Decoded logs/traces written
+    pub rows_out: usize,
+    /// Written logs/traces matched with an ABI item
+    pub matched_rows: usize,
+    /// matched_rows / rows_out, 0 if no rows were written
+    pub match_rate: f64,
+    pub duration_secs: f64,
+}
+
+/// Status of a file of a `decode_folder` run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Decoded,
+    Failed,
+}
+
+/// Totals of a `decode_folder` run
+#[derive(Debug, Clone, Serialize)]
+pub struct RunTotals {
+    pub files: usize,
+    pub decoded_files: usize,
+    pub failed_files: usize,
+    pub rows_in: usize,
+    pub rows_out: usize,
+    pub matched_rows: usize,
+    /// matched_rows / rows_out, 0 if no rows were written
+    pub match_rate: f64,
+    pub duration_secs: f64,
+}
+
+/// Summary of a `decode_partition` call, also stored in the metadata of its parquet output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitionSummary {
+    pub input_path: String,
+    pub output_path: String,
+    /// Version of glaciers that decoded the partition
+    pub glaciers_version: String,
+    /// Hash of the config that decoded the partition, see the decoded files metadata
+    pub config_hash: String,
+    /// Raw logs/traces read
+    pub rows_in: usize,
+    /// Decoded logs/traces written
+    pub rows_out: usize,
+    /// Written logs/traces matched with an ABI item
+    pub matched_rows: usize,
+    /// matched_rows / rows_out, 0 if no rows were written
+    pub match_rate: f64,
+    /// Duration of the decoding, when the partition was decoded
+    pub duration_secs: f64,
+    /// True if the output already existed with the same config hash, so this call didn't decode anything
+    pub skipped: bool,
+}
+
+/// Result of `decode_file`: the decoded DataFrame, with the paths and row counts of the file
+#[derive(Debug, Clone)]
+pub struct DecodeFileResult {
+    /// Decoded DataFrame, as saved in output_path
+    pub df: DataFrame,
+    /// Raw file path
+    pub input_path: PathBuf,
+    /// Decoded file path
+    pub output_path: PathBuf,
+    /// Raw logs/traces read
+    pub input_rows: u64,
+    /// Decoded logs/traces written
+    pub output_rows: u64,
+    /// Written logs/traces matched with an ABI item
+    pub matched_rows: u64,
+    /// Duration of reading, decoding and saving the file
+    pub duration: Duration,
+}
+
+/// Row counts and output path of a decoded file or dataset, for the run manifest
+struct DecodedOutput {
+    rows_in: usize,
+    rows_out: usize,
+    matched_rows: usize,
+    output_path: PathBuf,
+}
+
+impl DecodedOutput {
+    fn new(rows_in: usize, decoded_df: &DataFrame, output_path: PathBuf) -> Self {
+        let matched_rows = decoded_df
+            .column("full_signature")
+            .map(|column| column.len() - column.null_count())
+            .unwrap_or(0);
+        DecodedOutput { rows_in, rows_out: decoded_df.height(), matched_rows, output_path }
+    }
+}
+
+impl From<&DecodeFileResult> for DecodedOutput {
+    fn from(result: &DecodeFileResult) -> Self {
+        DecodedOutput {
+            rows_in: result.input_rows as usize,
+            rows_out: result.output_rows as usize,
+            matched_rows: result.matched_rows as usize,
+            output_path: result.output_path.clone(),
+        }
+    }
+}
+
+impl FileManifest {
+    fn new(input_path: String, result: &Result<DecodedOutput, DecoderError>, started: Instant) -> Self {
+        let duration_secs = started.elapsed().as_secs_f64();
+        match result {
+            Ok(output) => FileManifest {
+                input_path,
+                output_path: Some(output.output_path.to_string_lossy().into_owned()),
+                status: FileStatus::Decoded,
+                error: None,
+                rows_in: output.rows_in,
+                rows_out: output.rows_out,
+                matched_rows: output.matched_rows,
+                match_rate: match_rate(output.matched_rows, output.rows_out),
+                duration_secs,
+            },
+            Err(e) => FileManifest {
+                input_path,
+                output_path: None,
+                status: FileStatus::Failed,
+                error: Some(e.to_string()),
+                rows_in: 0,
+                rows_out: 0,
+                matched_rows: 0,
+                match_rate: 0.0,
+                duration_secs,
+            },
+        }
+    }
+}
+
+fn match_rate(matched_rows: usize, rows: usize) -> f64 {
+    if rows == 0 {
+        0.0
+    } else {
+        matched_rows as f64 / rows as f64
+    }
+}
+
+/// Aggregation applied to a column by `decode_and_aggregate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggKind {
+    /// Exact sum of the integer values, as a decimal String, so uint256 amounts don't lose precision
+    Sum,
+    /// Mean of the values, cast to Float64
+    Mean,
+    /// Minimum of the values, cast to Float64
+    Min,
+    /// Maximum of the values, cast to Float64
+    Max,
+    /// Number of non-null values
+    Count,
+    /// Number of distinct values
+    NUnique,
+    /// First value of each group
+    First,
+    /// Last value of each group
+    Last,
+}
+
+impl AggKind {
+    /// Name of the aggregation, used as suffix of the aggregated column, i.e: `value_sum`
+    fn name(&self) -> &'static str {
+        match self {
+            AggKind::Sum => "sum",
+            AggKind::Mean => "mean",
+            AggKind::Min => "min",
+            AggKind::Max => "max",
+            AggKind::Count => "count",
+            AggKind::NUnique => "n_unique",
+            AggKind::First => "first",
+            AggKind::Last => "last",
+        }
+    }
+
+    /// Returns the aggregation expression of a column, aliased `{column}_{name}`
+    fn expr(&self, column: &str) -> Expr {
+        let values = col(column);
+        // Decoded params are strings, so numeric aggregations need a numeric cast
+        let numeric = || col(column).cast(DataType::Float64);
+        let expr = match self {
+            // the UDF returns a single value Series for each group, taken with first() to not get a list column
+            AggKind::Sum => values.apply(exact_sum_udf, GetOutput::from_type(DataType::String)).first(),
+            AggKind::Mean => numeric().mean(),
+            AggKind::Min => numeric().min(),
+            AggKind::Max => numeric().max(),
+            AggKind::Count => values.count(),
+            AggKind::NUnique => values.n_unique(),
+            AggKind::First => values.first(),
+            AggKind::Last => values.last(),
+        };
+        expr.alias(&format!("{}_{}", column, self.name()))
+    }
+}
+
+/// UDF summing a String column of decoded integer values (up to int256/uint256) exactly, see `AggKind::Sum`
+///
+/// Returns the sum as a single decimal String value, "0" for a column of nulls, or an error if a value isn't an integer.
+fn exact_sum_udf(s: Series) -> PolarsResult<Option<Series>> {
+    // 512 bits hold the sum of more rows than a DataFrame can have, whatever the sign of the values
+    type I512 = Signed<512, 8>;
+    let mut sum = I512::ZERO;
+    for value in s.str()?.into_iter().flatten() {
+        let value = I512::from_dec_str(value).map_err(|e| {
+            PolarsError::ComputeError(format!("Can't sum '{}' of column {}, it isn't an integer: {}", value, s.name(), e).into())
+        })?;
+        sum = sum.checked_add(value).ok_or_else(|| {
+            PolarsError::ComputeError(format!("The sum of column {} overflowed", s.name()).into())
+        })?;
+    }
+    Ok(Some(Series::new(s.name(), [sum.to_string()])))
+}
+
+/// Parses the aggregation names, i.e: "sum" or "n_unique"
+impl FromStr for AggKind {
+    type Err = DecoderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "sum" => Ok(AggKind::Sum),
+            "mean" => Ok(AggKind::Mean),
+            "min" => Ok(AggKind::Min),
+            "max" => Ok(AggKind::Max),
+            "count" => Ok(AggKind::Count),
+            "n_unique" => Ok(AggKind::NUnique),
+            "first" => Ok(AggKind::First),
+            "last" => Ok(AggKind::Last),
+            _ => Err(DecoderError::DecodingError(format!(
+                "invalid aggregation '{}', allowed values are: [\"sum\", \"mean\", \"min\", \"max\", \"count\", \"n_unique\", \"first\", \"last\"]",
+                s
+            ))),
+        }
+    }
+}
+
+/// One or more ABI database file paths.
+///
+/// When more than one path is provided, the ABI DBs are combined before matching,
+/// see `utils::read_abi_db_files`.
+#[derive(Debug, Clone)]
+pub struct AbiDbPaths(pub Vec<String>);
+
+impl From<String> for AbiDbPaths {
+    fn from(path: String) -> Self {
+        AbiDbPaths(vec![path])
+    }
+}
+
+impl From<&str> for AbiDbPaths {
+    fn from(path: &str) -> Self {
+        AbiDbPaths(vec![path.to_string()])
+    }
+}
+
+impl From<Vec<String>> for AbiDbPaths {
+    fn from(paths: Vec<String>) -> Self {
+        AbiDbPaths(paths)
+    }
+}
+
+/// ABI columns used by the matchers and kept in the decoded output, see `AbiInput::LazyFrame`.
+const ABI_MATCHER_COLUMNS: [&str; 8] = [
+    "address", "full_signature", "name", "anonymous", "num_indexed_args", "state_mutability", "id", "chain_id",
+];
+
+/// The ABI items used for decoding, as given to `decode_df_with_abi_df` (not to be confused with
+/// `miscellaneous::AbiSource`, where each ABI item was fetched from).
+///
+/// `DataFrame` is used as is, `Path` is read on each call (see `utils::read_abi_db_files`), and `LazyFrame`
+/// is collected on each call, so ABI DBs updated on disk are picked up without reloading them.
+/// Only the hash column, the columns in `ABI_MATCHER_COLUMNS` and the decoder.abi_passthrough_columns are collected
+/// from a LazyFrame.
+#[derive(Clone)]
+pub enum AbiInput {
+    Path(AbiDbPaths),
+    DataFrame(DataFrame),
+    LazyFrame(Box<LazyFrame>),
+}
+
+impl AbiInput {
+    /// Loads the ABI items into a DataFrame
+    pub fn load(self) -> Result<DataFrame, DecoderError> {
+        match self {
+            AbiInput::Path(paths) => Ok(utils::read_abi_db_files(&paths.0)?),
+            AbiInput::DataFrame(abi_df) => Ok(abi_df),
+            AbiInput::LazyFrame(abi_lf) => {
+                let schema = abi_lf.schema()?;
+                let hash_column = get_config().abi_reader.hash_column_name;
+                let abi_id_column = get_config().decoder.abi_id_column;
+                let abi_passthrough_columns = get_config().decoder.abi_passthrough_columns;
+                let mut columns: Vec<&str> = Vec::new();
+                let candidates = std::iter::once(hash_column.as_str())
+                    .chain(ABI_MATCHER_COLUMNS)
+                    .chain(std::iter::once(abi_id_column.as_str()))
+                    .chain(abi_passthrough_columns.iter().map(|name| name.as_str()));
+                for name in candidates {
+                    if schema.contains(name) && !columns.contains(&name) {
+                        columns.push(name);
+                    }
+                }
+                Ok((*abi_lf).select(columns.into_iter().map(col).collect::<Vec<Expr>>()).collect()?)
+            }
+        }
+    }
+}
+
+impl From<DataFrame> for AbiInput {
+    fn from(abi_df: DataFrame) -> Self {
+        AbiInput::DataFrame(abi_df)
+    }
+}
+
+impl From<LazyFrame> for AbiInput {
+    fn from(abi_lf: LazyFrame) -> Self {
+        AbiInput::LazyFrame(Box::new(abi_lf))
+    }
+}
+
+impl From<AbiDbPaths> for AbiInput {
+    fn from(paths: AbiDbPaths) -> Self {
+        AbiInput::Path(paths)
+    }
+}
+
+impl From<String> for AbiInput {
+    fn from(path: String) -> Self {
+        AbiInput::Path(path.into())
+    }
+}
+
+impl From<&str> for AbiInput {
+    fn from(path: &str) -> Self {
+        AbiInput::Path(path.into())
+    }
+}
+
+impl From<Vec<String>> for AbiInput {
+    fn from(paths: Vec<String>) -> Self {
+        AbiInput::Path(paths.into())
+    }
+}
+
+/// Decodes all files in a folder. It spawns a task for each file to parallelize the decoding process.
+/// Decoded files are saved in a "decoded" folder, in the parent folder of the raw data.
+/// The file name is the same as the raw file name, but with the "logs" or "traces" replaced with "decoded_logs" or "decoded_traces".
+///
+/// # Arguments
+/// * `folder_path` - Path to folder containing files to decode
+/// * `abi_db_path` - Path to ABI database file, or a list of paths to multiple ABI database files
+/// * `decoder_type` - Type of data to decode (Log or Trace)
+///
+/// # Returns
+/// * `Ok(RunManifest)` with the results of each file, if all files were processed successfully
+/// * `Err(DecoderError)` if the ABI DB can't be read, or with the error of the first failed file
+/// 
+/// 
+/// # Notes
+/// This function gets the max_concurrent_files_decoding from the config and uses it
+/// to limit the number of concurrent files that can be decoded at the same time.
+/// If decoder.max_in_flight_bytes is set, the files decoded at the same time are also limited by the sum of their sizes.
+/// The decoded files are the ones listed by `plan_folder`, see `decode_folder_with_options` to filter them.
+/// The ABI DB files are read once, and each file is decoded like in `decode_file` with the read ABI DataFrame.
+/// The paths, row counts and duration of each file are collected in the manifest files. The decoded DataFrames aren't kept.
+/// If decoder.dataset_mode is set, the folder is decoded as a single partitioned dataset instead (see `decode_dataset`).
+/// If decoder.write_run_manifest is set, the manifest is saved as `RUN_MANIFEST_FILE_NAME` in the "decoded" folder,
+/// also when a file fails, with the failed files' errors.
+///
+/// # Example
+/// ```no_run
+/// use glaciers::decoder::{decode_folder, DecoderType};
+/// 
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let manifest = decode_folder(
+///         "path/to/folder".to_string(),
+///         "path/to/abi_db.parquet".to_string(),
+///         DecoderType::Log
+///     ).await?;
+///     println!("{} rows decoded, match rate {}", manifest.totals.rows_out, manifest.totals.match_rate);
+///     Ok(())
+/// }
+/// ```
+pub async fn decode_folder(
+    folder_path: String,
+    abi_db_path: impl Into<AbiDbPaths>,
+    decoder_type: DecoderType,
+) -> Result<RunManifest, DecoderError> {
+    decode_folder_with_options(folder_path, abi_db_path, decoder_type, FilePlanOptions::default()).await
+}
+
+/// Decodes the files of a folder selected by the filters of `FilePlanOptions`, see `plan_folder`
+///
+/// # Arguments
+/// * `folder_path` - Path to folder containing files to decode
+/// * `abi_db_path` - Path to ABI database file, or a list of paths to multiple ABI database files
+/// * `decoder_type` - Type of data to decode (Log or Trace)
+/// * `options` - Filters of the folder files to decode, by extension, modification time and block range
+///
+/// # Returns
+/// * `Ok(RunManifest)` with the results of each planned file, like `decode_folder`
+/// * `Err(DecoderError)` if the ABI DB can't be read, or with the error of the first failed file
+///
+/// # Notes
+/// Files are decoded like in `decode_folder`, which is this function without filters. If decoder.dataset_mode is set,
+/// the whole folder is decoded as a single dataset, ignoring the options.
+///
+/// # Example
+/// ```no_run
+/// use glaciers::decoder::{decode_folder_with_options, DecoderType, FilePlanOptions};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let options = FilePlanOptions { block_range: Some((18_000_000, 18_999_999)), ..Default::default() };
+///     let manifest = decode_folder_with_options(
+///         "path/to/folder".to_string(),
+///         "path/to/abi_db.parquet".to_string(),
+///         DecoderType::Log,
+///         options,
+///     ).await?;
+///     println!("{} files decoded", manifest.totals.decoded_files);
+///     Ok(())
+/// }
+/// ```
+pub async fn decode_folder_with_options(
+    folder_path: String,
+    abi_db_path: impl Into<AbiDbPaths>,
+    decoder_type: DecoderType,
+    options: FilePlanOptions,
+) -> Result<RunManifest, DecoderError> {
+    let abi_db_path = abi_db_path.into();
+    let config = get_config();
+    let started_at = Local::now();
+    let started = Instant::now();
+    let abi_df = utils::read_abi_db_files(&abi_db_path.0)?;
+    let abi_db_rows = config.decoder.write_run_manifest.then(|| abi_df.height());
+
+    let results = if config.decoder.dataset_mode {
+        let result = TOTAL_CHUNK_SEMAPHORE
+            .scope(new_total_chunk_semaphore(), decode_dataset(Path::new(&folder_path), abi_df, decoder_type.clone()))
+            .await;
+        vec![(FileManifest::new(folder_path.clone(), &result, started), result.err())]
+    } else {
+        let plan = plan_folder(Path::new(&folder_path), &decoder_type, &options)?;
+        decode_folder_files(plan, &abi_df, &decoder_type).await?
+    };
+
+    let run = FolderRun { folder_path, abi_db_path, abi_db_rows, decoder_type, config, started_at, started };
+    run.finish(results)
+}
+
+/// Filters of the files of a folder to decode, see `plan_folder`. The default options select all files.
+#[derive(Debug, Clone, Default)]
+pub struct FilePlanOptions {
+    /// Extensions of the files to decode, without the dot (i.e: "parquet"). All extensions if empty
+    pub extensions: Vec<String>,
+    /// Only the files modified after this time
+    pub modified_since: Option<DateTime<Utc>>,
+    /// Only the files whose block range, inferred from the file name (see `file_block_range`), overlaps this
+    /// inclusive range. Files without a block range in their name are left out
+    pub block_range: Option<(u64, u64)>,
+}
+
+/// A file of a folder to decode, with the path where its decoded file is saved, see `plan_folder`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedFile {
+    pub input_path: PathBuf,
+    /// Decoded file path, in the "decoded" folder, like in `decode_file`
+    pub output_path: PathBuf,
+    pub modified: DateTime<Utc>,
+    pub size_bytes: u64,
+    /// Inclusive block range inferred from the file name, if any
+    pub block_range: Option<(u64, u64)>,
+}
+
+/// Lists the files of a folder to decode, with their decoded file paths, applying the filters of `FilePlanOptions`
+///
+/// # Arguments
+/// * `folder_path` - Path to folder containing files to decode
+/// * `decoder_type` - Type of data to decode (Log or Trace), used to name the decoded files
+/// * `options` - Filters of the folder files, by extension, modification time and block range
+///
+/// # Returns
+/// * `Ok(Vec<PlannedFile>)` with the selected files, sorted by path. Subfolders are skipped
+/// * `Err(DecoderError)` if the folder can't be read
+///
+/// # Notes
+/// Symlinked files are planned like regular files. Entries that can't be read are skipped with a warning.
+/// `decode_folder` decodes the planned files, so the plan can be inspected before a run, like the CLI `--dry-run` flag.
+///
+/// # Example
+/// ```no_run
+/// use std::path::Path;
+/// use glaciers::decoder::{plan_folder, DecoderType, FilePlanOptions};
+///
+/// let options = FilePlanOptions { extensions: vec!["parquet".to_string()], ..Default::default() };
+/// for file in plan_folder(Path::new("path/to/folder"), &DecoderType::Log, &options).unwrap() {
+///     println!("{} -> {}", file.input_path.display(), file.output_path.display());
+/// }
+/// ```
+pub fn plan_folder(folder_path: &Path, decoder_type: &DecoderType, options: &FilePlanOptions) -> Result<Vec<PlannedFile>, DecoderError> {
+    let mut plan = Vec::new();
+    for entry in fs::read_dir(folder_path)? {
+        // Entries that can't be read (i.e: broken symlinks) are skipped, without aborting the run
+        let entry_metadata = entry.and_then(|entry| {
+            let input_path = entry.path();
+            file_metadata(&input_path).map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", input_path.display(), e)))
+                .map(|(metadata, modified)| (input_path, metadata, modified))
+        });
+        let (input_path, metadata, modified) = match entry_metadata {
+            Ok(entry_metadata) => entry_metadata,
+            Err(e) => {
+                println!(
+                    "[{}] Skipping a file of {} that can't be read: {}",
+                    Local::now().format("%Y-%m-%d %H:%M:%S"),
+                    folder_path.display(),
+                    e
+                );
+                continue
+            },
+        };
+        if !metadata.is_file() {
+            continue
+        }
+        let extension = input_path.extension().and_then(OsStr::to_str).unwrap_or_default();
+        if !options.extensions.is_empty() && !options.extensions.iter().any(|allowed| allowed.trim_start_matches('.') == extension) {
+            continue
+        }
+        let modified = DateTime::<Utc>::from(modified);
+        if options.modified_since.is_some_and(|modified_since| modified <= modified_since) {
+            continue
+        }
+        let file_name = input_path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let block_range = file_block_range(&file_name);
+        if let Some((from_block, to_block)) = options.block_range {
+            if !block_range.is_some_and(|(start, end)| start <= to_block && end >= from_block) {
+                continue
+            }
+        }
+        plan.push(PlannedFile {
+            output_path: decoded_output_path(&input_path, decoder_type),
+            input_path,
+            modified,
+            size_bytes: metadata.len(),
+            block_range,
+        });
+    }
+    plan.sort_by(|a, b| a.input_path.cmp(&b.input_path));
+    Ok(plan)
+}
+
+/// Returns the metadata and modification time of a file, following symlinks like `Path::is_file`
+fn file_metadata(path: &Path) -> std::io::Result<(fs::Metadata, std::time::SystemTime)> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata.modified()?;
+    Ok((metadata, modified))
+}
+
+/// Converts a folder plan into a DataFrame, for inspection before a run
+///
+/// # Returns
+/// A DataFrame with the `input_path`, `output_path`, `modified` (UTC datetime), `size_bytes`, `from_block` and
+/// `to_block` (null if the file name has no block range) of each planned file.
+pub fn plan_to_df(plan: &[PlannedFile]) -> Result<DataFrame, DecoderError> {
+    let modified = Series::new("modified", plan.iter().map(|file| file.modified.timestamp_micros()).collect::<Vec<i64>>())
+        .cast(&DataType::Datetime(TimeUnit::Microseconds, Some("UTC".to_string())))?;
+    Ok(DataFrame::new(vec![
+        Series::new("input_path", plan.iter().map(|file| file.input_path.to_string_lossy().into_owned()).collect::<Vec<String>>()),
+        Series::new("output_path", plan.iter().map(|file| file.output_path.to_string_lossy().into_owned()).collect::<Vec<String>>()),
+        modified,
+        Series::new("size_bytes", plan.iter().map(|file| file.size_bytes).collect::<Vec<u64>>()),
+        Series::new("from_block", plan.iter().map(|file| file.block_range.map(|range| range.0)).collect::<Vec<Option<u64>>>()),
+        Series::new("to_block", plan.iter().map(|file| file.block_range.map(|range| range.1)).collect::<Vec<Option<u64>>>()),
+    ])?)
+}
+
+/// Infers the inclusive block range of a raw data file from its name, i.e: `ethereum__logs__00018000000_to_00018099999.parquet`
+/// (the cryo naming). Returns None if the name has no `<start>_to_<end>` block numbers, or if start is after end.
+///
+/// # Example
+/// ```
+/// use glaciers::decoder::file_block_range;
+///
+/// assert_eq!(file_block_range("ethereum__logs__00018000000_to_00018099999.parquet"), Some((18_000_000, 18_099_999)));
+/// assert_eq!(file_block_range("sample_log.parquet"), None);
+/// ```
+pub fn file_block_range(file_name: &str) -> Option<(u64, u64)> {
+    let (before, after) = file_name.split_once("_to_")?;
+    let start_digits = &before[before.trim_end_matches(|c: char| c.is_ascii_digit()).len()..];
+    let end_digits = &after[..after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len()];
+    let (start, end) = (start_digits.parse::<u64>().ok()?, end_digits.parse::<u64>().ok()?);
+    (start <= end).then_some((start, end))
+}
+
+/// Returns the path of the decoded file of a raw file, in the "decoded" folder of the raw file folder's parent,
+/// with the decoder.output_file_format extension, see `decode_file`
+fn decoded_output_path(file_path: &Path, decoder_type: &DecoderType) -> PathBuf {
+    let file_name = file_path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    let raw_data_folder = file_path.parent().and_then(Path::parent).unwrap_or(Path::new(""));
+    raw_data_folder
+        .join("decoded")
+        .join(decoded_file_name(&file_name, decoder_type))
+        .with_extension(get_config().decoder.output_file_format)
+}
+
+/// Decodes the planned files of a decode_folder run, with up to max_concurrent_files_decoding files at the same time,
+/// all with the same ABI DataFrame
+///
+/// # Returns
+/// The manifest and error, if any, of each file.
+async fn decode_folder_files(
+    plan: Vec<PlannedFile>,
+    abi_df: &DataFrame,
+    decoder_type: &DecoderType,
+) -> Result<Vec<(FileManifest, Option<DecoderError>)>, DecoderError> {
+    let total_chunk_semaphore = new_total_chunk_semaphore();
+    // Create a semaphore with MAX_CONCURRENT_FILES_DECODING permits
+    let semaphore = Arc::new(Semaphore::new(get_config().decoder.max_concurrent_files_decoding));
+    // Optional budget of the bytes of the files decoded at the same time, for folders with big files
+    let in_flight_bytes = InFlightBytesBudget::new();
+    // Create a vector to hold our join handles
+    let mut handles = Vec::new();
+
+    // Spawn a task for each file
+    for PlannedFile { input_path: file_path, .. } in plan {
+        let task_input_path = file_path.to_string_lossy().into_owned();
+        // Set by the task once it has its permits, so a panicked task's duration doesn't count the wait for them
+        let task_started = Arc::new(OnceLock::new());
+        let file_started = task_started.clone();
+        // Clone the DataFrame and semafore for each task
+        let abi_df = abi_df.clone();
+        let semaphore = semaphore.clone();
+        let in_flight_bytes = in_flight_bytes.clone();
+        let decoder_type_clone = decoder_type.clone();
+        // Spawn a tokio task for each file, sharing the total chunks semaphore
+        let handle = task::spawn(TOTAL_CHUNK_SEMAPHORE.scope(total_chunk_semaphore.clone(), async move {
+            // Acquire a permit before processing, and the file size from the bytes budget, if any
+            let _permit = semaphore.acquire().await.unwrap();
+            let _bytes_permit = match &in_flight_bytes {
+                Some(budget) => Some(budget.acquire(&file_path).await),
+                None => None,
+            };
+            let file_started = *file_started.get_or_init(Instant::now);
+            let input_path = file_path.to_string_lossy().into_owned();
+            // Only the stats of each file are kept, not its decoded DataFrame
+            let result = decode_file_with_abi(file_path, abi_df.into(), decoder_type_clone)
+                .await
+                .map(|result| DecodedOutput::from(&result));
+            (FileManifest::new(input_path, &result, file_started), result.err())
+        }));
+
+        handles.push((task_input_path, task_started, handle));
+    }
+
+    // Wait for all tasks to complete and collect their results. A task that panicked is recorded as a failed file,
+    // so the results of the other files are kept
+    let mut results = Vec::new();
+    for (input_path, task_started, handle) in handles {
+        let result = match handle.await {
+            Ok(result) => result,
+            Err(e) => {
+                let result = Err(DecoderError::from(e));
+                let started = task_started.get().copied().unwrap_or_else(Instant::now);
+                (FileManifest::new(input_path, &result, started), result.err())
+            },
+        };
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// The inputs of a decode_folder (or decode_folder_incremental) run, used to build its manifest
+struct FolderRun {
+    folder_path: String,
+    abi_db_path: AbiDbPaths,
+    abi_db_rows: Option<usize>,
+    decoder_type: DecoderType,
+    config: Config,
+    started_at: DateTime<Local>,
+    started: Instant,
+}
+
+impl FolderRun {
+    /// Builds the run manifest from the results of each file, and saves it if decoder.write_run_manifest is set
+    ///
+    /// # Returns
+    /// The manifest, or the error of the first failed file. The manifest is saved also when a file fails.
+    fn finish(self, results: Vec<(FileManifest, Option<DecoderError>)>) -> Result<RunManifest, DecoderError> {
+        let (files, errors): (Vec<FileManifest>, Vec<Option<DecoderError>>) = results.into_iter().unzip();
+        let decoded_files: Vec<&FileManifest> = files.iter().filter(|file| file.status == FileStatus::Decoded).collect();
+        let rows_out = decoded_files.iter().map(|file| file.rows_out).sum();
+        let matched_rows = decoded_files.iter().map(|file| file.matched_rows).sum();
+        let totals = RunTotals {
+            files: files.len(),
+            decoded_files: decoded_files.len(),
+            failed_files: files.len() - decoded_files.len(),
+            rows_in: decoded_files.iter().map(|file| file.rows_in).sum(),
+            rows_out,
+            matched_rows,
+            match_rate: match_rate(matched_rows, rows_out),
+            duration_secs: self.started.elapsed().as_secs_f64(),
+        };
+        let manifest = RunManifest {
+            input_folder: self.folder_path.clone(),
+            decoder_type: match self.decoder_type {
+                DecoderType::Log => String::from("log"),
+                DecoderType::Trace => String::from("trace"),
+            },
+            abi_db_paths: self.abi_db_path.0,
+            abi_db_rows: self.abi_db_rows,
+            glaciers_version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at: self.started_at.to_rfc3339(),
+            config: self.config.clone(),
+            files,
+            totals,
+        };
+
+        if self.config.decoder.write_run_manifest {
+            let decoded_folder = decoded_folder_path(&self.folder_path);
+            fs::create_dir_all(&decoded_folder)?;
+            let manifest_json = serde_json::to_string_pretty(&manifest)
+                .map_err(|e| DecoderError::DecodingError(format!("Error serializing the run manifest: {}", e)))?;
+            fs::write(decoded_folder.join(RUN_MANIFEST_FILE_NAME), manifest_json)?;
+        }
+
+        if let Some(error) = errors.into_iter().flatten().next() {
+            return Err(error);
+        }
+
+        println!(
+            "[{}] All files processed",
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        );
+        Ok(manifest)
+    }
+}
+
+/// Returns the "decoded" folder of a raw data folder, in its parent folder, where decode_folder saves the decoded files
+fn decoded_folder_path(folder_path: &str) -> PathBuf {
+    Path::new(folder_path).parent().unwrap_or(Path::new("")).join("decoded")
+}
+
+/// Watermark of a `decode_folder_incremental` run: the latest modification time of the decoded files, and the names
+/// of the decoded files modified at this time, so files modified in the same instant after the run aren't skipped
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeWatermark {
+    pub time: DateTime<Utc>,
+    /// Names of the decoded files modified at `time`. Other files modified at `time` are decoded by the next run
+    pub files: Vec<String>,
+}
+
+/// A watermark without the names of its files, i.e: given by hand. The files modified at its time are decoded again.
+impl From<DateTime<Utc>> for DecodeWatermark {
+    fn from(time: DateTime<Utc>) -> Self {
+        DecodeWatermark { time, files: Vec::new() }
+    }
+}
+
+/// Decodes the files of a folder modified after a watermark, i.e: the files added since the last run of a scheduled pipeline
+///
+/// # Arguments
+/// * `folder_path` - Path to folder containing files to decode
+/// * `abi_db_path` - Path to ABI database file, or a list of paths to multiple ABI database files
+/// * `decoder_type` - Type of data to decode (Log or Trace)
+/// * `watermark` - Only the files modified at or after its time, and not in its files, are decoded
+///
+/// # Returns
+/// * `Ok((RunManifest, DecodeWatermark))` with the results of the decoded files, and the new watermark: the latest
+///   modification time of the decoded files with the names of the files modified at this time, or the same watermark
+///   if no file was newer
+/// * `Err(DecoderError)` if the ABI DB can't be read, or with the error of the first failed file
+///
+/// # Notes
+/// Files are decoded like in `decode_folder`, and saved in the same "decoded" folder. decoder.dataset_mode is ignored.
+/// If decoder.write_watermark_file is set, the new watermark is saved as `WATERMARK_FILE_NAME` in the "decoded" folder
+/// when all files are decoded, and can be read for the next run with `read_decode_watermark`.
+/// Files modified while they are decoded are decoded again in the next run. Since modification times can be coarse
+/// (i.e: 1 second on some filesystems), files modified at the watermark time are compared by name with its files,
+/// instead of being skipped.
+///
+/// # Example
+/// ```no_run
+/// use chrono::DateTime;
+/// use glaciers::decoder::{decode_folder_incremental, read_decode_watermark, DecoderType};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let watermark = read_decode_watermark("path/to/folder")?.unwrap_or(DateTime::UNIX_EPOCH.into());
+///     let (manifest, watermark) = decode_folder_incremental(
+///         "path/to/folder".to_string(),
+///         "path/to/abi_db.parquet".to_string(),
+///         DecoderType::Log,
+///         watermark,
+///     ).await?;
+///     println!("{} new files decoded, next watermark {}", manifest.totals.files, watermark.time);
+///     Ok(())
+/// }
+/// ```
+pub async fn decode_folder_incremental(
+    folder_path: String,
+    abi_db_path: impl Into<AbiDbPaths>,
+    decoder_type: DecoderType,
+    watermark: impl Into<DecodeWatermark>,
+) -> Result<(RunManifest, DecodeWatermark), DecoderError> {
+    let watermark = watermark.into();
+    let abi_db_path = abi_db_path.into();
+    let config = get_config();
+    let started_at = Local::now();
+    let started = Instant::now();
+    let abi_df = utils::read_abi_db_files(&abi_db_path.0)?;
+    let abi_db_rows = config.decoder.write_run_manifest.then(|| abi_df.height());
+
+    let mut plan = plan_folder(Path::new(&folder_path), &decoder_type, &FilePlanOptions::default())?;
+    plan.retain(|file| {
+        file.modified > watermark.time
+            || (file.modified == watermark.time && !watermark.files.contains(&planned_file_name(file)))
+    });
+    let new_watermark = new_decode_watermark(&plan, watermark.clone());
+    println!(
+        "[{}] {} files modified after the watermark {}",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        plan.len(),
+        watermark.time.to_rfc3339()
+    );
+
+    let results = decode_folder_files(plan, &abi_df, &decoder_type).await?;
+    let run = FolderRun { folder_path: folder_path.clone(), abi_db_path, abi_db_rows, decoder_type, config: config.clone(), started_at, started };
+    let manifest = run.finish(results)?;
+
+    if config.decoder.write_watermark_file {
+        let decoded_folder = decoded_folder_path(&folder_path);
+        fs::create_dir_all(&decoded_folder)?;
+        let watermark_json = serde_json::json!({ "watermark": new_watermark.time.to_rfc3339(), "files": new_watermark.files });
+        fs::write(decoded_folder.join(WATERMARK_FILE_NAME), watermark_json.to_string())?;
+    }
+    Ok((manifest, new_watermark))
+}
+
+/// Returns the file name of a planned file, used to record the files of a `DecodeWatermark`
+fn planned_file_name(file: &PlannedFile) -> String {
+    file.input_path.file_name().unwrap_or_default().to_string_lossy().into_owned()
+}
+
+/// Returns the watermark after decoding the planned files: their latest modification time, with the names of the files
+/// modified at this time, including the files of the previous watermark if the time didn't change
+fn new_decode_watermark(plan: &[PlannedFile], watermark: DecodeWatermark) -> DecodeWatermark {
+    let Some(time) = plan.iter().map(|file| file.modified).max() else {
+        return watermark
+    };
+    let mut files = if time == watermark.time { watermark.files } else { Vec::new() };
+    for file in plan.iter().filter(|file| file.modified == time) {
+        let file_name = planned_file_name(file);
+        if !files.contains(&file_name) {
+            files.push(file_name);
+        }
+    }
+    DecodeWatermark { time, files }
+}
+
+/// Reads the watermark saved by `decode_folder_incremental` for a raw data folder, if decoder.write_watermark_file is set
+///
+/// # Arguments
+/// * `folder_path` - Path to the raw data folder, whose "decoded" folder has the `WATERMARK_FILE_NAME` file
+///
+/// # Returns
+/// * `Ok(Some(watermark))` with the saved watermark, or `Ok(None)` if the folder has no watermark file yet
+/// * `Err(DecoderError)` if the watermark file can't be read or parsed
+///
+/// # Notes
+/// Watermark files without the names of their files are read with no files, so the files modified at its time are
+/// decoded again.
+pub fn read_decode_watermark(folder_path: &str) -> Result<Option<DecodeWatermark>, DecoderError> {
+    let watermark_path = decoded_folder_path(folder_path).join(WATERMARK_FILE_NAME);
+    if !watermark_path.exists() {
+        return Ok(None);
+    }
+    let invalid = |e: String| DecoderError::DecodingError(format!("Invalid watermark file {}: {}", watermark_path.display(), e));
+    let watermark_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&watermark_path)?)
+        .map_err(|e| invalid(e.to_string()))?;
+    let watermark = watermark_json["watermark"].as_str().ok_or_else(|| invalid("missing watermark".to_string()))?;
+    let time = DateTime::parse_from_rfc3339(watermark).map_err(|e| invalid(e.to_string()))?.with_timezone(&Utc);
+    let files = watermark_json["files"]
+        .as_array()
+        .map(|files| files.iter().filter_map(|file| file.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    Ok(Some(DecodeWatermark { time, files }))
+}
+
+/// Decodes all the parquet files of a folder, and its subfolders, as a single dataset
+///
+/// The files are scanned as a single LazyFrame, with the hive partition columns of their paths (i.e: "date=2024-01-01"),
+/// and matched and decoded in a single plan (see `decode_lazy`), collected with the Polars streaming engine. The output
+/// is saved in a "decoded" folder, in the parent folder of the dataset, in a subfolder named like the dataset folder with
+/// "logs" or "traces" replaced with "decoded_logs" or "decoded_traces", partitioned by the same hive partition columns,
+/// with one file per partition.
+///
+/// # Arguments
+/// * `folder_path` - Path to the dataset root folder
+/// * `abi_df` - ABI DataFrame, read from the ABI DB files of the run
+/// * `decoder_type` - Type of data to decode (Log or Trace)
+///
+/// # Returns
+/// * `Ok(DecodedOutput)` with the row counts and the decoded dataset folder, if the dataset was decoded and saved successfully
+/// * `Err(DecoderError)` if the folder has no parquet files, or reading, decoding or saving fails
+///
+/// # Notes
+/// The partition columns are taken from the path of the first file, so all files must have the same partition columns.
+/// They are kept as columns of the decoded files, besides the partition folders. max_concurrent_files_decoding,
+/// decoded_chunk_size and max_chunk_threads_per_file are ignored, in favor of the Polars internal parallelism, and the
+/// streaming limitations of `decode_lazy` apply. The decoded dataset is collected in memory before being partitioned.
+async fn decode_dataset(
+    folder_path: &Path,
+    abi_df: DataFrame,
+    decoder_type: DecoderType,
+) -> Result<DecodedOutput, DecoderError> {
+    let files = collect_dataset_files(folder_path)?;
+    let Some(first_file) = files.first() else {
+        return Err(DecoderError::DecodingError(format!("No parquet files found in the dataset folder {}", folder_path.display())));
+    };
+    let partition_columns = hive_partition_columns(folder_path, first_file);
+
+    println!(
+        "[{}] Starting decoding dataset: {} ({} files)",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        folder_path.display(),
+        files.len()
+    );
+
+    let mut dataset_lf = LazyFrame::scan_parquet_files(
+        files.into(),
+        ScanArgsParquet { hive_partitioning: true, ..Default::default() },
+    )?;
+    // Only read the alias, passthrough and partition columns, like utils::read_raw_df_file
+    if let Some(projection) = utils::raw_columns_projection(&decoder_type) {
+        let columns: Vec<Expr> = dataset_lf
+            .schema()?
+            .iter_names()
+            .filter(|name| projection.contains(&name.to_string()) || partition_columns.contains(&name.to_string()))
+            .map(|name| col(name))
+            .collect();
+        dataset_lf = dataset_lf.select(columns);
+    }
+    let rows_in = dataset_lf.clone().select([len()]).collect()?.column("len")?.u32()?.get(0).unwrap_or(0) as usize;
+    let dataset_lf = utils::hex_string_columns_to_binary_lazy(dataset_lf, &decoder_type);
+    let decoded_lf = decode_lazy(dataset_lf, abi_df, decoder_type.clone())?;
+    // The streaming parquet source blocks on the Polars async runtime, so the plan is collected outside of the tokio workers
+    let decoded_df = task::spawn_blocking(move || decoded_lf.with_streaming(true).collect()).await??;
+
+    let folder_name = folder_path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    let save_path = folder_path
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join("decoded")
+        .join(decoded_file_name(&folder_name, &decoder_type));
+    let file_name = match decoder_type {
+        DecoderType::Log => "decoded_logs",
+        DecoderType::Trace => "decoded_traces",
+    };
+    println!(
+        "[{}] Saving decoded dataset to: {:?}",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        save_path
+    );
+    let output = DecodedOutput::new(rows_in, &decoded_df, save_path.clone());
+    write_partitioned_df(decoded_df, &save_path, &partition_columns, file_name)?;
+    Ok(output)
+}
+
+/// Collects the paths of the parquet files of a folder and its subfolders, sorted.
+fn collect_dataset_files(folder_path: &Path) -> Result<Vec<PathBuf>, DecoderError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(folder_path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(collect_dataset_files(&path)?);
+        } else if path.extension() == Some(OsStr::new("parquet")) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the hive partition column names (i.e: "date" for "date=2024-01-01") of a dataset file path,
+/// in the order of its folders below the dataset root folder.
+fn hive_partition_columns(folder_path: &Path, file_path: &Path) -> Vec<String> {
+    file_path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(folder_path).ok())
+        .map(|relative_path| {
+            relative_path
+                .components()
+                .filter_map(|component| {
+                    let component = component.as_os_str().to_string_lossy();
+                    component.split_once('=').map(|(name, _)| name.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Writes a DataFrame in hive partitioned folders (i.e: "save_path/date=2024-01-01/file_name.parquet"),
+/// with one file per partition, or a single file in save_path if there are no partition columns.
+/// The partition columns are kept in the files.
+fn write_partitioned_df(
+    mut df: DataFrame,
+    save_path: &Path,
+    partition_columns: &[String],
+    file_name: &str,
+) -> Result<(), DecoderError> {
+    let extension = get_config().decoder.output_file_format;
+    if partition_columns.is_empty() {
+        fs::create_dir_all(save_path)?;
+        utils::write_df_file(&mut df, &save_path.join(file_name).with_extension(&extension))?;
+        return Ok(());
+    }
+
+    for mut partition_df in df.partition_by_stable(partition_columns, true)? {
+        let mut partition_path = save_path.to_path_buf();
+        for column in partition_columns {
+            let value = partition_df.column(column)?.cast(&DataType::String)?;
+            let value = value.str()?.get(0).unwrap_or(HIVE_NULL_PARTITION).to_string();
+            partition_path.push(format!("{}={}", column, value));
+        }
+        fs::create_dir_all(&partition_path)?;
+        utils::write_df_file(&mut partition_df, &partition_path.join(file_name).with_extension(&extension))?;
+    }
+    Ok(())
+}
+
+/// Decodes a single file using the specified ABI database
+/// Decoded file is saved in a "decoded" folder, in the parent folder of the raw data.
+/// The file name is the same as the raw file name, but with the "logs" or "traces" replaced with "decoded_logs" or "decoded_traces".
+/// 
+/// # Arguments
+/// * `file_path` - Path to file to decode
+/// * `abi_db_path` - Path to ABI database file, or a list of paths to multiple ABI database files
+/// * `decoder_type` - Type of data to decode (Log or Trace)
+///
+/// # Returns
+/// * `Ok(DecodeFileResult)` containing the decoded data, the input and output paths, the row counts and the duration
+/// * `Err(DecoderError)` if decoding fails
+///
+/// # Notes
+/// The output format (binary/hex) of some columns is determined by configuration.
+/// Parquet files are read with the columns of decoder.passthrough_columns only, see `utils::raw_columns_projection`.
+/// A read failed with an IO error is retried up to decoder.io_max_retries times, with exponential backoff.
+/// If decoder.add_source_file_column is set, a `source_file` column with the input file name is added to the decoded rows.
+/// If decoder.timestamp_column is set and the file has this column, its Unix timestamps are converted to datetimes in
+/// decoder.timestamp_timezone.
+/// Parquet outputs store the glaciers version and config in their metadata. If the output file already exists and was
+/// written by another version or config, the file isn't decoded and `DecoderError::OutputMismatch` lists the differences,
+/// unless decoder.overwrite_mismatched_outputs is set. Outputs without this metadata (i.e: csv files) are overwritten.
+pub async fn decode_file(
+    file_path: PathBuf,
+    abi_db_path: impl Into<AbiDbPaths>,
+    decoder_type: DecoderType,
+) -> Result<DecodeFileResult, DecoderError> {
+    decode_file_with_abi(file_path, AbiInput::Path(abi_db_path.into()), decoder_type).await
+}
+
+/// Decodes a single file with the ABI items of an `AbiInput`, see `decode_file`. Used by `decode_folder` to decode
+/// all the files with the ABI DataFrame read once for the run.
+async fn decode_file_with_abi(
+    file_path: PathBuf,
+    abi: AbiInput,
+    decoder_type: DecoderType,
+) -> Result<DecodeFileResult, DecoderError> {
+    let started = Instant::now();
+    let file_path_str = file_path.to_string_lossy().into_owned();
+    let file_name = file_path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned();
+    let save_path = decoded_output_path(&file_path, &decoder_type);
+    let config = get_config();
+    let output_metadata = output_metadata(&config);
+    check_existing_output(&save_path, &output_metadata, config.decoder.overwrite_mismatched_outputs)?;
+
+    println!(
+        "[{}] Starting decoding file: {}",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        file_path_str
+    );
+
+    let file_df = read_raw_df_file_with_retries(&file_path, &decoder_type, config.decoder.io_max_retries).await?;
+    let file_df = utils::hex_string_columns_to_binary(file_df, &decoder_type)?;
+    let rows_in = file_df.height();
+    let mut decoded_df = decode_df_with_abi_df(file_df, abi, decoder_type).await?;
+    decoded_df = with_timestamp_datetime(decoded_df, &config)?;
+    if config.decoder.add_source_file_column {
+        decoded_df = decoded_df.lazy().with_column(lit(file_name.as_str()).alias("source_file")).collect()?;
+    }
+
+    println!(
+        "[{}] Finished decoding file: {}",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        file_name
+    );
+
+    if let Some(parent) = save_path.parent() {
+        // create folder if it doesn't exist
+        fs::create_dir_all(parent.to_string_lossy().into_owned())?;
+    }
+
+    utils::write_df_file_with_metadata(&mut decoded_df, &save_path, &output_metadata)?;
+    
+    println!(
+        "[{}] Saving decoded to: {:?}",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        save_path
+    );
+
+    let output = DecodedOutput::new(rows_in, &decoded_df, save_path);
+    Ok(DecodeFileResult {
+        df: decoded_df,
+        input_path: file_path,
+        output_path: output.output_path,
+        input_rows: output.rows_in as u64,
+        output_rows: output.rows_out as u64,
+        matched_rows: output.matched_rows as u64,
+        duration: started.elapsed(),
+    })
+}
+
+/// Reads a raw logs/traces file with `utils::read_raw_df_file`, retrying a read failed with an IO error up to
+/// `max_retries` times and waiting twice as long before each retry, i.e: for transient errors of network filesystems.
+/// Other errors (i.e: a corrupted file) are returned right away, since reading the file again wouldn't fix them.
+async fn read_raw_df_file_with_retries(
+    file_path: &Path,
+    decoder_type: &DecoderType,
+    max_retries: usize,
+) -> Result<DataFrame, DecoderError> {
+    let mut attempt = 0;
+    loop {
+        match utils::read_raw_df_file(file_path, decoder_type) {
+            Err(e @ PolarsError::Io(_)) if attempt < max_retries => {
+                attempt += 1;
+                let backoff = Duration::from_millis(IO_RETRY_BASE_DELAY_MS << (attempt - 1).min(16));
+                println!(
+                    "[{}] Retrying reading file {} ({}/{}) in {:?}: {}",
+                    Local::now().format("%Y-%m-%d %H:%M:%S"),
+                    file_path.display(),
+                    attempt,
+                    max_retries,
+                    backoff,
+                    e
+                );
+                tokio::time::sleep(backoff).await;
+            }
+            file_df => return Ok(file_df?),
+        }
+    }
+}
+
+/// Converts the decoder.timestamp_column Unix timestamp (seconds) column to a datetime in decoder.timestamp_timezone.
+/// The datetime has milliseconds precision, as polars has no seconds time unit. Files without the column are unchanged.
+fn with_timestamp_datetime(df: DataFrame, config: &Config) -> Result<DataFrame, DecoderError> {
+    let Some(column) = config.decoder.timestamp_column.as_deref() else {
+        return Ok(df)
+    };
+    let Ok(dtype) = df.schema().try_get(column).cloned() else {
+        return Ok(df)
+    };
+    if !dtype.is_integer() {
+        return Err(DecoderError::DecodingError(format!(
+            "decoder.timestamp_column {} must be an integer Unix timestamp, found {}",
+            column, dtype
+        )))
+    }
+    let timezone = config.decoder.timestamp_timezone.clone();
+    validate_timezone(&timezone).map_err(|e| DecoderError::DecodingError(e.to_string()))?;
+    Ok(df
+        .lazy()
+        .with_column(
+            (col(column).cast(DataType::Int64) * lit(1000i64))
+                .cast(DataType::Datetime(TimeUnit::Milliseconds, Some(timezone)))
+                .alias(column),
+        )
+        .collect()?)
+}
+
+/// Decodes a single partition file into an explicit output path, i.e: for orchestrators calling glaciers once per partition
+///
+/// # Arguments
+/// * `input_file` - Path to the raw logs/traces file to decode
+/// * `abi_db_path` - Path to ABI database file, or a list of paths to multiple ABI database files
+/// * `output_file` - Path of the decoded file. Its extension sets the format (csv, parquet or ndjson_pretty)
+/// * `decoder_type` - Type of data to decode (Log or Trace)
+///
+/// # Returns
+/// * `Ok(PartitionSummary)` with the row counts of the partition
+/// * `Err(DecoderError)` if reading, decoding or saving fails, or the output exists and was written by another config
+///
+/// # Notes
+/// Unlike `decode_file`, the output path isn't derived from the input path. The output is written to a temporary file
+/// in the same folder and renamed, so it's never left half written. The summary is stored in the parquet output
+/// metadata: if the output already exists with the same config hash, nothing is decoded and its stored summary is
+/// returned, with `skipped` set. Outputs written by another config follow decoder.overwrite_mismatched_outputs, like
+/// in `decode_file`. Outputs without metadata (csv and ndjson_pretty files) are always decoded again.
+///
+/// # Example
+/// ```no_run
+/// use std::path::PathBuf;
+/// use glaciers::decoder::{decode_partition, DecoderType};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let summary = decode_partition(
+///         PathBuf::from("raw/logs/date=2024-01-01.parquet"),
+///         "path/to/abi_db.parquet",
+///         PathBuf::from("decoded/logs/date=2024-01-01.parquet"),
+///         DecoderType::Log,
+///     ).await?;
+///     println!("{}", serde_json::to_string(&summary)?);
+///     Ok(())
+/// }
+/// ```
+pub async fn decode_partition(
+    input_file: PathBuf,
+    abi_db_path: impl Into<AbiDbPaths>,
+    output_file: PathBuf,
+    decoder_type: DecoderType,
+) -> Result<PartitionSummary, DecoderError> {
+    let started = Instant::now();
+    let config = get_config();
+    let mut output_metadata = output_metadata(&config);
+    let config_hash = output_metadata
+        .iter()
+        .find(|(key, _)| key == OUTPUT_CONFIG_HASH_KEY)
+        .map(|(_, value)| value.clone())
+        .unwrap_or_default();
+
+    if let Some(mut summary) = existing_partition_summary(&output_file, &config_hash) {
+        println!(
+            "[{}] Skipping partition, already decoded with the same config: {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            output_file.display()
+        );
+        summary.skipped = true;
+        return Ok(summary);
+    }
+    check_existing_output(&output_file, &output_metadata, config.decoder.overwrite_mismatched_outputs)?;
+
+    println!(
+        "[{}] Starting decoding partition: {}",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        input_file.display()
+    );
+    let file_df = read_raw_df_file_with_retries(&input_file, &decoder_type, config.decoder.io_max_retries).await?;
+    let file_df = utils::hex_string_columns_to_binary(file_df, &decoder_type)?;
+    let rows_in = file_df.height();
+    let mut decoded_df = decode_df(file_df, abi_db_path, decoder_type).await?;
+    decoded_df = with_timestamp_datetime(decoded_df, &config)?;
+
+    let output = DecodedOutput::new(rows_in, &decoded_df, output_file.clone());
+    let summary = PartitionSummary {
+        input_path: input_file.to_string_lossy().into_owned(),
+        output_path: output_file.to_string_lossy().into_owned(),
+        glaciers_version: env!("CARGO_PKG_VERSION").to_string(),
+        config_hash,
+        rows_in: output.rows_in,
+        rows_out: output.rows_out,
+        matched_rows: output.matched_rows,
+        match_rate: match_rate(output.matched_rows, output.rows_out),
+        duration_secs: started.elapsed().as_secs_f64(),
+        skipped: false,
+    };
+    let summary_json = serde_json::to_string(&summary)
+        .map_err(|e| DecoderError::DecodingError(format!("Error serializing the partition summary: {}", e)))?;
+    output_metadata.push((OUTPUT_SUMMARY_KEY.to_string(), summary_json));
+
+    if let Some(parent) = output_file.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    // The temporary file is hidden (dot prefixed) and keeps the output extension, that sets the format
+    let file_name = output_file.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    let temp_file = output_file.with_file_name(format!(".glaciers_tmp_{}_{}", std::process::id(), file_name));
+    let written = utils::write_df_file_with_metadata(&mut decoded_df, &temp_file, &output_metadata)
+        .map_err(DecoderError::from)
+        .and_then(|_| fs::rename(&temp_file, &output_file).map_err(DecoderError::from));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp_file);
+        return Err(e);
+    }
+
+    println!(
+        "[{}] Saving decoded partition to: {}",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        output_file.display()
+    );
+    Ok(summary)
+}
+
+/// Reads the `PartitionSummary` stored in a decoded parquet file, if it exists and was written with the given config hash
+fn existing_partition_summary(path: &Path, config_hash: &str) -> Option<PartitionSummary> {
+    if !path.exists() || path.extension().and_then(OsStr::to_str) != Some("parquet") {
+        return None;
+    }
+    let metadata = utils::read_parquet_metadata(path).ok()?;
+    if metadata.get(OUTPUT_CONFIG_HASH_KEY).map(String::as_str) != Some(config_hash) {
+        return None;
+    }
+    serde_json::from_str(metadata.get(OUTPUT_SUMMARY_KEY)?).ok()
+}
+
+/// Builds the metadata stored in the decoded files: the glaciers version, the config and the config hash.
+/// The config is flattened to dotted keys (i.e: "decoder.algorithm"), without the `OUTPUT_CONFIG_IGNORED_KEYS`.
+fn output_metadata(config: &Config) -> Vec<(String, String)> {
+    let mut flat_config = BTreeMap::new();
+    if let Ok(config) = toml::Value::try_from(config) {
+        flatten_toml_value("", &config, &mut flat_config);
+    }
+    flat_config.retain(|key, _| {
+        !OUTPUT_CONFIG_IGNORED_KEYS.iter().any(|ignored| key == ignored || key.starts_with(&format!("{}.", ignored)))
+    });
+    let config_json = serde_json::to_string(&flat_config).unwrap_or_default();
+
+    vec![
+        (OUTPUT_VERSION_KEY.to_string(), env!("CARGO_PKG_VERSION").to_string()),
+        (OUTPUT_CONFIG_HASH_KEY.to_string(), keccak256(config_json.as_bytes()).to_string()),
+        (OUTPUT_CONFIG_KEY.to_string(), config_json),
+    ]
+}
+
+/// Flattens a TOML value into a map of dotted keys to the TOML representation of the leaf values
+fn flatten_toml_value(prefix: &str, value: &toml::Value, flat: &mut BTreeMap<String, String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten_toml_value(&key, value, flat);
+            }
+        }
+        value => {
+            flat.insert(prefix.to_string(), value.to_string());
+        }
+    }
+}
+
+/// Checks that an existing decoded parquet file can be overwritten, comparing its metadata with the current one.
+///
+/// # Returns
+/// * `Ok(())` if the file doesn't exist, has no glaciers metadata, was written by the same version and config, or `overwrite` is set
+/// * `Err(DecoderError::OutputMismatch)` listing the version and config keys that differ, otherwise
+fn check_existing_output(path: &Path, metadata: &[(String, String)], overwrite: bool) -> Result<(), DecoderError> {
+    if !path.exists() || path.extension().and_then(OsStr::to_str) != Some("parquet") {
+        return Ok(());
+    }
+    // Unreadable files (i.e: left by a crashed run) are overwritten
+    let Ok(existing) = utils::read_parquet_metadata(path) else {
+        return Ok(());
+    };
+    let Some(existing_version) = existing.get(OUTPUT_VERSION_KEY) else {
+        return Ok(());
+    };
+    let current: HashMap<&str, &str> = metadata.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect();
+
+    let mut differences = Vec::new();
+    if existing_version != current[OUTPUT_VERSION_KEY] {
+        differences.push(format!("glaciers version: {} -> {}", existing_version, current[OUTPUT_VERSION_KEY]));
+    }
+    if existing.get(OUTPUT_CONFIG_HASH_KEY).map(String::as_str) != Some(current[OUTPUT_CONFIG_HASH_KEY]) {
+        let parse = |json: Option<&str>| -> BTreeMap<String, String> {
+            json.and_then(|json| serde_json::from_str(json).ok()).unwrap_or_default()
+        };
+        let existing_config = parse(existing.get(OUTPUT_CONFIG_KEY).map(String::as_str));
+        let current_config = parse(Some(current[OUTPUT_CONFIG_KEY]));
+        let keys: BTreeSet<&String> = existing_config.keys().chain(current_config.keys()).collect();
+        for key in keys {
+            let (old, new) = (existing_config.get(key), current_config.get(key));
+            if old != new {
+                let unset = String::from("<unset>");
+                differences.push(format!("{}: {} -> {}", key, old.unwrap_or(&unset), new.unwrap_or(&unset)));
+            }
+        }
+    }
+    if differences.is_empty() {
+        return Ok(());
+    }
+
+    let differences = differences.join(", ");
+    if overwrite {
+        println!(
+            "[{}] Overwriting {:?}, written by another glaciers version or config: {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            path,
+            differences
+        );
+        return Ok(());
+    }
+    Err(DecoderError::OutputMismatch { path: path.to_string_lossy().into_owned(), differences })
+}
+
+/// Decodes the parquet members of a .tar.gz archive of logs/traces, saving the decoded files to output_dir
+///
+/// Members are streamed out of the archive and decoded in memory, so the archive is never unpacked to disk.
+/// The decoded file name follows the same rule as decode_file: "logs" or "traces" in the member file name
+/// is replaced with "decoded_logs" or "decoded_traces".
+///
+/// # Arguments
+/// * `archive_path` - Path to the .tar.gz archive
+/// * `abi_db_path` - Path to ABI database file, or a list of paths to multiple ABI database files
+/// * `decoder_type` - Type of data to decode (Log or Trace)
+/// * `output_dir` - Folder where the decoded files are saved, created if it doesn't exist
+///
+/// # Returns
+/// * `Ok(())` if all parquet members were processed successfully
+/// * `Err(DecoderError)` if reading the archive or decoding any member fails
+///
+/// # Notes
+/// Non parquet members (and folders) are skipped. Members are saved by file name only, so members with the same
+/// file name in different folders of the archive overwrite each other.
+/// Like decode_folder, max_concurrent_files_decoding limits the number of members decoded at the same time,
+/// and also the number of members read ahead in memory.
+///
+/// # Example
+/// ```no_run
+/// use std::path::PathBuf;
+/// use glaciers::decoder::{decode_archive, DecoderType};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     decode_archive(
+///         PathBuf::from("path/to/logs.tar.gz"),
+///         "path/to/abi_db.parquet",
+///         DecoderType::Log,
+///         PathBuf::from("path/to/decoded"),
+///     ).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn decode_archive(
+    archive_path: PathBuf,
+    abi_db_path: impl Into<AbiDbPaths>,
+    decoder_type: DecoderType,
+    output_dir: PathBuf,
+) -> Result<(), DecoderError> {
+    let abi_df = utils::read_abi_db_files(&abi_db_path.into().0)?;
+    fs::create_dir_all(&output_dir)?;
+    let member_decoder_type = decoder_type.clone();
+    let max_concurrent_files = get_config().decoder.max_concurrent_files_decoding;
+
+    // The tar entries can't be held across an await, so the archive is read in a blocking task,
+    // sending each parquet member through a bounded channel
+    let (tx, mut rx) = mpsc::channel::<(String, DataFrame)>(max_concurrent_files);
+    let reader_handle = task::spawn_blocking(move || -> Result<(), DecoderError> {
+        let mut archive = tar::Archive::new(GzDecoder::new(File::open(&archive_path)?));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let entry_path = entry.path()?.into_owned();
+            if !entry.header().entry_type().is_file() || entry_path.extension() != Some(OsStr::new("parquet")) {
+                continue
+            }
+            let file_name = entry_path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            let member_df = utils::read_raw_parquet(Cursor::new(bytes), &member_decoder_type)?;
+            // the receiver is only dropped if decode_archive was cancelled
+            if tx.blocking_send((file_name, member_df)).is_err() {
+                break
+            }
+        }
+        Ok(())
+    });
+
+    let semaphore = Arc::new(Semaphore::new(max_concurrent_files));
+    let total_chunk_semaphore = new_total_chunk_semaphore();
+    let mut handles = Vec::new();
+    while let Some((file_name, member_df)) = rx.recv().await {
+        let abi_df = abi_df.clone();
+        let decoder_type_clone = decoder_type.clone();
+        let save_path = output_dir
+            .join(decoded_file_name(&file_name, &decoder_type))
+            .with_extension(get_config().decoder.output_file_format);
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+        let handle = task::spawn(TOTAL_CHUNK_SEMAPHORE.scope(total_chunk_semaphore.clone(), async move {
+            let _permit = permit;
+            println!(
+                "[{}] Starting decoding archive member: {}",
+                Local::now().format("%Y-%m-%d %H:%M:%S"),
+                file_name
+            );
+            let member_df = utils::hex_string_columns_to_binary(member_df, &decoder_type_clone)?;
+            let mut decoded_df = decode_df_with_abi_df(member_df, abi_df, decoder_type_clone).await?;
+            utils::write_df_file(&mut decoded_df, &save_path)?;
+            println!(
+                "[{}] Saving decoded to: {:?}",
+                Local::now().format("%Y-%m-%d %H:%M:%S"),
+                save_path
+            );
+            Ok::<(), DecoderError>(())
+        }));
+        handles.push(handle);
+    }
+
+    reader_handle.await??;
+    for handle in handles {
+        handle.await??;
+    }
+
+    println!(
+        "[{}] All archive members processed",
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+    Ok(())
+}
+
+/// Returns the decoded file name of a raw logs/traces file name.
+///
+/// "logs" or "traces" in the file name is replaced with "decoded_logs" or "decoded_traces",
+/// or the "decoded_logs_"/"decoded_traces_" prefix is added if the file name doesn't contain it.
+fn decoded_file_name(file_name: &str, decoder_type: &DecoderType) -> String {
+    let (raw, decoded) = match decoder_type {
+        DecoderType::Log => ("logs", "decoded_logs"),
+        DecoderType::Trace => ("traces", "decoded_traces"),
+    };
+    if file_name.contains(raw) {
+        file_name.replace(raw, decoded)
+    } else {
+        format!("{}_{}", decoded, file_name)
+    }
+}
+
+/// Decodes a logs/traces DataFrame using one or more ABI database file paths
+///
+/// # Arguments
+/// * `df` - DataFrame containing raw blockchain data
+/// * `abi_db_path` - Path to ABI database file, or a list of paths to multiple ABI database files
+/// * `decoder_type` - Type of data to decode
+///
+/// # Returns
+/// * `Ok(DataFrame)` containing decoded data
+/// * `Err(DecoderError)` if decoding fails
+///
+/// # Notes
+/// When multiple ABI DBs are provided, they are combined (union of columns, filled with nulls)
+/// and deduplicated by hash, full_signature and address before matching.
+pub async fn decode_df(
+    df: DataFrame,
+    abi_db_path: impl Into<AbiDbPaths>,
+    decoder_type: DecoderType,
+) -> Result<DataFrame, DecoderError> {
+    let abi_df = utils::read_abi_db_files(&abi_db_path.into().0)?;
+
+    decode_df_with_abi_df(df, abi_df, decoder_type).await
+}
+
+/// Decodes a logs/traces DataFrame using a pre-loaded ABI DataFrame
+///
+/// # Arguments
+/// * `df` - DataFrame containing raw blockchain data
+/// * `abi_df` - ABI definitions, as a DataFrame, a LazyFrame or one or more ABI DB file paths (see `AbiInput`)
+/// * `decoder_type` - Type of data to decode
+///
+/// # Returns
+/// * `Ok(DataFrame)` containing decoded data
+/// * `Err(DecoderError)` if loading the ABI items or decoding fails
+/// 
+/// # Notes
+/// The function gets the matching algorithm from the config and uses it to join the logs/traces with ABI itens.
+/// A LazyFrame ABI source is collected on each call, so a LazyFrame scanning an ABI DB file picks up its new items.
+/// If decoder.decode_all_candidates is set, logs whose topic0 has more than one full signature in the ABI DataFrame
+/// are decoded with `decode_df_with_priority`, using the signatures returned by `candidate_signatures` as candidates.
+/// The candidates of a topic0 are all its ABI items, whatever their address.
+pub async fn decode_df_with_abi_df(
+    df: DataFrame,
+    abi_df: impl Into<AbiInput>,
+    decoder_type: DecoderType,
+) -> Result<DataFrame, DecoderError> {
+    let abi_df = abi_df.into().load()?;
+    let config = get_config();
+    if config.decoder.decode_all_candidates && matches!(decoder_type, DecoderType::Log) {
+        let df = expand_topics_list(df, &decoder_type)?;
+        let priority = candidate_signatures(&df, &abi_df, &config)?;
+        return decode_df_with_priority(df, abi_df, priority, decoder_type).await;
+    }
+    let matched_df = match_df(df, abi_df, &decoder_type, &config)?;
+
+    // Split logs files in chunk, decode logs, collected and union results and save in the decoded folder
+    let semaphore = Arc::new(Semaphore::new(config.decoder.max_chunk_threads_per_file));
+    decode(matched_df, decoder_type, &config, semaphore).await
+}
+
+/// Decodes a batch of logs/traces DataFrames using a pre-loaded ABI DataFrame, as a stream of results
+///
+/// # Arguments
+/// * `dfs` - DataFrames containing raw blockchain data
+/// * `abi_df` - DataFrame containing ABI definitions, shared by all the DataFrames
+/// * `decoder_type` - Type of data to decode
+///
+/// # Returns
+/// A stream yielding, for each input DataFrame, the result of `decode_df_with_abi_df`
+///
+/// # Notes
+/// Up to `max_concurrent_files_decoding` DataFrames are decoded at the same time, and results are yielded
+/// as they complete, so they can come out of the input order. The stream is lazy: nothing is decoded until it is polled.
+///
+/// # Example
+/// ```no_run
+/// use futures::StreamExt;
+/// use glaciers::decoder::{decode_df_batch, DecoderType};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let abi_df = glaciers::utils::read_df_file("path/to/abi_db.parquet".as_ref())?;
+///     let dfs = vec![
+///         glaciers::utils::read_df_file("path/to/logs_1.parquet".as_ref())?,
+///         glaciers::utils::read_df_file("path/to/logs_2.parquet".as_ref())?,
+///     ];
+///     let mut results = decode_df_batch(dfs, abi_df, DecoderType::Log);
+///     while let Some(decoded_df) = results.next().await {
+///         println!("{}", decoded_df?.height());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn decode_df_batch(
+    dfs: Vec<DataFrame>,
+    abi_df: DataFrame,
+    decoder_type: DecoderType,
+) -> impl Stream<Item = Result<DataFrame, DecoderError>> {
+    let max_concurrent = get_config().decoder.max_concurrent_files_decoding.max(1);
+    stream::iter(dfs)
+        .map(move |df| decode_df_with_abi_df(df, abi_df.clone(), decoder_type.clone()))
+        .buffer_unordered(max_concurrent)
+}
+
+/// Estimates the decoding throughput of this machine, in rows per second, by decoding a sample of logs/traces
+///
+/// # Arguments
+/// * `sample_df` - DataFrame with a sample of the raw blockchain data to decode, i.e: one file of the job
+/// * `abi_df` - ABI definitions, as a DataFrame, a LazyFrame or one or more ABI DB file paths (see `AbiInput`)
+/// * `decoder_type` - Type of data to decode
+///
+/// # Returns
+/// * `Ok(f64)` with the rows of the sample decoded per second
+/// * `Err(DecoderError)` if the sample is empty, or if loading the ABI items or decoding fails
+///
+/// # Notes
+/// The sample is decoded with `decode_df_with_abi_df`, so the matching and the decoder.decoded_chunk_size and
+/// decoder.max_chunk_threads_per_file configs are part of the measure. Loading the ABI items isn't.
+/// decode_folder also decodes up to decoder.max_concurrent_files_decoding files at the same time, so its throughput
+/// can be higher if the chunk threads don't use all the cores. A sample with the same signatures mix as the job gives a
+/// better estimate, since rows without a matched ABI item aren't decoded.
+///
+/// # Example
+/// ```no_run
+/// use glaciers::decoder::{benchmark_decode, DecoderType};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let sample_df = glaciers::utils::read_df_file("path/to/logs.parquet".as_ref())?;
+///     let rows_per_sec = benchmark_decode(sample_df, "path/to/abi_db.parquet", DecoderType::Log).await?;
+///     println!("{:.0} rows/sec, {:.1} hours for 10B rows", rows_per_sec, 1e10 / rows_per_sec / 3600.0);
+///     Ok(())
+/// }
+/// ```
+pub async fn benchmark_decode(
+    sample_df: DataFrame,
+    abi_df: impl Into<AbiInput>,
+    decoder_type: DecoderType,
+) -> Result<f64, DecoderError> {
+    let rows = sample_df.height();
+    if rows == 0 {
+        return Err(DecoderError::DecodingError("The sample DataFrame is empty".to_string()));
+    }
+    let abi_df = abi_df.into().load()?;
+
+    let start = Instant::now();
+    decode_df_with_abi_df(sample_df, abi_df, decoder_type).await?;
+    Ok(rows as f64 / start.elapsed().as_secs_f64())
+}
+
+/// Checks how many logs/traces of a DataFrame decode with a pre-loaded ABI DataFrame, without producing the decoded columns
+///
+/// # Arguments
+/// * `df` - DataFrame containing raw blockchain data
+/// * `abi_df` - DataFrame containing ABI definitions
+/// * `decoder_type` - Type of data to decode
+///
+/// # Returns
+/// * `Ok(DecodeStats)` with the number of total, matched, decoded and failed logs/traces
+/// * `Err(DecoderError)` if matching fails
+///
+/// # Notes
+/// The rows are matched like `decode_df_with_abi_df` and each matched row is decoded, but the decoded values are discarded
+/// instead of converted to strings and JSON, so it's cheaper than a full decode. Useful to gate CI on a file decoding cleanly.
+///
+/// # Example
+/// ```no_run
+/// use glaciers::decoder::{can_decode, DecoderType};
+///
+/// let logs_df = glaciers::utils::read_df_file("path/to/logs.parquet".as_ref()).unwrap();
+/// let abi_df = glaciers::utils::read_df_file("path/to/abi_db.parquet".as_ref()).unwrap();
+/// let stats = can_decode(logs_df, abi_df, DecoderType::Log).unwrap();
+/// assert_eq!(stats.failed, 0);
+/// ```
+pub fn can_decode(
+    df: DataFrame,
+    abi_df: DataFrame,
+    decoder_type: DecoderType,
+) -> Result<DecodeStats, DecoderError> {
+    let config = get_config();
+    let matched_lf = match_df(df, abi_df, &decoder_type, &config)?.lazy();
+    let checked_lf = match decoder_type {
+        DecoderType::Log => log_decoder::decodable_logs_lazy(matched_lf),
+        DecoderType::Trace => trace_decoder::decodable_traces_lazy(matched_lf),
+    };
+    let counts_df = checked_lf
+        .select([
+            len().cast(DataType::UInt64).alias("total"),
+            col("full_signature").is_not_null().sum().cast(DataType::UInt64).alias("matched"),
+            col("decodable").eq(lit(true)).sum().cast(DataType::UInt64).alias("decoded"),
+            col("decodable").eq(lit(false)).sum().cast(DataType::UInt64).alias("failed"),
+        ])
+        .collect()?;
+    let count = |name: &str| -> Result<usize, DecoderError> {
+        Ok(counts_df.column(name)?.u64()?.get(0).unwrap_or(0) as usize)
+    };
+
+    Ok(DecodeStats {
+        total: count("total")?,
+        matched: count("matched")?,
+        decoded: count("decoded")?,
+        failed: count("failed")?,
+    })
+}
+
+/// Decodes a logs/traces DataFrame using a pre-loaded ABI DataFrame and a user-provided matcher
+///
+/// # Arguments
+/// * `df` - DataFrame containing raw blockchain data
+/// * `abi_df` - DataFrame containing ABI definitions
+/// * `decoder_type` - Type of data to decode
+/// * `matcher` - Closure that joins the raw data with the matched ABI items, replacing the algorithm from the config
+///
+/// # Returns
+/// * `Ok(DataFrame)` containing decoded data
+/// * `Err(DecoderError)` if the matcher fails, its output has no `full_signature` column, or decoding fails
+///
+/// # Notes
+/// The ABI hash and address columns are converted to binary before calling the matcher, like for the built-in algorithms.
+/// The matcher output must keep the raw data columns and add the `full_signature` column of the matched ABI item
+/// (null for unmatched rows), see `matcher::match_logs_by_topic0_address` for an example. It runs in a blocking thread.
+///
+/// # Example
+/// ```no_run
+/// use glaciers::decoder::{decode_df_with_custom_matcher, DecoderType};
+/// use glaciers::matcher;
+/// use polars::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let logs_df = glaciers::utils::read_df_file("path/to/logs.parquet".as_ref())?;
+///     let abi_df = glaciers::utils::read_df_file("path/to/abi_db.parquet".as_ref())?;
+///     // only match logs from block 18_000_000 onwards
+///     let decoded_df = decode_df_with_custom_matcher(logs_df, abi_df, DecoderType::Log, |df, abi_df| {
+///         let df = df.lazy().filter(col("block_number").gt_eq(lit(18_000_000))).collect()?;
+///         matcher::match_logs_by_topic0(df, abi_df)
+///     }).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn decode_df_with_custom_matcher(
+    df: DataFrame,
+    abi_df: DataFrame,
+    decoder_type: DecoderType,
+    matcher: impl Fn(DataFrame, DataFrame) -> Result<DataFrame, matcher::MatcherError> + Send + 'static,
+) -> Result<DataFrame, DecoderError> {
+    let config = get_config();
+    let abi_df = prepare_abi_df(abi_df, &config)?;
+    let df = expand_topics_list(df, &decoder_type)?;
+    let matched_df = task::spawn_blocking(move || matcher(df, abi_df)).await??;
+    if !matched_df.get_column_names().contains(&"full_signature") {
+        return Err(DecoderError::DecodingError("The custom matcher output has no full_signature column".to_string()));
+    }
+
+    let semaphore = Arc::new(Semaphore::new(config.decoder.max_chunk_threads_per_file));
+    decode(matched_df, decoder_type, &config, semaphore).await
+}
+
+/// Returns the full signatures of each log topic0 with more than one full signature in the ABI DataFrame, for
+/// decoder.decode_all_candidates
+///
+/// # Notes
+/// Only the ABI items of the topic0s in the logs DataFrame are grouped, so the cost doesn't grow with the ABI DB size.
+/// The signatures of each topic0 are ordered by their number of ABI items, like the tie-break of the `hash` matching
+/// algorithm, and then by full signature, so the matcher's choice is usually tried first.
+fn candidate_signatures(df: &DataFrame, abi_df: &DataFrame, config: &Config) -> Result<HashMap<Vec<u8>, Vec<String>>, DecoderError> {
+    let hash_column = config.abi_reader.hash_column_name.as_str();
+    let topic0_alias = config.log_decoder.log_schema.log_alias.topic0.as_str();
+    let counts_df = utils::abi_df_hex_string_columns_to_binary(abi_df.clone())?
+        .lazy()
+        .filter(col(hash_column).is_not_null().and(col("full_signature").is_not_null()))
+        .join(
+            df.clone().lazy().select([col(topic0_alias)]).unique(None, UniqueKeepStrategy::Any),
+            [col(hash_column)],
+            [col(topic0_alias)],
+            JoinArgs::new(JoinType::Semi),
+        )
+        .group_by([col(hash_column), col("full_signature")])
+        .agg([len().alias("signature_count")])
+        .collect()?;
+
+    let mut counts: HashMap<Vec<u8>, Vec<(String, u32)>> = HashMap::new();
+    let hashes = counts_df.column(hash_column)?.binary()?;
+    let signatures = counts_df.column("full_signature")?.str()?;
+    let signature_counts = counts_df.column("signature_count")?.u32()?;
+    for ((hash, signature), count) in hashes.into_iter().zip(signatures).zip(signature_counts) {
+        if let (Some(hash), Some(signature), Some(count)) = (hash, signature, count) {
+            counts.entry(hash.to_vec()).or_default().push((signature.to_string(), count));
+        }
+    }
+    Ok(counts
+        .into_iter()
+        .filter(|(_, signatures)| signatures.len() > 1)
+        .map(|(hash, mut signatures)| {
+            signatures.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+            (hash, signatures.into_iter().map(|(signature, _)| signature).collect())
+        })
+        .collect())
+}
+
+/// Decodes a logs/traces DataFrame using a pre-loaded ABI DataFrame and ordered candidate signatures for some hashes
+///
+/// # Arguments
+/// * `df` - DataFrame containing raw blockchain data
+/// * `abi_df` - DataFrame containing ABI definitions
+/// * `priority` - Map of topic0s (logs) or 4 bytes selectors (traces) to their candidate full signatures, in priority order
+/// * `decoder_type` - Type of data to decode
+///
+/// # Returns
+/// * `Ok(DataFrame)` containing decoded data
+/// * `Err(DecoderError)` if matching or decoding fails
+///
+/// # Notes
+/// Each row of a hash in the map is decoded with the first of its candidates it decodes with (see
+/// `log_decoder::decode_with_candidates`), instead of the matcher's tie-break. The candidates are picked in a single pass
+/// over the rows, whatever the number of hashes in the map. Candidates are looked up by hash and full signature in the
+/// ABI DataFrame, so they are still subject to the signature allowlist and denylist, and the ABI item columns (i.e: the
+/// decoder.abi_id_column) come from the first ABI item with the hash and signature, whatever its address.
+/// Rows no candidate decodes, and rows of hashes not in the map, are matched with the algorithm from the config, like in
+/// `decode_df_with_abi_df`. The original row order is kept.
+pub async fn decode_df_with_priority(
+    df: DataFrame,
+    abi_df: DataFrame,
+    priority: HashMap<Vec<u8>, Vec<String>>,
+    decoder_type: DecoderType,
+) -> Result<DataFrame, DecoderError> {
+    let config = get_config();
+    let hash_alias = match decoder_type {
+        DecoderType::Log => config.log_decoder.log_schema.log_alias.topic0.clone(),
+        DecoderType::Trace => config.trace_decoder.trace_schema.trace_alias.selector.clone(),
+    };
+    let hash_column = config.abi_reader.hash_column_name.clone();
+    let abi_df = prepare_abi_df(abi_df, &config)?;
+    let semaphore = Arc::new(Semaphore::new(config.decoder.max_chunk_threads_per_file));
+
+    let mut df = expand_topics_list(df, &decoder_type)?
+        .lazy()
+        .with_row_index("priority_row_index", None)
+        .collect()?;
+
+    // Candidates missing from the ABI items of the DataFrame hashes are skipped
+    let abi_signatures_df = abi_df
+        .clone()
+        .lazy()
+        .join(
+            df.clone().lazy().select([col(&hash_alias)]).unique(None, UniqueKeepStrategy::Any),
+            [col(&hash_column)],
+            [col(&hash_alias)],
+            JoinArgs::new(JoinType::Semi),
+        )
+        .select([col(&hash_column), col("full_signature")])
+        .collect()?;
+    let mut abi_signatures: HashMap<&[u8], HashSet<&str>> = HashMap::new();
+    let hashes = abi_signatures_df.column(&hash_column)?.binary()?;
+    let signatures = abi_signatures_df.column("full_signature")?.str()?;
+    for (hash, signature) in hashes.into_iter().zip(signatures) {
+        if let (Some(hash), Some(signature)) = (hash, signature) {
+            abi_signatures.entry(hash).or_default().insert(signature);
+        }
+    }
+    let priority: HashMap<Vec<u8>, Vec<String>> = priority
+        .into_iter()
+        .filter_map(|(hash, candidates)| {
+            let hash_signatures = abi_signatures.get(hash.as_slice())?;
+            let candidates: Vec<String> = candidates.into_iter().filter(|c| hash_signatures.contains(c.as_str())).collect();
+            (!candidates.is_empty()).then_some((hash, candidates))
+        })
+        .collect();
+
+    let df_columns: Vec<String> = df.get_column_names().iter().map(|name| name.to_string()).collect();
+    let priority_signatures = match decoder_type {
+        DecoderType::Log => priority_log_signatures(&df, &priority, &config)?,
+        DecoderType::Trace => priority_trace_signatures(&df, &priority, &config)?,
+    };
+    df.with_column(priority_signatures)?;
+
+    // Rows with a picked candidate get the ABI item columns of its hash and signature, in the matchers' column order
+    let mut dropped_columns = vec![hash_column.as_str(), "address"];
+    if df_columns.iter().any(|name| name == "chain_id") {
+        dropped_columns.push("chain_id");
+    }
+    let mut selected_columns: Vec<Expr> = df_columns.iter().map(|name| col(name)).collect();
+    selected_columns.extend(
+        abi_df.get_column_names().into_iter().filter(|name| !dropped_columns.contains(name)).map(col),
+    );
+    let mut priority_abi_df = abi_df
+        .clone()
+        .lazy()
+        .unique_stable(Some(vec![hash_column.clone(), "full_signature".to_string()]), UniqueKeepStrategy::First);
+    if matches!(decoder_type, DecoderType::Log) {
+        priority_abi_df = priority_abi_df.with_column(col("num_indexed_args").cast(DataType::UInt32));
+    }
+    let prioritized_df = df
+        .clone()
+        .lazy()
+        .filter(col("priority_signature").is_not_null())
+        .join(
+            priority_abi_df,
+            [col(&hash_alias), col("priority_signature")],
+            [col(&hash_column), col("full_signature")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .rename(["priority_signature"], ["full_signature"])
+        .select(selected_columns)
+        .collect()?;
+    let remaining_df = df
+        .lazy()
+        .filter(col("priority_signature").is_null())
+        .drop(["priority_signature"])
+        .collect()?;
+
+    let mut decoded_dfs = Vec::new();
+    if prioritized_df.height() > 0 {
+        decoded_dfs.push(decode(prioritized_df, decoder_type.clone(), &config, semaphore.clone()).await?.lazy());
+    }
+    if remaining_df.height() > 0 || decoded_dfs.is_empty() {
+        let matched_df = match_df(remaining_df, abi_df, &decoder_type, &config)?;
+        decoded_dfs.push(decode(matched_df, decoder_type, &config, semaphore).await?.lazy());
+    }
+
+    let decoded_df = concat_lf_diagonal(decoded_dfs, UnionArgs::default())?
+        .sort("priority_row_index", Default::default())
+        .drop(["priority_row_index"])
+        .collect()?;
+    Ok(decoded_df)
+}
+
+/// Picks the candidate signature of each log of a topic0 in the priority map, see `decode_df_with_priority`
+///
+/// # Returns
+/// A `priority_signature` String Series with the first candidate each log decodes with (see
+/// `log_decoder::decode_with_candidates`), null for logs of other topic0s or that don't decode with any candidate.
+fn priority_log_signatures(
+    df: &DataFrame,
+    priority: &HashMap<Vec<u8>, Vec<String>>,
+    config: &Config,
+) -> Result<Series, DecoderError> {
+    let log_alias = &config.log_decoder.log_schema.log_alias;
+    let topic0 = df.column(&log_alias.topic0)?.binary()?;
+    let topic1 = df.column(&log_alias.topic1)?.binary()?;
+    let topic2 = df.column(&log_alias.topic2)?.binary()?;
+    let topic3 = df.column(&log_alias.topic3)?.binary()?;
+    let data = df.column(&log_alias.data)?.binary()?;
+
+    let signatures: StringChunked = topic0
+        .into_iter()
+        .zip(topic1)
+        .zip(topic2)
+        .zip(topic3)
+        .zip(data)
+        .map(|((((topic0, topic1), topic2), topic3), data)| {
+            let candidates = priority.get(topic0?)?;
+            // Null topics are handled by decode_with_candidates, following log_decoder.missing_topics_mode
+            let topics: Vec<&[u8]> = [topic0, topic1, topic2, topic3].into_iter().flatten().collect();
+            log_decoder::decode_with_candidates(&topics, data.unwrap_or(&[]), candidates.clone())
+                .ok()
+                .map(|(signature, _)| signature)
+        })
+        .collect();
+    Ok(signatures.with_name("priority_signature").into_series())
+}
+
+/// Picks the candidate signature of each trace of a selector in the priority map, see `decode_df_with_priority`
+///
+/// # Returns
+/// A `priority_signature` String Series with the first candidate each trace decodes with (see
+/// `trace_decoder::decode_with_candidates`), null for traces of other selectors or that don't decode with any candidate.
+fn priority_trace_signatures(
+    df: &DataFrame,
+    priority: &HashMap<Vec<u8>, Vec<String>>,
+    config: &Config,
+) -> Result<Series, DecoderError> {
+    let trace_alias = &config.trace_decoder.trace_schema.trace_alias;
+    let selector = df.column(&trace_alias.selector)?.binary()?;
+    let input = df.column(&trace_alias.action_input)?.binary()?;
+    let output = df.column(&trace_alias.result_output)?.binary()?;
+
+    let signatures: StringChunked = selector
+        .into_iter()
+        .zip(input)
+        .zip(output)
+        .map(|((selector, input), output)| {
+            let candidates = priority.get(selector?)?;
+            trace_decoder::decode_with_candidates(input.unwrap_or(&[]), output.unwrap_or(&[]), candidates).ok()
+        })
+        .collect();
+    Ok(signatures.with_name("priority_signature").into_series())
+}
+
+/// Decodes a logs/traces DataFrame using a pre-loaded ABI DataFrame, overriding the matched signature of some hashes
+///
+/// # Arguments
+/// * `df` - DataFrame containing raw blockchain data
+/// * `abi_df` - DataFrame containing ABI definitions
+/// * `overrides` - Map of topic0s (logs) or 4 bytes selectors (traces) to the full signature to decode them with
+/// * `decoder_type` - Type of data to decode
+///
+/// # Returns
+/// * `Ok(DataFrame)` containing decoded data
+/// * `Err(DecoderError)` if an override signature can't be parsed, or matching or decoding fails
+///
+/// # Notes
+/// The rows are matched with the algorithm from the config, like in `decode_df_with_abi_df`. Then the rows whose hash is
+/// in the map get its signature, whatever the matcher chose (or if it found none), to patch wrong or missing ABI DB entries
+/// without editing the DB. The override signatures don't need to be in the ABI DataFrame, and aren't subject to the
+/// signature allowlist and denylist. For overridden rows, the name is taken from the signature and the ABI DB item columns
+/// (the decoder.abi_id_column, anonymous and state_mutability) are null.
+pub async fn decode_df_with_overrides(
+    df: DataFrame,
+    abi_df: DataFrame,
+    overrides: HashMap<Vec<u8>, String>,
+    decoder_type: DecoderType,
+) -> Result<DataFrame, DecoderError> {
+    let config = get_config();
+    let hash_alias = match decoder_type {
+        DecoderType::Log => config.log_decoder.log_schema.log_alias.topic0.clone(),
+        DecoderType::Trace => config.trace_decoder.trace_schema.trace_alias.selector.clone(),
+    };
+
+    let mut hashes = Vec::with_capacity(overrides.len());
+    let mut signatures = Vec::with_capacity(overrides.len());
+    let mut names = Vec::with_capacity(overrides.len());
+    for (hash, signature) in overrides {
+        let name = match decoder_type {
+            DecoderType::Log => Event::parse(&signature)
+                .or_else(|_| Event::parse(&utils::strip_tuple_component_names(&signature)))
+                .map(|event| event.name)
+                .map_err(|e| e.to_string()),
+            DecoderType::Trace => Function::parse(&signature).map(|function| function.name).map_err(|e| e.to_string()),
+        }
+        .map_err(|e| DecoderError::DecodingError(format!("Invalid override signature '{}': {}", signature, e)))?;
+        hashes.push(hash);
+        signatures.push(signature);
+        names.push(name);
+    }
+    let overrides_df = DataFrame::new(vec![
+        Series::new("override_hash", hashes),
+        Series::new("override_signature", signatures),
+        Series::new("override_name", names),
+    ])?;
+
+    let matched_df = match_df(df, abi_df, &decoder_type, &config)?;
+    let is_overridden = col("override_signature").is_not_null();
+    let mut override_exprs = vec![
+        when(is_overridden.clone()).then(col("override_signature")).otherwise(col("full_signature")).alias("full_signature"),
+    ];
+    let matched_schema = matched_df.schema();
+    if matched_schema.contains("name") {
+        override_exprs.push(when(is_overridden.clone()).then(col("override_name")).otherwise(col("name")).alias("name"));
+    }
+    for column in [config.decoder.abi_id_column.as_str(), "anonymous", "state_mutability"] {
+        if let Some(dtype) = matched_schema.get(column) {
+            override_exprs.push(
+                when(is_overridden.clone()).then(lit(Null {}).cast(dtype.clone())).otherwise(col(column)).alias(column),
+            );
+        }
+    }
+    let matched_df = matched_df
+        .lazy()
+        .join(
+            overrides_df.lazy(),
+            [col(&hash_alias)],
+            [col("override_hash")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .with_columns(override_exprs)
+        .select([col("*").exclude(["override_signature", "override_name"])])
+        .collect()?;
+
+    let semaphore = Arc::new(Semaphore::new(config.decoder.max_chunk_threads_per_file));
+    decode(matched_df, decoder_type, &config, semaphore).await
+}
+
+/// Builds a LazyFrame plan decoding logs/traces with a pre-loaded ABI DataFrame, i.e: for the Polars streaming engine
+///
+/// # Arguments
+/// * `lf` - LazyFrame containing raw blockchain data
+/// * `abi_df` - DataFrame containing ABI definitions
+/// * `decoder_type` - Type of data to decode
+///
+/// # Returns
+/// * `Ok(LazyFrame)` with the matching and decoding steps added to the plan
+/// * `Err(DecoderError)` if the ABI conversion or the input schema resolution fails
+///
+/// # Notes
+/// Nothing is decoded until the plan is collected, use `.with_streaming(true)` to run it with the streaming engine.
+/// The matching algorithm is taken from the config, like in `decode_df_with_abi_df`, and the output columns are the same.
+/// Streaming limitations:
+/// - The decoding UDFs are element-wise functions, so they run on each streamed batch, but the ABI DataFrame is
+///   fully materialized as the build side of the matching joins.
+/// - log_decoder.adjust_indexed_args and the single-topic0 fast path aren't applied.
+/// - decoder.decoded_chunk_size and decoder.max_chunk_threads_per_file don't apply, the batches and threads are
+///   managed by Polars.
+/// - Rows keep the input order, unlike the eager path, which puts the rows matched with the address first.
+/// - Polars falls back to the in-memory engine for plan nodes the streaming engine doesn't support.
+///
+/// Raw hex string columns aren't converted to binary, see `utils::hex_string_columns_to_binary_lazy`.
+///
+/// # Example
+/// ```no_run
+/// use glaciers::decoder::{decode_lazy, DecoderType};
+/// use polars::prelude::*;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let logs_lf = LazyFrame::scan_parquet("path/to/logs.parquet", Default::default())?;
+///     let abi_df = glaciers::utils::read_df_file("path/to/abi_db.parquet".as_ref())?;
+///     let decoded_df = decode_lazy(logs_lf, abi_df, DecoderType::Log)?
+///         .with_streaming(true)
+///         .collect()?;
+///     Ok(())
+/// }
+/// ```
+pub fn decode_lazy(
+    lf: LazyFrame,
+    abi_df: DataFrame,
+    decoder_type: DecoderType,
+) -> Result<LazyFrame, DecoderError> {
+    let config = get_config();
+    let abi_df = prepare_abi_df(abi_df, &config)?;
+    let lf = match decoder_type {
+        DecoderType::Log => log_decoder::fill_missing_topic_columns_lazy(log_decoder::expand_topics_list_lazy(lf)),
+        DecoderType::Trace => lf,
+    };
+    let matched_lf = matcher::match_lazy(lf, abi_df, &decoder_type, &config.decoder.algorithm)?;
+
+    let decoded_lf = match decoder_type {
+        DecoderType::Log => log_decoder::decode_logs_lazy(matched_lf),
+        DecoderType::Trace => {
+            let matched_lf = if config.trace_decoder.parse_hex_numeric_columns {
+                let parse_exprs = trace_decoder::parse_hex_numeric_exprs(&*matched_lf.schema()?);
+                matched_lf.with_columns(parse_exprs)
+            } else {
+                matched_lf
+            };
+            let matched_lf = match trace_decoder::call_depth_expr(&*matched_lf.schema()?)? {
+                Some(expr) => matched_lf.with_columns([expr]),
+                None => matched_lf,
+            };
+            trace_decoder::decode_traces_lazy(matched_lf)
+        },
+    };
+
+    Ok(if config.decoder.output_hex_string_encoding {
+        utils::binary_columns_to_hex_string_lazy(decoded_lf)?
+    } else {
+        decoded_lf
+    })
+}
+
+/// Decodes logs/traces arrow-rs record batches with a pre-loaded ABI DataFrame, i.e: for zero-copy interop with Arrow Flight services
+///
+/// # Arguments
+/// * `batches` - Record batches containing raw blockchain data, all with the same schema
+/// * `abi_df` - DataFrame containing ABI definitions
+/// * `decoder_type` - Type of data to decode
+///
+/// # Returns
+/// * `Ok(Vec<RecordBatch>)` with the decoded data, or no batches if there is no input batch
+/// * `Err(DecoderError)` if the batches have different schemas or decoding fails
+///
+/// # Notes
+/// Requires the `arrow-interop` feature. The batches are moved to Polars through the Arrow C data interface, and become the
+/// chunks of the raw DataFrame columns without copying their buffers, except for string and binary arrays, which Polars
+/// converts to its view types. The decoded batches are moved back the same way, with the standard Arrow types (i.e: large
+/// strings instead of string views). Like in `decode_df_with_abi_df`, raw hex string columns aren't converted to binary.
+#[cfg(feature = "arrow-interop")]
+pub async fn decode_record_batches(
+    batches: Vec<arrow::record_batch::RecordBatch>,
+    abi_df: DataFrame,
+    decoder_type: DecoderType,
+) -> Result<Vec<arrow::record_batch::RecordBatch>, DecoderError> {
+    let Some(schema) = batches.first().map(|batch| batch.schema()) else {
+        return Ok(Vec::new());
+    };
+    let mut fields = Vec::new();
+    let mut columns: Vec<Vec<ArrayRef>> = vec![Vec::with_capacity(batches.len()); schema.fields().len()];
+    for batch in batches {
+        if batch.schema() != schema {
+            return Err(DecoderError::DecodingError("Record batches have different schemas".to_string()));
+        }
+        let struct_array = record_batch_to_polars(batch)?;
+        if fields.is_empty() {
+            fields = struct_array.fields().to_vec();
+        }
+        for (chunks, array) in columns.iter_mut().zip(struct_array.values()) {
+            chunks.push(array.clone());
+        }
+    }
+    let series = fields
+        .iter()
+        .zip(columns)
+        .map(|(field, chunks)| Series::try_from((field, chunks)))
+        .collect::<PolarsResult<Vec<Series>>>()?;
+
+    let mut decoded_df = decode_df_with_abi_df(DataFrame::new(series)?, abi_df, decoder_type).await?;
+    decoded_df.align_chunks();
+    let decoded_type = ArrowDataType::Struct(decoded_df.schema().to_arrow(false).fields);
+    decoded_df
+        .iter_chunks(false)
+        .map(|chunk| polars_chunk_to_record_batch(chunk, decoded_type.clone()))
+        .collect()
+}
+
+/// Moves an arrow-rs record batch to a Polars struct array through the Arrow C data interface, without copying its buffers
+#[cfg(feature = "arrow-interop")]
+fn record_batch_to_polars(batch: arrow::record_batch::RecordBatch) -> Result<StructArray, DecoderError> {
+    let (array, schema) = arrow::ffi::to_ffi(&arrow::array::ArrayData::from(arrow::array::StructArray::from(batch)))
+        .map_err(|e| DecoderError::DecodingError(format!("Error exporting the record batch: {}", e)))?;
+    // SAFETY: the arrow-rs and Polars structs are both the `#[repr(C)]` structs of the Arrow C data interface, and
+    // their ownership is moved, so the imported array releases the buffers once
+    let array = unsafe { std::mem::transmute::<arrow::ffi::FFI_ArrowArray, ffi::ArrowArray>(array) };
+    let schema = unsafe { std::mem::transmute::<arrow::ffi::FFI_ArrowSchema, ffi::ArrowSchema>(schema) };
+    let field = unsafe { ffi::import_field_from_c(&schema) }?;
+    let array = unsafe { ffi::import_array_from_c(array, field.data_type) }?;
+    array
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .cloned()
+        .ok_or_else(|| DecoderError::DecodingError("The imported record batch isn't a struct array".to_string()))
+}
+
+/// Moves a chunk of a Polars DataFrame to an arrow-rs record batch through the Arrow C data interface, without copying its buffers
+#[cfg(feature = "arrow-interop")]
+fn polars_chunk_to_record_batch(chunk: Chunk<ArrayRef>, data_type: ArrowDataType) -> Result<arrow::record_batch::RecordBatch, DecoderError> {
+    let struct_array = StructArray::try_new(data_type.clone(), chunk.into_arrays(), None)?;
+    let array = ffi::export_array_to_c(Box::new(struct_array));
+    let schema = ffi::export_field_to_c(&ArrowField::new("", data_type, false));
+    // SAFETY: see `record_batch_to_polars`
+    let array = unsafe { std::mem::transmute::<ffi::ArrowArray, arrow::ffi::FFI_ArrowArray>(array) };
+    let schema = unsafe { std::mem::transmute::<ffi::ArrowSchema, arrow::ffi::FFI_ArrowSchema>(schema) };
+    let data = unsafe { arrow::ffi::from_ffi(array, &schema) }
+        .map_err(|e| DecoderError::DecodingError(format!("Error importing the decoded data: {}", e)))?;
+    Ok(arrow::record_batch::RecordBatch::from(arrow::array::StructArray::from(data)))
+}
+
+/// Decodes the logs and traces of a transaction bundle in a single call, i.e: for a tx-level investigation
+///
+/// # Arguments
+/// * `logs_df` - DataFrame containing raw logs
+/// * `traces_df` - DataFrame containing raw traces
+/// * `events_abi_db_path` - Path to the events ABI database file, or a list of paths to multiple ABI database files
+/// * `functions_abi_db_path` - Path to the functions ABI database file, or a list of paths to multiple ABI database files
+///
+/// # Returns
+/// * `Ok(DecodedTxBundle)` with the decoded logs and traces
+/// * `Err(DecoderError)` if reading the ABI DBs or decoding fails
+///
+/// # Notes
+/// The config is read once and shared by both decodings, so a concurrent `set_config` can't make logs and traces
+/// be matched with different algorithms or chunk sizes. Logs and traces are decoded concurrently, sharing a single
+/// budget of `max_chunk_threads_per_file` threads.
+pub async fn decode_tx_bundle(
+    logs_df: DataFrame,
+    traces_df: DataFrame,
+    events_abi_db_path: impl Into<AbiDbPaths>,
+    functions_abi_db_path: impl Into<AbiDbPaths>,
+) -> Result<DecodedTxBundle, DecoderError> {
+    let config = get_config();
+    let events_abi_df = utils::read_abi_db_files(&events_abi_db_path.into().0)?;
+    let functions_abi_df = utils::read_abi_db_files(&functions_abi_db_path.into().0)?;
+
+    let matched_logs_df = match_df(logs_df, events_abi_df, &DecoderType::Log, &config)?;
+    let matched_traces_df = match_df(traces_df, functions_abi_df, &DecoderType::Trace, &config)?;
+
+    let semaphore = Arc::new(Semaphore::new(config.decoder.max_chunk_threads_per_file));
+    let (logs, traces) = tokio::try_join!(
+        decode(matched_logs_df, DecoderType::Log, &config, semaphore.clone()),
+        decode(matched_traces_df, DecoderType::Trace, &config, semaphore),
+    )?;
+
+    Ok(DecodedTxBundle { logs, traces })
+}
+
+/// Matches a logs/traces DataFrame with the ABI items, using the matching algorithm from the config
+///
+/// # Arguments
+/// * `df` - DataFrame containing raw blockchain data
+/// * `abi_df` - DataFrame containing ABI definitions
+/// * `decoder_type` - Type of data to match
+/// * `config` - Config snapshot with the matching algorithm
+///
+/// # Returns
+/// * `Ok(DataFrame)` with the raw data joined with the matched ABI items
+/// * `Err(DecoderError)` if matching fails
+fn match_df(
+    df: DataFrame,
+    abi_df: DataFrame,
+    decoder_type: &DecoderType,
+    config: &Config,
+) -> Result<DataFrame, DecoderError> {
+    let abi_df = prepare_abi_df(abi_df, config)?;
+    let df = expand_topics_list(df, decoder_type)?;
+
+    let matched_df = match decoder_type {
+        DecoderType::Log if config.log_decoder.adjust_indexed_args => {
+            matcher::match_logs_with_adjusted_indexed_args(df, abi_df, &config.decoder.algorithm)?
+        },
+        DecoderType::Log => match config.decoder.algorithm {
+            DecoderAlgorithm::HashAddress => matcher::match_logs_by_topic0_address(df, abi_df)?,
+            // Fast path for single-event extracts, the logs are joined directly with the single signature of their topic0
+            DecoderAlgorithm::Hash => match matcher::match_logs_by_single_topic0(&df, &abi_df)? {
+                Some(matched_df) => matched_df,
+                None => matcher::match_logs_by_topic0(df, abi_df)?,
+            },
+        },
+        DecoderType::Trace => match config.decoder.algorithm {
+            DecoderAlgorithm::HashAddress => matcher::match_traces_by_4bytes_address(df, abi_df)?,
+            DecoderAlgorithm::Hash => matcher::match_traces_by_4bytes(df, abi_df)?
+        }
+    };
+    Ok(matched_df)
+}
+
+/// Expands the configured topics list column of a raw logs DataFrame, see `log_decoder::expand_topics_list_lazy`, and
+/// adds its missing optional topic columns, see `log_decoder::fill_missing_topic_columns_lazy`.
+/// Traces DataFrames are returned unchanged.
+fn expand_topics_list(df: DataFrame, decoder_type: &DecoderType) -> Result<DataFrame, DecoderError> {
+    match decoder_type {
+        DecoderType::Log => Ok(log_decoder::fill_missing_topic_columns_lazy(log_decoder::expand_topics_list_lazy(df.lazy())).collect()?),
+        DecoderType::Trace => Ok(df),
+    }
+}
+
+/// Prepares an ABI DataFrame for matching, converting the hash and address columns to binary if they aren't already,
+/// keeping only the matcher columns and the decoder.abi_passthrough_columns, and applying the decoder.signature_allowlist
+/// and decoder.signature_denylist.
 ///
 /// # Arguments
-/// * `folder_path` - Path to folder containing files to decode
-/// * `abi_db_path` - Path to ABI database file
-/// * `decoder_type` - Type of data to decode (Log or Trace)
+/// * `abi_df` - DataFrame containing ABI definitions
+/// * `config` - Config snapshot with the signature lists, the decoder.abi_id_column and the decoder.abi_passthrough_columns
 ///
 /// # Returns
-/// * `Ok(())` if all files were processed successfully. Succeful return is empty, since multiple files are decoded in parallel.
-/// * `Err(DecoderError)` if any file fails to process
-/// 
-/// 
-/// # Notes
-/// This function gets the max_concurrent_files_decoding from the config and uses it
-/// to limit the number of concurrent files that can be decoded at the same time.
+/// * `Ok(DataFrame)` with the ABI items to match
+/// * `Err(DecoderError)` if the conversion fails or a signature list has an invalid hex entry
 ///
-/// # Example
-/// ```no_run
-/// use glaciers::decoder::{decode_folder, DecoderType};
-/// 
-/// #[tokio::main]
-/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-///     decode_folder(
-///         "path/to/folder".to_string(),
-///         "path/to/abi_db.parquet".to_string(),
-///         DecoderType::Log
-///     ).await?;
-///     Ok(())
-/// }
-/// ```
-pub async fn decode_folder(
-    folder_path: String,
-    abi_db_path: String,
-    decoder_type: DecoderType,
-) -> Result<(), DecoderError> {
+/// # Notes
+/// The ABI id column is renamed to decoder.abi_id_column, so the matched ABI item id is carried to the decoded output
+/// under this name. ABI DataFrames without an id column (i.e: built by hand) get a null one.
+fn prepare_abi_df(abi_df: DataFrame, config: &Config) -> Result<DataFrame, DecoderError> {
+    let abi_df = utils::abi_df_hex_string_columns_to_binary(abi_df)?;
+    let abi_df = with_abi_id_column(abi_df, &config.decoder.abi_id_column, &config.abi_reader.hash_column_name)?;
+    let abi_df = select_abi_columns(abi_df, config)?;
+    Ok(matcher::filter_abi_df_by_signature_lists(
+        abi_df,
+        &config.decoder.signature_allowlist,
+        &config.decoder.signature_denylist,
+    )?)
+}
 
-    // Collect files' paths from folder_path
-    let files: Vec<PathBuf> = fs::read_dir(folder_path)?
-        .filter_map(|entry| entry.ok())
-        .map(|entry| entry.path())
+/// Keeps the ABI DataFrame columns used by the matchers (the hash column, `ABI_MATCHER_COLUMNS` and the
+/// decoder.abi_id_column) and the decoder.abi_passthrough_columns, so the same ABI columns are carried to the decoded
+/// output whatever the matching path. Passthrough columns missing from the ABI DataFrame are skipped with a warning.
+fn select_abi_columns(abi_df: DataFrame, config: &Config) -> Result<DataFrame, DecoderError> {
+    let schema = abi_df.schema();
+    let missing_columns: Vec<&str> = config
+        .decoder
+        .abi_passthrough_columns
+        .iter()
+        .map(|name| name.as_str())
+        .filter(|name| !schema.contains(name))
         .collect();
+    if !missing_columns.is_empty() {
+        println!(
+            "[{}] decoder.abi_passthrough_columns not found in the ABI DataFrame, skipping them: {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            missing_columns.join(", ")
+        );
+    }
 
-    // Create a semaphore with MAX_CONCURRENT_FILES_DECODING permits
-    let semaphore = Arc::new(Semaphore::new(get_config().decoder.max_concurrent_files_decoding));
-    // Create a vector to hold our join handles
-    let mut handles = Vec::new();
-
-    // Spawn a task for each file
-    for file_path in files {
-        // skip PathBuf belonging to folders
-        if file_path.is_dir() {
-            continue
+    let mut columns: Vec<&str> = Vec::new();
+    let candidates = std::iter::once(config.abi_reader.hash_column_name.as_str())
+        .chain(ABI_MATCHER_COLUMNS)
+        .chain(std::iter::once(config.decoder.abi_id_column.as_str()))
+        .chain(config.decoder.abi_passthrough_columns.iter().map(|name| name.as_str()));
+    for name in candidates {
+        if schema.contains(name) && !columns.contains(&name) {
+            columns.push(name);
         }
-        // Clone the DataFrame and semafore for each task
-        let abi_db_path = abi_db_path.clone();
-        let semaphore = semaphore.clone();
-        let decoder_type_clone = decoder_type.clone();
-        // Spawn a tokio task for each file
-        let handle = task::spawn(async move {
-            // Acquire a permit before processing
-            let _permit = semaphore.acquire().await.unwrap();
-            decode_file(file_path, abi_db_path, decoder_type_clone).await
-        });
-
-        handles.push(handle);
     }
+    Ok(abi_df.select(columns)?)
+}
 
-    // Wait for all tasks to complete and collect errors
-    for handle in handles {
-        // Unwrap the outer Result from task::spawn
-        handle.await??;
+/// Renames the id column of an ABI DataFrame to the given name, or adds a null one if the ABI DataFrame has no id column.
+/// ABI DataFrames already prepared, with the given column, are returned unchanged.
+/// Returns a `DecoderError::ConfigError` if the name is another ABI column used by the matchers (i.e: "name"), or
+/// the ABI DataFrame has both an id column and a column with the given name.
+fn with_abi_id_column(mut abi_df: DataFrame, id_column: &str, hash_column: &str) -> Result<DataFrame, DecoderError> {
+    let column_names = abi_df.get_column_names();
+    if id_column != "id" {
+        let is_matcher_column = ABI_MATCHER_COLUMNS.contains(&id_column) || id_column == hash_column;
+        if is_matcher_column || (column_names.contains(&id_column) && column_names.contains(&"id")) {
+            return Err(DecoderError::ConfigError(format!(
+                "decoder.abi_id_column = '{}' collides with an existing column of the ABI DataFrame",
+                id_column
+            )));
+        }
     }
-
-    println!(
-        "[{}] All files processed",
-        Local::now().format("%Y-%m-%d %H:%M:%S")
-    );
-    Ok(())
+    if column_names.contains(&id_column) {
+        return Ok(abi_df);
+    }
+    if column_names.contains(&"id") {
+        abi_df.rename("id", id_column)?;
+        return Ok(abi_df);
+    }
+    Ok(abi_df.lazy().with_column(lit(Null {}).cast(DataType::String).alias(id_column)).collect()?)
 }
 
-/// Decodes a single file using the specified ABI database
-/// Decoded file is saved in a "decoded" folder, in the parent folder of the raw data.
-/// The file name is the same as the raw file name, but with the "logs" or "traces" replaced with "decoded_logs" or "decoded_traces".
-/// 
+/// Decodes a logs/traces DataFrame using a pre-loaded ABI DataFrame, adding context metadata columns to the result
+///
 /// # Arguments
-/// * `file_path` - Path to file to decode
-/// * `abi_db_path` - Path to ABI database file
-/// * `decoder_type` - Type of data to decode (Log or Trace)
+/// * `df` - DataFrame containing raw blockchain data
+/// * `abi_df` - DataFrame containing ABI definitions
+/// * `decoder_type` - Type of data to decode
+/// * `context` - Map of column names to literal values to add to every decoded row (i.e: {"chain_id": "1"})
 ///
 /// # Returns
-/// * `Ok(DataFrame)` containing decoded data
+/// * `Ok(DataFrame)` containing decoded data and one extra String column per context item
 /// * `Err(DecoderError)` if decoding fails
 ///
 /// # Notes
-/// The output format (binary/hex) of some columns is determined by configuration.
-pub async fn decode_file(
-    file_path: PathBuf,
-    abi_db_path: String,
+/// Context columns are added in alphabetical order of their names, and overwrite existing columns with the same name.
+pub async fn decode_df_with_context(
+    df: DataFrame,
+    abi_df: DataFrame,
     decoder_type: DecoderType,
+    context: HashMap<String, String>,
 ) -> Result<DataFrame, DecoderError> {
-    let file_path_str = file_path.to_string_lossy().into_owned();
-    let file_name = file_path
-        .file_name()
-        .unwrap()
-        .to_string_lossy()
-        .into_owned();
-    let mut file_folder_path = file_path
-        .parent()
-        .unwrap()
-        .parent()
-        .unwrap()
-        .to_string_lossy()
-        .into_owned();
-    
-    if !file_folder_path.is_empty() {
-        file_folder_path = file_folder_path + "/";
-    }
-    let save_path = match decoder_type {
-        DecoderType::Log => format!(
-            "{}decoded/{}",
-            file_folder_path,
-            if file_name.contains("logs") {
-                file_name.replace("logs", "decoded_logs")
-            } else {
-                format!("decoded_logs_{}", file_name)
-            }
-        ),
-        DecoderType::Trace => format!(
-            "{}decoded/{}",
-            file_folder_path,
-            if file_name.contains("traces") {
-                file_name.replace("traces", "decoded_traces")
-            } else {
-                format!("decoded_traces_{}", file_name)
-            }
-        )
+    let decoded_df = decode_df_with_abi_df(df, abi_df, decoder_type).await?;
+
+    let mut context: Vec<(String, String)> = context.into_iter().collect();
+    context.sort();
+    let context_exprs: Vec<Expr> = context
+        .iter()
+        .map(|(key, value)| lit(value.as_str()).alias(key))
+        .collect();
+
+    Ok(decoded_df.lazy().with_columns(context_exprs).collect()?)
+}
+
+/// Lists the distinct contract addresses of a raw logs/traces file, with their number of logs/traces, without decoding
+///
+/// # Arguments
+/// * `path` - Path to the raw logs/traces file (parquet or csv), or to a folder of files
+/// * `decoder_type` - Type of the raw data, used to select the address column
+///
+/// # Returns
+/// * `Ok(DataFrame)` with an `address` column (0x prefixed hex string) and a `count` column, sorted by descending count
+/// * `Err(DecoderError)` if the file can't be read or the address column is missing
+///
+/// # Notes
+/// The address column is `log_decoder.log_schema.log_alias.address` for logs and `trace_decoder.trace_schema.trace_alias.action_to`
+/// for traces, and it can be binary or a hex string. Only this column is read from the file, and rows without an address
+/// (i.e: contract creation traces) are skipped. Useful to choose the ABIs to fetch before decoding.
+///
+/// # Example
+/// ```no_run
+/// use std::path::PathBuf;
+/// use glaciers::decoder::{distinct_addresses, DecoderType};
+///
+/// let addresses_df = distinct_addresses(PathBuf::from("data/logs/logs.parquet"), DecoderType::Log).unwrap();
+/// println!("{}", addresses_df);
+/// ```
+pub fn distinct_addresses(path: PathBuf, decoder_type: DecoderType) -> Result<DataFrame, DecoderError> {
+    let address_alias = match decoder_type {
+        DecoderType::Log => get_config().log_decoder.log_schema.log_alias.address,
+        DecoderType::Trace => get_config().trace_decoder.trace_schema.trace_alias.action_to,
     };
+    let addresses_df = utils::scan_df_path(&path)?
+        .select([col(&address_alias).alias("address")])
+        .filter(col("address").is_not_null())
+        .group_by([col("address")])
+        .agg([len().alias("count")])
+        .collect()?;
+    // only the distinct addresses are converted to hex, not every row
+    Ok(utils::binary_columns_to_hex_string(addresses_df)?
+        .sort(["count", "address"], vec![true, false], false)?)
+}
 
-    println!(
-        "[{}] Starting decoding file: {}",
-        Local::now().format("%Y-%m-%d %H:%M:%S"),
-        file_path_str
-    );
+/// Labels a DataFrame of 4 bytes function selectors with the functions ABI DB, without decoding any calldata
+///
+/// # Arguments
+/// * `df` - DataFrame with a selector column, named as `trace_decoder.trace_schema.trace_alias.selector` in the config
+/// * `abi_db_path` - Path to the functions ABI database file, or a list of paths to multiple ABI database files
+///
+/// # Returns
+/// * `Ok(DataFrame)` with the input columns plus `name`, `full_signature` and `candidate_count`
+/// * `Err(DecoderError)` if the ABI DB can't be read or the selector column is missing
+///
+/// # Notes
+/// The selector column can be binary or a hex string. When a selector is ambiguous, the most frequent
+/// signature in the ABI DB is used, and `candidate_count` has the number of distinct signatures for that selector.
+/// Unknown selectors have null labels and a `candidate_count` of 0.
+pub fn label_selectors(
+    df: DataFrame,
+    abi_db_path: impl Into<AbiDbPaths>,
+) -> Result<DataFrame, DecoderError> {
+    let abi_df = utils::read_abi_db_files(&abi_db_path.into().0)?;
+    let selector_alias = get_config().trace_decoder.trace_schema.trace_alias.selector;
+    let hash_column = get_config().abi_reader.hash_column_name;
 
-    let file_df = utils::read_df_file(&file_path)?;
-    let file_df = utils::hex_string_columns_to_binary(file_df, &decoder_type)?;
-    let mut decoded_df = decode_df(file_df, abi_db_path, decoder_type).await?;
+    // Join key is always binary, so hex string selectors are decoded
+    let selector_key = match df.column(&selector_alias)?.dtype() {
+        DataType::String => col(&selector_alias).str().strip_prefix(lit("0x")).str().hex_decode(true),
+        _ => col(&selector_alias),
+    };
 
-    println!(
-        "[{}] Finished decoding file: {}",
-        Local::now().format("%Y-%m-%d %H:%M:%S"),
-        file_name
-    );
+    // create a labels df with the most frequent signature and the number of distinct signatures for each hash.
+    // Signatures are compared without param names, so "transfer(address to, uint256 amount)" and
+    // "transfer(address recipient, uint256 value)" count as a single candidate.
+    let labels_df = abi_df
+        .lazy()
+        .with_column(col("full_signature")
+            .map(canonical_signature_udf, GetOutput::from_type(DataType::String))
+            .alias("canonical_signature"))
+        .group_by([hash_column.as_str(), "canonical_signature"])
+        .agg([col("full_signature").first(), col("name").first(), len().alias("signature_count")])
+        .sort("signature_count", SortOptions {
+            descending: true,
+            nulls_last: true,
+            ..Default::default()}
+        )
+        .group_by([hash_column.as_str()])
+        .agg([
+            col("full_signature").first(),
+            col("name").first(),
+            len().cast(DataType::UInt32).alias("candidate_count"),
+        ]);
+
+    let labeled_df = df
+        .lazy()
+        .with_column(selector_key.alias("selector_key"))
+        .join(
+            labels_df,
+            [col("selector_key")],
+            [col(hash_column.as_str())],
+            JoinArgs::new(JoinType::Left),
+        )
+        .with_column(col("candidate_count").fill_null(lit(0_u32)))
+        .select([col("*").exclude(["selector_key"])])
+        .collect()?;
 
-    let save_path: &Path = Path::new(&save_path);
+    Ok(labeled_df)
+}
 
-    if let Some(parent) = save_path.parent() {
-        // create folder if it doesn't exist
-        fs::create_dir_all(parent.to_string_lossy().into_owned())?;
+/// Checks that a decoded DataFrame has the expected number of rows for a signature
+///
+/// # Arguments
+/// * `df` - Decoded logs/traces DataFrame, with a `full_signature` column
+/// * `signature` - Full signature to count, i.e: "event Transfer(address indexed from, address indexed to, uint256 value)"
+/// * `expected` - Reference number of rows for the signature, i.e: from an indexer
+///
+/// # Returns
+/// * `Ok(())` if the number of rows with the signature matches `expected`
+/// * `Err(DecoderError::EventCountMismatch)` if it doesn't
+/// * `Err(DecoderError)` if the `full_signature` column is missing
+///
+/// # Notes
+/// The signature is compared as an exact string with the `full_signature` column, so it must have the same param names and indexed keywords.
+/// Undecoded rows (null `full_signature`) are never counted.
+pub fn assert_event_count(df: &DataFrame, signature: &str, expected: usize) -> Result<(), DecoderError> {
+    let actual = df
+        .column("full_signature")?
+        .str()?
+        .into_iter()
+        .filter(|full_signature| *full_signature == Some(signature))
+        .count();
+
+    if actual != expected {
+        return Err(DecoderError::EventCountMismatch { signature: signature.to_string(), expected, actual });
     }
+    Ok(())
+}
 
-    let save_path= save_path.with_extension(get_config().decoder.output_file_format);
-    utils::write_df_file(&mut decoded_df, &save_path)?;
-    
-    println!(
-        "[{}] Saving decoded to: {:?}",
-        Local::now().format("%Y-%m-%d %H:%M:%S"),
-        save_path
-    );
+/// Summarizes a decoded logs/traces DataFrame by contract and event/function name
+///
+/// # Arguments
+/// * `df` - Decoded logs/traces DataFrame
+///
+/// # Returns
+/// * `Ok(DataFrame)` with one row per address, name and match status, see `summarize_decoded_lazy`
+/// * `Err(DecoderError)` if the address column is missing
+pub fn summarize_decoded(df: DataFrame) -> Result<DataFrame, DecoderError> {
+    Ok(summarize_decoded_lazy(df.lazy())?.collect()?)
+}
 
-    Ok(decoded_df)
+/// Summarizes a decoded logs/traces LazyFrame by contract and event/function name, i.e: to summarize a decoded folder without loading it
+///
+/// # Arguments
+/// * `lf` - Decoded logs/traces LazyFrame
+///
+/// # Returns
+/// * `Ok(LazyFrame)` with the columns:
+///   - address: The contract address (log address or trace action_to)
+///   - name: The event/function name, null for unmatched rows
+///   - match_status: "decoded", "decoding_error" (matched a signature but failed to decode) or "unmatched"
+///   - count: The number of rows
+///   - first_block/last_block: The min and max block_number, only when the input has a block_number column
+/// * `Err(DecoderError)` if the address column is missing
+///
+/// # Notes
+/// The address column is `log_decoder.log_schema.log_alias.address` in the config for logs and
+/// `trace_decoder.trace_schema.trace_alias.action_to` for traces, whichever is in the input.
+/// Rows are sorted by address and by descending count.
+pub fn summarize_decoded_lazy(lf: LazyFrame) -> Result<LazyFrame, DecoderError> {
+    let schema = lf.schema()?;
+    let config = get_config();
+    let address_alias = [
+        config.log_decoder.log_schema.log_alias.address,
+        config.trace_decoder.trace_schema.trace_alias.action_to,
+    ]
+    .into_iter()
+    .find(|alias| schema.contains(alias))
+    .ok_or_else(|| DecoderError::DecodingError("Decoded DataFrame has no address (logs) or action_to (traces) column".to_string()))?;
+
+    let match_status = if schema.contains("decoding_error") {
+        when(col("full_signature").is_null())
+            .then(lit("unmatched"))
+            .when(col("decoding_error").is_not_null())
+            .then(lit("decoding_error"))
+            .otherwise(lit("decoded"))
+    } else {
+        when(col("full_signature").is_null())
+            .then(lit("unmatched"))
+            .otherwise(lit("decoded"))
+    };
+
+    let mut aggs = vec![len().cast(DataType::UInt32).alias("count")];
+    if schema.contains("block_number") {
+        aggs.push(col("block_number").min().alias("first_block"));
+        aggs.push(col("block_number").max().alias("last_block"));
+    }
+
+    Ok(lf
+        .with_columns([
+            col(&address_alias).alias("address"),
+            match_status.alias("match_status"),
+        ])
+        .group_by([col("address"), col("name"), col("match_status")])
+        .agg(aggs)
+        .sort_by_exprs(
+            [col("address"), col("count")],
+            [false, true],
+            true,
+            false,
+        ))
+}
+
+/// UDF (User Defined Function) to convert function full signatures into canonical signatures, i.e: "transfer(address,uint256)"
+///
+/// Signatures that can't be parsed are kept as they are.
+fn canonical_signature_udf(s: Series) -> PolarsResult<Option<Series>> {
+    let canonical: StringChunked = s
+        .str()?
+        .into_iter()
+        .map(|signature| {
+            signature.map(|signature| {
+                Function::parse(signature).map(|f| f.signature()).unwrap_or_else(|_| signature.to_string())
+            })
+        })
+        .collect();
+    Ok(Some(canonical.into_series()))
 }
 
-/// Decodes a logs/traces DataFrame using an ABI database file path
+/// Decodes a logs/traces DataFrame using a pre-loaded ABI DataFrame, pivoting the params of each signature into columns
 ///
 /// # Arguments
 /// * `df` - DataFrame containing raw blockchain data
-/// * `abi_db_path` - Path to ABI database file
+/// * `abi_df` - DataFrame containing ABI definitions
 /// * `decoder_type` - Type of data to decode
 ///
 /// # Returns
-/// * `Ok(DataFrame)` containing decoded data
+/// * `Ok(DataFrame)` containing decoded data, a discriminator column (`event_name` for logs, `function_name` for traces)
+///   and one String column per param of each decoded signature, i.e: `Transfer_from`, `Transfer_value`
 /// * `Err(DecoderError)` if decoding fails
-pub async fn decode_df(
+///
+/// # Notes
+/// Param columns are null for rows of other signatures. Trace outputs are prefixed with `output_`, i.e: `transfer_output_success`.
+/// Unnamed params are called `field_{index}`. Signatures sharing the same name and param names share the same columns.
+/// Param columns named like a decoded column are prefixed with `param_`, see `param_column_names`.
+/// The original row order is kept.
+pub async fn decode_to_wide(
     df: DataFrame,
-    abi_db_path: String,
+    abi_df: DataFrame,
     decoder_type: DecoderType,
 ) -> Result<DataFrame, DecoderError> {
-    let abi_db_path = Path::new(&abi_db_path);
-    let abi_df = utils::read_df_file(&abi_db_path)?;
+    let (name_column, json_columns) = match decoder_type {
+        DecoderType::Log => ("event_name", vec![("event_json", "")]),
+        DecoderType::Trace => ("function_name", vec![("input_json", ""), ("output_json", "output_")]),
+    };
+    let decoded_df = decode_df_with_abi_df(df, abi_df, decoder_type)
+        .await?
+        .lazy()
+        .with_row_index("wide_row_index", None)
+        .with_column(col("name").alias(name_column))
+        .collect()?;
 
-    decode_df_with_abi_df(df, abi_df, decoder_type).await
+    // Rows without a matched signature are kept, with nulls in all param columns
+    let mut wide_dfs = vec![decoded_df.clone().lazy().filter(col("full_signature").is_null())];
+    let signatures = decoded_df.column("full_signature")?.unique_stable()?;
+    for signature in signatures.str()?.into_iter().flatten() {
+        let mut signature_df = decoded_df
+            .clone()
+            .lazy()
+            .filter(col("full_signature").eq(lit(signature)))
+            .collect()?;
+        let name = signature_df.column("name")?.str()?.get(0).unwrap_or("").to_string();
+        for (json_column, prefix) in &json_columns {
+            let json_series = signature_df.column(json_column)?.str()?.clone();
+            let columns = signature_df.get_column_names();
+            for param_series in json_params_to_series(&json_series, &format!("{}_{}", name, prefix), &columns) {
+                signature_df.with_column(param_series)?;
+            }
+        }
+        wide_dfs.push(signature_df.lazy());
+    }
+
+    let wide_df = concat_lf_diagonal(wide_dfs, UnionArgs::default())?
+        .sort("wide_row_index", Default::default())
+        .drop(["wide_row_index"])
+        .collect()?;
+    Ok(wide_df)
 }
 
-/// Decodes a logs/traces DataFrame using a pre-loaded ABI DataFrame
+/// Decodes a logs/traces DataFrame with a single signature and aggregates its params, i.e: total value transferred per `to` address
 ///
 /// # Arguments
 /// * `df` - DataFrame containing raw blockchain data
 /// * `abi_df` - DataFrame containing ABI definitions
+/// * `signature` - Full signature to decode, as in the ABI DB full_signature column
+/// * `group_by` - Columns to group by, params or raw columns (i.e: `address`)
+/// * `agg` - Columns to aggregate and their aggregation, i.e: `("value", AggKind::Sum)`
 /// * `decoder_type` - Type of data to decode
 ///
 /// # Returns
-/// * `Ok(DataFrame)` containing decoded data
-/// * `Err(DecoderError)` if decoding fails
-/// 
+/// * `Ok(DataFrame)` with the group_by columns and one column per aggregation, named `{column}_{aggregation}`, i.e: `value_sum`
+/// * `Err(DecoderError)` if the signature isn't in the ABI DataFrame, a column doesn't exist or decoding fails
+///
 /// # Notes
-/// The function gets the matching algorithm from the config and uses it to join the logs/traces with ABI itens.
-pub async fn decode_df_with_abi_df(
+/// Only the logs/traces decoded with the signature are aggregated. The params are pivoted into one String column per param,
+/// like in `decode_to_wide` but without the name prefix, i.e: `from`, `to`, `value`. Trace outputs are prefixed with `output_`.
+/// A param named like a decoded column (i.e: `address` or `data`) doesn't replace it, it's named `param_address`.
+/// Sums are exact, as decimal Strings, so wei amounts don't lose precision, and fail if a value isn't an integer.
+/// The mean, min and max cast the values to Float64, so integers above 2^53 lose precision. Groups are kept in the order they first appear, and
+/// an empty group_by aggregates all the rows in a single row.
+///
+/// # Example
+/// ```no_run
+/// use glaciers::decoder::{decode_and_aggregate, AggKind, DecoderType};
+/// use polars::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let logs_df = ParquetReader::new(std::fs::File::open("logs.parquet")?).finish()?;
+///     let abi_df = ParquetReader::new(std::fs::File::open("ethereum__events__abis.parquet")?).finish()?;
+///     let totals_df = decode_and_aggregate(
+///         logs_df,
+///         abi_df,
+///         "event Transfer(address indexed from, address indexed to, uint256 value)",
+///         vec!["to".to_string()],
+///         vec![("value".to_string(), AggKind::Sum)],
+///         DecoderType::Log,
+///     ).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn decode_and_aggregate(
     df: DataFrame,
     abi_df: DataFrame,
+    signature: &str,
+    group_by: Vec<String>,
+    agg: Vec<(String, AggKind)>,
     decoder_type: DecoderType,
 ) -> Result<DataFrame, DecoderError> {
-    // Convert hash and address columns to binary if they aren't already
-    let abi_df = utils::abi_df_hex_string_columns_to_binary(abi_df)?;
+    let json_columns = match decoder_type {
+        DecoderType::Log => vec![("event_json", "")],
+        DecoderType::Trace => vec![("input_json", ""), ("output_json", "output_")],
+    };
+    let signature_abi_df = abi_df
+        .lazy()
+        .filter(col("full_signature").eq(lit(signature)))
+        .collect()?;
+    if signature_abi_df.height() == 0 {
+        return Err(DecoderError::DecodingError(format!("Signature '{}' not found in the ABI DataFrame", signature)));
+    }
 
-    // perform matching
-    let matched_df = match decoder_type {
-        DecoderType::Log => match get_config().decoder.algorithm {
-            DecoderAlgorithm::HashAddress => matcher::match_logs_by_topic0_address(df, abi_df)?,
-            DecoderAlgorithm::Hash => matcher::match_logs_by_topic0(df, abi_df)?
-        },
-        DecoderType::Trace => match get_config().decoder.algorithm {
-            DecoderAlgorithm::HashAddress => matcher::match_traces_by_4bytes_address(df, abi_df)?,
-            DecoderAlgorithm::Hash => matcher::match_traces_by_4bytes(df, abi_df)?
+    let mut signature_df = decode_df_with_abi_df(df, signature_abi_df, decoder_type)
+        .await?
+        .lazy()
+        .filter(col("full_signature").eq(lit(signature)))
+        .collect()?;
+    for (json_column, prefix) in json_columns {
+        let json_series = signature_df.column(json_column)?.str()?.clone();
+        let columns = signature_df.get_column_names();
+        for param_series in json_params_to_series(&json_series, prefix, &columns) {
+            signature_df.with_column(param_series)?;
         }
+    }
+
+    let group_by_exprs: Vec<Expr> = group_by.iter().map(|column| col(column)).collect();
+    let agg_exprs: Vec<Expr> = agg.iter().map(|(column, kind)| kind.expr(column)).collect();
+    // Without group_by columns, the whole signature is aggregated in a single row
+    let aggregated_df = if group_by_exprs.is_empty() {
+        signature_df.lazy().select(agg_exprs).collect()?
+    } else {
+        signature_df.lazy().group_by_stable(group_by_exprs).agg(agg_exprs).collect()?
     };
+    Ok(aggregated_df)
+}
 
-    // Split logs files in chunk, decode logs, collected and union results and save in the decoded folder
-    decode(matched_df, decoder_type).await
+/// Names the columns of unnested params, so they never replace the other columns of a DataFrame
+///
+/// # Arguments
+/// * `param_names` - Names of the params, in order
+/// * `prefix` - Prefix added to each param name
+/// * `existing_columns` - Columns of the DataFrame the params are added to
+///
+/// # Returns
+/// One column name per param. A param named like an existing column or a previous param (i.e: a `data` param, like
+/// the raw data column) is prefixed with `param_`, and its index is added if the prefixed name is also taken.
+///
+/// # Example
+/// ```
+/// use glaciers::decoder::param_column_names;
+///
+/// let param_names = vec!["data".to_string(), "value".to_string(), "value".to_string()];
+/// let column_names = param_column_names(&param_names, "", &["address", "data"]);
+/// assert_eq!(column_names, vec!["param_data", "value", "param_value"]);
+/// ```
+pub fn param_column_names(param_names: &[String], prefix: &str, existing_columns: &[&str]) -> Vec<String> {
+    let mut taken: HashSet<String> = existing_columns.iter().map(|column| column.to_string()).collect();
+    param_names
+        .iter()
+        .enumerate()
+        .map(|(i, param_name)| {
+            let mut column_name = format!("{}{}", prefix, param_name);
+            if taken.contains(&column_name) {
+                column_name = format!("param_{}", column_name);
+                if taken.contains(&column_name) {
+                    column_name = format!("{}_{}", column_name, i);
+                }
+            }
+            taken.insert(column_name.clone());
+            column_name
+        })
+        .collect()
+}
+
+/// Auxiliary function to convert a column of JSON structured params into one String Series per param
+///
+/// # Arguments
+/// * `json_series` - Column of JSON strings (event_json, input_json or output_json) of a single signature
+/// * `prefix` - Prefix added to each param name
+/// * `existing_columns` - Columns of the DataFrame the Series are added to
+///
+/// # Returns
+/// A vector with one Series per param, named with `param_column_names`. The params are taken from the first row
+/// that can be parsed.
+fn json_params_to_series(json_series: &StringChunked, prefix: &str, existing_columns: &[&str]) -> Vec<Series> {
+    let rows: Vec<Option<Vec<StructuredParam>>> = json_series
+        .into_iter()
+        .map(|json| json.and_then(|json| serde_json::from_str(json.trim()).ok()))
+        .collect();
+    let param_names: Vec<String> = match rows.iter().flatten().next() {
+        Some(params) => params
+            .iter()
+            .map(|p| if p.name.is_empty() { format!("field_{}", p.index) } else { p.name.clone() })
+            .collect(),
+        None => return vec![],
+    };
+
+    param_column_names(&param_names, prefix, existing_columns)
+        .into_iter()
+        .enumerate()
+        .map(|(i, column_name)| {
+            let values: Vec<Option<String>> = rows
+                .iter()
+                .map(|params| params.as_ref().and_then(|params| params.get(i)).map(|p| p.value.to_string()))
+                .collect();
+            Series::new(&column_name, values)
+        })
+        .collect()
 }
 
 /// Handles the decoding of matched logs/traces with ABI itens. It spawns a thread for each chunk to parallelize the decoding process.
@@ -296,6 +3314,8 @@ pub async fn decode_df_with_abi_df(
 /// # Arguments
 /// * `df` - DataFrame containing matched logs/traces and ABI itens
 /// * `decoder_type` - Type of data to decode
+/// * `config` - Config snapshot with the decoded_chunk_size
+/// * `semaphore` - Semaphore limiting the number of parallel threads decoding chunks
 ///
 /// # Returns
 /// * `Ok(DataFrame)` containing all decoded chunks combined
@@ -303,37 +3323,83 @@ pub async fn decode_df_with_abi_df(
 /// 
 /// # Notes
 /// The function gets the decoded_chunk_size from the config and uses it to split the DataFrame in chunks.
-/// It also gets the max_chunk_threads_per_file from the config and uses it to limit the number 
-/// of parallel threads that can be used to decode each chunk.
+/// The semaphore is usually created with max_chunk_threads_per_file permits, limiting the number 
+/// of parallel threads that can be used to decode each chunk. It can be shared to give a single thread budget to multiple DataFrames.
 /// Total number of threads can be a max of max_chunk_threads_per_file * max_concurrent_files_decoding.
-async fn decode(df: DataFrame, decoder_type: DecoderType) -> Result<DataFrame, DecoderError> {
-    // Create a semaphore with MAX_THREAD_NUMBER permits
-    let semaphore = Arc::new(Semaphore::new(get_config().decoder.max_chunk_threads_per_file));
+/// Within decode_folder and decode_archive runs, each chunk also takes a permit of the semaphore shared by all files,
+/// so no more than decoder.max_total_chunk_threads chunks are decoded at the same time (if it isn't 0).
+/// A chunk that fails decoding is retried up to decoder.chunk_retry_count times with exponential backoff, keeping
+/// the other chunks' results, before its error is returned.
+/// An empty DataFrame is decoded without spawning chunk tasks, returning an empty DataFrame with the decoded columns.
+async fn decode(
+    df: DataFrame,
+    decoder_type: DecoderType,
+    config: &Config,
+    semaphore: Arc<Semaphore>,
+) -> Result<DataFrame, DecoderError> {
+    if df.height() == 0 {
+        return match decoder_type {
+            DecoderType::Log => log_decoder::polars_decode_logs(df),
+            DecoderType::Trace => trace_decoder::polars_decode_traces(df),
+        };
+    }
+
     // Create a channel to communicate tasks results
     let (tx, mut rx) = mpsc::channel(10);
     // Shared vector to collect DataFrame chunks
     let collected_dfs = Arc::new(Mutex::new(Vec::new()));
     // Vector to hold our tasks handles
     let mut handles = Vec::new();
+    // Semaphore shared with the other files of a decode_folder/decode_archive run, if any
+    let total_semaphore = TOTAL_CHUNK_SEMAPHORE.try_with(|semaphore| semaphore.clone()).ok().flatten();
     
     // Split the DataFrame in chunks and spawn a task for each chunk
     let total_height = df.height();
     let mut i = 0;
     while i < total_height {
-        let end = (i + get_config().decoder.decoded_chunk_size).min(total_height);
+        let end = (i + config.decoder.decoded_chunk_size).min(total_height);
         let chunk_df = df.slice(i as i64, end - i);
 
         let sem_clone = semaphore.clone();
+        let total_sem_clone = total_semaphore.clone();
         let tx_clone = tx.clone();
         let collected_dfs_clone = collected_dfs.clone();
         let decoder_type_clone = decoder_type.clone();
+        let chunk_retry_count = config.decoder.chunk_retry_count;
         let handle = task::spawn(async move {
 
             let _permit = sem_clone.acquire().await;
+            // The file permit is taken first, so chunks waiting for it don't hold total permits
+            let _total_permit = match &total_sem_clone {
+                Some(total_sem) => Some(total_sem.acquire().await),
+                None => None,
+            };
             //Use polars to iterate through each row and decode, communicate through channel the result.
-            let decoded_chunk = match decoder_type_clone {
-                DecoderType::Log => log_decoder::polars_decode_logs(chunk_df),
-                DecoderType::Trace => trace_decoder::polars_decode_traces(chunk_df)
+            //A failed chunk is decoded again up to chunk_retry_count times, waiting twice as long before each retry.
+            let mut attempt = 0;
+            let decoded_chunk = loop {
+                let decoded_chunk = match decoder_type_clone {
+                    DecoderType::Log => log_decoder::polars_decode_logs(chunk_df.clone()),
+                    DecoderType::Trace => trace_decoder::polars_decode_traces(chunk_df.clone())
+                };
+                match decoded_chunk {
+                    Err(e) if attempt < chunk_retry_count => {
+                        attempt += 1;
+                        let backoff = Duration::from_millis(CHUNK_RETRY_BASE_DELAY_MS << (attempt - 1).min(16));
+                        println!(
+                            "[{}] Retrying decoding chunk of rows {} to {} ({}/{}) in {:?}: {}",
+                            Local::now().format("%Y-%m-%d %H:%M:%S"),
+                            i,
+                            end,
+                            attempt,
+                            chunk_retry_count,
+                            backoff,
+                            e
+                        );
+                        tokio::time::sleep(backoff).await;
+                    }
+                    decoded_chunk => break decoded_chunk,
+                }
             };
             match decoded_chunk {
                 Ok(decoded_chunk) => {
@@ -386,11 +3452,14 @@ async fn decode(df: DataFrame, decoder_type: DecoderType) -> Result<DataFrame, D
 ///
 /// # Returns
 /// * `Ok(DataFrame)` containing all input DataFrames combined
-/// * `Err(DecoderError)` if union operation fails
+/// * `Err(DecoderError)` if there are no DataFrames or the union operation fails
 async fn union_dataframes(dfs: Vec<DataFrame>) -> Result<DataFrame, DecoderError> {
-    // If only one DataFrame, return it directly
+    if dfs.is_empty() {
+        return Err(DecoderError::DecodingError("No decoded chunks to combine".to_string()));
+    }
+    // If only one DataFrame, take ownership and return it directly
     if dfs.len() == 1 {
-        return Ok(dfs[0].clone());
+        return Ok(dfs.into_iter().next().unwrap());
     }
 
     // Use Polars' vertical concatenation with union semantics
@@ -398,4 +3467,66 @@ async fn union_dataframes(dfs: Vec<DataFrame>) -> Result<DataFrame, DecoderError
     let unioned_df = concat(&lazy_dfs, UnionArgs::default())?.collect()?;
 
     Ok(unioned_df)
-}
\ No newline at end of file
+}
+
+#[cfg(all(test, feature = "arrow-interop"))]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+    use arrow::array::{Array, BinaryArray, LargeStringArray, UInt64Array};
+    use arrow::datatypes::{DataType as ArrowRsDataType, Field as ArrowRsField, Schema as ArrowRsSchema};
+    use arrow::record_batch::RecordBatch;
+    use std::sync::Arc;
+
+    fn transfer_logs_batch(schema: Arc<ArrowRsSchema>, block_numbers: Vec<u64>) -> RecordBatch {
+        let address = [0x11u8; 20];
+        let topic0 = keccak256("Transfer(address,address,uint256)");
+        let from = [[0u8; 12].as_slice(), [0x22u8; 20].as_slice()].concat();
+        let to = [[0u8; 12].as_slice(), [0x33u8; 20].as_slice()].concat();
+        let mut data = [0u8; 32];
+        data[31] = 5;
+        let rows = block_numbers.len();
+        let column = |value: &[u8]| Arc::new(BinaryArray::from_iter_values(std::iter::repeat_n(value, rows))) as Arc<dyn Array>;
+        RecordBatch::try_new(schema, vec![
+            Arc::new(UInt64Array::from(block_numbers)),
+            column(&address),
+            column(topic0.as_slice()),
+            column(&from),
+            column(&to),
+            Arc::new(BinaryArray::from(vec![None::<&[u8]>; rows])),
+            column(&data),
+        ]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn decode_record_batches_round_trip() {
+        let abi_df = crate::abi_reader::AbiDfBuilder::new()
+            .add_event("event Transfer(address indexed from, address indexed to, uint256 value)", Address::repeat_byte(0x11))
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut fields = vec![ArrowRsField::new("block_number", ArrowRsDataType::UInt64, false)];
+        for name in ["address", "topic0", "topic1", "topic2", "topic3", "data"] {
+            fields.push(ArrowRsField::new(name, ArrowRsDataType::Binary, true));
+        }
+        let schema = Arc::new(ArrowRsSchema::new(fields));
+        let batches = vec![
+            transfer_logs_batch(schema.clone(), vec![1, 2]),
+            transfer_logs_batch(schema.clone(), vec![3]),
+        ];
+
+        let decoded = decode_record_batches(batches, abi_df, DecoderType::Log).await.unwrap();
+        let decoded_rows: usize = decoded.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(decoded_rows, 3);
+        let batch = arrow::compute::concat_batches(&decoded[0].schema(), &decoded).unwrap();
+        let block_numbers = batch.column_by_name("block_number").unwrap().as_any().downcast_ref::<UInt64Array>().unwrap();
+        assert_eq!(block_numbers.values().to_vec(), vec![1, 2, 3]);
+        let names = batch.column_by_name("name").unwrap().as_any().downcast_ref::<LargeStringArray>().unwrap();
+        assert!(names.iter().all(|name| name == Some("Transfer")));
+        let event_values = batch.column_by_name("event_values").unwrap().as_any().downcast_ref::<LargeStringArray>().unwrap();
+        assert!(event_values.value(0).contains('5'));
+
+        let empty = decode_record_batches(Vec::new(), DataFrame::empty(), DecoderType::Log).await.unwrap();
+        assert!(empty.is_empty());
+    }
+}
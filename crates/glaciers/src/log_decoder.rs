@@ -2,18 +2,28 @@
 //! 
 //! This module provides functions to:
 //! - Run through a DataFrame of logs calling the UDF (User Defined Function) each line
-//! - A UDF to decode a single log line into a 3 parts string separated by ;
+//! - Add the same decoding steps to a LazyFrame plan, for streaming decoding
+//! - Flag which matched logs decode without errors, without building the decoded columns
+//! - A UDF to decode a single log line into a 4 parts string separated by the unit separator character
 //! - A function to extract from an array of series the topics, data and signature
 //! - A function to decode the log line using the alloy library decode_log_parts function
+//! - A function to decode a single log from its raw topics and data, without building a DataFrame
+//! - A function to decode a single log trying several candidate signatures, i.e: for topic0s shared by more than one ABI item
 //! - A function to map the decoded log parts into a StructuredParam for serialization
-use alloy::dyn_abi::{DynSolValue, EventExt};
-use alloy::json_abi::{Event, EventParam};
+//! - A function to build a raw logs DataFrame, matching the configured schema, from lists of bytes or hex strings
+//! - A function to expand a list column of topics (and optionally the data) into the topics and data columns
+//! - A function to add the optional topic columns missing from raw logs (i.e: exporters only emitting topic0) as nulls
+//! - A function to rewrite the indexed markers of an event signature to match the number of topics of a log
+use alloy::dyn_abi::{DynSolEvent, DynSolType, DynSolValue, EventExt, Specifier};
+use alloy::hex;
+use alloy::json_abi::Event;
 use alloy::primitives::FixedBytes;
 use polars::prelude::*;
+use pyo3::FromPyObject;
 use thiserror::Error;
 
-use crate::configger::get_config;
-use crate::decoder::{DecoderError, StructuredParam};
+use crate::configger::{self, get_config, MissingTopicsMode};
+use crate::decoder::{self, DecoderError, ParamFormat, StructuredParam, DECODING_ERROR_PREFIX, UDF_SEPARATOR};
 use crate::utils;
 
 /// Error types specific to log decoding operations.
@@ -23,13 +33,38 @@ pub enum LogDecoderError {
     DecodingError(String),
     #[error("Polars error: {0}")]
     PolarsError(#[from] PolarsError),
+    #[error("Invalid log field '{field}' at row {row}: {reason}")]
+    InvalidLogField { row: usize, field: String, reason: String },
+    #[error("Invalid log fields length: {0}")]
+    InvalidLength(String),
 }
 
-/// Internal structure to hold each part of the decoded event
-struct ExtDecodedEvent {
-    event_values: Vec<String>,
-    event_keys: Vec<String>,
-    event_json: String,
+/// A raw log field value, received either as bytes or as a hex string (with or without the 0x prefix)
+#[derive(Clone, FromPyObject, Debug)]
+pub enum RawBytes {
+    Bytes(Vec<u8>),
+    HexString(String),
+}
+
+/// value_type used in event_json for indexed tuple params, which are decoded as the 32 bytes hash stored in the topic
+const HASHED_TUPLE_VALUE_TYPE: &str = "indexed (hashed tuple)";
+
+/// Log fields extracted from each row: topics, data and signature
+type LogFields<'a> = (Vec<FixedBytes<32>>, &'a [u8], &'a str);
+
+/// Each part of a decoded event, as in the decoded logs' columns
+#[derive(Debug, Clone)]
+pub struct DecodedLog {
+    /// Decoded parameter values, the indexed values followed by the data values
+    pub event_values: Vec<String>,
+    /// Parameter names, in the same order as the values
+    pub event_keys: Vec<String>,
+    /// JSON string representation of the decoded event
+    pub event_json: String,
+    /// False if any string param contained invalid UTF-8 and required a lossy conversion
+    pub string_validity: bool,
+    /// True if any value was truncated to decoder.max_value_length
+    pub truncated_values: bool,
 }
 
 /// Decodes EVM logs in a DataFrame into human-readable format.
@@ -42,10 +77,36 @@ struct ExtDecodedEvent {
 ///   - event_values: Array of decoded parameter values
 ///   - event_keys: Array of parameter names
 ///   - event_json: JSON string representation of the decoded event
+///   - string_validity: False if any string param contained invalid UTF-8 and required a lossy conversion
+///   - decoding_error: The error message for logs that matched a signature but failed to decode (i.e: malformed topics)
+///   - truncated_values: True if any value was truncated, only if decoder.max_value_length is set
 /// 
 /// # Notes
-/// The output format (binary/hex) of some columns is determined by configuration
+/// The output format (binary/hex) of some columns is determined by configuration.
+/// If decoder.drop_raw_columns is set, the raw topics and data columns are removed from the output.
+/// The num_indexed_args column computed by the matcher is removed, unless log_decoder.include_num_indexed_args is set.
+/// If log_decoder.indexed_only is set, only the topics are decoded, and the decoded columns only contain the indexed params.
 pub fn polars_decode_logs(df: DataFrame) -> Result<DataFrame, DecoderError> {
+    let decoded_chuck_df = decode_logs_lazy(df.lazy()).collect()?;
+
+    Ok(if get_config().decoder.output_hex_string_encoding {
+        utils::binary_columns_to_hex_string(decoded_chuck_df)?
+    } else {
+        decoded_chuck_df
+    })    
+}
+
+/// Adds the log decoding steps of `polars_decode_logs` to a LazyFrame plan of matched logs.
+///
+/// # Arguments
+/// * `lf` - LazyFrame containing raw log data and a matching event item.
+///
+/// # Returns
+/// The LazyFrame with the decoded columns of `polars_decode_logs`. Binary columns aren't converted to hex strings.
+///
+/// # Notes
+/// The decoding UDF is an element-wise function, so the plan can be run by the Polars streaming engine.
+pub fn decode_logs_lazy(lf: LazyFrame) -> LazyFrame {
     let input_schema_alias = get_config().log_decoder.log_schema.log_alias;
 
     // using the alias to select columns that will be used in the decode_log_udf
@@ -55,43 +116,110 @@ pub fn polars_decode_logs(df: DataFrame) -> Result<DataFrame, DecoderError> {
         .map(|alias| col(alias.as_str()).alias(alias.as_str()))
         .collect();
     alias_exprs.push(col("full_signature").alias("full_signature"));
+
+    let mut excluded_columns = vec!["decoded_log".to_string()];
+    if get_config().decoder.drop_raw_columns {
+        excluded_columns.extend(input_schema_alias.as_array());
+    }
+    if !get_config().log_decoder.include_num_indexed_args {
+        excluded_columns.push("num_indexed_args".to_string());
+    }
+    let truncated_values_exprs = if get_config().decoder.max_value_length > 0 {
+        vec![col("decoded_log").str().split(lit(UDF_SEPARATOR)).list().get(lit(3)).eq(lit("true")).alias("truncated_values")]
+    } else {
+        vec![]
+    };
     
     // as_struct() passes the selected columns to the decode_log_udf and returns a column decoded_log of type String
-    // decoded_log column is then split into 3 columns separated by the ; character
-    let decoded_chuck_df = df
-        .lazy()
+    // decoded_log column is then split into its parts, separated by the unit separator character (UDF_SEPARATOR)
+    let decoded_chuck_lf = lf
         //apply decode_log_udf, creating a decoded_log column
         .with_columns([as_struct(alias_exprs)
         .map(decode_log_udf, GetOutput::from_type(DataType::String))
-        .alias("decoded_log")])
-        //split the udf output column (decoded_log) into 3 columns
+        .alias("decoded_log")]);
+    // move decoding errors to the decoding_error column
+    decoder::split_decoding_error(decoded_chuck_lf, "decoded_log")
+        //split the udf output column (decoded_log) into the decoded columns
         .with_columns([col("decoded_log")
             .str()
-            .split(lit(";"))
+            .split(lit(UDF_SEPARATOR))
             .list()
             .get(lit(0))
             .alias("event_values")])
         .with_columns([col("decoded_log")
             .str()
-            .split(lit(";"))
+            .split(lit(UDF_SEPARATOR))
             .list()
             .get(lit(1))
             .alias("event_keys")])
         .with_columns([col("decoded_log")
             .str()
-            .split(lit(";"))
+            .split(lit(UDF_SEPARATOR))
             .list()
             .get(lit(2))
             .alias("event_json")])
-        // Remove the original decoded_log column
-        .select([col("*").exclude(["decoded_log"])])
-        .collect()?;
+        // string_validity is always the last part, and is converted to a boolean column
+        .with_columns([col("decoded_log")
+            .str()
+            .split(lit(UDF_SEPARATOR))
+            .list()
+            .get(lit(-1))
+            .str()
+            .strip_chars(lit(Null {}))
+            .eq(lit("true"))
+            .alias("string_validity")])
+        .with_columns(truncated_values_exprs)
+        // Remove the original decoded_log column, the raw topics and data columns if drop_raw_columns is set,
+        // and num_indexed_args unless include_num_indexed_args is set
+        .select([col("*").exclude(excluded_columns)])
+}
 
-    Ok(if get_config().decoder.output_hex_string_encoding {
-        utils::binary_columns_to_hex_string(decoded_chuck_df)?
-    } else {
-        decoded_chuck_df
-    })    
+/// Adds a `decodable` Boolean column to a LazyFrame plan of matched logs, attempting to decode each log
+/// without building the decoded columns.
+///
+/// # Arguments
+/// * `lf` - LazyFrame containing raw log data and a matching event item.
+///
+/// # Returns
+/// The LazyFrame with a `decodable` column: true for logs that decode, false for logs that fail to decode
+/// (they would have a `decoding_error` with `decode_logs_lazy`) and null for logs without a matched signature
+/// and well-formed topics.
+///
+/// # Notes
+/// The values aren't converted to strings or serialized to JSON, so it's cheaper than `decode_logs_lazy`.
+pub fn decodable_logs_lazy(lf: LazyFrame) -> LazyFrame {
+    let input_schema_alias = get_config().log_decoder.log_schema.log_alias;
+    let mut alias_exprs: Vec<Expr> = input_schema_alias.as_array()
+        .iter()
+        .map(|alias| col(alias.as_str()).alias(alias.as_str()))
+        .collect();
+    alias_exprs.push(col("full_signature").alias("full_signature"));
+
+    lf.with_columns([as_struct(alias_exprs)
+        .map(decodable_log_udf, GetOutput::from_type(DataType::Boolean))
+        .alias("decodable")])
+}
+
+/// UDF (User Defined Function) attempting to decode individual log entries, see `decodable_logs_lazy`.
+fn decodable_log_udf(s: Series) -> PolarsResult<Option<Series>> {
+    let series_struct_array: &StructChunked = s.struct_()?;
+    let fields = series_struct_array.fields();
+    let missing_topics_mode = get_config().log_decoder.missing_topics_mode;
+    let topics_data_sig = extract_log_fields(fields, &missing_topics_mode)?;
+    let validate = missing_topics_mode == MissingTopicsMode::Strict;
+    let indexed_only = get_config().log_decoder.indexed_only;
+
+    let udf_output: BooleanChunked = topics_data_sig
+        .into_iter()
+        .map(|log_fields| {
+            if matches!(log_fields, Ok((_, _, ""))) {
+                return None;
+            }
+            Some(log_fields.and_then(|(topics, data, sig)| decode_values(sig, topics, data, validate, indexed_only)).is_ok())
+        })
+        .collect();
+
+    Ok(Some(udf_output.into_series()))
 }
 
 /// UDF (User Defined Function) for decoding individual log entries.
@@ -100,27 +228,40 @@ pub fn polars_decode_logs(df: DataFrame) -> Result<DataFrame, DecoderError> {
 /// * `s` - Series containing struct arrays of log topics, data and signature
 ///
 /// # Returns
-/// If successful, a Series containing decoded log in a string format, separated by ;
-///   "event_values";"event_keys";"event_json"
+/// If successful, a Series containing decoded log in a string format, separated by UDF_SEPARATOR (shown as ;)
+///   "event_values";"event_keys";"event_json";"truncated_values";"string_validity"
+/// Logs that failed to decode have the error message prefixed by DECODING_ERROR_PREFIX instead,
+/// and logs without a matched signature are null.
 fn decode_log_udf(s: Series) -> PolarsResult<Option<Series>> {
     let series_struct_array: &StructChunked = s.struct_()?;
     let fields = series_struct_array.fields();
+    let missing_topics_mode = get_config().log_decoder.missing_topics_mode;
     //extract topics, data and signature from the df struct arrays
-    let topics_data_sig = extract_log_fields(&fields)?;
+    let topics_data_sig = extract_log_fields(fields, &missing_topics_mode)?;
+    // In strict mode, alloy validates the number of topics against the signature
+    let validate = missing_topics_mode == MissingTopicsMode::Strict;
+    let indexed_only = get_config().log_decoder.indexed_only;
+    let format = ParamFormat::from_config(&get_config());
 
-    //iterate through each row value, calling the decode function and mapping it to a 3 parts result string separated by ;
+    //iterate through each row value, calling the decode function and mapping it to a 5 parts result string separated by UDF_SEPARATOR
     let udf_output: StringChunked = topics_data_sig
         .into_iter()
-        .map(|(topics, data, sig)| {
-            decode(sig, topics, data)
+        .map(|log_fields| {
+            // Logs without a matched signature aren't decoded
+            if matches!(log_fields, Ok((_, _, ""))) {
+                return None;
+            }
+            let decoded = log_fields.and_then(|(topics, data, sig)| decode(sig, topics, data, validate, indexed_only, format))
                 .map(|event| {
                     format!(
-                        "{:?}; {:?}; {}",
-                        event.event_values, event.event_keys, event.event_json
+                        "{:?}{sep}{:?}{sep}{}{sep}{}{sep}{}",
+                        event.event_values, event.event_keys, event.event_json, event.truncated_values, event.string_validity,
+                        sep = UDF_SEPARATOR
                     )
                 })
-                // Ignore decoding errors. In the future, we can have a param to log errors or store them in the table.
-                .ok()
+                // Decoding errors are stored in the table, in the decoding_error column
+                .unwrap_or_else(|e| format!("{}{}", DECODING_ERROR_PREFIX, e));
+            Some(decoded)
         })
         .collect();
 
@@ -129,16 +270,19 @@ fn decode_log_udf(s: Series) -> PolarsResult<Option<Series>> {
 
 /// Extracts each log field necessary for decoding from an array of Series.
 /// Translate [Series of topic0, Series of topic1, ..., Series of data, Series of sig] to Series of ([topic0, topic1, topic2, topic3], data, signature)
-
+///
 /// # Arguments
 /// * `fields` - Slice of Series containing log topics, data and signature
+/// * `missing_topics_mode` - How null topics are handled: replaced by zero filled topics (Compatible) or skipped (Strict)
 ///
 /// # Returns
 /// If successful, a vector (with items for each row) of tuples containing 3 values:
 ///   - Vector of topics as 32-byte fixed arrays
 ///   - Raw data as byte slice
 ///   - Event signature as string
-fn extract_log_fields(fields: &[Series]) -> PolarsResult<Vec<(Vec<FixedBytes<32>>, &[u8], &str)>> {
+/// 
+/// Rows with topics that aren't 32 bytes long (i.e: corrupted inputs) are returned as a DecodingError, instead of the tuple.
+fn extract_log_fields<'a>(fields: &'a [Series], missing_topics_mode: &MissingTopicsMode) -> PolarsResult<Vec<Result<LogFields<'a>, LogDecoderError>>> {
     let zero_filled_topic = vec![0u8; 32];
 
     let fields_topic0 = fields[0].binary()?;
@@ -151,23 +295,37 @@ fn extract_log_fields(fields: &[Series]) -> PolarsResult<Vec<(Vec<FixedBytes<32>
     //iterate through each row value, and map it to a tuple of topics, data and signature
     fields_topic0
         .into_iter()
-        .zip(fields_topic1.into_iter())
-        .zip(fields_topic2.into_iter())
-        .zip(fields_topic3.into_iter())
-        .zip(fields_data.into_iter())
-        .zip(fields_sig.into_iter())
+        .zip(fields_topic1)
+        .zip(fields_topic2)
+        .zip(fields_topic3)
+        .zip(fields_data)
+        .zip(fields_sig)
         .map(
             |(((((opt_topic0, opt_topic1), opt_topic2), opt_topic3), opt_data), opt_sig)| {
-                let topics = vec![
-                    FixedBytes::from_slice(opt_topic0.unwrap_or(&zero_filled_topic)),
-                    FixedBytes::from_slice(opt_topic1.unwrap_or(&zero_filled_topic)),
-                    FixedBytes::from_slice(opt_topic2.unwrap_or(&zero_filled_topic)),
-                    FixedBytes::from_slice(opt_topic3.unwrap_or(&zero_filled_topic)),
-                ];
+                let opt_topics = [opt_topic0, opt_topic1, opt_topic2, opt_topic3];
+                // FixedBytes::from_slice panics if the topic isn't 32 bytes long
+                for (i, topic) in opt_topics.iter().enumerate() {
+                    if let Some(topic) = topic.filter(|topic| topic.len() != 32) {
+                        return Ok(Err(LogDecoderError::DecodingError(format!(
+                            "topic{} has {} bytes, expected 32", i, topic.len()
+                        ))));
+                    }
+                }
+                let topics = match missing_topics_mode {
+                    MissingTopicsMode::Compatible => opt_topics
+                        .iter()
+                        .map(|opt_topic| FixedBytes::from_slice(opt_topic.unwrap_or(&zero_filled_topic)))
+                        .collect(),
+                    MissingTopicsMode::Strict => opt_topics
+                        .iter()
+                        .flatten()
+                        .map(|topic| FixedBytes::from_slice(topic))
+                        .collect(),
+                };
                 let data = opt_data.unwrap_or(&[]);
                 let sig = opt_sig.unwrap_or("");
 
-                Ok((topics, data, sig))
+                Ok(Ok((topics, data, sig)))
             },
         )
         .collect()
@@ -179,45 +337,281 @@ fn extract_log_fields(fields: &[Series]) -> PolarsResult<Vec<(Vec<FixedBytes<32>
 /// * `full_signature` - Event signature string
 /// * `topics` - Vector of event topics as 32-byte fixed arrays
 /// * `data` - Raw event data as byte slice
+/// * `validate` - If true, alloy validates the number of topics and the topic0 against the signature
+/// * `indexed_only` - If true, only the indexed params are decoded, see `decode_values`
+/// * `format` - How the params are named and the values converted to strings
 ///
 /// # Returns
-/// If successful, a struct containing 3 values:
+/// If successful, a struct containing 5 values:
 ///   - event_values: JSON string of decoded parameter values
 ///   - event_keys: JSON string of parameter names
 ///   - event_json: JSON string representation of the complete decoded event
+///   - string_validity: False if any string param required a lossy UTF-8 conversion
+///   - truncated_values: True if any value was truncated to decoder.max_value_length
 fn decode(
     full_signature: &str,
     topics: Vec<FixedBytes<32>>,
     data: &[u8],
-) -> Result<ExtDecodedEvent, LogDecoderError> {
-    //parse the full signature to create the event object
-    let event_obj = Event::parse(full_signature)
-        .map_err(|e| LogDecoderError::DecodingError(e.to_string()))?;
+    validate: bool,
+    indexed_only: bool,
+    format: ParamFormat,
+) -> Result<DecodedLog, LogDecoderError> {
+    let (event_obj, event_values) = decode_values(full_signature, topics, data, validate, indexed_only)?;
 
-    //decode the event calling the alloy decode_log_parts function
-    let decoded_event = event_obj.decode_log_parts(topics, data, false)
+    // Strings are never indexed (they are hashed into the topics), so only the body values are checked
+    let body_types = event_obj
+        .inputs
+        .iter()
+        .filter(|input| !input.indexed)
+        .map(|input| input.resolve())
+        .collect::<Result<Vec<DynSolType>, _>>()
         .map_err(|e| LogDecoderError::DecodingError(e.to_string()))?;
-
-    // Store the indexed values in a vector
-    let mut event_values: Vec<DynSolValue> = decoded_event.indexed.clone();
-    // Extend the vector with the body(data) values
-    event_values.extend(decoded_event.body.clone());
-
-    let structured_event = map_event_sig_and_values(&event_obj, &event_values)?;
+    let num_indexed = event_values.len() - body_types.len();
+    let string_validity = indexed_only || !utils::has_lossy_string(&body_types, &event_values[num_indexed..], data);
+    let structured_event = map_event_sig_and_values(&event_obj, &event_values, format)?;
     let event_keys: Vec<String> = structured_event.iter().map(|p| p.name.clone()).collect();
     let event_json = serde_json::to_string(&structured_event).unwrap_or_else(|_| "[]".to_string()).trim().to_string();
     // Convert the event_values to a vector of strings
-    let event_values: Vec<String> = event_values.iter().map(|d| utils::StrDynSolValue::from(d.clone()).to_string().unwrap_or("None".to_string())).collect();
+    let (event_values, truncated): (Vec<String>, Vec<bool>) = event_values.iter().map(|d| format.value_string(d)).unzip();
 
-    let extended_decoded_event = ExtDecodedEvent {
+    let extended_decoded_event = DecodedLog {
         event_values,
         event_keys,
         event_json,
+        string_validity,
+        truncated_values: truncated.contains(&true),
     };
 
     Ok(extended_decoded_event)
 }
 
+/// Decodes a single log from its raw topics and data, without building a DataFrame, i.e: for interactive use
+///
+/// # Arguments
+/// * `full_signature` - Full event signature, i.e: "event Transfer(address indexed from, address indexed to, uint256 value)"
+/// * `topics` - Present topics of the log, topic0 first, 32 bytes each
+/// * `data` - Raw event data
+///
+/// # Returns
+/// If successful, the decoded log, with the same values as the event_values, event_keys, event_json and string_validity
+/// columns of a decoded logs DataFrame. A `LogDecoderError` if there are more than 4 topics, a topic isn't 32 bytes long,
+/// or the log doesn't decode with the signature.
+///
+/// # Notes
+/// Topics missing from a signature's indexed params follow log_decoder.missing_topics_mode, like in DataFrame decoding:
+/// they are zero filled in "compatible" mode, and the log fails to decode in "strict" mode.
+/// If log_decoder.indexed_only is set, only the indexed params are decoded, and the data is ignored.
+///
+/// # Example
+/// ```
+/// use glaciers::log_decoder::decode_log_from_bytes;
+///
+/// let topic0 = glaciers::utils::normalize_hex_string("0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").unwrap();
+/// let (mut from, mut to, mut data) = ([0u8; 32], [0u8; 32], [0u8; 32]);
+/// from[31] = 0x11;
+/// to[31] = 0x22;
+/// data[31] = 100;
+/// let decoded = decode_log_from_bytes(
+///     "event Transfer(address indexed from, address indexed to, uint256 value)",
+///     &[&topic0, &from, &to],
+///     &data,
+/// ).unwrap();
+/// assert_eq!(decoded.event_keys, vec!["from", "to", "value"]);
+/// assert_eq!(decoded.event_values[2], "100");
+/// ```
+///
+/// Arrays and tuples keep their structure in event_json, with the tuple fields named by the ABI components:
+/// ```
+/// use glaciers::log_decoder::decode_log_from_bytes;
+///
+/// let topic0 = alloy::primitives::keccak256("OrdersPlaced((address,uint256[])[])");
+/// let word = |n: u8| { let mut word = [0u8; 32]; word[31] = n; word };
+/// let mut maker = [0u8; 32];
+/// maker[12..].copy_from_slice(&[0x11; 20]);
+/// // One order, with maker 0x11..11 and amounts [1, 2]
+/// let data = [word(0x20), word(1), word(0x20), maker, word(0x40), word(2), word(1), word(2)].concat();
+/// let decoded = decode_log_from_bytes(
+///     "event OrdersPlaced(tuple(address maker, uint256[] amounts)[] orders)",
+///     &[topic0.as_slice()],
+///     &data,
+/// ).unwrap();
+/// let event_json: serde_json::Value = serde_json::from_str(&decoded.event_json).unwrap();
+/// assert_eq!(event_json[0]["value"][0]["maker"], "0x1111111111111111111111111111111111111111");
+/// assert_eq!(event_json[0]["value"][0]["amounts"], serde_json::json!(["1", "2"]));
+/// ```
+///
+/// Unnamed params are named by their position in the signature, while the indexed params come first:
+/// ```
+/// use glaciers::log_decoder::decode_log_from_bytes;
+///
+/// let topic0 = alloy::primitives::keccak256("Swap(uint256,address,uint256,address)");
+/// let word = |n: u8| { let mut word = [0u8; 32]; word[31] = n; word };
+/// let decoded = decode_log_from_bytes(
+///     "event Swap(uint256, address indexed, uint256, address indexed)",
+///     &[topic0.as_slice(), &word(0x11), &word(0x22)],
+///     &[word(1), word(2)].concat(),
+/// ).unwrap();
+/// assert_eq!(decoded.event_keys, vec!["arg1", "arg3", "arg0", "arg2"]);
+/// assert_eq!(decoded.event_values[2], "1");
+/// ```
+pub fn decode_log_from_bytes(full_signature: &str, topics: &[&[u8]], data: &[u8]) -> Result<DecodedLog, LogDecoderError> {
+    if topics.len() > 4 {
+        return Err(LogDecoderError::InvalidLength(format!("{} topics, expected up to 4", topics.len())));
+    }
+    if let Some((i, topic)) = topics.iter().enumerate().find(|(_, topic)| topic.len() != 32) {
+        return Err(LogDecoderError::DecodingError(format!("topic{} has {} bytes, expected 32", i, topic.len())));
+    }
+    let mut topics: Vec<FixedBytes<32>> = topics.iter().map(|topic| FixedBytes::from_slice(topic)).collect();
+    // In strict mode, alloy validates the number of topics against the signature
+    let validate = get_config().log_decoder.missing_topics_mode == MissingTopicsMode::Strict;
+    if !validate {
+        topics.resize(4, FixedBytes::ZERO);
+    }
+    decode(full_signature, topics, data, validate, get_config().log_decoder.indexed_only, ParamFormat::from_config(&get_config()))
+}
+
+/// Decodes a single log trying each candidate signature in order, keeping the first one the log decodes with
+///
+/// # Arguments
+/// * `topics` - Present topics of the log, topic0 first, 32 bytes each
+/// * `data` - Raw event data
+/// * `candidates` - Full event signatures to try, in priority order, i.e: the ABI items sharing the log topic0
+///
+/// # Returns
+/// If successful, the candidate the log decoded with and the decoded log, see `decode_log_from_bytes`.
+/// A `LogDecoderError` with the error of the last candidate if the log doesn't decode with any of them,
+/// or if there are no candidates.
+///
+/// # Notes
+/// Candidates are tried until one decodes without an error, so a log matching a wrong ABI item (i.e: an event with
+/// the same topic0 but other indexed params) can still be decoded, at the cost of extra decoding attempts.
+///
+/// # Example
+/// ```
+/// use glaciers::log_decoder::decode_with_candidates;
+///
+/// // Both events have the same topic0 and number of topics, but index a different param
+/// let topic0 = alloy::primitives::keccak256("Message(string,uint256)");
+/// let text_hash = alloy::primitives::keccak256("hello");
+/// let mut data = [0u8; 32];
+/// data[31] = 5;
+/// let (signature, decoded) = decode_with_candidates(
+///     &[topic0.as_slice(), text_hash.as_slice()],
+///     &data,
+///     vec![
+///         "event Message(string text, uint256 indexed id)".to_string(),
+///         "event Message(string indexed text, uint256 id)".to_string(),
+///     ],
+/// ).unwrap();
+/// assert_eq!(signature, "event Message(string indexed text, uint256 id)");
+/// assert_eq!(decoded.event_values[1], "5");
+/// ```
+pub fn decode_with_candidates(
+    topics: &[&[u8]],
+    data: &[u8],
+    candidates: Vec<String>,
+) -> Result<(String, DecodedLog), LogDecoderError> {
+    let mut last_error = LogDecoderError::DecodingError("No candidate signatures to decode the log with".to_string());
+    for candidate in candidates {
+        match decode_log_from_bytes(&candidate, topics, data) {
+            Ok(decoded) => return Ok((candidate, decoded)),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// Parses the event signature and decodes the log parts with Alloy's decode_log_parts function
+///
+/// # Returns
+/// If successful, the parsed event and its decoded values, the indexed values followed by the body (data) values
+///
+/// # Notes
+/// With `indexed_only`, the data isn't decoded and the returned event only keeps its indexed params.
+/// The topic0 is still validated against the selector of the full signature.
+fn decode_values(
+    full_signature: &str,
+    topics: Vec<FixedBytes<32>>,
+    data: &[u8],
+    validate: bool,
+    indexed_only: bool,
+) -> Result<(Event, Vec<DynSolValue>), LogDecoderError> {
+    //parse the full signature to create the event object. Signatures with tuple params are retried without the tuple
+    //component names, that are set back after parsing
+    let mut event_obj = match Event::parse(full_signature) {
+        Ok(event_obj) => event_obj,
+        Err(_) => {
+            let mut event_obj = Event::parse(&utils::strip_tuple_component_names(full_signature))
+                .map_err(|e| LogDecoderError::DecodingError(e.to_string()))?;
+            if let Some(names) = utils::tuple_component_names(full_signature).first() {
+                for (input, names) in event_obj.inputs.iter_mut().zip(names) {
+                    utils::set_component_names(&mut input.components, &names.components);
+                }
+            }
+            event_obj
+        },
+    };
+
+    //decode the event calling the alloy decode_log_parts function
+    let decoded_event = if indexed_only {
+        // Decode the topics with an empty body, keeping the selector of the full signature
+        let resolved_event = event_obj.resolve().map_err(|e| LogDecoderError::DecodingError(e.to_string()))?;
+        let indexed_event = DynSolEvent::new_unchecked(
+            resolved_event.topic_0(),
+            resolved_event.indexed().to_vec(),
+            DynSolType::Tuple(vec![]),
+        );
+        event_obj.inputs.retain(|input| input.indexed);
+        indexed_event.decode_log_parts(topics, &[], validate)
+    } else {
+        event_obj.decode_log_parts(topics, data, validate)
+    }
+    .map_err(|e| LogDecoderError::DecodingError(e.to_string()))?;
+
+    // Store the indexed values in a vector
+    let mut event_values: Vec<DynSolValue> = decoded_event.indexed;
+    // Extend the vector with the body(data) values
+    event_values.extend(decoded_event.body);
+
+    Ok((event_obj, event_values))
+}
+
+/// Rewrites the indexed markers of an event signature to match the number of indexed args observed in a log.
+///
+/// The first `num_indexed_args - 1` params (left-to-right) are marked as indexed, and the other params are
+/// moved to the data section. It's used to decode nonstandard tokens, that emit a known event with a
+/// different number of indexed params than the canonical signature.
+///
+/// # Arguments
+/// * `full_signature` - Full event signature, i.e: "event Transfer(address indexed from, address indexed to, uint256 value)"
+/// * `num_indexed_args` - Number of topics of the log, including topic0
+///
+/// # Returns
+/// The rewritten full signature, or None if the signature can't be parsed, the event is anonymous,
+/// or the log has more indexed args than the event has params.
+///
+/// # Example
+/// ```
+/// use glaciers::log_decoder::adjust_indexed_args;
+///
+/// let adjusted = adjust_indexed_args("event Transfer(address indexed from, address indexed to, uint256 value)", 4);
+/// assert_eq!(adjusted.as_deref(), Some("event Transfer(address indexed from, address indexed to, uint256 indexed value)"));
+/// assert_eq!(adjust_indexed_args("event Transfer(address indexed from, address indexed to, uint256 value)", 5), None);
+/// ```
+pub fn adjust_indexed_args(full_signature: &str, num_indexed_args: u32) -> Option<String> {
+    let mut event_obj = Event::parse(full_signature)
+        .or_else(|_| Event::parse(&utils::strip_tuple_component_names(full_signature)))
+        .ok()?;
+    let indexed_params = (num_indexed_args as usize).checked_sub(1)?;
+    if event_obj.anonymous || indexed_params > event_obj.inputs.len() {
+        return None;
+    }
+    for (i, input) in event_obj.inputs.iter_mut().enumerate() {
+        input.indexed = i < indexed_params;
+    }
+    Some(event_obj.full_signature())
+}
+
 /// Maps event signature parameters names to their corresponding decoded values.
 /// This function is necessary because the source of param values (output of decode_log_parts) 
 /// is different from the source of param names (Signature - Event Object), and we want to keep them in the same order.
@@ -225,12 +619,16 @@ fn decode(
 /// # Arguments
 /// * `event_sig` - Reference to the parsed Event object
 /// * `event_values` - Vector of decoded parameter values
+/// * `format` - How the params are named and the values converted to strings
 ///
 /// # Returns
-/// If successful, a vector of StructuredParam (each item of the log_json)
+/// If successful, a vector of StructuredParam (each item of the log_json), with the indexed params first. Unnamed params
+/// are named by their position in the signature (`arg0`, `arg1`, ...), whatever their decoded order, unless
+/// `decoder.positional_names_when_unnamed` is false.
 fn map_event_sig_and_values(
     event_sig: &Event,
-    event_values: &Vec<DynSolValue>,
+    event_values: &[DynSolValue],
+    format: ParamFormat,
 ) -> Result<Vec<StructuredParam>, LogDecoderError> {
     // This error might be impossible, because it would make decode_log_parts fail before.
     if event_values.len() != event_sig.inputs.len() {
@@ -239,11 +637,12 @@ fn map_event_sig_and_values(
         ));
     }
 
-    // Partition event inputs into indexed and non-indexed so it has the same order as the event_values
-    let (event_indexed_inputs, event_data_inputs): (Vec<EventParam>, Vec<EventParam>) = 
+    // Partition event inputs into indexed and non-indexed so it has the same order as the event_values,
+    // keeping the position of each input in the signature for the positional names
+    let (event_indexed_inputs, event_data_inputs): (Vec<_>, Vec<_>) =
         event_sig.inputs.iter()
-            .cloned() // Clone to convert &EventParam to EventParam
-            .partition(|e| e.indexed);
+            .enumerate()
+            .partition(|(_, e)| e.indexed);
 
     // Combine indexed inputs followed by detail inputs
     let mut event_inputs = Vec::with_capacity(event_indexed_inputs.len() + event_data_inputs.len());
@@ -251,17 +650,242 @@ fn map_event_sig_and_values(
     event_inputs.extend(event_data_inputs);
 
     let mut structured_event: Vec<StructuredParam> = Vec::new();
-    for (i, input) in event_inputs.iter().enumerate() {
-        let str_value = utils::StrDynSolValue::from(event_values[i].clone());
+    for (i, (position, input)) in event_inputs.into_iter().enumerate() {
+        // Indexed tuples are stored in the topic as the keccak256 hash of their encoding, so the value is the 32 bytes hash
+        let value_type = if input.indexed && input.ty == "tuple" {
+            HASHED_TUPLE_VALUE_TYPE.to_string()
+        } else {
+            input.ty.to_string()
+        };
         // This is each item of event_json
         let event_param = StructuredParam {
-            name: input.name.clone(),
+            name: format.param_name(&input.name, position),
             index: i as u32,
-            value_type: input.ty.to_string(),
-            value: str_value.to_string().unwrap_or_else(|| "None".to_string()),
+            value_type,
+            value: format.param_value(&event_values[i], &input.components),
         };
         structured_event.push(event_param);
     }
 
     Ok(structured_event)
-}
\ No newline at end of file
+}
+
+/// Expands the topics list column (`log_decoder.topics_list_column`) of a raw logs LazyFrame plan into the log_alias columns.
+///
+/// # Arguments
+/// * `lf` - LazyFrame containing raw logs
+///
+/// # Returns
+/// The LazyFrame with the list column replaced by the topic0..topic3 alias columns, and by the data alias column if
+/// `log_decoder.topics_list_last_is_data` is set. Topics missing from a list are null. The LazyFrame is returned unchanged
+/// if no topics list column is configured or it isn't in the schema, i.e: it was already expanded.
+///
+/// # Notes
+/// With `log_decoder.topics_list_last_is_data`, the last element of each list is the data and the other elements are the
+/// topics, i.e: `[topic0, topic1, data]`. Otherwise all elements are topics and the data is read from its alias column.
+/// The list elements are converted like the alias columns, following `log_decoder.log_schema.log_datatype`.
+pub fn expand_topics_list_lazy(lf: LazyFrame) -> LazyFrame {
+    let log_config = get_config().log_decoder;
+    let list_column = log_config.topics_list_column;
+    // Schema errors are returned when the plan is collected
+    let has_list_column = !list_column.is_empty()
+        && lf.schema().map(|schema| schema.get(&list_column).is_some()).unwrap_or(false);
+    if !has_list_column {
+        return lf;
+    }
+
+    let log_alias = log_config.log_schema.log_alias;
+    let num_topics = if log_config.topics_list_last_is_data {
+        col(&list_column).list().len().cast(DataType::Int64) - lit(1i64)
+    } else {
+        col(&list_column).list().len().cast(DataType::Int64)
+    };
+    let mut expand_exprs: Vec<Expr> = [log_alias.topic0, log_alias.topic1, log_alias.topic2, log_alias.topic3]
+        .iter()
+        .enumerate()
+        .map(|(i, alias)| {
+            when(num_topics.clone().gt(lit(i as i64)))
+                .then(col(&list_column).list().get(lit(i as i64)))
+                .otherwise(lit(Null {}))
+                .alias(alias)
+        })
+        .collect();
+    if log_config.topics_list_last_is_data {
+        expand_exprs.push(col(&list_column).list().last().alias(&log_alias.data));
+    }
+
+    lf.with_columns(expand_exprs).drop([list_column])
+}
+
+/// Adds the topic1..topic3 alias columns missing from a raw logs LazyFrame plan as null columns.
+///
+/// # Arguments
+/// * `lf` - LazyFrame containing raw logs
+///
+/// # Returns
+/// The LazyFrame with all the topic alias columns. Columns already in the schema are kept unchanged.
+///
+/// # Notes
+/// Minimal exporters only emit the topic0, data and address columns when the events they track have no indexed params.
+/// The missing topics are null, so they count as absent in num_indexed_args, and are handled like null topics when
+/// decoding (see log_decoder.missing_topics_mode). The null columns have the dtype of log_decoder.log_schema.log_datatype
+/// (Binary or String), so they are converted like the other alias columns.
+pub fn fill_missing_topic_columns_lazy(lf: LazyFrame) -> LazyFrame {
+    let log_schema = get_config().log_decoder.log_schema;
+    // Schema errors are returned when the plan is collected
+    let Ok(schema) = lf.schema() else {
+        return lf;
+    };
+    let topics = [
+        (log_schema.log_alias.topic1, log_schema.log_datatype.topic1),
+        (log_schema.log_alias.topic2, log_schema.log_datatype.topic2),
+        (log_schema.log_alias.topic3, log_schema.log_datatype.topic3),
+    ];
+    let missing_exprs: Vec<Expr> = topics
+        .into_iter()
+        .filter(|(alias, _)| schema.get(alias).is_none())
+        .map(|(alias, datatype)| {
+            let dtype = match datatype {
+                configger::DataType::Binary => DataType::Binary,
+                configger::DataType::HexString => DataType::String,
+            };
+            lit(Null {}).cast(dtype).alias(&alias)
+        })
+        .collect();
+    if missing_exprs.is_empty() {
+        return lf;
+    }
+    lf.with_columns(missing_exprs)
+}
+
+/// Builds a raw logs DataFrame from lists of fields, matching the configured log schema.
+///
+/// Column names follow `log_decoder.log_schema.log_alias` and column types follow
+/// `log_decoder.log_schema.log_datatype`, so the output can be passed directly to the decoder functions.
+///
+/// # Arguments
+/// * `addresses` - Contract address of each log, 20 bytes each
+/// * `topics` - Topics of each log, up to 4 per log with 32 bytes each. Missing topics are filled with nulls
+/// * `data` - Data field of each log, any length
+/// * `block_numbers` - Optional block number of each log, added as a `block_number` column
+/// * `tx_hashes` - Optional transaction hash of each log, 32 bytes each, added as a binary `transaction_hash` column
+///
+/// # Returns
+/// * `Ok(DataFrame)` with one row per log
+/// * `Err(LogDecoderError)` if the fields have different lengths, or a value is malformed, reporting the row and field
+pub fn build_logs_df(
+    addresses: Vec<RawBytes>,
+    topics: Vec<Vec<Option<RawBytes>>>,
+    data: Vec<RawBytes>,
+    block_numbers: Option<Vec<u64>>,
+    tx_hashes: Option<Vec<RawBytes>>,
+) -> Result<DataFrame, LogDecoderError> {
+    let num_rows = addresses.len();
+    let mut lengths = vec![("topics", topics.len()), ("data", data.len())];
+    if let Some(block_numbers) = &block_numbers {
+        lengths.push(("block_numbers", block_numbers.len()));
+    }
+    if let Some(tx_hashes) = &tx_hashes {
+        lengths.push(("tx_hashes", tx_hashes.len()));
+    }
+    for (field, len) in lengths {
+        if len != num_rows {
+            return Err(LogDecoderError::InvalidLength(format!(
+                "'{}' has {} rows, but 'addresses' has {} rows", field, len, num_rows
+            )));
+        }
+    }
+
+    let address_values = addresses
+        .iter()
+        .enumerate()
+        .map(|(row, value)| parse_raw_bytes(value, row, "address", Some(20)).map(Some))
+        .collect::<Result<Vec<Option<Vec<u8>>>, LogDecoderError>>()?;
+    let data_values = data
+        .iter()
+        .enumerate()
+        .map(|(row, value)| parse_raw_bytes(value, row, "data", None).map(Some))
+        .collect::<Result<Vec<Option<Vec<u8>>>, LogDecoderError>>()?;
+
+    let mut topic_values: [Vec<Option<Vec<u8>>>; 4] = Default::default();
+    for (row, row_topics) in topics.iter().enumerate() {
+        if row_topics.len() > 4 {
+            return Err(LogDecoderError::InvalidLogField {
+                row,
+                field: "topics".to_string(),
+                reason: format!("expected at most 4 topics, got {}", row_topics.len()),
+            });
+        }
+        for (i, values) in topic_values.iter_mut().enumerate() {
+            let topic = match row_topics.get(i) {
+                Some(Some(value)) => Some(parse_raw_bytes(value, row, &format!("topic{}", i), Some(32))?),
+                _ => None,
+            };
+            values.push(topic);
+        }
+    }
+
+    let alias = get_config().log_decoder.log_schema.log_alias;
+    let datatype = get_config().log_decoder.log_schema.log_datatype;
+    let [topic0_values, topic1_values, topic2_values, topic3_values] = topic_values;
+    let mut columns = vec![
+        raw_bytes_series(&alias.address, address_values, &datatype.address),
+        raw_bytes_series(&alias.topic0, topic0_values, &datatype.topic0),
+        raw_bytes_series(&alias.topic1, topic1_values, &datatype.topic1),
+        raw_bytes_series(&alias.topic2, topic2_values, &datatype.topic2),
+        raw_bytes_series(&alias.topic3, topic3_values, &datatype.topic3),
+        raw_bytes_series(&alias.data, data_values, &datatype.data),
+    ];
+    if let Some(block_numbers) = block_numbers {
+        columns.push(Series::new("block_number", block_numbers));
+    }
+    if let Some(tx_hashes) = tx_hashes {
+        let tx_hash_values = tx_hashes
+            .iter()
+            .enumerate()
+            .map(|(row, value)| parse_raw_bytes(value, row, "tx_hash", Some(32)).map(Some))
+            .collect::<Result<Vec<Option<Vec<u8>>>, LogDecoderError>>()?;
+        columns.push(raw_bytes_series("transaction_hash", tx_hash_values, &configger::DataType::Binary));
+    }
+
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Converts a RawBytes value into bytes, validating its length when an expected length is given
+fn parse_raw_bytes(
+    value: &RawBytes,
+    row: usize,
+    field: &str,
+    expected_len: Option<usize>,
+) -> Result<Vec<u8>, LogDecoderError> {
+    let bytes = match value {
+        RawBytes::Bytes(bytes) => bytes.clone(),
+        RawBytes::HexString(hex_string) => utils::normalize_hex_string(hex_string).map_err(|e| LogDecoderError::InvalidLogField {
+            row,
+            field: field.to_string(),
+            reason: e.to_string(),
+        })?,
+    };
+    match expected_len {
+        Some(len) if bytes.len() != len => Err(LogDecoderError::InvalidLogField {
+            row,
+            field: field.to_string(),
+            reason: format!("expected {} bytes, got {}", len, bytes.len()),
+        }),
+        _ => Ok(bytes),
+    }
+}
+
+/// Creates a Series from bytes values, encoded as binary or as 0x prefixed hex strings
+fn raw_bytes_series(name: &str, values: Vec<Option<Vec<u8>>>, datatype: &configger::DataType) -> Series {
+    match datatype {
+        configger::DataType::Binary => {
+            let values: Vec<Option<&[u8]>> = values.iter().map(|v| v.as_deref()).collect();
+            Series::new(name, values)
+        }
+        configger::DataType::HexString => {
+            let values: Vec<Option<String>> = values.iter().map(|v| v.as_ref().map(hex::encode_prefixed)).collect();
+            Series::new(name, values)
+        }
+    }
+}
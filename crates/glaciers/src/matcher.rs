@@ -2,11 +2,27 @@
 //! 
 //! This module provides functionality to match Ethereum logs and traces with their corresponding
 //! ABI signatures using various matching strategies. It supports matching by topic0/4bytes signatures
-//! with and without address verification.
+//! with and without address verification, a fast path for logs sharing a single topic0, signature allow/deny lists, and an opt-in fallback that adjusts the indexed args of the
+//! candidate signature for logs of nonstandard events. The matching can also be added to a LazyFrame plan. It also provides a dry-run compatibility report between
+//! a raw logs/traces DataFrame and an ABI DataFrame.
 
+use chrono::Local;
 use polars::prelude::*;
 use thiserror::Error;
-use crate::configger::get_config;
+use polars::functions::concat_df_diagonal;
+use crate::configger::{get_config, DecoderAlgorithm};
+use crate::decoder::DecoderType;
+use crate::log_decoder;
+use crate::utils;
+
+/// Maximum number of example signatures listed for each category of the compatibility report
+const MAX_EXAMPLE_SIGNATURES: usize = 5;
+
+/// Suffix of the ABI columns of the second matching step in `match_lazy`
+const FALLBACK_SUFFIX: &str = "_fallback";
+
+/// Suffix of the ABI columns of the items without chain_id, see `join_abi_by_chain_id`
+const UNSCOPED_SUFFIX: &str = "_unscoped";
 
 /// Errors that can occur during the matching process
 #[derive(Error, Debug)]
@@ -22,6 +38,12 @@ pub enum MatcherError {
 /// - topic0 (event signature hash)
 /// - contract address
 /// - number of indexed arguments
+/// - chain_id, if both the logs and the ABI DataFrames have a chain_id column
+///
+/// ABI items with a null chain_id aren't scoped to a chain, and match the logs of any chain_id that have no
+/// match among the ABI items of their chain_id (see `join_abi_by_chain_id`).
+/// ABI DataFrames without an address column (i.e: built from a protocol-level ABI) are matched by topic0 only,
+/// with `match_logs_by_topic0`, printing a warning.
 ///
 /// # Arguments
 /// * `log_df` - DataFrame containing log entries
@@ -30,25 +52,91 @@ pub enum MatcherError {
 /// # Returns
 /// * `Result<DataFrame>` - Joined DataFrame with matched signatures, or error
 pub fn match_logs_by_topic0_address(log_df: DataFrame, abi_df: DataFrame) -> Result<DataFrame, MatcherError> {
+    if !has_address_column(&abi_df) {
+        println!(
+            "[{}] The ABI DataFrame has no address column, matching the logs by topic0 only",
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        );
+        return match_logs_by_topic0(log_df, abi_df);
+    }
+    let chain_id_dtype = chain_id_dtype(&log_df.schema(), &abi_df.clone().lazy())?;
+    let logs_left_join_abi_df = join_logs_by_topic0_address(log_df.lazy(), abi_df.lazy(), chain_id_dtype)?.collect()?;
+
+    Ok(logs_left_join_abi_df)
+}
+
+/// Auxiliary function adding the `match_logs_by_topic0_address` join to a LazyFrame plan
+///
+/// # Arguments
+/// * `log_lf` - LazyFrame containing log entries
+/// * `abi_df` - LazyFrame containing ABI signatures
+/// * `chain_id_dtype` - The logs chain_id dtype, if the chain_id is used in the join (see `chain_id_dtype`)
+fn join_logs_by_topic0_address(log_lf: LazyFrame, abi_df: LazyFrame, chain_id_dtype: Option<DataType>) -> Result<LazyFrame, MatcherError> {
     let topic0_alias = get_config().log_decoder.log_schema.log_alias.topic0;
     let address_alias = get_config().log_decoder.log_schema.log_alias.address;
+    let hash_column = get_config().abi_reader.hash_column_name;
 
+    join_abi_by_chain_id(
+        log_lf.with_column(num_indexed_args_expr()),
+        cast_num_indexed_args(abi_df),
+        vec![col(topic0_alias.as_str()), col(address_alias.as_str()), col("num_indexed_args")],
+        &[hash_column.as_str(), "address", "num_indexed_args"],
+        chain_id_dtype,
+    )
+}
 
-    let logs_left_join_abi_df = log_df
-        .lazy()
-        .with_column((lit(1 as u32) +
-            col("topic1").is_not_null() +
-            col("topic2").is_not_null() +
-            col("topic3").is_not_null()).alias("num_indexed_args"))
+/// Auxiliary function left joining raw logs/traces with ABI items on the keys, and on the chain_id if `chain_id_dtype` is set.
+///
+/// ABI items with a null chain_id aren't scoped to a chain: they are joined in a second step on the keys only, and
+/// their columns are used for the rows without a match among the ABI items of their chain_id. The input row order is kept.
+///
+/// # Arguments
+/// * `lf` - LazyFrame containing raw log/trace entries
+/// * `abi_df` - LazyFrame containing ABI signatures
+/// * `left_on` - The join keys of the raw data, without the chain_id
+/// * `right_on` - The join keys of the ABI items, without the chain_id
+/// * `chain_id_dtype` - The raw data chain_id dtype, if the chain_id is used in the join (see `chain_id_dtype`)
+fn join_abi_by_chain_id(lf: LazyFrame, abi_df: LazyFrame, left_on: Vec<Expr>, right_on: &[&str], chain_id_dtype: Option<DataType>) -> Result<LazyFrame, MatcherError> {
+    let right_on_exprs: Vec<Expr> = right_on.iter().map(|name| col(name)).collect();
+    let Some(dtype) = chain_id_dtype else {
+        return Ok(lf.join(abi_df, left_on, right_on_exprs, JoinArgs::new(JoinType::Left)));
+    };
+
+    let scoped_abi_df = abi_df.clone().filter(col("chain_id").is_not_null()).with_column(col("chain_id").cast(dtype));
+    // the raw data chain_id is kept for the rows matched by the unscoped ABI items
+    let unscoped_abi_df = abi_df.filter(col("chain_id").is_null()).drop(["chain_id"]);
+    let unscoped_columns: Vec<String> = unscoped_abi_df
+        .schema()?
+        .iter_names()
+        .filter(|name| !right_on.contains(&name.as_str()))
+        .map(|name| name.to_string())
+        .collect();
+    let not_matched = col("full_signature").is_null();
+    let coalesce_exprs: Vec<Expr> = unscoped_columns
+        .iter()
+        .map(|name| {
+            when(not_matched.clone())
+                .then(col(&format!("{}{}", name, UNSCOPED_SUFFIX)))
+                .otherwise(col(name))
+                .alias(name)
+        })
+        .collect();
+    let suffixed_columns: Vec<String> = unscoped_columns.iter().map(|name| format!("{}{}", name, UNSCOPED_SUFFIX)).collect();
+
+    let mut scoped_left_on = left_on.clone();
+    scoped_left_on.push(col("chain_id"));
+    let mut scoped_right_on = right_on_exprs.clone();
+    scoped_right_on.push(col("chain_id"));
+    Ok(lf
+        .join(scoped_abi_df, scoped_left_on, scoped_right_on, JoinArgs::new(JoinType::Left))
         .join(
-            abi_df.lazy(),
-            [col(topic0_alias.as_str()), col(address_alias.as_str()), col("num_indexed_args")],
-            [col("hash"), col("address"), col("num_indexed_args")],
-            JoinArgs::new(JoinType::Left),
+            unscoped_abi_df,
+            left_on,
+            right_on_exprs,
+            JoinArgs { suffix: Some(UNSCOPED_SUFFIX.to_string()), ..JoinArgs::new(JoinType::Left) },
         )
-        .collect()?;
-
-    Ok(logs_left_join_abi_df)
+        .with_columns(coalesce_exprs)
+        .drop(suffixed_columns))
 }
 
 /// Matches logs with ABI signatures using a two-step matching process.
@@ -63,17 +151,131 @@ pub fn match_logs_by_topic0_address(log_df: DataFrame, abi_df: DataFrame) -> Res
 /// # Returns
 /// * `Result<DataFrame>` - DataFrame with matched signatures, or error
 pub fn match_logs_by_topic0(log_df: DataFrame, abi_df: DataFrame) -> Result<DataFrame, MatcherError> {
+    let (logs_address_matched, logs_address_not_matched) = split_logs_by_address_match(&log_df, &abi_df)?;
+    let mut dropped_columns = second_step_dropped_columns(&log_df, &abi_df)?;
+    dropped_columns.push("signature_count");
+
+    // create an abi_df with the most frequent signature for each hash
+    let abi_df = most_frequent_log_signatures(abi_df.lazy(), &dropped_columns);
+
+    join_second_step(logs_address_matched, logs_address_not_matched, abi_df)
+}
+
+/// Fast path of `match_logs_by_topic0` for single-event extracts, where all the logs share a single topic0.
+///
+/// The ABI items are filtered to the topic0 of the logs, and the logs not matched by address are joined directly
+/// with the single signature of this topic0, skipping the grouping and sorting of the whole ABI DataFrame in
+/// `match_logs_by_topic0`.
+///
+/// # Arguments
+/// * `log_df` - DataFrame containing log entries
+/// * `abi_df` - DataFrame containing ABI signatures
+///
+/// # Returns
+/// * `Ok(Some(DataFrame))` - The same output as `match_logs_by_topic0`
+/// * `Ok(None)` - If the logs don't share a single topic0, or the ABI DataFrame doesn't have a single signature for
+///   it, so the logs must be matched with `match_logs_by_topic0`
+pub fn match_logs_by_single_topic0(log_df: &DataFrame, abi_df: &DataFrame) -> Result<Option<DataFrame>, MatcherError> {
+    let topic0_alias = get_config().log_decoder.log_schema.log_alias.topic0;
+    let hash_column = get_config().abi_reader.hash_column_name;
+
+    let topic0 = log_df.column(&topic0_alias)?;
+    if topic0.n_unique()? != 1 {
+        return Ok(None);
+    }
+    let topic0_abi_df = abi_df
+        .clone()
+        .lazy()
+        .join(
+            topic0.head(Some(1)).into_frame().lazy(),
+            [col(hash_column.as_str())],
+            [col(topic0_alias.as_str())],
+            JoinArgs::new(JoinType::Semi),
+        )
+        .collect()?;
+    if topic0_abi_df.column("full_signature")?.n_unique()? != 1 {
+        return Ok(None);
+    }
+
+    let (logs_address_matched, logs_address_not_matched) = split_logs_by_address_match(log_df, &topic0_abi_df)?;
+    let dropped_columns = second_step_dropped_columns(log_df, &topic0_abi_df)?;
+    // the single signature, with the ABI columns of its first item, like the most frequent signature of the general path
+    let signature_df = cast_num_indexed_args(topic0_abi_df.lazy()).drop(dropped_columns).limit(1);
+
+    Ok(Some(join_second_step(logs_address_matched, logs_address_not_matched, signature_df)?))
+}
+
+/// Auxiliary function running the first step of `match_logs_by_topic0`, matching the logs by topic0 and address.
+///
+/// # Returns
+/// The logs matched in the first step, and the logs not matched, with their original columns. ABI DataFrames without
+/// an address column can't be matched in the first step, so all the logs are returned as not matched.
+fn split_logs_by_address_match(log_df: &DataFrame, abi_df: &DataFrame) -> Result<(Option<DataFrame>, LazyFrame), MatcherError> {
+    if !has_address_column(abi_df) {
+        return Ok((None, log_df.clone().lazy()));
+    }
     let logs_1 = match_logs_by_topic0_address(log_df.clone(), abi_df.clone())?;
     let log_df_cols: Vec<Expr> = log_df.get_columns().iter().map(|s| col(s.name())).collect();
     // Split the logs into matched and not matched in the first step
-    let logs_address_matched = logs_1.clone().lazy().filter(col("full_signature").is_not_null()).collect()?;
-    let logs_address_not_matched = logs_1.lazy().filter(col("full_signature").is_null()).select(log_df_cols);
+    Ok((
+        Some(logs_1.clone().lazy().filter(col("full_signature").is_not_null()).collect()?),
+        logs_1.lazy().filter(col("full_signature").is_null()).select(log_df_cols),
+    ))
+}
 
-    // create an abi_df with the most frequent signature for each hash
-    let abi_df = abi_df
-        .lazy()
+/// Auxiliary function listing the ABI columns that aren't kept in the second step of `match_logs_by_topic0`:
+/// the log address and chain_id are kept instead of the ABI ones.
+fn second_step_dropped_columns(log_df: &DataFrame, abi_df: &DataFrame) -> Result<Vec<&'static str>, MatcherError> {
+    let mut dropped_columns = vec![];
+    if has_address_column(abi_df) {
+        dropped_columns.push("address");
+    }
+    if chain_id_dtype(&log_df.schema(), &abi_df.clone().lazy())?.is_some() {
+        dropped_columns.push("chain_id");
+    }
+    Ok(dropped_columns)
+}
+
+/// Auxiliary function running the second step of `match_logs_by_topic0`, joining the logs not matched by address
+/// with a signature for each hash and number of indexed args, and combining them with the logs matched by address.
+fn join_second_step(
+    logs_address_matched: Option<DataFrame>,
+    logs_address_not_matched: LazyFrame,
+    abi_df: LazyFrame,
+) -> Result<DataFrame, MatcherError> {
+    let topic0_alias = get_config().log_decoder.log_schema.log_alias.topic0;
+    let hash_column = get_config().abi_reader.hash_column_name;
+    // add a column with the number of indexed args
+    let logs_2 = logs_address_not_matched
+        .with_column(num_indexed_args_expr())
+        // Perform left join with the most frequent signature for each hash that has the same number of indexed args
+        .join(
+            abi_df,
+            [col(topic0_alias.as_str()), col("num_indexed_args")],
+            [col(hash_column.as_str()), col("num_indexed_args")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .collect()?;
+
+    // Combine first and second matching steps
+    let logs_df = match logs_address_matched {
+        Some(logs_address_matched) => logs_address_matched.vstack(&logs_2)?,
+        None => logs_2,
+    };
+
+    Ok(logs_df)
+}
+
+/// Auxiliary function creating an ABI LazyFrame with the most frequent event signature for each hash and number of indexed args
+///
+/// # Arguments
+/// * `abi_df` - LazyFrame containing ABI signatures
+/// * `dropped_columns` - ABI columns that aren't kept, i.e: the address, since the log address is kept
+fn most_frequent_log_signatures(abi_df: LazyFrame, dropped_columns: &[&str]) -> LazyFrame {
+    let hash_column = get_config().abi_reader.hash_column_name;
+    cast_num_indexed_args(abi_df)
         //count the number of rows for each full_signature
-        .group_by(["hash", "full_signature", "name", "anonymous", "num_indexed_args"])
+        .group_by([hash_column.as_str(), "full_signature", "name", "anonymous", "num_indexed_args"])
         .agg([all().first(), len().alias("signature_count")])
         //sort the rows by signature_count in descending order
         .sort("signature_count", SortOptions {
@@ -82,36 +284,226 @@ pub fn match_logs_by_topic0(log_df: DataFrame, abi_df: DataFrame) -> Result<Data
             ..Default::default()}
         )
         // group by hash and num_indexed_args and keep the first row (most frequent hash and num_indexed_args)
-        .group_by(["hash", "num_indexed_args"]).agg([
+        .group_by([hash_column.as_str(), "num_indexed_args"]).agg([
             all().first()
-        ]).drop(["address", "signature_count"]);
+        ]).drop(dropped_columns.to_vec())
+}
+
+/// Filters the ABI DataFrame with a signature allowlist and denylist, before matching.
+///
+/// Each list entry is either a full signature (i.e: "event Transfer(address indexed from, address indexed to, uint256 value)")
+/// or a hex hash (topic0 for events, 4 bytes selector for functions), starting with "0x".
+///
+/// # Arguments
+/// * `abi_df` - DataFrame containing ABI signatures, with a binary hash column
+/// * `allowlist` - If not empty, only the ABI items in the list are kept
+/// * `denylist` - ABI items in the list are removed, even if they are in the allowlist
+///
+/// # Returns
+/// * `Result<DataFrame>` - The filtered ABI items, or error if a hex entry is invalid
+pub fn filter_abi_df_by_signature_lists(abi_df: DataFrame, allowlist: &[String], denylist: &[String]) -> Result<DataFrame, MatcherError> {
+    if allowlist.is_empty() && denylist.is_empty() {
+        return Ok(abi_df);
+    }
+
+    let mut abi_lf = abi_df.lazy();
+    if let Some(allowed) = signature_list_expr(allowlist)? {
+        abi_lf = abi_lf.filter(allowed);
+    }
+    if let Some(denied) = signature_list_expr(denylist)? {
+        abi_lf = abi_lf.filter(denied.not());
+    }
+    Ok(abi_lf.collect()?)
+}
+
+/// Auxiliary function creating an expression that is true for the ABI items in a signature list, or None if the list is empty
+fn signature_list_expr(signatures: &[String]) -> Result<Option<Expr>, MatcherError> {
+    let hash_column = get_config().abi_reader.hash_column_name;
+    let mut exprs = Vec::new();
+    for signature in signatures {
+        let signature = signature.trim();
+        if signature.starts_with("0x") || signature.starts_with("0X") {
+            let hash = utils::normalize_hex_string(signature)?;
+            exprs.push(col(hash_column.as_str()).eq(lit(hash.as_slice())));
+        } else {
+            exprs.push(col("full_signature").eq(lit(signature)));
+        }
+    }
+    // nulls are not in the list
+    Ok(exprs.into_iter().reduce(|a, b| a.or(b)).map(|expr| expr.fill_null(lit(false))))
+}
+
+/// Matches logs with ABI signatures using the algorithm, retrying unmatched logs with adjusted indexed args.
+///
+/// Logs are first matched with `match_logs_by_topic0_address` or `match_logs_by_topic0`. For logs left unmatched,
+/// the most frequent signature of their topic0 (and address, with the hash_address algorithm) is taken regardless
+/// of its number of indexed args, and its indexed markers are rewritten to match the topics of the log
+/// (see `log_decoder::adjust_indexed_args`).
+///
+/// A `matched_by` column is added, with `exact` for the rows matched by the algorithm, `indexed_args_adjusted`
+/// for the rows matched by the fallback, and null for the rows still unmatched. The logs keep their original order.
+///
+/// # Arguments
+/// * `log_df` - DataFrame containing log entries
+/// * `abi_df` - DataFrame containing ABI signatures
+/// * `algorithm` - Algorithm used for the first matching step
+///
+/// # Returns
+/// * `Result<DataFrame>` - DataFrame with matched signatures, or error
+pub fn match_logs_with_adjusted_indexed_args(log_df: DataFrame, abi_df: DataFrame, algorithm: &DecoderAlgorithm) -> Result<DataFrame, MatcherError> {
+    // the logs are split by matching step, so their original order is restored with a row index
+    let log_df = log_df.with_row_index("adjusted_row_index", None)?;
+    let mut log_df_cols: Vec<Expr> = log_df.get_columns().iter().map(|s| col(s.name())).collect();
+    log_df_cols.push(col("num_indexed_args"));
+    let chain_id_dtype = chain_id_dtype(&log_df.schema(), &abi_df.clone().lazy())?;
+
+    let matched_df = match algorithm {
+        DecoderAlgorithm::HashAddress => match_logs_by_topic0_address(log_df, abi_df.clone())?,
+        DecoderAlgorithm::Hash => match_logs_by_topic0(log_df, abi_df.clone())?,
+    };
+    let exact_matched = matched_df
+        .clone()
+        .lazy()
+        .filter(col("full_signature").is_not_null())
+        .with_column(lit("exact").alias("matched_by"))
+        .collect()?;
+    let not_matched = matched_df
+        .lazy()
+        .filter(col("full_signature").is_null())
+        .select(log_df_cols);
 
+    let hash_column = get_config().abi_reader.hash_column_name;
     let topic0_alias = get_config().log_decoder.log_schema.log_alias.topic0;
-    // add a column with the number of indexed args
-    let logs_2 = logs_address_not_matched
-        .with_column((lit(1 as u32) +
-            col("topic1").is_not_null() +
-            col("topic2").is_not_null() +
-            col("topic3").is_not_null()).alias("num_indexed_args"))
-        // Perform left join with the most frequent signature for each hash that has the same number of indexed args
+    let address_alias = get_config().log_decoder.log_schema.log_alias.address;
+    let mut right_on = vec![hash_column.as_str()];
+    let mut left_on = vec![col(topic0_alias.as_str())];
+    let mut dropped_columns = vec!["signature_count", "num_indexed_args"];
+    // with the hash_address algorithm, the ABI items are grouped by chain_id too, and joined with `join_abi_by_chain_id`
+    let mut group_keys = vec![col(hash_column.as_str())];
+    let mut join_chain_id_dtype = None;
+    match algorithm {
+        DecoderAlgorithm::HashAddress => {
+            right_on.push("address");
+            left_on.push(col(address_alias.as_str()));
+            group_keys.push(col("address"));
+            if chain_id_dtype.is_some() {
+                group_keys.push(col("chain_id"));
+                join_chain_id_dtype = chain_id_dtype;
+            }
+        },
+        DecoderAlgorithm::Hash => {
+            // the log address and chain_id are kept, so the ABI ones are dropped
+            dropped_columns.push("address");
+            if chain_id_dtype.is_some() {
+                dropped_columns.push("chain_id");
+            }
+        },
+    }
+
+    // create an abi_df with the most frequent signature for each hash (and address), for any number of indexed args
+    let mut signature_keys = group_keys.clone();
+    signature_keys.push(col("full_signature"));
+    let candidates = abi_df
+        .lazy()
+        .group_by(signature_keys)
+        .agg([all().first(), len().alias("signature_count")])
+        .sort("signature_count", SortOptions {
+            descending: true,
+            nulls_last: true,
+            ..Default::default()}
+        )
+        .group_by(group_keys)
+        .agg([all().first()])
+        .drop(dropped_columns);
+
+    let adjusted = join_abi_by_chain_id(not_matched.clone(), candidates, left_on, &right_on, join_chain_id_dtype)?
+        .with_column(
+            as_struct(vec![col("full_signature"), col("num_indexed_args")])
+                .map(adjust_indexed_args_udf, GetOutput::from_type(DataType::String))
+                .alias("full_signature"),
+        )
+        .filter(col("full_signature").is_not_null())
+        .with_column(lit("indexed_args_adjusted").alias("matched_by"))
+        .collect()?;
+    let still_not_matched = not_matched
         .join(
-            abi_df,
-            [col(topic0_alias.as_str()), col("num_indexed_args")],
-            [col("hash"), col("num_indexed_args")],
-            JoinArgs::new(JoinType::Left),
+            adjusted.clone().lazy().select([col("adjusted_row_index")]),
+            [col("adjusted_row_index")],
+            [col("adjusted_row_index")],
+            JoinArgs::new(JoinType::Anti),
         )
         .collect()?;
 
-    // Combine first and second matching steps
-    let logs_df = logs_address_matched.vstack(&logs_2)?;
+    // the ABI columns and matched_by of the still not matched logs are filled with nulls
+    let logs_df = concat_df_diagonal(&[exact_matched, adjusted, still_not_matched])?
+        .sort(["adjusted_row_index"], false, false)?
+        .drop("adjusted_row_index")?;
 
     Ok(logs_df)
 }
 
+/// UDF adjusting the indexed args of each full_signature to the num_indexed_args of the log.
+///
+/// Returns null for signatures that can't be adjusted.
+fn adjust_indexed_args_udf(s: Series) -> Result<Option<Series>, PolarsError> {
+    let fields = s.struct_()?.fields();
+    let signatures = fields[0].str()?;
+    let num_indexed_args = fields[1].u32()?;
+    let adjusted: StringChunked = signatures
+        .into_iter()
+        .zip(num_indexed_args)
+        .map(|(signature, num_indexed_args)| {
+            log_decoder::adjust_indexed_args(signature?, num_indexed_args?)
+        })
+        .collect();
+    Ok(Some(adjusted.into_series()))
+}
+
+/// Expression computing the number of indexed args of each log, from its non null topics.
+///
+/// The result is cast to `UInt32`, the same dtype used by `cast_num_indexed_args` in the ABI DataFrame.
+/// The topic0 is counted only if present: logs of anonymous events without indexed params have no topics,
+/// and their num_indexed_args is 0 in the ABI DB.
+/// Topic columns missing from the logs must be added as nulls first, see `log_decoder::fill_missing_topic_columns_lazy`.
+fn num_indexed_args_expr() -> Expr {
+    let log_alias = get_config().log_decoder.log_schema.log_alias;
+    (col(&log_alias.topic0).is_not_null().cast(DataType::UInt32) +
+        col(&log_alias.topic1).is_not_null() +
+        col(&log_alias.topic2).is_not_null() +
+        col(&log_alias.topic3).is_not_null())
+        .cast(DataType::UInt32)
+        .alias("num_indexed_args")
+}
+
+/// Returns true if the ABI DataFrame has the address column used by the address matchers
+fn has_address_column(abi_df: &DataFrame) -> bool {
+    abi_df.get_column_names().contains(&"address")
+}
+
+/// Returns the dtype of the chain_id column of the logs/traces, if both DataFrames have a chain_id column.
+///
+/// The ABI DataFrame chain_id column is cast to this dtype before joining, since the logs/traces chain_id
+/// can be stored with a different integer type (or as a string).
+fn chain_id_dtype(schema: &Schema, abi_df: &LazyFrame) -> Result<Option<DataType>, MatcherError> {
+    let dtype = schema.get("chain_id").cloned();
+    let abi_has_chain_id = abi_df.schema()?.get("chain_id").is_some();
+    Ok(dtype.filter(|_| abi_has_chain_id))
+}
+
+/// Casts the ABI DataFrame num_indexed_args column to `UInt32`, so it can be joined with the logs.
+///
+/// ABI DBs created by older versions, or round-tripped through csv, can store num_indexed_args as
+/// `Int64` or as a string, which would make the join silently fail to match because of the dtype mismatch.
+fn cast_num_indexed_args(abi_df: LazyFrame) -> LazyFrame {
+    abi_df.with_column(col("num_indexed_args").cast(DataType::UInt32))
+}
+
 /// Matches traces with ABI signatures using function selector and contract address.
 ///
 /// Performs a left join between traces and ABI signatures based on the 4-byte function
-/// selector and the contract address.
+/// selector and the contract address. If both the traces and the ABI DataFrames have a chain_id
+/// column, the chain_id is also used in the join, and ABI items with a null chain_id match the traces of
+/// any chain_id that have no match among the ABI items of their chain_id (see `join_abi_by_chain_id`).
 ///
 /// # Arguments
 /// * `trace_df` - DataFrame containing trace entries
@@ -120,20 +512,30 @@ pub fn match_logs_by_topic0(log_df: DataFrame, abi_df: DataFrame) -> Result<Data
 /// # Returns
 /// * `Result<DataFrame>` - Joined DataFrame with matched signatures, or error
 pub fn match_traces_by_4bytes_address(trace_df: DataFrame, abi_df: DataFrame) -> Result<DataFrame, MatcherError> {
+    let chain_id_dtype = chain_id_dtype(&trace_df.schema(), &abi_df.clone().lazy())?;
+    let traces_left_join_abi_df = join_traces_by_4bytes_address(trace_df.lazy(), abi_df.lazy(), chain_id_dtype)?.collect()?;
+
+    Ok(traces_left_join_abi_df)
+}
+
+/// Auxiliary function adding the `match_traces_by_4bytes_address` join to a LazyFrame plan
+///
+/// # Arguments
+/// * `trace_lf` - LazyFrame containing trace entries
+/// * `abi_df` - LazyFrame containing ABI signatures
+/// * `chain_id_dtype` - The traces chain_id dtype, if the chain_id is used in the join (see `chain_id_dtype`)
+fn join_traces_by_4bytes_address(trace_lf: LazyFrame, abi_df: LazyFrame, chain_id_dtype: Option<DataType>) -> Result<LazyFrame, MatcherError> {
     let selector_alias = get_config().trace_decoder.trace_schema.trace_alias.selector;
     let action_to = get_config().trace_decoder.trace_schema.trace_alias.action_to;
+    let hash_column = get_config().abi_reader.hash_column_name;
 
-    let traces_left_join_abi_df = trace_df
-        .lazy()
-        .join(
-            abi_df.lazy(),
-            [col(selector_alias.as_str()), col(action_to.as_str())],
-            [col("hash"), col("address")],
-            JoinArgs::new(JoinType::Left),
-        )
-        .collect()?;
-
-    Ok(traces_left_join_abi_df)
+    join_abi_by_chain_id(
+        trace_lf,
+        abi_df,
+        vec![col(selector_alias.as_str()), col(action_to.as_str())],
+        &[hash_column.as_str(), "address"],
+        chain_id_dtype,
+    )
 }
 
 /// Matches traces with ABI signatures using a two-step matching process.
@@ -152,30 +554,23 @@ pub fn match_traces_by_4bytes(trace_df: DataFrame, abi_df: DataFrame) -> Result<
     let trace_df_cols: Vec<Expr> = trace_df.get_columns().iter().map(|s| col(s.name())).collect();
     let traces_address_matched = traces_1.clone().lazy().filter(col("full_signature").is_not_null()).collect()?;
     let traces_address_not_matched = traces_1.lazy().filter(col("full_signature").is_null()).select(trace_df_cols);
+    let hash_column = get_config().abi_reader.hash_column_name;
+
+    // the trace chain_id is kept, so the ABI chain_id is dropped in the second step
+    let mut dropped_columns = vec!["address", "signature_count"];
+    if chain_id_dtype(&trace_df.schema(), &abi_df.clone().lazy())?.is_some() {
+        dropped_columns.push("chain_id");
+    }
 
     // create an abi_df with the most frequent signature for each hash
-    let abi_df = abi_df
-        .lazy()
-        //count the number of rows for each full_signature
-        .group_by(["hash", "full_signature", "name"])
-        .agg([all().first(), len().alias("signature_count")])
-        //sort the rows by signature_count in descending order
-        .sort("signature_count", SortOptions {
-            descending: true,
-            nulls_last: true,
-            ..Default::default()}
-        )
-        // group by hash and num_indexed_args and keep the first row (most frequent hash and num_indexed_args)
-        .group_by(["hash"]).agg([
-            all().first()
-        ]).drop(["address", "signature_count"]);
+    let abi_df = most_frequent_trace_signatures(abi_df.lazy(), &dropped_columns);
 
     let selector_alias = get_config().trace_decoder.trace_schema.trace_alias.selector;
     let trace_2 = traces_address_not_matched
             .join(
                 abi_df,
                 [col(selector_alias.as_str())],
-                [col("hash")],
+                [col(hash_column.as_str())],
                 JoinArgs::new(JoinType::Left),
             )
             .collect()?;
@@ -184,3 +579,233 @@ pub fn match_traces_by_4bytes(trace_df: DataFrame, abi_df: DataFrame) -> Result<
 
     Ok(traces_df)
 }
+
+/// Auxiliary function creating an ABI LazyFrame with the most frequent function signature for each hash
+///
+/// # Arguments
+/// * `abi_df` - LazyFrame containing ABI signatures
+/// * `dropped_columns` - ABI columns that aren't kept, i.e: the address, since the trace action_to is kept
+fn most_frequent_trace_signatures(abi_df: LazyFrame, dropped_columns: &[&str]) -> LazyFrame {
+    let hash_column = get_config().abi_reader.hash_column_name;
+    abi_df
+        //count the number of rows for each full_signature
+        .group_by([hash_column.as_str(), "full_signature", "name"])
+        .agg([all().first(), len().alias("signature_count")])
+        //sort the rows by signature_count in descending order
+        .sort("signature_count", SortOptions {
+            descending: true,
+            nulls_last: true,
+            ..Default::default()}
+        )
+        // group by hash and keep the first row (most frequent signature)
+        .group_by([hash_column.as_str()]).agg([
+            all().first()
+        ]).drop(dropped_columns.to_vec())
+}
+
+/// Matches logs/traces with ABI signatures in a LazyFrame plan, i.e: for streaming decoding
+///
+/// The matching is the same as `match_logs_by_topic0_address`/`match_logs_by_topic0` for logs and
+/// `match_traces_by_4bytes_address`/`match_traces_by_4bytes` for traces, according to the algorithm.
+/// With the hash algorithm, both matching steps are left joins of the same plan, and the ABI columns of the
+/// second step are used for the rows not matched by the first one.
+///
+/// # Arguments
+/// * `lf` - LazyFrame containing raw log/trace entries
+/// * `abi_df` - DataFrame containing ABI signatures
+/// * `decoder_type` - Type of data to match
+/// * `algorithm` - Matching algorithm
+///
+/// # Returns
+/// * `Result<LazyFrame>` - The plan of the raw data joined with the matched ABI items, or error
+///
+/// # Notes
+/// Unlike the eager matchers, which return the rows matched by the first step first, the input row order is kept.
+pub fn match_lazy(lf: LazyFrame, abi_df: DataFrame, decoder_type: &DecoderType, algorithm: &DecoderAlgorithm) -> Result<LazyFrame, MatcherError> {
+    let abi_lf = abi_df.lazy();
+    let chain_id_dtype = chain_id_dtype(&*lf.schema()?, &abi_lf)?;
+    let hash_column = get_config().abi_reader.hash_column_name;
+
+    // the raw data chain_id is kept, so the ABI chain_id is dropped in the second step
+    let mut dropped_columns = vec!["address", "signature_count"];
+    if chain_id_dtype.is_some() {
+        dropped_columns.push("chain_id");
+    }
+
+    let (matched_lf, fallback_abi_lf, left_on, right_on): (_, _, _, Vec<&str>) = match decoder_type {
+        DecoderType::Log => {
+            let topic0_alias = get_config().log_decoder.log_schema.log_alias.topic0;
+            (
+                join_logs_by_topic0_address(lf, abi_lf.clone(), chain_id_dtype)?,
+                most_frequent_log_signatures(abi_lf, &dropped_columns),
+                vec![col(topic0_alias.as_str()), col("num_indexed_args")],
+                vec![hash_column.as_str(), "num_indexed_args"],
+            )
+        },
+        DecoderType::Trace => {
+            let selector_alias = get_config().trace_decoder.trace_schema.trace_alias.selector;
+            (
+                join_traces_by_4bytes_address(lf, abi_lf.clone(), chain_id_dtype)?,
+                most_frequent_trace_signatures(abi_lf, &dropped_columns),
+                vec![col(selector_alias.as_str())],
+                vec![hash_column.as_str()],
+            )
+        },
+    };
+    if let DecoderAlgorithm::HashAddress = algorithm {
+        return Ok(matched_lf);
+    }
+    // the most frequent signatures are collected before joining, the streaming group_by doesn't keep the sorted order
+    let fallback_abi_lf = fallback_abi_lf.collect()?.lazy();
+
+    // ABI columns of the second step, suffixed in the join and used for the rows not matched by the first step
+    let fallback_columns: Vec<String> = fallback_abi_lf
+        .schema()?
+        .iter_names()
+        .filter(|name| !right_on.contains(&name.as_str()))
+        .map(|name| name.to_string())
+        .collect();
+    let not_matched = col("full_signature").is_null();
+    let coalesce_exprs: Vec<Expr> = fallback_columns
+        .iter()
+        .map(|name| {
+            when(not_matched.clone())
+                .then(col(&format!("{}{}", name, FALLBACK_SUFFIX)))
+                .otherwise(col(name))
+                .alias(name)
+        })
+        .collect();
+    let suffixed_columns: Vec<String> = fallback_columns.iter().map(|name| format!("{}{}", name, FALLBACK_SUFFIX)).collect();
+
+    Ok(matched_lf
+        .join(
+            fallback_abi_lf,
+            left_on,
+            right_on.iter().map(|name| col(name)).collect::<Vec<Expr>>(),
+            JoinArgs { suffix: Some(FALLBACK_SUFFIX.to_string()), ..JoinArgs::new(JoinType::Left) },
+        )
+        .with_columns(coalesce_exprs)
+        .drop(suffixed_columns))
+}
+
+/// Reports, without decoding, how the hashes of a raw logs/traces DataFrame are covered by an ABI DataFrame.
+///
+/// Each distinct hash (topic0 for logs, 4 bytes selector for traces) and number of indexed args in the raw data
+/// is categorized in one of the buckets:
+/// - `matched`: the ABI DataFrame has a signature with this hash and number of indexed args
+/// - `indexed_arg_mismatch`: the ABI DataFrame has the hash, but only with a different number of indexed args
+///   (i.e: an ERC-721 Transfer matched against an ERC-20 Transfer, or a nonstandard ERC-20)
+/// - `missing`: the ABI DataFrame doesn't have the hash
+///
+/// # Arguments
+/// * `df` - DataFrame containing raw logs or traces
+/// * `abi_df` - DataFrame containing ABI signatures
+/// * `decoder_type` - Type of the raw data, logs or traces
+///
+/// # Returns
+/// * `Result<DataFrame>` - Report DataFrame with one row per category, in the order above, with the columns:
+///   `category`, `hash_count` (distinct hashes), `row_count` (raw rows), both UInt64, and `example_signatures`
+///   (up to 5 ABI signatures for the hashes in the category, null for `missing`), or error
+///
+/// # Notes
+/// Hashes are compared regardless of the address and of decoder.algorithm, so the report is an upper bound of
+/// the `hash` algorithm coverage. Traces have no indexed args, so their `indexed_arg_mismatch` bucket is always empty.
+/// A hash seen with different numbers of indexed args can be counted in more than one category.
+pub fn compatibility_report(df: DataFrame, abi_df: DataFrame, decoder_type: DecoderType) -> Result<DataFrame, MatcherError> {
+    let hash_alias = match decoder_type {
+        DecoderType::Log => get_config().log_decoder.log_schema.log_alias.topic0,
+        DecoderType::Trace => get_config().trace_decoder.trace_schema.trace_alias.selector,
+    };
+    let hash_column = get_config().abi_reader.hash_column_name;
+    let raw_num_indexed_args = match decoder_type {
+        DecoderType::Log => num_indexed_args_expr(),
+        DecoderType::Trace => lit(0_u32).alias("num_indexed_args"),
+    };
+    let abi_num_indexed_args = match decoder_type {
+        DecoderType::Log => col("num_indexed_args").cast(DataType::UInt32),
+        DecoderType::Trace => lit(0_u32).alias("num_indexed_args"),
+    };
+
+    // count the raw rows of each hash and number of indexed args
+    let df = match decoder_type {
+        DecoderType::Log => log_decoder::fill_missing_topic_columns_lazy(df.lazy()).collect()?,
+        DecoderType::Trace => df,
+    };
+    let raw_hash = binary_hash_expr(&df, &hash_alias)?;
+    let raw_df = df
+        .lazy()
+        .select([raw_hash, raw_num_indexed_args])
+        .filter(col("hash").is_not_null())
+        .group_by([col("hash"), col("num_indexed_args")])
+        .agg([len().cast(DataType::UInt64).alias("row_count")]);
+
+    let abi_hash = binary_hash_expr(&abi_df, &hash_column)?;
+    let abi_df = abi_df
+        .lazy()
+        .select([abi_hash, abi_num_indexed_args, col("full_signature")])
+        .unique(None, UniqueKeepStrategy::First);
+    // ABI signatures with the same hash and number of indexed args
+    let exact_signatures = abi_df
+        .clone()
+        .group_by([col("hash"), col("num_indexed_args")])
+        .agg([col("full_signature").alias("exact_signatures")]);
+    // ABI signatures with the same hash, for any number of indexed args
+    let hash_signatures = abi_df
+        .group_by([col("hash")])
+        .agg([col("full_signature").alias("hash_signatures")]);
+
+    let categorized = raw_df
+        .join(
+            exact_signatures,
+            [col("hash"), col("num_indexed_args")],
+            [col("hash"), col("num_indexed_args")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .join(hash_signatures, [col("hash")], [col("hash")], JoinArgs::new(JoinType::Left))
+        .select([
+            col("hash"),
+            col("row_count"),
+            when(col("exact_signatures").is_not_null())
+                .then(lit("matched"))
+                .when(col("hash_signatures").is_not_null())
+                .then(lit("indexed_arg_mismatch"))
+                .otherwise(lit("missing"))
+                .alias("category"),
+            when(col("exact_signatures").is_not_null())
+                .then(col("exact_signatures"))
+                .otherwise(col("hash_signatures"))
+                .alias("signatures"),
+        ])
+        .group_by([col("category")])
+        .agg([
+            col("hash").n_unique().cast(DataType::UInt64).alias("hash_count"),
+            col("row_count").sum().alias("row_count"),
+            col("signatures").flatten().drop_nulls().unique_stable().head(Some(MAX_EXAMPLE_SIGNATURES)).alias("example_signatures"),
+        ]);
+
+    // keep all the categories in the report, in a fixed order, even if they are empty
+    let categories = df!("category" => ["matched", "indexed_arg_mismatch", "missing"])?;
+    let report = categories
+        .lazy()
+        .join(categorized, [col("category")], [col("category")], JoinArgs::new(JoinType::Left))
+        .with_columns([
+            col("hash_count").fill_null(lit(0_u64)),
+            col("row_count").fill_null(lit(0_u64)),
+            when(col("category").eq(lit("missing")))
+                .then(lit(NULL).cast(DataType::List(Box::new(DataType::String))))
+                .otherwise(col("example_signatures"))
+                .alias("example_signatures"),
+        ])
+        .collect()?;
+
+    Ok(report)
+}
+
+/// Expression selecting a hash column as binary, decoding it if it's stored as a hex string.
+fn binary_hash_expr(df: &DataFrame, hash_column: &str) -> Result<Expr, MatcherError> {
+    let expr = match df.column(hash_column)?.dtype() {
+        DataType::String => col(hash_column).str().strip_prefix(lit("0x")).str().hex_decode(true),
+        _ => col(hash_column),
+    };
+    Ok(expr.alias("hash"))
+}
@@ -4,24 +4,42 @@
 //! 
 //! The module provides the following functions:
 //!  - decode_df_using_single_contract: Decodes a DataFrame with only a single contract address, by downloading the ABI from Sourcify.
+//!  - parse_contract_address: Validates and normalizes a contract address string, with or without 0x and in any case.
+//!  - fetch_abis_from_manifest: Downloads the ABIs listed in a manifest file (address, chain_id, source) and appends them to the ABI DB.
+//!  - with_abi_source: Adds the source of an ABI (AbiSource) as a column, for provenance tracking.
+//!  - http_client / send_with_retry: The HTTP client used by every ABI fetcher, and its request retry policy.
 
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use reqwest::Client;
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Local;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use alloy::{json_abi::JsonAbi, primitives::Address};
 use polars::prelude::*;
 use thiserror::Error;
+use tokio::sync::Semaphore;
 
 use crate::abi_reader;
+use crate::configger::get_config;
 use crate::decoder::{self, DecoderType};
+use crate::utils;
 
 /// Error types that can occur during miscellaneous operations
 #[derive(Error, Debug)]
 pub enum MiscellaneousError {
-    #[error("Unable to download ABI from Sourcify, Reqwest error: {0}")]
+    #[error("Unable to download ABI, Reqwest error: {0}")]
     ReqwestError(#[from] reqwest::Error),
-    #[error("Unable to download ABI from Sourcify, invalid JSON response: {0}")]
+    #[error("Unable to download ABI, invalid JSON response: {0}")]
     InvalidJsonResponse(String),
-    #[error("Alloy error, invalid address: {0}")]
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+    #[error("Polars error: {0}")]
+    PolarsError(#[from] PolarsError),
+    #[error("Join error: {0}")]
+    JoinError(#[from] tokio::task::JoinError),
+    #[error("Invalid address: {0}")]
     InvalidAddress(String),
     #[error("Abi reader error: {0}")]
     AbiReaderError(#[from] abi_reader::AbiReaderError),
@@ -42,25 +60,404 @@ pub enum MiscellaneousError {
 /// # Notes
 /// - This is a shortcuting function that automatically downloads the ABI from Sourcify, reads it and decodes the DataFrame.
 /// - Nevertheless, we recommend following the normal flow and creating the ABI DB first.
+/// - The address is validated before downloading, see `parse_contract_address`, so a malformed address returns
+///   `MiscellaneousError::InvalidAddress` without a request to Sourcify.
 pub async fn decode_df_using_single_contract(df: DataFrame, contract_address: String, decoder_type: DecoderType) -> Result<DataFrame, MiscellaneousError> {
+    let address = parse_contract_address(&contract_address)?;
+
     // Download the ABI from Sourcify
-    let client = Client::new();
-    let response = client
-        .get(format!("https://repo.sourcify.dev/contracts/partial_match/1/{}/metadata.json", contract_address))
-        .send().await?;
+    let client = http_client()?;
+    let (abi, _source) = download_sourcify_abi(&client, 1, &format!("{:#x}", address)).await?;
+
+    let abi_df = abi_reader::read_new_abi_json(abi, address)?;
+    let decoded_df = decoder::decode_df_with_abi_df(df, abi_df, decoder_type).await?;
+
+    Ok(decoded_df)
+}
+
+/// Validates and normalizes a contract address string
+///
+/// # Arguments
+/// * `address` - Contract address as 40 hex characters, with or without the 0x prefix and in any case.
+///   Surrounding whitespace is ignored. Mixed case addresses aren't checked against their EIP-55 checksum.
+///
+/// # Returns
+/// * If successful, the parsed address. Format it with `{:#x}` for its lowercase 0x prefixed string.
+/// * `MiscellaneousError::InvalidAddress` with the offending string, if it has a non hex character or isn't 20 bytes long.
+///
+/// # Example
+/// ```
+/// use glaciers::miscellaneous::parse_contract_address;
+///
+/// let address = parse_contract_address("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2").unwrap();
+/// assert_eq!(format!("{:#x}", address), "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+/// assert_eq!(parse_contract_address("C02AAA39B223FE8D0A0E5C4F27EAD9083C756CC2").unwrap(), address);
+///
+/// // Malformed and short addresses
+/// assert!(parse_contract_address("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cg2").is_err());
+/// assert!(parse_contract_address("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756C").is_err());
+/// assert!(parse_contract_address("").is_err());
+/// ```
+pub fn parse_contract_address(address: &str) -> Result<Address, MiscellaneousError> {
+    let trimmed = address.trim();
+    let hex_digits = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+    if let Some(c) = hex_digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(MiscellaneousError::InvalidAddress(format!("'{}' has an invalid character '{}'", address, c)));
+    }
+    if hex_digits.len() != 40 {
+        return Err(MiscellaneousError::InvalidAddress(format!(
+            "'{}' has {} hex characters, expected 40", address, hex_digits.len()
+        )));
+    }
+    Address::from_str(&hex_digits.to_lowercase())
+        .map_err(|e| MiscellaneousError::InvalidAddress(format!("'{}': {}", address, e)))
+}
+
+/// Source an ABI came from, i.e: listed in the manifest or returned with a downloaded ABI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiSource {
+    Sourcify,
+    Etherscan,
+    FourByte,
+    /// ABI file read from disk
+    Local(PathBuf),
+    /// Any other source, named by the user
+    Custom(String),
+}
+
+/// Parses the manifest sources. Local sources are parsed from "local:<path>", custom sources aren't parsed.
+impl FromStr for AbiSource {
+    type Err = MiscellaneousError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(path) = s.strip_prefix("local:") {
+            return Ok(AbiSource::Local(PathBuf::from(path)));
+        }
+        match s.to_lowercase().as_str() {
+            "sourcify" => Ok(AbiSource::Sourcify),
+            "etherscan" => Ok(AbiSource::Etherscan),
+            "4byte" => Ok(AbiSource::FourByte),
+            _ => Err(MiscellaneousError::InvalidManifest(format!("invalid source '{}', allowed values are: [\"sourcify\", \"etherscan\"]", s))),
+        }
+    }
+}
+
+/// Formats the source as stored in the abi_source column, i.e: "sourcify" or "local:path/to/0x...json"
+impl fmt::Display for AbiSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbiSource::Sourcify => write!(f, "sourcify"),
+            AbiSource::Etherscan => write!(f, "etherscan"),
+            AbiSource::FourByte => write!(f, "4byte"),
+            AbiSource::Local(path) => write!(f, "local:{}", path.display()),
+            AbiSource::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Adds an `abi_source` column with the source of the ABI to an ABI DataFrame, if the abi_reader.store_abi_source config is set.
+///
+/// # Arguments
+/// * `abi_df` - ABI DataFrame read from a single source
+/// * `source` - Source of the ABI
+///
+/// # Returns
+/// * If successful, the ABI DataFrame, with the abi_source column if the config is set.
+pub fn with_abi_source(abi_df: DataFrame, source: &AbiSource) -> Result<DataFrame, PolarsError> {
+    if !get_config().abi_reader.store_abi_source {
+        return Ok(abi_df);
+    }
+    abi_df.lazy().with_column(lit(source.to_string()).alias("abi_source")).collect()
+}
+
+/// Downloads the ABIs listed in a manifest file and appends them to the ABI DB, with a single deduplication pass.
+///
+/// # Arguments
+/// * `manifest_path` - Path to a csv or parquet manifest, with the columns `address`, `chain_id` and `source` ("sourcify" or "etherscan")
+/// * `abi_db_path` - Path to the existing or new ABI database file
+///
+/// # Returns
+/// * If successful, a report DataFrame with one row per manifest entry, with the columns
+///   address, chain_id, source, success, num_items and error.
+///
+/// # Notes
+/// - The number of parallel downloads is bounded by the `abi_reader.max_concurrent_abi_downloads` config.
+/// - Sourcify and Etherscan base URLs are read from the `abi_reader.sourcify_url` and `abi_reader.etherscan_url` configs.
+/// - The Etherscan API key is read from the `ETHERSCAN_API_KEY` environment variable.
+/// - A failure to download an ABI doesn't stop the process, it is reported in the returned DataFrame.
+/// - If the `abi_reader.store_abi_source` config is set, the source of each ABI is stored in the abi_source column.
+///
+/// # Examples
+/// ```no_run
+/// use glaciers::miscellaneous::fetch_abis_from_manifest;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let report = fetch_abis_from_manifest(
+///         "path/to/manifest.csv".to_string(),
+///         "path/to/abi_db.parquet".to_string()
+///     ).await?;
+///     println!("{}", report);
+///     Ok(())
+/// }
+/// ```
+pub async fn fetch_abis_from_manifest(manifest_path: String, abi_db_path: String) -> Result<DataFrame, MiscellaneousError> {
+    let manifest_df = utils::read_df_file(Path::new(&manifest_path))?;
+    let manifest_df = manifest_df
+        .lazy()
+        .select([
+            col("address").cast(DataType::String),
+            col("chain_id").cast(DataType::UInt64),
+            col("source").cast(DataType::String),
+        ])
+        .collect()
+        .map_err(|e| MiscellaneousError::InvalidManifest(e.to_string()))?;
+    let addresses = manifest_df.column("address")?.str()?;
+    let chain_ids = manifest_df.column("chain_id")?.u64()?;
+    let sources = manifest_df.column("source")?.str()?;
+
+    let client = http_client()?;
+    let semaphore = Arc::new(Semaphore::new(get_config().abi_reader.max_concurrent_abi_downloads));
+    let mut tasks = Vec::new();
+    for ((address, chain_id), source) in addresses.into_iter().zip(chain_ids).zip(sources) {
+        let address = address.unwrap_or("").to_string();
+        let source = source.unwrap_or("").to_string();
+        let client = client.clone();
+        let semaphore = semaphore.clone();
+        let task = tokio::spawn(async move {
+            let _permit = semaphore.acquire().await.expect("Semaphore should not be closed");
+            let result = fetch_manifest_entry(&client, &address, chain_id, &source).await;
+            (address, chain_id, source, result)
+        });
+        tasks.push(task);
+    }
+
+    let mut report_rows = Vec::new();
+    let mut abi_dfs = Vec::new();
+    for task in tasks {
+        let (address, chain_id, source, result) = task.await?;
+        let chain_id_str = chain_id.map_or("missing".to_string(), |c| c.to_string());
+        match result {
+            Ok(abi_df) => {
+                println!(
+                    "[{}] Fetched ABI for {} (chain_id: {}) from {}, {} items",
+                    Local::now().format("%Y-%m-%d %H:%M:%S"), address, chain_id_str, source, abi_df.height()
+                );
+                report_rows.push((address, chain_id, source, true, Some(abi_df.height() as u32), None));
+                abi_dfs.push(abi_df.lazy());
+            }
+            Err(e) => {
+                println!(
+                    "[{}] Failed to fetch ABI for {} (chain_id: {}) from {}: {}",
+                    Local::now().format("%Y-%m-%d %H:%M:%S"), address, chain_id_str, source, e
+                );
+                report_rows.push((address, chain_id, source, false, None, Some(e.to_string())));
+            }
+        }
+    }
+
+    // Append all fetched ABIs to the ABI DB, with a single deduplication pass
+    if !abi_dfs.is_empty() {
+        let path = Path::new(&abi_db_path);
+        if path.exists() {
+            abi_dfs.insert(0, abi_reader::read_existing_abi_db(path)?.lazy());
+        }
+        // diagonal, since only the ABIs read with abi_reader.store_abi_source have the abi_source column
+        let combined_df = concat_lf_diagonal(abi_dfs, UnionArgs::default())?.collect()?;
+        let mut combined_df = abi_reader::deduplicate_abi_df(combined_df, abi_reader::DedupStrategy::First)?;
+        utils::write_df_file(&mut combined_df, path)?;
+    }
+
+    let report_df = DataFrame::new(vec![
+        Series::new("address", report_rows.iter().map(|r| r.0.clone()).collect::<Vec<String>>()),
+        Series::new("chain_id", report_rows.iter().map(|r| r.1).collect::<Vec<Option<u64>>>()),
+        Series::new("source", report_rows.iter().map(|r| r.2.clone()).collect::<Vec<String>>()),
+        Series::new("success", report_rows.iter().map(|r| r.3).collect::<Vec<bool>>()),
+        Series::new("num_items", report_rows.iter().map(|r| r.4).collect::<Vec<Option<u32>>>()),
+        Series::new("error", report_rows.iter().map(|r| r.5.clone()).collect::<Vec<Option<String>>>()),
+    ])?;
+
+    Ok(report_df)
+}
+
+/// Downloads and reads the ABI of a single manifest entry
+async fn fetch_manifest_entry(client: &Client, address: &str, chain_id: Option<u64>, source: &str) -> Result<DataFrame, MiscellaneousError> {
+    let chain_id = chain_id.ok_or(MiscellaneousError::InvalidManifest(format!("missing chain_id for address {}", address)))?;
+    let contract_address = parse_contract_address(address)?;
+    let address = format!("{:#x}", contract_address);
+    let (abi, source) = match AbiSource::from_str(source)? {
+        AbiSource::Sourcify => download_sourcify_abi(client, chain_id, &address).await?,
+        AbiSource::Etherscan => download_etherscan_abi(client, chain_id, &address).await?,
+        source => return Err(MiscellaneousError::InvalidManifest(format!("source '{}' isn't supported in manifests", source))),
+    };
+    let abi_df = abi_reader::read_new_abi_json_with_chain_id(abi, contract_address, Some(chain_id))?;
+    Ok(with_abi_source(abi_df, &source)?)
+}
+
+/// Builds the HTTP client used by every ABI fetcher, from the abi_reader HTTP config
+///
+/// # Returns
+/// * If successful, a client with the `abi_reader.http_user_agent` User-Agent and the `abi_reader.http_timeout_secs`
+///   timeout per request (no timeout if 0).
+///
+/// # Notes
+/// Proxies are read from the HTTPS_PROXY, HTTP_PROXY and ALL_PROXY environment variables, and NO_PROXY lists the hosts
+/// reached without a proxy. Send the requests with `send_with_retry` to retry transient failures.
+pub fn http_client() -> Result<Client, MiscellaneousError> {
+    let config = get_config().abi_reader;
+    let mut builder = Client::builder().user_agent(config.http_user_agent);
+    if config.http_timeout_secs > 0 {
+        builder = builder.timeout(Duration::from_secs(config.http_timeout_secs as u64));
+    }
+    Ok(builder.build()?)
+}
+
+/// Sends an HTTP request, retrying it if it fails to connect, times out or gets a 429 or 5xx response.
+///
+/// # Arguments
+/// * `request` - Request built from a client returned by `http_client`
+///
+/// # Returns
+/// * If successful, the response. Other error statuses (i.e: 404) are returned as responses, without retrying.
+/// * `MiscellaneousError::ReqwestError` if the last attempt failed, including a 429/5xx status after the last retry.
+///
+/// # Notes
+/// The request is retried up to `abi_reader.http_retry_count` times, waiting `abi_reader.http_retry_backoff_ms`
+/// before the first retry and twice as long before each following one.
+///
+/// # Example
+/// ```no_run
+/// use glaciers::miscellaneous::{http_client, send_with_retry};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = http_client()?;
+///     let response = send_with_retry(client.get("https://sourcify.dev/server/health")).await?;
+///     println!("{}", response.status());
+///     Ok(())
+/// }
+/// ```
+pub async fn send_with_retry(request: RequestBuilder) -> Result<Response, MiscellaneousError> {
+    let config = get_config().abi_reader;
+    let mut attempt = 0;
+    loop {
+        // Requests with a streaming body can't be cloned, so they are sent only once
+        let Some(attempt_request) = request.try_clone() else {
+            return Ok(request.send().await?);
+        };
+        let result = attempt_request.send().await;
+        let retryable = match &result {
+            Ok(response) => response.status().is_server_error() || response.status() == StatusCode::TOO_MANY_REQUESTS,
+            Err(e) => e.is_connect() || e.is_timeout(),
+        };
+        if !retryable || attempt >= config.http_retry_count {
+            let response = result?;
+            // A 429/5xx status after the last retry is returned as an error
+            return Ok(if retryable { response.error_for_status()? } else { response });
+        }
+        attempt += 1;
+        let backoff = Duration::from_millis((config.http_retry_backoff_ms as u64) << (attempt - 1).min(16));
+        // The URL isn't printed, since it can include an API key
+        let reason = match &result {
+            Ok(response) => response.status().to_string(),
+            Err(e) if e.is_timeout() => "request timed out".to_string(),
+            Err(_) => "connection failed".to_string(),
+        };
+        println!(
+            "[{}] Retrying HTTP request ({}/{}) in {:?}: {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            attempt,
+            config.http_retry_count,
+            backoff,
+            reason
+        );
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+/// Downloads a contract ABI from the Sourcify repository, reading it from the metadata.json file
+async fn download_sourcify_abi(client: &Client, chain_id: u64, contract_address: &str) -> Result<(JsonAbi, AbiSource), MiscellaneousError> {
+    let request = client
+        .get(format!("{}/contracts/partial_match/{}/{}/metadata.json", get_config().abi_reader.sourcify_url, chain_id, contract_address));
+    let response = send_with_retry(request).await?;
     let json_response: serde_json::Value = response.json().await?;
     let abi_value = json_response
         .get("output")
         .ok_or(MiscellaneousError::InvalidJsonResponse(json_response.to_string()))?
         .get("abi")
         .ok_or(MiscellaneousError::InvalidJsonResponse(json_response.to_string()))?;
-    let abi: JsonAbi = serde_json::from_str(&abi_value.to_string()).map_err(|e| MiscellaneousError::InvalidJsonResponse(e.to_string()))?;
+    let abi = serde_json::from_str(&abi_value.to_string()).map_err(|e| MiscellaneousError::InvalidJsonResponse(e.to_string()))?;
+    Ok((abi, AbiSource::Sourcify))
+}
 
-    let contract_address = contract_address.to_lowercase();
-    let address = Address::from_str(&contract_address).map_err(|e| MiscellaneousError::InvalidAddress(e.to_string()))?;
+/// Downloads a contract ABI from the Etherscan API, using the ETHERSCAN_API_KEY environment variable
+async fn download_etherscan_abi(client: &Client, chain_id: u64, contract_address: &str) -> Result<(JsonAbi, AbiSource), MiscellaneousError> {
+    let api_key = std::env::var("ETHERSCAN_API_KEY").unwrap_or_default();
+    let request = client
+        .get(get_config().abi_reader.etherscan_url)
+        .query(&[
+            ("chainid", chain_id.to_string().as_str()),
+            ("module", "contract"),
+            ("action", "getabi"),
+            ("address", contract_address),
+            ("apikey", api_key.as_str()),
+        ]);
+    let response = send_with_retry(request).await?;
+    let json_response: serde_json::Value = response.json().await?;
+    // Etherscan returns status "1" on success, and the ABI as a JSON string in the result field
+    let abi_string = match (json_response.get("status").and_then(|s| s.as_str()), json_response.get("result").and_then(|r| r.as_str())) {
+        (Some("1"), Some(result)) => result,
+        _ => return Err(MiscellaneousError::InvalidJsonResponse(json_response.to_string())),
+    };
+    let abi = serde_json::from_str(abi_string).map_err(|e| MiscellaneousError::InvalidJsonResponse(e.to_string()))?;
+    Ok((abi, AbiSource::Etherscan))
+}
 
-    let abi_df = abi_reader::read_new_abi_json(abi, address)?;
-    let decoded_df = decoder::decode_df_with_abi_df(df, abi_df, decoder_type).await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configger::{set_config, ConfigValue};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
 
-    Ok(decoded_df)
-}
\ No newline at end of file
+    #[tokio::test]
+    async fn send_with_retry_retries_server_errors() {
+        // Local mock server, answering the first 2 requests with a 503
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server_requests = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buffer = [0u8; 1024];
+                let _ = socket.read(&mut buffer).await;
+                let status = if server_requests.fetch_add(1, Ordering::SeqCst) < 2 { "503 Service Unavailable" } else { "200 OK" };
+                let response = format!("HTTP/1.1 {}\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok", status);
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let config = get_config().abi_reader;
+        set_config("abi_reader.http_retry_backoff_ms", ConfigValue::Number(1)).unwrap();
+        let client = http_client().unwrap();
+        let response = send_with_retry(client.get(&url)).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "ok");
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // Without retries, the first 503 is returned as an error
+        requests.store(0, Ordering::SeqCst);
+        set_config("abi_reader.http_retry_count", ConfigValue::Number(0)).unwrap();
+        let result = send_with_retry(client.get(&url)).await;
+        set_config("abi_reader.http_retry_count", ConfigValue::Number(config.http_retry_count)).unwrap();
+        set_config("abi_reader.http_retry_backoff_ms", ConfigValue::Number(config.http_retry_backoff_ms)).unwrap();
+        assert!(result.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
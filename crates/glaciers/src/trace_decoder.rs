@@ -2,17 +2,23 @@
 //! 
 //! This module provides functions to:
 //! - Run through a DataFrame of traces calling the UDF (User Defined Function) each line
-//! - A UDF to decode a single trace line into a 6 parts string separated by ;
+//! - Add the same decoding steps to a LazyFrame plan, for streaming decoding
+//! - Flag which matched traces decode without errors, without building the decoded columns
+//! - A UDF to decode a single trace line into a 8 parts string separated by the unit separator character
 //! - A function to extract from an array of series the input, output and signature
 //! - A function to decode the trace line using the alloy library decode_inputs/decode_outputs function
+//! - A function to find the first of several candidate signatures a single trace decodes with
 //! - A function to map the decoded input/output parts into a StructuredParam for serialization
-use alloy::dyn_abi::{DynSolValue, FunctionExt, JsonAbiExt};
-use alloy::json_abi::Function;
+//! - A function to parse hex encoded gas, gas_used and value columns into integers
+//! - A function to compute the call_depth of each trace from its traceAddress array
+use alloy::dyn_abi::{DynSolType, DynSolValue, FunctionExt, JsonAbiExt, Specifier};
+use alloy::json_abi::{Function, Param};
+use alloy::primitives::U256;
 use polars::prelude::*;
 use thiserror::Error;
 
 use crate::configger::get_config;
-use crate::decoder::{DecoderError, StructuredParam};
+use crate::decoder::{self, DecoderError, ParamFormat, StructuredParam, DECODING_ERROR_PREFIX, UDF_SEPARATOR};
 use crate::utils;
 
 /// Error types specific to trace decoding operations.
@@ -24,6 +30,9 @@ pub enum TraceDecoderError {
     PolarsError(#[from] PolarsError),   
 }
 
+/// Trace fields extracted from each row: input, output and signature
+type TraceFields<'a> = (&'a [u8], &'a [u8], &'a str);
+
 /// Internal structure to hold each part of the decoded function
 struct ExtDecodedFunction {
     input_values: Vec<String>,
@@ -32,6 +41,8 @@ struct ExtDecodedFunction {
     output_values: Vec<String>,
     output_keys: Vec<String>, 
     output_json: String,
+    string_validity: bool,
+    truncated_values: bool,
 }
 
 /// Decodes EVM transaction traces in a DataFrame and decodes both the input
@@ -48,10 +59,48 @@ struct ExtDecodedFunction {
 ///   - output_values: Array of decoded output parameter values
 ///   - output_keys: Array of output parameter names  
 ///   - output_json: JSON string representation of decoded outputs
+///   - string_validity: False if any string param contained invalid UTF-8 and required a lossy conversion
+///   - decoding_error: The error message for traces that matched a signature but failed to decode (i.e: malformed input)
+///   - truncated_values: True if any input or output value was truncated, only if decoder.max_value_length is set
+///   - call_depth: The nesting level of each call, only if trace_decoder.trace_schema.trace_alias.trace_address is set
+///     (see `call_depth_expr`)
 ///
 /// # Notes
-/// The output format (binary/hex) of some columns is determined by configuration
+/// The output format (binary/hex) of some columns is determined by configuration.
+/// If decoder.drop_raw_columns is set, the raw input and output columns are removed from the output.
+/// The gas, gas_used and value columns are passed through untouched, unless trace_decoder.parse_hex_numeric_columns
+/// is set, in which case hex string columns are parsed (see `parse_hex_numeric_columns`).
 pub fn polars_decode_traces(df: DataFrame) -> Result<DataFrame, DecoderError> {
+    let df = if get_config().trace_decoder.parse_hex_numeric_columns {
+        parse_hex_numeric_columns(df)?
+    } else {
+        df
+    };
+    let lf = match call_depth_expr(&df.schema())? {
+        Some(expr) => df.lazy().with_columns([expr]),
+        None => df.lazy(),
+    };
+    let decoded_df = decode_traces_lazy(lf).collect()?;
+
+    Ok(if get_config().decoder.output_hex_string_encoding {
+        utils::binary_columns_to_hex_string(decoded_df)?
+    } else {
+        decoded_df
+    })
+}
+
+/// Adds the trace decoding steps of `polars_decode_traces` to a LazyFrame plan of matched traces.
+///
+/// # Arguments
+/// * `lf` - LazyFrame containing raw trace data and matching function signatures
+///
+/// # Returns
+/// The LazyFrame with the decoded columns of `polars_decode_traces`. Binary columns aren't converted to hex strings,
+/// and the gas, gas_used and value columns aren't parsed (see `parse_hex_numeric_exprs`).
+///
+/// # Notes
+/// The decoding UDF is an element-wise function, so the plan can be run by the Polars streaming engine.
+pub fn decode_traces_lazy(lf: LazyFrame) -> LazyFrame {
     let input_schema_alias = get_config().trace_decoder.trace_schema.trace_alias;
 
     // using the alias to select columns that will be used in the decode_trace_udf
@@ -61,61 +110,246 @@ pub fn polars_decode_traces(df: DataFrame) -> Result<DataFrame, DecoderError> {
         .map(|alias| col(alias.as_str()).alias(alias.as_str()))
         .collect();
     alias_exprs.push(col("full_signature").alias("full_signature"));
-    
+
+    let mut excluded_columns = vec!["decoded_trace".to_string()];
+    if get_config().decoder.drop_raw_columns {
+        excluded_columns.extend(input_schema_alias.as_array());
+    }
+    let truncated_values_exprs = if get_config().decoder.max_value_length > 0 {
+        vec![col("decoded_trace").str().split(lit(UDF_SEPARATOR)).list().get(lit(6)).eq(lit("true")).alias("truncated_values")]
+    } else {
+        vec![]
+    };
+
+
     // as_struct() passes the selected columns to the decode_trace_udf and returns a column decoded_trace of type String
-    // decoded_trace column is then split into 6 columns separated by the ; character
-    let decoded_df = df
-        .lazy()
+    // decoded_trace column is then split into its parts, separated by the unit separator character (UDF_SEPARATOR)
+    let decoded_lf = lf
         .with_columns([as_struct(alias_exprs)
             .map(decode_trace_udf, GetOutput::from_type(DataType::String))
             .alias("decoded_trace")
-        ])
+        ]);
+    // move decoding errors to the decoding_error column
+    decoder::split_decoding_error(decoded_lf, "decoded_trace")
         .with_columns([
             col("decoded_trace")
                 .str()
-                .split(lit(";"))
+                .split(lit(UDF_SEPARATOR))
                 .list()
                 .get(lit(0))
                 .alias("input_values"),
             col("decoded_trace")
                 .str()
-                .split(lit(";"))
+                .split(lit(UDF_SEPARATOR))
                 .list()
                 .get(lit(1))
                 .alias("input_keys"),
             col("decoded_trace")
                 .str()
-                .split(lit(";"))
+                .split(lit(UDF_SEPARATOR))
                 .list()
                 .get(lit(2))
                 .alias("input_json"),
             col("decoded_trace")
                 .str()
-                .split(lit(";"))
+                .split(lit(UDF_SEPARATOR))
                 .list()
                 .get(lit(3))
                 .alias("output_values"),
             col("decoded_trace")
                 .str()
-                .split(lit(";"))
+                .split(lit(UDF_SEPARATOR))
                 .list()
                 .get(lit(4))
                 .alias("output_keys"),
             col("decoded_trace")
                 .str()
-                .split(lit(";"))
+                .split(lit(UDF_SEPARATOR))
                 .list()
                 .get(lit(5))
-                .alias("output_json")
+                .alias("output_json"),
+            // string_validity is always the last part, and is converted to a boolean column
+            col("decoded_trace")
+                .str()
+                .split(lit(UDF_SEPARATOR))
+                .list()
+                .get(lit(-1))
+                .str()
+                .strip_chars(lit(Null {}))
+                .eq(lit("true"))
+                .alias("string_validity")
         ])
-        .select([col("*").exclude(["decoded_trace"])])
-        .collect()?;
+        .with_columns(truncated_values_exprs)
+        // Remove the original decoded_trace column, and the raw input and output columns if drop_raw_columns is set
+        .select([col("*").exclude(excluded_columns)])
+}
 
-    Ok(if get_config().decoder.output_hex_string_encoding {
-        utils::binary_columns_to_hex_string(decoded_df)?
-    } else {
-        decoded_df
-    })
+/// Adds a `decodable` Boolean column to a LazyFrame plan of matched traces, attempting to decode each trace
+/// without building the decoded columns.
+///
+/// # Arguments
+/// * `lf` - LazyFrame containing raw trace data and matching function signatures
+///
+/// # Returns
+/// The LazyFrame with a `decodable` column: true for traces that decode, false for traces that fail to decode
+/// (they would have a `decoding_error` with `decode_traces_lazy`) and null for traces without a matched signature
+/// and a well-formed input.
+///
+/// # Notes
+/// The values aren't converted to strings or serialized to JSON, so it's cheaper than `decode_traces_lazy`.
+pub fn decodable_traces_lazy(lf: LazyFrame) -> LazyFrame {
+    let input_schema_alias = get_config().trace_decoder.trace_schema.trace_alias;
+    let mut alias_exprs: Vec<Expr> = input_schema_alias.as_array()
+        .iter()
+        .map(|alias| col(alias.as_str()).alias(alias.as_str()))
+        .collect();
+    alias_exprs.push(col("full_signature").alias("full_signature"));
+
+    lf.with_columns([as_struct(alias_exprs)
+        .map(decodable_trace_udf, GetOutput::from_type(DataType::Boolean))
+        .alias("decodable")])
+}
+
+/// UDF (User Defined Function) attempting to decode individual trace entries, see `decodable_traces_lazy`.
+fn decodable_trace_udf(s: Series) -> PolarsResult<Option<Series>> {
+    let series_struct_array: &StructChunked = s.struct_()?;
+    let fields = series_struct_array.fields();
+    let traces_data = extract_trace_fields(fields)?;
+
+    let udf_output: BooleanChunked = traces_data
+        .into_iter()
+        .map(|trace_fields| {
+            if matches!(trace_fields, Ok((_, _, ""))) {
+                return None;
+            }
+            Some(trace_fields.and_then(|(input, output, func_sig)| decode_values(input, output, func_sig)).is_ok())
+        })
+        .collect();
+
+    Ok(Some(udf_output.into_series()))
+}
+
+/// Parses the hex encoded gas, gas_used and value columns of a traces DataFrame into integers.
+///
+/// # Arguments
+/// * `df` - DataFrame containing raw traces
+///
+/// # Returns
+/// * `Ok(DataFrame)` with the gas and gas_used columns as UInt64 and the value column as a base 10 integer string
+/// * `Err(DecoderError)` if a value isn't a valid integer
+///
+/// # Notes
+/// Only String columns are parsed; missing columns and columns that are already numeric are left untouched.
+/// Values with a "0x" prefix are read as hex and the others as base 10 integers.
+/// The value is kept as a String because a wei amount can overflow 64 bits.
+pub fn parse_hex_numeric_columns(df: DataFrame) -> Result<DataFrame, DecoderError> {
+    let parse_exprs = parse_hex_numeric_exprs(&df.schema());
+    if parse_exprs.is_empty() {
+        return Ok(df);
+    }
+    Ok(df.lazy().with_columns(parse_exprs).collect()?)
+}
+
+/// Builds the expressions parsing the hex encoded gas, gas_used and value columns, see `parse_hex_numeric_columns`.
+///
+/// # Arguments
+/// * `schema` - Schema of the traces DataFrame/LazyFrame
+///
+/// # Returns
+/// One expression for each of the gas, gas_used and value columns that is a String column in the schema
+pub fn parse_hex_numeric_exprs(schema: &Schema) -> Vec<Expr> {
+    let aliases = get_config().trace_decoder.trace_schema.trace_alias;
+    let is_string = |alias: &str| matches!(schema.get(alias), Some(DataType::String));
+
+    let mut parse_exprs: Vec<Expr> = Vec::new();
+    for alias in [aliases.gas, aliases.gas_used] {
+        if is_string(&alias) {
+            parse_exprs.push(
+                col(&alias)
+                    .map(parse_u64_udf, GetOutput::from_type(DataType::UInt64))
+                    .alias(&alias),
+            );
+        }
+    }
+    if is_string(&aliases.value) {
+        parse_exprs.push(
+            col(&aliases.value)
+                .map(parse_u256_udf, GetOutput::from_type(DataType::String))
+                .alias(&aliases.value),
+        );
+    }
+    parse_exprs
+}
+
+/// Builds the expression computing the call_depth column from the traceAddress column of the traces.
+///
+/// Traces from OpenEthereum/Erigon-like providers have a traceAddress array with the position of each call in the
+/// call tree, i.e: `[]` for the top-level call, `[0]` for its first subcall and `[0, 1]` for the second subcall of it.
+/// The call depth is the length of this array, so `call_depth == 0` selects the top-level calls.
+///
+/// # Arguments
+/// * `schema` - Schema of the traces DataFrame/LazyFrame
+///
+/// # Returns
+/// * `Ok(None)` if trace_decoder.trace_schema.trace_alias.trace_address isn't set
+/// * `Ok(Some(Expr))` with the UInt32 call_depth column, null if the traceAddress column isn't in the schema
+/// * `Err(DecoderError)` if the traceAddress column isn't a list column
+pub fn call_depth_expr(schema: &Schema) -> Result<Option<Expr>, DecoderError> {
+    let Some(trace_address) = get_config().trace_decoder.trace_schema.trace_alias.trace_address else {
+        return Ok(None)
+    };
+    let expr = match schema.get(&trace_address) {
+        Some(DataType::List(_)) => col(&trace_address).list().len().cast(DataType::UInt32),
+        Some(dtype) => return Err(DecoderError::DecodingError(
+            format!("The {} column must be a list of integers to compute call_depth, found {}", trace_address, dtype)
+        )),
+        None => lit(Null {}).cast(DataType::UInt32),
+    };
+    Ok(Some(expr.alias("call_depth")))
+}
+
+/// Splits an integer string into its digits and radix, reading a "0x" prefix as hex
+fn integer_digits_and_radix(s: &str) -> (&str, u32) {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => (hex, 16),
+        None => (s, 10),
+    }
+}
+
+/// UDF parsing a String Series of hex or base 10 integers into a UInt64 Series
+fn parse_u64_udf(s: Series) -> Result<Option<Series>, PolarsError> {
+    let parsed = s
+        .str()?
+        .into_iter()
+        .map(|opt| {
+            opt.map(|v| {
+                let (digits, radix) = integer_digits_and_radix(v);
+                u64::from_str_radix(digits, radix).map_err(|e| {
+                    PolarsError::ComputeError(format!("Invalid integer {:?} in column {}: {}", v, s.name(), e).into())
+                })
+            })
+            .transpose()
+        })
+        .collect::<Result<UInt64Chunked, PolarsError>>()?;
+    Ok(Some(parsed.with_name(s.name()).into_series()))
+}
+
+/// UDF parsing a String Series of hex or base 10 integers into a base 10 String Series
+fn parse_u256_udf(s: Series) -> Result<Option<Series>, PolarsError> {
+    let parsed = s
+        .str()?
+        .into_iter()
+        .map(|opt| {
+            opt.map(|v| {
+                let (digits, radix) = integer_digits_and_radix(v);
+                U256::from_str_radix(digits, radix as u64).map(|n| n.to_string()).map_err(|e| {
+                    PolarsError::ComputeError(format!("Invalid integer {:?} in column {}: {}", v, s.name(), e).into())
+                })
+            })
+            .transpose()
+        })
+        .collect::<Result<StringChunked, PolarsError>>()?;
+    Ok(Some(parsed.with_name(s.name()).into_series()))
 }
 
 /// UDF (User Defined Function) for decoding individual traces entries.
@@ -124,33 +358,45 @@ pub fn polars_decode_traces(df: DataFrame) -> Result<DataFrame, DecoderError> {
 /// * `s` - Series containing struct arrays of input, output and signature
 ///
 /// # Returns
-/// If successful, a Series containing decoded trace in a string format, separated by ;
-///   "input_values";"input_keys";"input_json";"output_values";"output_keys";"output_json"
+/// If successful, a Series containing decoded trace in a string format, separated by UDF_SEPARATOR (shown as ;)
+///   "input_values";"input_keys";"input_json";"output_values";"output_keys";"output_json";"truncated_values";"string_validity"
+/// Traces that failed to decode have the error message prefixed by DECODING_ERROR_PREFIX instead,
+/// and traces without a matched signature are null.
 ///
 fn decode_trace_udf(s: Series) -> PolarsResult<Option<Series>> {
     let series_struct_array: &StructChunked = s.struct_()?;
     let fields = series_struct_array.fields();
 
     //extract input, output and signature from the df struct arrays
-    let traces_data = extract_trace_fields(&fields)?;
+    let traces_data = extract_trace_fields(fields)?;
+    let format = ParamFormat::from_config(&get_config());
 
-    //iterate through each row value, calling the decode function and mapping it to a 6 parts result string separated by ;
+    //iterate through each row value, calling the decode function and mapping it to a 8 parts result string separated by UDF_SEPARATOR
     let udf_output: StringChunked = traces_data
         .into_iter()
-        .map(|(input, output, func_sig)| {
-            decode(input, output, func_sig)
+        .map(|trace_fields| {
+            // Traces without a matched signature aren't decoded
+            if matches!(trace_fields, Ok((_, _, ""))) {
+                return None;
+            }
+            let decoded = trace_fields.and_then(|(input, output, func_sig)| decode(input, output, func_sig, format))
                 .map(|func| {
                     format!(
-                        "{:?}; {:?}; {}; {:?}; {:?}; {}", 
+                        "{:?}{sep}{:?}{sep}{}{sep}{:?}{sep}{:?}{sep}{}{sep}{}{sep}{}",
                         func.input_values,
                         func.input_keys,
                         func.input_json,
                         func.output_values,
                         func.output_keys,
-                        func.output_json
+                        func.output_json,
+                        func.truncated_values,
+                        func.string_validity,
+                        sep = UDF_SEPARATOR
                     )
                 })
-                .ok()
+                // Decoding errors are stored in the table, in the decoding_error column
+                .unwrap_or_else(|e| format!("{}{}", DECODING_ERROR_PREFIX, e));
+            Some(decoded)
         })
         .collect();
 
@@ -159,7 +405,7 @@ fn decode_trace_udf(s: Series) -> PolarsResult<Option<Series>> {
 
 /// Extracts each trace field necessary for decoding from an array of Series.
 /// Translate [Series of input, Series of output, Series of signature] to Series of (input, output, signature)
-
+///
 /// # Arguments
 /// * `fields` - Slice of Series containing input, output and signature
 ///
@@ -168,7 +414,9 @@ fn decode_trace_udf(s: Series) -> PolarsResult<Option<Series>> {
 ///   - Vector of input as byte slice
 ///   - Vector of output as byte slice
 ///   - Event signature as string
-fn extract_trace_fields(fields: &[Series]) -> PolarsResult<Vec<(&[u8], &[u8], &str)>> {
+/// 
+/// Rows with a non empty input shorter than the 4 bytes selector (i.e: corrupted inputs) are returned as a DecodingError, instead of the tuple.
+fn extract_trace_fields(fields: &[Series]) -> PolarsResult<Vec<Result<TraceFields<'_>, TraceDecoderError>>> {
     //extract input, output and signature from the df struct arrays
     let fields_input = fields[0].binary()?;
     let fields_output = fields[1].binary()?;
@@ -177,14 +425,19 @@ fn extract_trace_fields(fields: &[Series]) -> PolarsResult<Vec<(&[u8], &[u8], &s
     //iterate through each row value, and map it to a tuple of input, output and signature
     fields_input
         .into_iter()
-        .zip(fields_output.into_iter())
-        .zip(fields_sig.into_iter())
+        .zip(fields_output)
+        .zip(fields_sig)
         .map(|((opt_input, opt_output), opt_sig)| {
             let inputs = opt_input.unwrap_or(&[]);
+            if (1..4).contains(&inputs.len()) {
+                return Ok(Err(TraceDecoderError::DecodingError(format!(
+                    "input has {} bytes, shorter than the 4 bytes selector", inputs.len()
+                ))));
+            }
             let outputs = opt_output.unwrap_or(&[]);
             let sigs = opt_sig.unwrap_or("");
 
-            Ok((inputs, outputs, sigs))
+            Ok(Ok((inputs, outputs, sigs)))
         }
         )
         .collect()
@@ -196,37 +449,39 @@ fn extract_trace_fields(fields: &[Series]) -> PolarsResult<Vec<(&[u8], &[u8], &s
 /// * `input` - Raw input data as bytes
 /// * `output` - Raw output data as bytes  
 /// * `full_signature` - Function signature string
+/// * `format` - How the params are named and the values converted to strings
 ///
 /// # Returns
-/// If successful, a struct containing 6 values:
+/// If successful, a struct containing 8 values:
 ///   - input_values: JSON string of decoded input parameter values
 ///   - input_keys: JSON string of input parameter names
 ///   - input_json: JSON string representation of the decoded inputs
 ///   - output_values: JSON string of decoded output parameter values
 ///   - output_keys: JSON string of output parameter names
 ///   - output_json: JSON string representation of the decoded outputs
+///   - string_validity: False if any string param required a lossy UTF-8 conversion
+///   - truncated_values: True if any value was truncated to decoder.max_value_length
 fn decode(
     input: &[u8],
     output: &[u8],
     full_signature: &str,
+    format: ParamFormat,
 ) -> Result<ExtDecodedFunction, TraceDecoderError> {
-    //parse the full signature to create the function object
-    let function_obj = Function::parse(full_signature)
-        .map_err(|e| TraceDecoderError::DecodingError(e.to_string()))?;
+    let (function_obj, decoded_input, decoded_output) = decode_values(input, output, full_signature)?;
 
-    // Decode input data calling the alloy abi_decode_input function
-    let decoded_input = function_obj
-        .abi_decode_input(input, true)
-        .map_err(|e| TraceDecoderError::DecodingError(e.to_string()))?;
-
-    // Decode output data calling the alloy abi_decode_output function  
-    let decoded_output = function_obj
-        .abi_decode_output(output, true)
-        .map_err(|e| TraceDecoderError::DecodingError(e.to_string()))?;
+    let resolve_types = |params: &[Param]| {
+        params
+            .iter()
+            .map(|param| param.resolve())
+            .collect::<Result<Vec<DynSolType>, _>>()
+            .map_err(|e| TraceDecoderError::DecodingError(e.to_string()))
+    };
+    let string_validity = !utils::has_lossy_string(&resolve_types(&function_obj.inputs)?, &decoded_input, input)
+        && !utils::has_lossy_string(&resolve_types(&function_obj.outputs)?, &decoded_output, output);
 
     // Map function inputs and values to structured format
-    let structured_inputs = map_function_params(&function_obj.inputs, &decoded_input)?;
-    let structured_outputs = map_function_params(&function_obj.outputs, &decoded_output)?;
+    let structured_inputs = map_function_params(&function_obj.inputs, &decoded_input, format)?;
+    let structured_outputs = map_function_params(&function_obj.outputs, &decoded_output, format)?;
 
     // Extract keys (param names)
     let input_keys: Vec<String> = structured_inputs.iter().map(|p| p.name.clone()).collect();
@@ -243,14 +498,14 @@ fn decode(
         .to_string();
 
     // Convert values to strings
-    let input_values: Vec<String> = decoded_input
+    let (input_values, input_truncated): (Vec<String>, Vec<bool>) = decoded_input
         .iter()
-        .map(|d| utils::StrDynSolValue::from(d.clone()).to_string().unwrap_or("None".to_string()))
-        .collect();
-    let output_values: Vec<String> = decoded_output
+        .map(|d| format.value_string(d))
+        .unzip();
+    let (output_values, output_truncated): (Vec<String>, Vec<bool>) = decoded_output
         .iter()
-        .map(|d| utils::StrDynSolValue::from(d.clone()).to_string().unwrap_or("None".to_string()))
-        .collect();
+        .map(|d| format.value_string(d))
+        .unzip();
 
     Ok(ExtDecodedFunction {
         input_values,
@@ -259,9 +514,78 @@ fn decode(
         output_values,
         output_keys,
         output_json,
+        string_validity,
+        truncated_values: input_truncated.contains(&true) || output_truncated.contains(&true),
     })
 }
 
+/// Decodes a single trace trying each candidate signature in order, keeping the first one the trace decodes with,
+/// like `log_decoder::decode_with_candidates` for logs
+///
+/// # Arguments
+/// * `input` - Raw input data as bytes, starting with the 4 bytes selector
+/// * `output` - Raw output data as bytes
+/// * `candidates` - Full function signatures to try, in priority order
+///
+/// # Returns
+/// If successful, the candidate the trace decoded with. A `TraceDecoderError` with the error of the last candidate
+/// if the trace doesn't decode with any of them, or if there are no candidates.
+pub(crate) fn decode_with_candidates(input: &[u8], output: &[u8], candidates: &[String]) -> Result<String, TraceDecoderError> {
+    if (1..4).contains(&input.len()) {
+        return Err(TraceDecoderError::DecodingError(format!(
+            "input has {} bytes, shorter than the 4 bytes selector", input.len()
+        )));
+    }
+    let format = ParamFormat::from_config(&get_config());
+    let mut last_error = TraceDecoderError::DecodingError("No candidate signatures to decode the trace with".to_string());
+    for candidate in candidates {
+        match decode(input, output, candidate, format) {
+            Ok(_) => return Ok(candidate.clone()),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// Parses the function signature and decodes the input and output data with Alloy's abi_decode_input/abi_decode_output functions
+///
+/// # Returns
+/// If successful, the parsed function, and its decoded input and output values
+fn decode_values(
+    input: &[u8],
+    output: &[u8],
+    full_signature: &str,
+) -> Result<(Function, Vec<DynSolValue>, Vec<DynSolValue>), TraceDecoderError> {
+    //parse the full signature to create the function object. Signatures with tuple params are retried without the tuple
+    //component names, that are set back after parsing
+    let function_obj = match Function::parse(full_signature) {
+        Ok(function_obj) => function_obj,
+        Err(_) => {
+            let mut function_obj = Function::parse(&utils::strip_tuple_component_names(full_signature))
+                .map_err(|e| TraceDecoderError::DecodingError(e.to_string()))?;
+            let names = utils::tuple_component_names(full_signature);
+            for (params, names) in [&mut function_obj.inputs, &mut function_obj.outputs].into_iter().zip(&names) {
+                for (param, names) in params.iter_mut().zip(names) {
+                    utils::set_component_names(&mut param.components, &names.components);
+                }
+            }
+            function_obj
+        },
+    };
+
+    // Decode input data calling the alloy abi_decode_input function
+    let decoded_input = function_obj
+        .abi_decode_input(input, true)
+        .map_err(|e| TraceDecoderError::DecodingError(e.to_string()))?;
+
+    // Decode output data calling the alloy abi_decode_output function  
+    let decoded_output = function_obj
+        .abi_decode_output(output, true)
+        .map_err(|e| TraceDecoderError::DecodingError(e.to_string()))?;
+
+    Ok((function_obj, decoded_input, decoded_output))
+}
+
 /// Maps function signature parameters names to their corresponding decoded values.
 /// This function is necessary because the source of param values (output of decode_input/decode_output) 
 /// is different from the source of param names (Signature - Function Object), and we want to keep them in the same order.
@@ -269,12 +593,15 @@ fn decode(
 /// # Arguments
 /// * `params` - Slice of function parameters from the ABI
 /// * `values` - Vector of decoded parameter values
+/// * `format` - How the params are named and the values converted to strings
 ///
 /// # Returns
-/// If successful, a vector of StructuredParam (each item of the log_json)
+/// If successful, a vector of StructuredParam (each item of the log_json). Unnamed params are named by their index
+/// (`arg0`, `arg1`, ...), unless `decoder.positional_names_when_unnamed` is false.
 fn map_function_params(
     params: &[alloy::json_abi::Param],
     values: &[DynSolValue],
+    format: ParamFormat,
 ) -> Result<Vec<StructuredParam>, TraceDecoderError> {
     // This error might be impossible, because it would make abi_decode_input/output fail before.
     if values.len() != params.len() {
@@ -286,12 +613,11 @@ fn map_function_params(
     //iterate through each param, and map it to a StructuredFunctionParam
     let mut structured_params = Vec::new();
     for (i, param) in params.iter().enumerate() {
-        let str_value = utils::StrDynSolValue::from(values[i].clone());
         let function_param = StructuredParam {
-            name: param.name.clone(),
+            name: format.param_name(&param.name, i),
             index: i as u32,
             value_type: param.ty.to_string(),
-            value: str_value.to_string().unwrap_or_else(|| "None".to_string()),
+            value: format.param_value(&values[i], &param.components),
         };
         structured_params.push(function_param);
     }
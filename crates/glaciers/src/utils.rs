@@ -3,18 +3,49 @@
 //! This module provides utility functions that are not part of the main functionality of the Glaciers.
 //! 
 //! The module provides the following functions:
-//!  - binary_columns_to_hex_string: Converts binary columns to hex string columns.
-//!  - hex_string_columns_to_binary: Converts hex string columns to binary columns.
+//!  - binary_columns_to_hex_string: Converts binary columns to hex string columns, also for a LazyFrame.
+//!  - hex_string_columns_to_binary: Converts hex string columns to binary columns, also for a LazyFrame.
 //!  - abi_df_hex_string_columns_to_binary: Converts hex string columns to binary columns in an ABI DataFrame.
+//!  - restore_abi_df_dtypes: Restores the dtypes of the ABI DB columns inferred when reading a csv file.
+//!  - to_hex_columns: Converts the chosen columns to hex string columns, without using the config.
+//!  - to_binary_columns: Converts the chosen columns to binary columns, without using the config.
+//!  - normalize_hex_string: Converts a single hex string into bytes.
+//!  - strip_tuple_component_names: Removes the names of tuple components from a full signature, so it can be parsed.
+//!  - tuple_component_names: Reads the names of the params and tuple components of a full signature, set back with set_component_names.
+//!  - read_abi_db_files: Reads and combines one or more ABI DB files into a single ABI DataFrame, caching the normalized files.
 //!  - read_df_file: Reads a DataFrame from a file.
-//!  - write_df_file: Writes a DataFrame to a file.
-//!  - StrDynSolValue: A wrapper type around DynSolValue, to implement to_string function.  
+//!  - read_raw_df_file: Reads a raw logs/traces file, reading only the needed columns of parquet files.
+//!  - scan_df_path: Lazily scans a file, or all the csv/parquet files of a folder.
+//!  - write_df_file: Writes a DataFrame to a file, with binary columns as hex strings in csv and json files.
+//!  - write_json_array: Writes a DataFrame as a pretty-printed JSON array of objects.
+//!  - write_df_file_with_metadata: Writes a DataFrame to a file, with key-value metadata in parquet files.
+//!  - read_parquet_metadata: Reads the key-value metadata of a parquet file.
+//!  - StrDynSolValue: A wrapper type around DynSolValue, to implement to_string function, with the bytes encoding of the config.  
+//!  - has_lossy_string: Checks if the raw bytes of any decoded string value aren't valid UTF-8.
 
-use std::{ffi::OsStr, fs::File, path::Path};
-use polars::{error::ErrString, prelude::*};
-use alloy::dyn_abi::DynSolValue;
-use crate::configger::{self, get_config};
+use std::{collections::HashMap, ffi::OsStr, fs::File, io::{BufWriter, Write}, path::{Path, PathBuf}, sync::{LazyLock, Mutex}, time::SystemTime};
+use polars::{error::ErrString, io::mmap::MmapBytesReader, prelude::*};
+use alloy::dyn_abi::{DynSolType, DynSolValue};
+use alloy::json_abi::{parser::{Parameters, TypeStem}, Param};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use crate::abi_reader;
+use crate::configger::{self, get_config, BytesEncoding};
 use crate::decoder::DecoderType;
+use crate::log_decoder;
+
+/// Maximum number of ABI DB files kept in `ABI_DB_CACHE`
+const ABI_DB_CACHE_SIZE: usize = 8;
+
+/// Normalized ABI DB files read in this process, keyed by path, see `read_abi_db_file_cached`
+static ABI_DB_CACHE: LazyLock<Mutex<HashMap<PathBuf, CachedAbiDb>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A normalized ABI DB file, with the file modification time and size, and the hash column name it was normalized with
+struct CachedAbiDb {
+    file_version: (SystemTime, u64),
+    hash_column: String,
+    abi_df: DataFrame,
+}
 
 /// Converts binary columns to hex string columns. Used when outputting hex strings, instead of binary.
 /// 
@@ -23,17 +54,38 @@ use crate::decoder::DecoderType;
 /// 
 /// # Returns
 /// * If successful, a DataFrame with the converted columns.
+/// 
+/// # Notes
+/// * `List<Binary>` columns (i.e: an array of topics) are converted to `List<String>`, encoding each element.
 pub fn binary_columns_to_hex_string(df: DataFrame) -> Result<DataFrame, PolarsError> {
+    binary_columns_to_hex_string_lazy(df.lazy())?.collect()
+}
+
+/// Converts the binary columns of a LazyFrame plan to hex string columns.
+/// 
+/// # Arguments
+/// * `lf` - The LazyFrame to convert
+/// 
+/// # Returns
+/// * If successful, the LazyFrame with the conversion added to the plan.
+pub fn binary_columns_to_hex_string_lazy(lf: LazyFrame) -> Result<LazyFrame, PolarsError> {
+    let schema = lf.schema()?;
     // Get names of binary columns
-    let binary_cols: Vec<String> = df.schema()
+    let binary_cols: Vec<String> = schema
         .iter()
         .filter(|(_, dtype)| matches!(dtype, DataType::Binary))
         .map(|(name, _)| name.to_string())
         .collect();
+    // Get names of list of binary columns
+    let binary_list_cols: Vec<String> = schema
+        .iter()
+        .filter(|(_, dtype)| matches!(dtype, DataType::List(inner) if **inner == DataType::Binary))
+        .map(|(name, _)| name.to_string())
+        .collect();
 
     // Return early if no binary columns
-    if binary_cols.is_empty() {
-        return Ok(df.clone());
+    if binary_cols.is_empty() && binary_list_cols.is_empty() {
+        return Ok(lf);
     }
 
     // Create hex encode expressions
@@ -46,15 +98,27 @@ pub fn binary_columns_to_hex_string(df: DataFrame) -> Result<DataFrame, PolarsEr
         .map(|name| concat_str([lit("0x"), col(name)], "", true).alias(name))
         .collect();
 
+    // Create hex encode and 0x prefix expressions for each element of the lists
+    let list_exprs: Vec<Expr> = binary_list_cols.iter()
+        .map(|name| {
+            col(name)
+                .list()
+                .eval(concat_str([lit("0x"), col("").binary().hex_encode()], "", true), false)
+                .alias(name)
+        })
+        .collect();
+
     // Apply transformations
-    df.lazy()
+    Ok(lf
         .with_columns(hex_exprs)
         .with_columns(prefix_exprs)
-        .collect()
+        .with_columns(list_exprs))
 }
 
 /// Converts columns from logs/traces dataframes from hex string to binary columns.
 /// Only the necessary columns are converted, based on the input schema in the configs.
+/// Only the alias columns of the decoder type are touched, so a DataFrame holding the columns of both the log
+/// and the trace schemas keeps the other type's columns as they are.
 /// 
 /// # Arguments
 /// * `df` - The DataFrame to convert
@@ -63,9 +127,26 @@ pub fn binary_columns_to_hex_string(df: DataFrame) -> Result<DataFrame, PolarsEr
 /// # Returns
 /// * If successful, a DataFrame with the converted columns.
 pub fn hex_string_columns_to_binary(df: DataFrame, decoder_type: &DecoderType) -> Result<DataFrame, PolarsError> {
+    hex_string_columns_to_binary_lazy(df.lazy(), decoder_type).collect()
+}
+
+/// Converts columns from logs/traces LazyFrame plans from hex string to binary columns, see `hex_string_columns_to_binary`.
+/// 
+/// # Arguments
+/// * `lf` - The LazyFrame to convert
+/// * `decoder_type` - The type of decoder to use
+/// 
+/// # Returns
+/// * The LazyFrame with the conversion added to the plan.
+pub fn hex_string_columns_to_binary_lazy(lf: LazyFrame, decoder_type: &DecoderType) -> LazyFrame {
+    // A topics list column is expanded first, so its elements are converted like the alias columns
+    let lf = match decoder_type {
+        DecoderType::Log => log_decoder::fill_missing_topic_columns_lazy(log_decoder::expand_topics_list_lazy(lf)),
+        DecoderType::Trace => lf,
+    };
     let (input_schema_datatype, input_schema_alias) = match decoder_type {
-        DecoderType::Log => (get_config().log_decoder.log_schema.log_datatype.as_array(), get_config().log_decoder.log_schema.log_alias.as_array()),
-        DecoderType::Trace => (get_config().trace_decoder.trace_schema.trace_datatype.as_array(), get_config().trace_decoder.trace_schema.trace_alias.as_array()),
+        DecoderType::Log => (get_config().log_decoder.log_schema.log_datatype.as_array(), log_datatype_aliases()),
+        DecoderType::Trace => (get_config().trace_decoder.trace_schema.trace_datatype.as_array(), trace_datatype_aliases()),
     };
 
     let bin_exprs: Vec<Expr> = input_schema_datatype
@@ -74,7 +155,21 @@ pub fn hex_string_columns_to_binary(df: DataFrame, decoder_type: &DecoderType) -
         .filter(|(f, _alias)| matches!(f, configger::DataType::HexString))
         .map(|(_f, alias)| col(alias.as_str()).str().strip_prefix(lit("0x")).str().hex_decode(true).alias(alias.as_str()))
         .collect();
-    df.lazy().with_columns(bin_exprs).collect()   
+    lf.with_columns(bin_exprs)
+}
+
+/// Auxiliary function returning the log alias columns in the same order as `LogDatatypeConfig::as_array`
+fn log_datatype_aliases() -> Vec<String> {
+    let log_alias = get_config().log_decoder.log_schema.log_alias;
+    let mut aliases = log_alias.as_array();
+    aliases.push(log_alias.address);
+    aliases
+}
+
+/// Auxiliary function returning the trace alias columns in the same order as `TraceDatatypeConfig::as_array`
+fn trace_datatype_aliases() -> Vec<String> {
+    let trace_alias = get_config().trace_decoder.trace_schema.trace_alias;
+    vec![trace_alias.selector, trace_alias.action_input, trace_alias.result_output, trace_alias.action_to]
 }
 
 /// Converts columns from hex string to binary columns if the ABI DB was saved as hex strings.
@@ -84,28 +179,402 @@ pub fn hex_string_columns_to_binary(df: DataFrame, decoder_type: &DecoderType) -
 /// 
 /// # Returns
 /// * If successful, a DataFrame with the converted columns.
-pub fn abi_df_hex_string_columns_to_binary(mut abi_df: DataFrame) -> Result<DataFrame, PolarsError> {
+/// 
+/// # Notes
+/// * The hash column name is taken from the config (`abi_reader.hash_column_name`).
+/// * The ABI DB dtypes of the columns inferred when reading a csv file are restored, see `restore_abi_df_dtypes`.
+pub fn abi_df_hex_string_columns_to_binary(abi_df: DataFrame) -> Result<DataFrame, PolarsError> {
+   let abi_df = restore_abi_df_dtypes(abi_df)?;
+
    // Convert hash and address columns to binary if they aren't already
-   let columns_to_convert = ["hash", "address"];
+   let hash_column = get_config().abi_reader.hash_column_name;
+   let columns_to_convert = [hash_column.as_str(), "address"];
 
+   let mut bin_exprs: Vec<Expr> = Vec::new();
    for col_name in columns_to_convert {
-       if abi_df
-           .column(col_name)?
-           .dtype() != &DataType::Binary {
-               abi_df = abi_df
-                   .lazy()
-                   .with_columns([
-                       col(col_name)
-                           .str()
-                           .strip_prefix(lit("0x"))
-                           .str()
-                           .hex_decode(true)
-                           .alias(col_name)
-                   ])
-                   .collect()?;
+       // ABI DataFrames without addresses (i.e: from a protocol-level ABI) can still be matched by hash
+       if col_name == "address" && !abi_df.get_column_names().contains(&"address") {
+           continue;
+       }
+       if abi_df.column(col_name)?.dtype() != &DataType::Binary {
+           bin_exprs.push(col(col_name).str().strip_prefix(lit("0x")).str().hex_decode(true).alias(col_name));
        }
    }
-   Ok(abi_df)
+   // ABI DBs saved with binary columns (the default) are returned without collecting a new DataFrame
+   if bin_exprs.is_empty() {
+       return Ok(abi_df);
+   }
+   abi_df.lazy().with_columns(bin_exprs).collect()
+}
+
+/// Restores the ABI DB dtypes of the columns inferred when reading an ABI DB saved as csv.
+/// 
+/// # Arguments
+/// * `abi_df` - The DataFrame to convert
+/// 
+/// # Returns
+/// * If successful, a DataFrame with num_indexed_args as UInt32 and chain_id as UInt64, when present, like `abi_reader::output_schema`.
+/// 
+/// # Notes
+/// * CSV readers infer num_indexed_args as Int64, and chain_id as String when all its values are null.
+pub fn restore_abi_df_dtypes(abi_df: DataFrame) -> Result<DataFrame, PolarsError> {
+    let cast_exprs: Vec<Expr> = [("num_indexed_args", DataType::UInt32), ("chain_id", DataType::UInt64)]
+        .into_iter()
+        .filter(|(name, dtype)| abi_df.column(name).is_ok_and(|s| s.dtype() != dtype))
+        .map(|(name, dtype)| col(name).cast(dtype))
+        .collect();
+    if cast_exprs.is_empty() {
+        return Ok(abi_df);
+    }
+    abi_df.lazy().with_columns(cast_exprs).collect()
+}
+
+/// Converts the chosen columns to 0x prefixed hex string columns, without using the config.
+/// 
+/// # Arguments
+/// * `df` - The DataFrame to convert
+/// * `columns` - Names of the columns to convert
+/// 
+/// # Returns
+/// * If successful, a DataFrame with the converted columns.
+/// * An error naming the offending column, if a column is missing or isn't binary or string.
+/// 
+/// # Notes
+/// * The conversion is idempotent: binary columns are hex encoded, string columns only get the 0x prefix if it's missing.
+pub fn to_hex_columns(df: DataFrame, columns: &[String]) -> Result<DataFrame, PolarsError> {
+    let mut hex_exprs: Vec<Expr> = Vec::new();
+    for name in columns {
+        let dtype = df.column(name)
+            .map_err(|_| PolarsError::ColumnNotFound(ErrString::from(format!("Column '{}' not found", name))))?
+            .dtype();
+        match dtype {
+            DataType::Binary => hex_exprs.push(concat_str([lit("0x"), col(name).binary().hex_encode()], "", true).alias(name)),
+            DataType::String => hex_exprs.push(
+                when(col(name).str().starts_with(lit("0x")))
+                    .then(col(name))
+                    .otherwise(concat_str([lit("0x"), col(name)], "", true))
+                    .alias(name)
+            ),
+            _ => return Err(PolarsError::SchemaMismatch(ErrString::from(format!("Column '{}' has type {}, expected binary or string", name, dtype)))),
+        }
+    }
+    df.lazy().with_columns(hex_exprs).collect()
+}
+
+/// Converts the chosen columns to binary columns, without using the config.
+/// 
+/// # Arguments
+/// * `df` - The DataFrame to convert
+/// * `columns` - Names of the columns to convert
+/// 
+/// # Returns
+/// * If successful, a DataFrame with the converted columns.
+/// * An error naming the offending column, if a column is missing, isn't binary or string, or has invalid hex strings.
+/// 
+/// # Notes
+/// * The conversion is idempotent: string columns are hex decoded (with or without the 0x prefix), binary columns are kept as they are.
+pub fn to_binary_columns(mut df: DataFrame, columns: &[String]) -> Result<DataFrame, PolarsError> {
+    for name in columns {
+        let dtype = df.column(name)
+            .map_err(|_| PolarsError::ColumnNotFound(ErrString::from(format!("Column '{}' not found", name))))?
+            .dtype()
+            .clone();
+        match dtype {
+            DataType::Binary => {},
+            DataType::String => {
+                // Decoding each column separately, so the error can name the offending column
+                let binary_df = df.clone()
+                    .lazy()
+                    .select([col(name).str().strip_prefix(lit("0x")).str().hex_decode(true).alias(name)])
+                    .collect()
+                    .map_err(|e| PolarsError::ComputeError(ErrString::from(format!("Column '{}' has invalid hex strings: {}", name, e))))?;
+                df.with_column(binary_df.column(name)?.clone())?;
+            },
+            _ => return Err(PolarsError::SchemaMismatch(ErrString::from(format!("Column '{}' has type {}, expected binary or string", name, dtype)))),
+        }
+    }
+    Ok(df)
+}
+
+/// Converts a single hex string into bytes.
+/// 
+/// # Arguments
+/// * `s` - The hex string to convert, with or without the 0x (or 0X) prefix
+/// 
+/// # Returns
+/// * If successful, the decoded bytes.
+/// * An error naming the invalid character, if the string has non hex characters.
+/// 
+/// # Notes
+/// * Odd-length hex strings are accepted, being left padded with a "0" (i.e: "0x123" is decoded as "0x0123").
+/// 
+/// # Example
+/// ```rust
+/// use glaciers::utils::normalize_hex_string;
+///
+/// assert_eq!(normalize_hex_string("0xA9059cbb").unwrap(), vec![0xa9, 0x05, 0x9c, 0xbb]);
+/// assert_eq!(normalize_hex_string("0X123").unwrap(), vec![0x01, 0x23]);
+/// assert_eq!(normalize_hex_string("").unwrap(), Vec::<u8>::new());
+/// assert!(normalize_hex_string("0xzz").is_err());
+/// ```
+pub fn normalize_hex_string(s: &str) -> Result<Vec<u8>, PolarsError> {
+    let trimmed = s.trim();
+    let hex_digits = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+    if let Some(c) = hex_digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(PolarsError::ComputeError(ErrString::from(format!("Invalid hex string '{}': invalid character '{}'", s, c))));
+    }
+
+    let hex_digits = if hex_digits.len() % 2 == 1 {
+        format!("0{}", hex_digits)
+    } else {
+        hex_digits.to_string()
+    };
+    alloy::hex::decode(&hex_digits)
+        .map_err(|e| PolarsError::ComputeError(ErrString::from(format!("Invalid hex string '{}': {}", s, e))))
+}
+
+/// Removes the names of tuple components from a full signature, so it can be parsed by alloy.
+/// 
+/// Alloy writes tuple params in full signatures as `tuple(address maker, uint256 amount)`, but its parser
+/// doesn't accept names inside tuples. Names of the top level params (and the `indexed` keyword) are kept.
+/// 
+/// # Arguments
+/// * `full_signature` - The full signature of the event or function
+/// 
+/// # Returns
+/// * The full signature without the tuple component names.
+/// 
+/// # Example
+/// ```rust
+/// use glaciers::utils::strip_tuple_component_names;
+///
+/// assert_eq!(
+///     strip_tuple_component_names("event OrderFilled(tuple(address maker, uint256 amount) indexed order, uint256 amount)"),
+///     "event OrderFilled(tuple(address,uint256) indexed order, uint256 amount)"
+/// );
+/// assert_eq!(strip_tuple_component_names("event Transfer(address indexed from)"), "event Transfer(address indexed from)");
+/// ```
+pub fn strip_tuple_component_names(full_signature: &str) -> String {
+    let mut stripped = String::with_capacity(full_signature.len());
+    let mut depth = 0;
+    // Inside tuples, whether the type of the current component was already written
+    let mut component_started = false;
+    // Inside tuples, whether the chars are part of a component name, being skipped
+    let mut skipping_name = false;
+    for c in full_signature.chars() {
+        match c {
+            '(' => {
+                depth += 1;
+                component_started = false;
+                skipping_name = false;
+                stripped.push(c);
+            },
+            ')' | ',' => {
+                if c == ')' {
+                    depth -= 1;
+                }
+                component_started = c == ')';
+                skipping_name = false;
+                stripped.push(c);
+            },
+            _ if depth < 2 => stripped.push(c),
+            _ if c.is_whitespace() => skipping_name = component_started,
+            _ if skipping_name => {},
+            _ => {
+                component_started = true;
+                stripped.push(c);
+            },
+        }
+    }
+    stripped
+}
+
+/// Names of a param and of its tuple components, read from a full signature by `tuple_component_names`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParamNames {
+    pub name: String,
+    pub components: Vec<ParamNames>,
+}
+
+/// Reads the names of the params and tuple components of a full signature.
+/// 
+/// It's the counterpart of `strip_tuple_component_names`: signatures parsed by alloy without their tuple component
+/// names get them back with `set_component_names`, i.e: to name the fields of the tuple values in the decoded JSON.
+/// 
+/// # Arguments
+/// * `full_signature` - The full signature of the event or function
+/// 
+/// # Returns
+/// * One list of params for each params list of the signature: the inputs, followed by the outputs of functions.
+///   Params lists that alloy can't parse are empty.
+/// 
+/// # Example
+/// ```rust
+/// use glaciers::utils::tuple_component_names;
+///
+/// let lists = tuple_component_names("function fill(tuple(address maker, uint256[] amounts)[] orders) returns (bool ok)");
+/// assert_eq!(lists.len(), 2);
+/// assert_eq!(lists[0][0].name, "orders");
+/// let components: Vec<&str> = lists[0][0].components.iter().map(|c| c.name.as_str()).collect();
+/// assert_eq!(components, vec!["maker", "amounts"]);
+/// assert_eq!(lists[1][0].name, "ok");
+/// ```
+pub fn tuple_component_names(full_signature: &str) -> Vec<Vec<ParamNames>> {
+    parenthesized_groups(full_signature)
+        .into_iter()
+        .map(|list| param_names_list(list).unwrap_or_default())
+        .collect()
+}
+
+/// Reads the names of a params list, with its parentheses, using alloy's `Parameters` parser.
+///
+/// Alloy's parser doesn't accept names inside tuples, so each tuple of the list is replaced by a `tuple` type
+/// before parsing it, and the names of its components are read from the replaced tuple.
+fn param_names_list(list: &str) -> Option<Vec<ParamNames>> {
+    let inner = list.strip_prefix('(')?.strip_suffix(')')?;
+    let tuples = parenthesized_groups(inner);
+    let mut flattened = String::with_capacity(list.len());
+    flattened.push('(');
+    let mut rest = inner;
+    for tuple in &tuples {
+        let (before, after) = rest.split_at(rest.find(tuple)?);
+        flattened.push_str(before);
+        if !before.ends_with("tuple") {
+            flattened.push_str("tuple");
+        }
+        rest = &after[tuple.len()..];
+    }
+    flattened.push_str(rest);
+    flattened.push(')');
+
+    let params = Parameters::parse(&flattened).ok()?;
+    let mut tuples = tuples.into_iter();
+    Some(
+        params
+            .params
+            .iter()
+            .map(|param| {
+                let components = match &param.ty.stem {
+                    TypeStem::Root(root) if root.span() == "tuple" => {
+                        tuples.next().and_then(param_names_list).unwrap_or_default()
+                    },
+                    _ => vec![],
+                };
+                ParamNames { name: param.name.unwrap_or_default().to_string(), components }
+            })
+            .collect(),
+    )
+}
+
+/// Splits the outermost parenthesized groups of a string, i.e: the params lists of a full signature, or the tuples
+/// of a params list. Each group is returned with its parentheses.
+fn parenthesized_groups(s: &str) -> Vec<&str> {
+    let mut groups = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => {
+                if depth == 0 {
+                    start = i;
+                }
+                depth += 1;
+            },
+            ')' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    groups.push(&s[start..=i]);
+                }
+            },
+            _ => {},
+        }
+    }
+    groups
+}
+
+/// Sets the names of tuple components parsed without them, from the names read by `tuple_component_names`.
+/// Components already named, or lists with another number of components than of names, are unchanged.
+pub fn set_component_names(components: &mut [Param], names: &[ParamNames]) {
+    if components.len() != names.len() {
+        return;
+    }
+    for (component, names) in components.iter_mut().zip(names) {
+        if component.name.is_empty() {
+            component.name = names.name.clone();
+        }
+        set_component_names(&mut component.components, &names.components);
+    }
+}
+
+/// Reads one or more ABI DB files and combines them into a single ABI DataFrame.
+/// 
+/// # Arguments
+/// * `paths` - The paths to the ABI DB files to read
+/// 
+/// # Returns
+/// * If successful, a DataFrame with the items of all ABI DBs.
+/// 
+/// # Notes
+/// * Each DB has its hash and address columns normalized to binary before being combined.
+/// * Columns missing in some of the DBs are filled with nulls.
+/// * When more than one DB is read, duplicated items (same hash, full_signature and address) are removed, keeping the first one.
+/// * Normalized DBs are cached in the process, see `read_abi_db_file_cached`, so decoding many files with the same DB
+///   (i.e: in decode_folder) reads and normalizes it only once.
+pub fn read_abi_db_files(paths: &[String]) -> Result<DataFrame, PolarsError> {
+    let abi_dfs = paths
+        .iter()
+        .map(|path| read_abi_db_file_cached(Path::new(path)))
+        .collect::<Result<Vec<DataFrame>, PolarsError>>()?;
+
+    match abi_dfs.len() {
+        0 => Err(PolarsError::ComputeError(ErrString::from("No ABI DB path was provided"))),
+        1 => Ok(abi_dfs.into_iter().next().unwrap()),
+        _ => {
+            let lazy_dfs: Vec<LazyFrame> = abi_dfs.into_iter().map(|df| df.lazy()).collect();
+            concat_lf_diagonal(lazy_dfs, UnionArgs { to_supertypes: true, ..Default::default() })?
+                .unique_stable(
+                    Some(vec![get_config().abi_reader.hash_column_name, "full_signature".to_string(), "address".to_string()]),
+                    UniqueKeepStrategy::First
+                )
+                .collect()
+        }
+    }
+}
+
+/// Reads a single ABI DB file with its hash and address columns normalized to binary, using the in-process cache.
+///
+/// The cached DataFrame is returned while the file modification time and size, and the abi_reader.hash_column_name,
+/// are the same as when it was cached, so ABI DBs updated on disk are read again. Files without a modification time
+/// aren't cached. The cache keeps up to `ABI_DB_CACHE_SIZE` files, and is cleared when it's full.
+fn read_abi_db_file_cached(path: &Path) -> Result<DataFrame, PolarsError> {
+    let file_version = std::fs::metadata(path).and_then(|metadata| Ok((metadata.modified()?, metadata.len()))).ok();
+    let hash_column = get_config().abi_reader.hash_column_name;
+    if let Some(file_version) = file_version {
+        if let Some(cached) = ABI_DB_CACHE.lock().unwrap().get(path) {
+            if cached.file_version == file_version && cached.hash_column == hash_column {
+                return Ok(cached.abi_df.clone());
+            }
+        }
+    }
+
+    let abi_df = abi_df_hex_string_columns_to_binary(abi_reader::read_abi_db_file(path)?)?;
+    if let Some(file_version) = file_version {
+        let mut cache = ABI_DB_CACHE.lock().unwrap();
+        if cache.len() >= ABI_DB_CACHE_SIZE && !cache.contains_key(path) {
+            cache.clear();
+        }
+        cache.insert(path.to_path_buf(), CachedAbiDb { file_version, hash_column, abi_df: abi_df.clone() });
+    }
+    Ok(abi_df)
+}
+
+/// Opens a file, keeping open errors as `PolarsError::Io` with the path in the message, so they can be told apart from
+/// parsing errors, i.e: to retry transient errors of network filesystems
+pub(crate) fn open_file(path: &Path) -> Result<File, PolarsError> {
+    File::open(path).map_err(|e| PolarsError::Io(std::io::Error::new(e.kind(), format!("Error opening path {}: {}", path.display(), e))))
 }
 
 /// Reads a DataFrame from a file.
@@ -115,19 +584,152 @@ pub fn abi_df_hex_string_columns_to_binary(mut abi_df: DataFrame) -> Result<Data
 /// 
 /// # Returns
 /// * If successful, a DataFrame with the read data.
+/// 
+/// # Notes
+/// * CSV files are read with the delimiter, header and quote char options in the `io` config section.
+/// * If io.csv_alias_columns_as_string is set, the log and trace alias columns used for decoding are read as strings,
+///   instead of inferring their dtype (i.e: a topic column with only digits inferred as int64).
+/// * JSON files aren't read, since only ABI DBs are saved as a JSON array of objects, see `abi_reader::read_abi_db_file`.
 pub fn read_df_file(path: &Path) -> Result<DataFrame, PolarsError> {
     let path_ext = path.extension();
     if path_ext == Some(OsStr::new("parquet")) {
-        ParquetReader::new(File::open(path).map_err(|e| PolarsError::ComputeError(ErrString::from(format!("Error opening path {}: {}" , path.display(), e.to_string()))))?)
+        ParquetReader::new(open_file(path)?)
             .finish()
     } else if path_ext == Some(OsStr::new("csv")) {
-        CsvReader::new(File::open(path).map_err(|e| PolarsError::ComputeError(ErrString::from(format!("Error opening path {}: {}" , path.display(), e.to_string()))))?)
+        let io_config = get_config().io;
+        let dtypes = io_config.csv_alias_columns_as_string.then(|| Arc::new(alias_columns_string_schema()));
+        CsvReader::new(open_file(path)?)
+            .with_separator(io_config.csv_delimiter.as_bytes().first().copied().unwrap_or(b','))
+            .has_header(io_config.csv_has_header)
+            .with_quote_char(io_config.csv_quote_char.as_bytes().first().copied())
+            .with_dtypes(dtypes)
             .finish()
     } else {
         Err(PolarsError::ComputeError(ErrString::from(format!("In the path {}, a file extension was not provided (csv or parquet)", path.display()))))
     }
 }
 
+/// Reads a raw logs/traces DataFrame from a file, only reading the needed columns of parquet files.
+/// 
+/// # Arguments
+/// * `path` - The path to the file to read
+/// * `decoder_type` - Type of the raw data, used to select the alias columns
+/// 
+/// # Returns
+/// * If successful, a DataFrame with the read data.
+/// 
+/// # Notes
+/// * See `raw_columns_projection` for the read columns. CSV files are read with all columns, like in `read_df_file`.
+pub fn read_raw_df_file(path: &Path, decoder_type: &DecoderType) -> Result<DataFrame, PolarsError> {
+    if path.extension() == Some(OsStr::new("parquet")) {
+        read_raw_parquet(open_file(path)?, decoder_type)
+    } else {
+        read_df_file(path)
+    }
+}
+
+/// Reads a raw logs/traces parquet, only reading the columns of `raw_columns_projection` that are in the file.
+/// 
+/// # Arguments
+/// * `reader` - The parquet file or bytes to read
+/// * `decoder_type` - Type of the raw data, used to select the alias columns
+/// 
+/// # Returns
+/// * If successful, a DataFrame with the read columns, in the file order.
+pub fn read_raw_parquet<R: MmapBytesReader>(reader: R, decoder_type: &DecoderType) -> Result<DataFrame, PolarsError> {
+    let mut reader = ParquetReader::new(reader);
+    let Some(projection) = raw_columns_projection(decoder_type) else {
+        return reader.finish()
+    };
+    let columns: Vec<String> = reader
+        .schema()?
+        .fields
+        .iter()
+        .filter(|field| projection.contains(&field.name))
+        .map(|field| field.name.clone())
+        .collect();
+    reader.with_columns(Some(columns)).finish()
+}
+
+/// Returns the raw logs/traces columns to read, or None if all columns are read.
+/// 
+/// # Arguments
+/// * `decoder_type` - Type of the raw data, used to select the alias columns
+/// 
+/// # Returns
+/// * None if decoder.passthrough_columns contains "*" (the default), otherwise the alias columns used for
+///   matching and decoding, the chain_id column used for matching and the decoder.passthrough_columns.
+pub fn raw_columns_projection(decoder_type: &DecoderType) -> Option<Vec<String>> {
+    let passthrough_columns = get_config().decoder.passthrough_columns;
+    if passthrough_columns.iter().any(|column| column == "*") {
+        return None
+    }
+    let alias_columns = match decoder_type {
+        DecoderType::Log => {
+            let mut aliases = log_datatype_aliases();
+            aliases.push(get_config().log_decoder.topics_list_column);
+            aliases
+        },
+        DecoderType::Trace => {
+            let trace_alias = get_config().trace_decoder.trace_schema.trace_alias;
+            let mut aliases = trace_datatype_aliases();
+            aliases.extend([trace_alias.gas, trace_alias.gas_used, trace_alias.value]);
+            aliases.extend(trace_alias.trace_address);
+            aliases
+        },
+    };
+    Some(alias_columns
+        .into_iter()
+        .chain(std::iter::once(String::from("chain_id")))
+        .chain(get_config().decoder.timestamp_column)
+        .chain(passthrough_columns)
+        .collect())
+}
+
+/// Auxiliary function to build a schema with the log and trace alias columns used for decoding as strings.
+/// Columns missing in the file are ignored by the CsvReader.
+fn alias_columns_string_schema() -> Schema {
+    log_datatype_aliases()
+        .into_iter()
+        .chain(trace_datatype_aliases())
+        .map(|alias| Field::new(&alias, DataType::String))
+        .collect()
+}
+
+/// Lazily scans a file, or all the csv/parquet files in a folder (not recursive).
+/// 
+/// # Arguments
+/// * `path` - The path to the file or folder to scan
+/// 
+/// # Returns
+/// * If successful, a LazyFrame with the data of the file, or of all the files of the folder combined.
+/// 
+/// # Notes
+/// * Columns missing in some of the files are filled with nulls.
+pub fn scan_df_path(path: &Path) -> Result<LazyFrame, PolarsError> {
+    if path.is_dir() {
+        let mut file_paths: Vec<_> = std::fs::read_dir(path)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| matches!(path.extension().and_then(|ext| ext.to_str()), Some("parquet") | Some("csv")))
+            .collect();
+        file_paths.sort();
+        if file_paths.is_empty() {
+            return Err(PolarsError::ComputeError(ErrString::from(format!("No csv or parquet files found in the folder {}", path.display()))));
+        }
+        let lazy_dfs = file_paths
+            .iter()
+            .map(|file_path| scan_df_path(file_path))
+            .collect::<Result<Vec<LazyFrame>, PolarsError>>()?;
+        return concat_lf_diagonal(lazy_dfs, UnionArgs::default());
+    }
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("parquet") => LazyFrame::scan_parquet(path, ScanArgsParquet::default()),
+        Some("csv") => LazyCsvReader::new(path).finish(),
+        _ => Err(PolarsError::ComputeError(ErrString::from(format!("In the path {}, a file extension was not provided (csv or parquet)", path.display()))))
+    }
+}
+
 /// Writes a DataFrame to a file.
 /// 
 /// # Arguments
@@ -136,42 +738,221 @@ pub fn read_df_file(path: &Path) -> Result<DataFrame, PolarsError> {
 /// 
 /// # Returns
 /// * If successful, a DataFrame with the read data.
+/// 
+/// # Notes
+/// * CSV is a text format, so the binary columns are written as 0x prefixed hex strings, whatever the encoding configs.
+///   They can be read back with `read_df_file`, and converted to binary with `hex_string_columns_to_binary`
+///   or `abi_df_hex_string_columns_to_binary`.
+/// * ndjson_pretty files, for human inspection, have one pretty-printed JSON object per row, see `write_ndjson_pretty`.
+/// * json files, used for ABI DBs, are a single pretty-printed JSON array of objects, see `write_json_array`.
 pub fn write_df_file(df: &mut DataFrame, path: &Path) -> Result<(), PolarsError> {
     let mut file = File::create(path).map_err(|e| PolarsError::ComputeError(ErrString::from(e.to_string())))?;
     
     match path.extension().and_then(|ext| ext.to_str()) {
         Some("parquet") => ParquetWriter::new(&mut file).finish(df).map(|_| ()),
-        Some("csv") => CsvWriter::new(&mut file).finish(df),
-        _ => Err(PolarsError::ComputeError(ErrString::from(format!("In the path {}, a file extension was not provided (csv or parquet)", path.display()))))
+        Some("csv") => CsvWriter::new(&mut file).finish(&mut binary_columns_to_hex_string(df.clone())?),
+        Some("ndjson_pretty") => write_ndjson_pretty(df, BufWriter::new(file)),
+        Some("json") => write_json_array(df, BufWriter::new(file)),
+        _ => Err(PolarsError::ComputeError(ErrString::from(format!("In the path {}, a file extension was not provided (csv, json, parquet or ndjson_pretty)", path.display()))))
     }?;
     Ok(())
 }
 
+/// Writes a DataFrame to a file, adding key-value metadata to the footer of parquet files.
+///
+/// # Arguments
+/// * `df` - The DataFrame to write
+/// * `path` - The path to the file to write
+/// * `metadata` - Key-value pairs stored in the parquet file metadata, i.e: the version that wrote the file
+///
+/// # Returns
+/// * Ok(()) if successful, or a PolarsError if the file can't be written.
+///
+/// # Notes
+/// * csv and ndjson_pretty files have no metadata, so they are written like `write_df_file`, without it.
+pub fn write_df_file_with_metadata(df: &mut DataFrame, path: &Path, metadata: &[(String, String)]) -> Result<(), PolarsError> {
+    if path.extension().and_then(|ext| ext.to_str()) != Some("parquet") {
+        return write_df_file(df, path);
+    }
+    let mut file = File::create(path).map_err(|e| PolarsError::ComputeError(ErrString::from(e.to_string())))?;
+    df.align_chunks();
+    let mut writer = ParquetWriter::new(&mut file).batched(&df.schema())?;
+    writer.write_batch(df)?;
+    let key_value_metadata = metadata
+        .iter()
+        .map(|(key, value)| polars_parquet::write::KeyValue::new(key.clone(), Some(value.clone())))
+        .collect();
+    writer.get_writer().lock().unwrap().end(Some(key_value_metadata))?;
+    Ok(())
+}
+
+/// Reads the key-value metadata of a parquet file, i.e: written by `write_df_file_with_metadata`.
+///
+/// # Arguments
+/// * `path` - The path to the parquet file
+///
+/// # Returns
+/// * If successful, a map of the metadata keys to their values. Keys without a value are skipped.
+pub fn read_parquet_metadata(path: &Path) -> Result<HashMap<String, String>, PolarsError> {
+    let file = File::open(path).map_err(|e| PolarsError::ComputeError(ErrString::from(e.to_string())))?;
+    let metadata = ParquetReader::new(file).get_metadata()?.clone();
+    Ok(metadata
+        .key_value_metadata()
+        .iter()
+        .flatten()
+        .filter_map(|kv| kv.value.clone().map(|value| (kv.key.clone(), value)))
+        .collect())
+}
+
+/// Writes a DataFrame as pretty-printed JSON objects, one per row, separated by blank lines.
+///
+/// # Arguments
+/// * `df` - The DataFrame to write
+/// * `writer` - The writer to write to, i.e: a file
+///
+/// # Returns
+/// * Ok(()) if successful, or a PolarsError if a row can't be serialized or written.
+///
+/// # Notes
+/// Each row is an object with the column names as keys, in the column order. Binary columns are written as
+/// 0x prefixed hex strings, like in CSV files, and lists as JSON arrays. Other dtypes without a JSON equivalent
+/// (i.e: dates) are written as their string representation.
+pub fn write_ndjson_pretty<W: Write>(df: &DataFrame, mut writer: W) -> Result<(), PolarsError> {
+    let to_polars_error = |e: String| PolarsError::ComputeError(ErrString::from(e));
+    let df = binary_columns_to_hex_string(df.clone())?;
+    let columns = df.get_columns();
+    for i in 0..df.height() {
+        let mut row = Vec::with_capacity(columns.len());
+        for column in columns {
+            row.push((column.name(), any_value_to_json(column.get(i)?)));
+        }
+        let json = serde_json::to_string_pretty(&JsonRow(row)).map_err(|e| to_polars_error(e.to_string()))?;
+        let separator = if i == 0 { "" } else { "\n" };
+        writeln!(writer, "{}{}", separator, json).map_err(|e| to_polars_error(e.to_string()))?;
+    }
+    writer.flush().map_err(|e| to_polars_error(e.to_string()))
+}
+
+/// Writes a DataFrame as a pretty-printed JSON array of objects, one per row, i.e: ABI DBs kept in git.
+///
+/// # Arguments
+/// * `df` - The DataFrame to write
+/// * `writer` - The writer of the JSON array
+///
+/// # Returns
+/// * Ok(()) if successful, or a PolarsError if the DataFrame can't be converted or written.
+///
+/// # Notes
+/// The objects are written like in `write_ndjson_pretty`, with binary columns as 0x prefixed hex strings,
+/// and can be read back with `abi_reader::read_abi_db_json`.
+pub fn write_json_array<W: Write>(df: &DataFrame, mut writer: W) -> Result<(), PolarsError> {
+    let to_polars_error = |e: String| PolarsError::ComputeError(ErrString::from(e));
+    let df = binary_columns_to_hex_string(df.clone())?;
+    let columns = df.get_columns();
+    let mut rows = Vec::with_capacity(df.height());
+    for i in 0..df.height() {
+        let mut row = Vec::with_capacity(columns.len());
+        for column in columns {
+            row.push((column.name(), any_value_to_json(column.get(i)?)));
+        }
+        rows.push(JsonRow(row));
+    }
+    serde_json::to_writer_pretty(&mut writer, &rows).map_err(|e| to_polars_error(e.to_string()))?;
+    writeln!(writer).map_err(|e| to_polars_error(e.to_string()))?;
+    writer.flush().map_err(|e| to_polars_error(e.to_string()))
+}
+
+/// A DataFrame row serialized as a JSON object, keeping the column order (serde_json::Map sorts the keys).
+struct JsonRow<'a>(Vec<(&'a str, serde_json::Value)>);
+
+impl Serialize for JsonRow<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in &self.0 {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Converts a Polars AnyValue into a JSON value, used by `write_ndjson_pretty` and `write_json_array`.
+fn any_value_to_json(value: AnyValue) -> serde_json::Value {
+    match value {
+        AnyValue::Null => serde_json::Value::Null,
+        AnyValue::Boolean(b) => serde_json::Value::Bool(b),
+        AnyValue::UInt8(v) => v.into(),
+        AnyValue::UInt16(v) => v.into(),
+        AnyValue::UInt32(v) => v.into(),
+        AnyValue::UInt64(v) => v.into(),
+        AnyValue::Int8(v) => v.into(),
+        AnyValue::Int16(v) => v.into(),
+        AnyValue::Int32(v) => v.into(),
+        AnyValue::Int64(v) => v.into(),
+        // NaN and infinite floats have no JSON representation, so they are written as null
+        AnyValue::Float32(v) => serde_json::Number::from_f64(v as f64).map_or(serde_json::Value::Null, serde_json::Value::Number),
+        AnyValue::Float64(v) => serde_json::Number::from_f64(v).map_or(serde_json::Value::Null, serde_json::Value::Number),
+        AnyValue::String(s) => serde_json::Value::String(s.to_string()),
+        AnyValue::StringOwned(s) => serde_json::Value::String(s.to_string()),
+        AnyValue::List(series) => serde_json::Value::Array(series.iter().map(any_value_to_json).collect()),
+        other => serde_json::Value::String(other.to_string()),
+    }
+}
+
 /// Wrapper type around DynSolValue, to implement to_string function.
+///
+/// Integers are rendered in base 10. Signed integers (`int8` to `int256`) keep their sign, i.e: `-1`,
+/// not their two's complement `2^256 - 1`.
+///
+/// # Example
+/// ```
+/// use alloy::dyn_abi::DynSolValue;
+/// use alloy::primitives::I256;
+/// use glaciers::log_decoder::decode_log_from_bytes;
+/// use glaciers::utils::StrDynSolValue;
+///
+/// assert_eq!(StrDynSolValue::from(DynSolValue::Int(I256::MINUS_ONE, 256)).to_string(), Some("-1".to_string()));
+///
+/// // An int256 of -1 in the data, and the minimum int256 in an indexed topic
+/// let mut int256_min = [0u8; 32];
+/// int256_min[0] = 0x80;
+/// let decoded = decode_log_from_bytes(
+///     "event PositionChanged(int256 indexed delta, int256 amount)",
+///     &[&[0u8; 32], &int256_min],
+///     &[0xffu8; 32],
+/// ).unwrap();
+/// assert_eq!(decoded.event_values, vec![I256::MIN.to_string(), "-1".to_string()]);
+/// assert!(decoded.event_values[0].starts_with("-5789604461865809771178549250434395392663"));
+/// ```
 pub struct StrDynSolValue(DynSolValue);
 
 impl StrDynSolValue {
+    /// Renders the value, with the dynamic bytes values encoded as in decoder.bytes_encoding
     pub fn to_string(&self) -> Option<String> {
+        self.to_string_with(&get_config().decoder.bytes_encoding)
+    }
+
+    /// Renders the value, with the dynamic bytes values encoded as in `bytes_encoding`, see `encode_bytes`
+    pub fn to_string_with(&self, bytes_encoding: &BytesEncoding) -> Option<String> {
         match &self.0 {
             DynSolValue::Bool(b) => Some(b.to_string()),
             DynSolValue::Int(i, _) => Some(i.to_string()),
             DynSolValue::Uint(u, _) => Some(u.to_string()),
-            DynSolValue::FixedBytes(w, _) => Some(format!("0x{}", w.to_string())),
+            DynSolValue::FixedBytes(w, size) => Some(format!("0x{}", alloy::hex::encode(&w[..*size]))),
             DynSolValue::Address(a) => Some(a.to_string()),
             DynSolValue::Function(f) => Some(f.to_string()),
-            DynSolValue::Bytes(b) => Some(format!("0x{}", b.iter().map(|b| format!("{:02x}", b)).collect::<String>())),
+            DynSolValue::Bytes(b) => Some(encode_bytes(b, bytes_encoding)),
             DynSolValue::String(s) => Some(s.clone()),
             DynSolValue::Array(arr) => Some(format!(
                 "[{}]",
                 arr.iter()
-                    .filter_map(|v| Self::from(v.clone()).to_string())
+                    .filter_map(|v| Self::from(v.clone()).to_string_with(bytes_encoding))
                     .collect::<Vec<_>>()
                     .join(", ")
             )),
             DynSolValue::FixedArray(arr) => Some(format!(
                 "[{}]",
                 arr.iter()
-                    .filter_map(|v| Self::from(v.clone()).to_string())
+                    .filter_map(|v| Self::from(v.clone()).to_string_with(bytes_encoding))
                     .collect::<Vec<_>>()
                     .join(", ")
             )),
@@ -179,7 +960,7 @@ impl StrDynSolValue {
                 "({})",
                 tuple
                     .iter()
-                    .filter_map(|v| Self::from(v.clone()).to_string())
+                    .filter_map(|v| Self::from(v.clone()).to_string_with(bytes_encoding))
                     .collect::<Vec<_>>()
                     .join(", ")
             )),
@@ -192,3 +973,161 @@ impl From<DynSolValue> for StrDynSolValue {
         StrDynSolValue(value)
     }
 }
+
+/// Prefix of the hex strings of the `bytes` values that aren't valid UTF-8, in the `BytesEncoding::Utf8Lossy` encoding
+pub const UTF8_FALLBACK_HEX_PREFIX: &str = "hex:";
+
+/// Encodes a dynamic bytes value (`bytes` params) for the decoded columns
+///
+/// # Arguments
+/// * `bytes` - The decoded bytes
+/// * `bytes_encoding` - The encoding, from decoder.bytes_encoding
+///
+/// # Returns
+/// * `Hex`: the 0x prefixed lowercase hex string
+/// * `Base64`: the standard base64 string, with padding
+/// * `Utf8Lossy`: the UTF-8 text. Bytes that aren't valid UTF-8 aren't converted with replacement characters,
+///   they are kept as the 0x prefixed hex string instead, so no data is lost. This hex string is marked with the
+///   `UTF8_FALLBACK_HEX_PREFIX` prefix (i.e: "hex:0xff00"), so it can be told apart from text like "0xff00".
+///
+/// # Example
+/// ```rust
+/// use glaciers::configger::BytesEncoding;
+/// use glaciers::utils::encode_bytes;
+///
+/// assert_eq!(encode_bytes(b"revert", &BytesEncoding::Hex), "0x726576657274");
+/// assert_eq!(encode_bytes(b"revert", &BytesEncoding::Base64), "cmV2ZXJ0");
+/// assert_eq!(encode_bytes(b"revert", &BytesEncoding::Utf8Lossy), "revert");
+/// assert_eq!(encode_bytes(b"0xff00", &BytesEncoding::Utf8Lossy), "0xff00");
+/// assert_eq!(encode_bytes(&[0xff, 0x00], &BytesEncoding::Utf8Lossy), "hex:0xff00");
+/// ```
+pub fn encode_bytes(bytes: &[u8], bytes_encoding: &BytesEncoding) -> String {
+    match bytes_encoding {
+        BytesEncoding::Hex => format!("0x{}", alloy::hex::encode(bytes)),
+        BytesEncoding::Base64 => BASE64_STANDARD.encode(bytes),
+        BytesEncoding::Utf8Lossy => match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_string(),
+            Err(_) => format!("{}0x{}", UTF8_FALLBACK_HEX_PREFIX, alloy::hex::encode(bytes)),
+        },
+    }
+}
+
+/// Returns the name of a decoded param, or its positional name (`arg0`, `arg1`, ...) if the ABI left it unnamed.
+///
+/// Signatures sourced from 4byte-like directories have no param names, i.e: `Transfer(address,address,uint256)`,
+/// which would otherwise produce keys full of empty strings.
+///
+/// # Arguments
+/// * `name` - The param name from the ABI
+/// * `index` - The param index in the decoded params
+/// * `positional_names` - Whether to fall back to positional names (`decoder.positional_names_when_unnamed`)
+///
+/// # Example
+/// ```rust
+/// use glaciers::utils::param_name_or_positional;
+///
+/// assert_eq!(param_name_or_positional("", 1, true), "arg1");
+/// assert_eq!(param_name_or_positional("", 1, false), "");
+/// assert_eq!(param_name_or_positional("value", 2, true), "value");
+/// ```
+pub fn param_name_or_positional(name: &str, index: usize, positional_names: bool) -> String {
+    if name.is_empty() && positional_names {
+        format!("arg{}", index)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Truncates a decoded value string longer than a maximum length, i.e: on-chain blobs of megabytes.
+///
+/// # Arguments
+/// * `value` - The decoded value, as a string
+/// * `max_length` - Maximum length in bytes of the value. 0 means unlimited.
+///
+/// # Returns
+/// * The value cut to `max_length` bytes (on a char boundary) followed by a `…(truncated, <length> bytes)` suffix
+///   with the full length of the value, and true, if it's longer than `max_length`. Otherwise, the same value and false.
+///
+/// # Example
+/// ```rust
+/// use glaciers::utils::truncate_value;
+///
+/// assert_eq!(truncate_value("0x0102030405".to_string(), 6), ("0x0102…(truncated, 12 bytes)".to_string(), true));
+/// assert_eq!(truncate_value("0x0102".to_string(), 6), ("0x0102".to_string(), false));
+/// assert_eq!(truncate_value("0x0102030405".to_string(), 0), ("0x0102030405".to_string(), false));
+/// ```
+pub fn truncate_value(mut value: String, max_length: usize) -> (String, bool) {
+    if max_length == 0 || value.len() <= max_length {
+        return (value, false);
+    }
+    let full_length = value.len();
+    let mut cut = max_length;
+    while !value.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    value.truncate(cut);
+    value.push_str(&format!("…(truncated, {} bytes)", full_length));
+    (value, true)
+}
+
+/// Checks if any string in the decoded values required a lossy UTF-8 conversion.
+///
+/// Alloy decodes `string` params using lossy UTF-8 conversion, so the decoded values don't tell invalid bytes from
+/// a valid replacement character (U+FFFD). Values with the replacement character are decoded again from the raw data,
+/// with their strings as bytes, and these bytes are checked with `std::str::from_utf8`. Nested arrays and tuples are
+/// also checked.
+///
+/// # Arguments
+/// * `types` - The types of the decoded params
+/// * `values` - The decoded values of the params
+/// * `data` - The ABI encoded data the params were decoded from
+///
+/// # Returns
+/// * `true` if the raw bytes of any string value aren't valid UTF-8, `false` otherwise.
+pub fn has_lossy_string(types: &[DynSolType], values: &[DynSolValue], data: &[u8]) -> bool {
+    if !has_replacement_character(values) {
+        return false;
+    }
+    let bytes_types = DynSolType::Tuple(types.iter().map(strings_as_bytes).collect());
+    match bytes_types.abi_decode_sequence(data) {
+        Ok(DynSolValue::Tuple(raw_values)) => {
+            types.iter().zip(&raw_values).any(|(ty, raw_value)| has_invalid_utf8(ty, raw_value))
+        },
+        _ => true,
+    }
+}
+
+/// Checks if any string in the decoded values contains the replacement character (U+FFFD)
+fn has_replacement_character(values: &[DynSolValue]) -> bool {
+    values.iter().any(|value| match value {
+        DynSolValue::String(s) => s.contains(char::REPLACEMENT_CHARACTER),
+        DynSolValue::Array(arr) | DynSolValue::FixedArray(arr) | DynSolValue::Tuple(arr) => has_replacement_character(arr),
+        _ => false,
+    })
+}
+
+/// Replaces the string types of a type by bytes, to decode the raw bytes of the strings
+fn strings_as_bytes(ty: &DynSolType) -> DynSolType {
+    match ty {
+        DynSolType::String => DynSolType::Bytes,
+        DynSolType::Array(inner) => DynSolType::Array(Box::new(strings_as_bytes(inner))),
+        DynSolType::FixedArray(inner, size) => DynSolType::FixedArray(Box::new(strings_as_bytes(inner)), *size),
+        DynSolType::Tuple(types) => DynSolType::Tuple(types.iter().map(strings_as_bytes).collect()),
+        ty => ty.clone(),
+    }
+}
+
+/// Checks if the raw bytes of any string of a value, decoded with `strings_as_bytes`, aren't valid UTF-8
+fn has_invalid_utf8(ty: &DynSolType, raw_value: &DynSolValue) -> bool {
+    match (ty, raw_value) {
+        (DynSolType::String, DynSolValue::Bytes(bytes)) => std::str::from_utf8(bytes).is_err(),
+        (DynSolType::Array(inner), DynSolValue::Array(values))
+        | (DynSolType::FixedArray(inner, _), DynSolValue::FixedArray(values)) => {
+            values.iter().any(|value| has_invalid_utf8(inner, value))
+        },
+        (DynSolType::Tuple(types), DynSolValue::Tuple(values)) => {
+            types.iter().zip(values).any(|(ty, value)| has_invalid_utf8(ty, value))
+        },
+        _ => false,
+    }
+}
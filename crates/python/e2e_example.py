@@ -162,9 +162,9 @@ print(f"\n Decoded logs saved in the decoded folder.\n\n")
 # - `abi_db_path`: Optional, default in config. Path to the abi parquet file
 #
 # # Returns
-# A `PyResult` containing a decoded logs/traces DataFrame or an error
+# A `DecodeFileResult`, with the decoded logs/traces DataFrame in `df`, the file paths and row counts
 trace_file = os.path.join(traces_folder_path, os.listdir(traces_folder_path)[0])  # Get first trace file
-decoded_df = gl.decode_file(decoder_type="trace", file_path=trace_file, abi_db_path=functions_abi_file_path)
+decoded_df = gl.decode_file(decoder_type="trace", file_path=trace_file, abi_db_path=functions_abi_file_path).df
 print(f"\nDecoded Traces in the trace file {trace_file}:\n{decoded_df.head()}\n\n")
 
 
@@ -180,7 +180,7 @@ print(f"\nDecoded Traces in the trace file {trace_file}:\n{decoded_df.head()}\n\
 # - `abi_db_path`: Optional, default in config. Path to the abi parquet file
 #
 # # Returns
-# A `PyResult` containing a decoded logs/traces DataFrame or an error
+# A `DecodeFileResult`, with the decoded logs/traces DataFrame in `df`, the file paths and row counts
 #
 # # Errors
 # Returns a `PyValueError` if there are issues processing the logs
@@ -203,7 +203,7 @@ print(f"\nDecoded Logs DataFrame:\n{decoded_df.head()}\n\n")
 # - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
 #
 # # Returns
-# A `PyResult` containing a decoded logs/traces DataFrame or an error
+# A `DecodeFileResult`, with the decoded logs/traces DataFrame in `df`, the file paths and row counts
 logs_df_path = os.path.join(logs_folder_path, os.listdir(logs_folder_path)[0])  # Get first logs file
 logs_df = pl.read_parquet(logs_df_path)
 abi_df = pl.read_parquet(events_abi_file_path)
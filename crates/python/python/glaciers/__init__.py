@@ -12,41 +12,123 @@ Functions:
 
     ABI Management:
         update_abi_db(): Update an ABI database file with new ABIs from a folder
+        update_abi_db_with_progress(): Update an ABI database file, with a progress callback and the counts of the update
         read_new_abi_folder(): Read ABIs from a folder and return a DataFrame
+        read_new_abi_folder_parallel(): Read the subfolders of a folder in parallel, skipping the subfolders with corrupt ABI files
         read_new_abi_file(): Read ABI from a file and return a DataFrame
         read_new_abi_json(): Parse ABI from JSON string and return a DataFrame
+        deduplicate_abi_df(): Deduplicate an ABI DataFrame using a chosen strategy
+        validate_num_indexed_args(): Check that the num_indexed_args of an ABI DataFrame match its event signatures
+        convert_abi_db_encoding(): Convert an ABI DB file between binary and hex string encodings
+        abi_reader_output_schema(): Get the schema of the ABI DataFrames produced under the current config
+        async_fetch_abis_from_manifest(): Asynchronously download the ABIs listed in a manifest into the ABI DB
+        fetch_abis_from_manifest(): Download the ABIs listed in a manifest into the ABI DB
 
     Log/Traces Decoding:
         async_decode_folder(): Asynchronously decode logs from a folder
         decode_folder(): Decode logs from a folder
+        async_decode_folder_incremental(): Asynchronously decode the files of a folder modified after a watermark
+        decode_folder_incremental(): Decode the files of a folder modified after a watermark
+        plan_folder(): List the files of a folder that decode_folder would decode, with their decoded file paths
         async_decode_file(): Asynchronously decode logs from a file
         decode_file(): Decode logs from a file
+        DecodeFileResult: Result of decode_file, with the decoded DataFrame, the file paths and row counts
+        async_decode_archive(): Asynchronously decode the parquet files inside a .tar.gz archive
+        decode_archive(): Decode the parquet files inside a .tar.gz archive
+        async_decode_partition(): Asynchronously decode a single partition file into an explicit output path
+        decode_partition(): Decode a single partition file into an explicit output path
         async_decode_df(): Asynchronously decode logs in a DataFrame
         decode_df(): Decode logs in a DataFrame
         async_decode_df_with_abi_df(): Decode logs using custom ABI DataFrame
         decode_df_with_abi_df(): Decode logs using custom ABI DataFrame
+        async_decode_tx_bundle(): Asynchronously decode the logs and traces of a transaction bundle together
+        decode_tx_bundle(): Decode the logs and traces of a transaction bundle together
+        async_decode_df_with_context(): Decode logs using custom ABI DataFrame, adding context columns
+        decode_df_with_context(): Decode logs using custom ABI DataFrame, adding context columns
+        async_decode_df_with_priority(): Decode logs using custom ABI DataFrame, with ordered candidate signatures for some hashes
+        decode_df_with_priority(): Decode logs using custom ABI DataFrame, with ordered candidate signatures for some hashes
+        async_decode_df_with_overrides(): Decode logs using custom ABI DataFrame, overriding the matched signature of some hashes
+        decode_df_with_overrides(): Decode logs using custom ABI DataFrame, overriding the matched signature of some hashes
+        async_decode_to_wide(): Decode logs using custom ABI DataFrame, pivoting each signature params into columns
+        decode_to_wide(): Decode logs using custom ABI DataFrame, pivoting each signature params into columns
+        async_decode_and_aggregate(): Decode a single signature using custom ABI DataFrame and aggregate its params
+        decode_and_aggregate(): Decode a single signature using custom ABI DataFrame and aggregate its params
         decode_df_using_single_contract(): Decode logs for a specific contract
+        distinct_addresses(): List the distinct contract addresses of a raw logs/traces file, with their counts
+        async_benchmark_decode(): Asynchronously estimate the decoding throughput, in rows/sec, on a sample DataFrame
+        benchmark_decode(): Estimate the decoding throughput, in rows/sec, on a sample DataFrame
+        can_decode(): Count the logs/traces that match and decode with an ABI DataFrame, without decoding output columns
+        label_selectors(): Label function selectors with their names and signatures, without decoding calldata
+        assert_event_count(): Check that a decoded DataFrame has the expected number of rows for a signature
+        summarize_decoded(): Summarize a decoded DataFrame by contract and event/function name
+        compatibility_report(): Report matched, missing and indexed args mismatch hashes, without decoding
+        build_logs_df(): Build a raw logs DataFrame matching the configured schema from lists of fields
+        decode_log_from_hex(): Decode a single log from hex strings, without building a DataFrame
         unnest_event(): Unnest decoded event data
+
+    Utilities:
+        to_hex_columns(): Convert the chosen columns to hex string columns
+        to_binary_columns(): Convert the chosen columns to binary columns
 """
 
 from ._glaciers_python import get_config
 from ._glaciers_python import set_config
 from ._glaciers_python import set_config_toml
 from ._abi_reader import update_abi_db
+from ._abi_reader import update_abi_db_with_progress
 from ._abi_reader import read_new_abi_folder
+from ._abi_reader import read_new_abi_folder_parallel
 from ._abi_reader import read_new_abi_file
 from ._abi_reader import read_new_abi_json
+from ._abi_reader import deduplicate_abi_df
+from ._abi_reader import validate_num_indexed_args
+from ._abi_reader import convert_abi_db_encoding
+from ._abi_reader import abi_reader_output_schema
+from ._fetch_abis_from_manifest import async_fetch_abis_from_manifest
+from ._fetch_abis_from_manifest import fetch_abis_from_manifest
 from ._decode_folder import async_decode_folder
 from ._decode_folder import decode_folder
+from ._decode_folder_incremental import async_decode_folder_incremental
+from ._decode_folder_incremental import decode_folder_incremental
+from ._plan_folder import plan_folder
 from ._decode_file import async_decode_file
 from ._decode_file import decode_file
+from ._decode_file import DecodeFileResult
+from ._decode_archive import async_decode_archive
+from ._decode_archive import decode_archive
+from ._decode_partition import async_decode_partition
+from ._decode_partition import decode_partition
 from ._decode_df import async_decode_df
 from ._decode_df import decode_df
 from ._decode_df_with_abi_df import async_decode_df_with_abi_df
 from ._decode_df_with_abi_df import decode_df_with_abi_df
+from ._decode_tx_bundle import async_decode_tx_bundle
+from ._decode_tx_bundle import decode_tx_bundle
+from ._decode_df_with_context import async_decode_df_with_context
+from ._decode_df_with_context import decode_df_with_context
+from ._decode_df_with_priority import async_decode_df_with_priority
+from ._decode_df_with_priority import decode_df_with_priority
+from ._decode_df_with_overrides import async_decode_df_with_overrides
+from ._decode_df_with_overrides import decode_df_with_overrides
+from ._decode_to_wide import async_decode_to_wide
+from ._decode_to_wide import decode_to_wide
+from ._decode_and_aggregate import async_decode_and_aggregate
+from ._decode_and_aggregate import decode_and_aggregate
 from ._decode_df_using_single_contract import decode_df_using_single_contract
+from ._distinct_addresses import distinct_addresses
+from ._benchmark_decode import async_benchmark_decode
+from ._benchmark_decode import benchmark_decode
+from ._can_decode import can_decode
+from ._label_selectors import label_selectors
+from ._assert_event_count import assert_event_count
+from ._summarize_decoded import summarize_decoded
+from ._compatibility_report import compatibility_report
+from ._build_logs_df import build_logs_df
+from ._decode_log_from_hex import decode_log_from_hex
 from ._unnest import unnest_event
 from ._unnest import unnest_trace
+from ._utils import to_hex_columns
+from ._utils import to_binary_columns
 
 def get_config() -> str:
     """Get the current Glaciers configuration as a TOML string.
@@ -101,21 +183,61 @@ def set_config_toml(config: str) -> None:
 
 __all__ = [
     'update_abi_db',
+    'update_abi_db_with_progress',
     'read_new_abi_folder',
+    'read_new_abi_folder_parallel',
     'read_new_abi_file',
     'read_new_abi_json',
+    'deduplicate_abi_df',
+    'validate_num_indexed_args',
+    'convert_abi_db_encoding',
+    'abi_reader_output_schema',
+    'async_fetch_abis_from_manifest',
+    'fetch_abis_from_manifest',
     'async_decode_folder',
     'decode_folder',
+    'async_decode_folder_incremental',
+    'decode_folder_incremental',
+    'plan_folder',
     'async_decode_file',
     'decode_file',
+    'DecodeFileResult',
+    'async_decode_archive',
+    'decode_archive',
+    'async_decode_partition',
+    'decode_partition',
     'async_decode_df',
     'decode_df',
     'async_decode_df_with_abi_df',
     'decode_df_with_abi_df',
+    'async_decode_tx_bundle',
+    'decode_tx_bundle',
+    'async_decode_df_with_context',
+    'decode_df_with_context',
+    'async_decode_df_with_priority',
+    'decode_df_with_priority',
+    'async_decode_df_with_overrides',
+    'decode_df_with_overrides',
+    'async_decode_to_wide',
+    'decode_to_wide',
+    'async_decode_and_aggregate',
+    'decode_and_aggregate',
     'decode_df_using_single_contract',
+    'distinct_addresses',
+    'async_benchmark_decode',
+    'benchmark_decode',
+    'can_decode',
+    'label_selectors',
+    'assert_event_count',
+    'summarize_decoded',
+    'compatibility_report',
+    'build_logs_df',
+    'decode_log_from_hex',
     'get_config',
     'set_config',
     'set_config_toml',
     'unnest_event',
-    'unnest_trace'
+    'unnest_trace',
+    'to_hex_columns',
+    'to_binary_columns'
 ]
@@ -1,5 +1,7 @@
 import toml 
-from ._dataframe_utils import DataFrameType, to_prefered_type
+import polars as pl
+from typing import Callable
+from ._dataframe_utils import DataFrameType, to_polars, to_prefered_type
 from . import _glaciers_python
 from glaciers import get_config
 
@@ -35,6 +37,40 @@ def update_abi_db(abi_db_path: str | None = None,
     df = _glaciers_python.update_abi_db(abi_db_path, abi_folder_path)
     return to_prefered_type(df)
 
+def update_abi_db_with_progress(abi_db_path: str | None = None,
+                                abi_folder_path: str | None = None,
+                                progress: Callable[[int, int], None] | None = None) -> tuple[DataFrameType, dict]:
+    """Updates the ABI DB file with new ABIs from the specified folder, reporting the progress and counts of the update.
+
+    Args:
+        abi_db_path (str | None, optional): Path to the ABI database file. If None,
+            uses the path set in the config file. Defaults to None.
+        abi_folder_path (str | None, optional): Path to the folder containing ABI files.
+            If None, uses the path set in the config file. Defaults to None.
+        progress (Callable[[int, int], None] | None, optional): Called after each file with the number
+            of files processed and the total number of files. Defaults to None.
+
+    Returns:
+        tuple[DataFrameType, dict]: A DataFrame containing the updated ABI items, and a dict with the
+            "files_read", "files_skipped" (i.e: the file name isn't an address) and "rows_added" counts.
+
+    Examples:
+        ```python
+        df, stats = update_abi_db_with_progress(
+            "ABIs/ethereum__events__abis.parquet",
+            "ABIs/abi_database",
+            progress=lambda done, total: print(f"{done}/{total} ABI files"),
+        )
+        print(f"{stats['rows_added']} new items, {stats['files_skipped']} files skipped")
+        ```
+    """
+    if abi_db_path is None:
+        abi_db_path = toml.loads(get_config())["main"]["events_abi_db_file_path"]
+    if abi_folder_path is None:
+        abi_folder_path = toml.loads(get_config())["main"]["abi_folder_path"]
+    df, stats = _glaciers_python.update_abi_db_with_progress(abi_db_path, abi_folder_path, progress)
+    return to_prefered_type(df), stats
+
 def read_new_abi_folder(abi_folder_path: str | None = None) -> DataFrameType:
     """Reads all ABI files from a specified folder.
 
@@ -61,6 +97,34 @@ def read_new_abi_folder(abi_folder_path: str | None = None) -> DataFrameType:
     df = _glaciers_python.read_new_abi_folder(abi_folder_path)
     return to_prefered_type(df)
 
+def read_new_abi_folder_parallel(root_path: str | None = None) -> DataFrameType:
+    """Reads the ABI files of each subfolder of a folder in parallel.
+
+    Each subfolder is read atomically: if any ABI file in it can't be read, the whole
+    subfolder is skipped and its errors are printed, without blocking the other subfolders.
+
+    Args:
+        root_path (str | None, optional): Path to the folder containing the ABI subfolders.
+            If None, uses the abi_folder_path set in the config file. Defaults to None.
+
+    Returns:
+        DataFrameType: A DataFrame containing the parsed ABI items from all the subfolders read.
+
+    Examples:
+        ```python
+        # Read from default folder specified in config
+        df = read_new_abi_folder_parallel()
+
+        # Read from custom folder
+        df = read_new_abi_folder_parallel("ABIs/abi_database")
+        ```
+    """
+    if root_path is None:
+        root_path = toml.loads(get_config())["main"]["abi_folder_path"]
+        print(f"Reading ABI DataFrame from {root_path}")
+    df = _glaciers_python.read_new_abi_folder_parallel(root_path)
+    return to_prefered_type(df)
+
 def read_new_abi_file(path: str) -> DataFrameType:
     """Reads and parses a single ABI file.
 
@@ -78,12 +142,14 @@ def read_new_abi_file(path: str) -> DataFrameType:
     df = _glaciers_python.read_new_abi_file(path)
     return to_prefered_type(df)
 
-def read_new_abi_json(abi: str, address: str) -> DataFrameType:
+def read_new_abi_json(abi: str, address: str, chain_id: int | None = None) -> DataFrameType:
     """Reads and parses an ABI from a JSON string.
 
     Args:
         abi (str): The ABI JSON string to parse.
         address (str): The contract address associated with this ABI.
+        chain_id (int | None, optional): The chain where the contract is deployed, stored in the chain_id column.
+            If None, the ABI items aren't scoped to a chain. Defaults to None.
 
     Returns:
         DataFrameType: A DataFrame containing the parsed ABI items.
@@ -93,7 +159,97 @@ def read_new_abi_json(abi: str, address: str) -> DataFrameType:
         abi_json = '{"inputs": [], "name": "totalSupply", ...}'
         address = "0x123..."
         df = read_new_abi_json(abi_json, address)
+
+        # Scope the ABI items to Ethereum mainnet
+        df = read_new_abi_json(abi_json, address, chain_id=1)
         ```
     """
-    df = _glaciers_python.read_new_abi_json(abi, address)
-    return to_prefered_type(df)
\ No newline at end of file
+    df = _glaciers_python.read_new_abi_json(abi, address, chain_id)
+    return to_prefered_type(df)
+
+def abi_reader_output_schema() -> pl.Schema:
+    """Returns the schema of the ABI DataFrames produced by the ABI reader, under the current config.
+
+    Returns:
+        pl.Schema: The column names and polars dtypes, in the same order as the ABI reader output.
+            The address and hash columns are Binary, or String if abi_reader.output_hex_string_encoding is set.
+
+    Examples:
+        ```python
+        schema = abi_reader_output_schema()
+        assert read_new_abi_folder("ABIs/abi_database").schema == schema
+        ```
+    """
+    return _glaciers_python.abi_reader_output_schema().schema
+
+def deduplicate_abi_df(df: DataFrameType, strategy: str = "first") -> DataFrameType:
+    """Deduplicates an ABI DataFrame by its id column.
+
+    Args:
+        df (DataFrameType): DataFrame (polars or pandas) containing the ABI items.
+        strategy (str, optional): Strategy used to choose which item to keep when ids are duplicated.
+            Allowed values = ["first", "last", "most_specific", "fail"]. "most_specific" prefers items
+            with a non-zero address over zero-address items, and "fail" raises an error if duplicates exist.
+            Defaults to "first".
+
+    Returns:
+        DataFrameType: A DataFrame with unique ABI items, keeping the original row order.
+
+    Raises:
+        ValueError: If the strategy is invalid, or if using the "fail" strategy and duplicates exist.
+
+    Examples:
+        ```python
+        abi_df = pl.read_parquet("external_abis.parquet")
+        df = deduplicate_abi_df(abi_df, "most_specific")
+        ```
+    """
+    valid_strategies = ["first", "last", "most_specific", "fail"]
+    if strategy not in valid_strategies:
+        raise ValueError(f"Strategy must be one of {valid_strategies}")
+
+    df = _glaciers_python.deduplicate_abi_df(to_polars(df), strategy)
+    return to_prefered_type(df)
+
+def validate_num_indexed_args(df: DataFrameType) -> None:
+    """Checks that the num_indexed_args of each event in an ABI DataFrame matches its full_signature.
+
+    The expected value is recomputed from the signature (and the anonymous column), like when the ABI items are read.
+    Use it to check ABI DBs loaded from an older format before decoding with them.
+
+    Args:
+        df (DataFrameType): DataFrame (polars or pandas) containing the ABI items.
+
+    Raises:
+        ValueError: Listing the inconsistent rows, if any event has a num_indexed_args that doesn't match its full_signature.
+
+    Examples:
+        ```python
+        abi_df = pl.read_parquet("ABIs/ethereum__events__abis.parquet")
+        validate_num_indexed_args(abi_df)
+        ```
+    """
+    _glaciers_python.validate_num_indexed_args(to_polars(df))
+
+def convert_abi_db_encoding(abi_db_path: str, to_hex: bool) -> DataFrameType:
+    """Converts the hash and address columns of an ABI DB file between binary and hex string encodings, rewriting the file.
+
+    Use it to align ABI DBs saved with different `abi_reader.output_hex_string_encoding` configs.
+
+    Args:
+        abi_db_path (str): Path to the ABI database file.
+        to_hex (bool): Convert the columns to 0x prefixed hex strings if True, or to binary if False.
+
+    Returns:
+        DataFrameType: A DataFrame containing the converted ABI items.
+
+    Raises:
+        ValueError: If reading or writing the ABI DB fails, or when converting a csv ABI DB to binary.
+
+    Examples:
+        ```python
+        df = convert_abi_db_encoding("ABIs/ethereum__events__abis.parquet", to_hex=True)
+        ```
+    """
+    df = _glaciers_python.convert_abi_db_encoding(abi_db_path, to_hex)
+    return to_prefered_type(df)
@@ -0,0 +1,30 @@
+from ._dataframe_utils import DataFrameType, to_polars
+from . import _glaciers_python
+
+def assert_event_count(
+    df: DataFrameType,
+    signature: str,
+    expected: int,
+) -> None:
+    """
+    Check that a decoded DataFrame has the expected number of rows for a signature, i.e: against a count from an indexer.
+    The signature is compared as an exact string with the full_signature column. Undecoded rows are never counted.
+
+    Args:
+        df (DataFrameType): Decoded DataFrame (polars or pandas) with a full_signature column.
+        signature (str): Full signature to count.
+        expected (int): Reference number of rows for the signature.
+
+    Raises:
+        ValueError: If the number of rows with the signature doesn't match expected, or the full_signature column is missing.
+
+    Example:
+        ```python
+        assert_event_count(
+            decoded_df,
+            "event Transfer(address indexed from, address indexed to, uint256 value)",
+            1250
+        )
+        ```
+    """
+    _glaciers_python.assert_event_count(to_polars(df), signature, expected)
@@ -0,0 +1,84 @@
+import polars as pl
+from ._dataframe_utils import DataFrameType, to_polars
+from . import _glaciers_python
+
+async def async_benchmark_decode(
+    decoder_type: str,
+    sample_df: DataFrameType,
+    abi_df,
+) -> float:
+    """
+    Asynchronously estimate the decoding throughput of this machine with the current config, i.e: to plan large decoding jobs.
+    The sample is decoded like in decode_df_with_abi_df, and the decoded DataFrame is discarded.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        sample_df (DataFrameType): DataFrame (polars or pandas) with a sample of the raw blockchain data to decode.
+        abi_df (DataFrameType | pl.LazyFrame | str | list[str]): DataFrame (polars or pandas) containing the ABI DB items, a polars LazyFrame of them, or the path(s) to one or more ABI DB files.
+
+    Returns:
+        float: Rows of the sample decoded per second.
+
+    Note:
+        The matching, decoder.decoded_chunk_size and decoder.max_chunk_threads_per_file are part of the measure, loading the ABI items isn't.
+        decode_folder also decodes up to decoder.max_concurrent_files_decoding files at the same time, so its throughput can be higher.
+
+    Example:
+        ```python
+        rows_per_sec = await async_benchmark_decode("log", raw_logs_df.head(100_000), "ABIs/ethereum__events__abis.parquet")
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    if isinstance(abi_df, (str, list)):
+        abi_source = abi_df
+    elif isinstance(abi_df, pl.LazyFrame):
+        abi_source = abi_df.collect()
+    else:
+        abi_source = to_polars(abi_df)
+    return await _glaciers_python.benchmark_decode(decoder_type, to_polars(sample_df), abi_source)
+
+def benchmark_decode(
+    decoder_type: str,
+    sample_df: DataFrameType,
+    abi_df,
+) -> float:
+    """
+    Estimate the decoding throughput of this machine with the current config, i.e: to plan large decoding jobs.
+    The sample is decoded like in decode_df_with_abi_df, and the decoded DataFrame is discarded.
+    This is a synchronous wrapper around async_benchmark_decode.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        sample_df (DataFrameType): DataFrame (polars or pandas) with a sample of the raw blockchain data to decode.
+        abi_df (DataFrameType | pl.LazyFrame | str | list[str]): DataFrame (polars or pandas) containing the ABI DB items, a polars LazyFrame of them, or the path(s) to one or more ABI DB files.
+
+    Returns:
+        float: Rows of the sample decoded per second.
+
+    Note:
+        The matching, decoder.decoded_chunk_size and decoder.max_chunk_threads_per_file are part of the measure, loading the ABI items isn't.
+        decode_folder also decodes up to decoder.max_concurrent_files_decoding files at the same time, so its throughput can be higher.
+
+    Example:
+        ```python
+        rows_per_sec = benchmark_decode("log", raw_logs_df.head(100_000), "ABIs/ethereum__events__abis.parquet")
+        hours = total_rows / rows_per_sec / 3600
+        ```
+    """
+    import asyncio
+    coroutine = async_benchmark_decode(decoder_type, sample_df, abi_df)
+
+    try:
+        import concurrent.futures
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        with concurrent.futures.ThreadPoolExecutor() as executor:
+            future = executor.submit(loop.run_until_complete, coroutine)
+            result = future.result()
+    except RuntimeError:
+        result = asyncio.run(coroutine)
+
+    return result
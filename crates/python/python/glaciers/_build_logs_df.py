@@ -0,0 +1,41 @@
+from typing import Optional, Union
+from ._dataframe_utils import DataFrameType, to_prefered_type
+from . import _glaciers_python
+
+BytesLike = Union[bytes, str]
+
+def build_logs_df(
+    addresses: list[BytesLike],
+    topics: list[list[Optional[BytesLike]]],
+    data: list[BytesLike],
+    block_numbers: Optional[list[int]] = None,
+    tx_hashes: Optional[list[BytesLike]] = None,
+) -> DataFrameType:
+    """
+    Build a raw logs DataFrame, matching the configured log schema and datatypes, from lists of fields.
+    Each field accepts bytes or hex strings (with or without the 0x prefix).
+
+    Args:
+        addresses (list[bytes | str]): Contract address of each log (20 bytes).
+        topics (list[list[bytes | str | None]]): Topics of each log, up to 4 topics of 32 bytes. Missing topics are filled with nulls.
+        data (list[bytes | str]): Data field of each log.
+        block_numbers (list[int], optional): Block number of each log, added as a block_number column.
+        tx_hashes (list[bytes | str], optional): Transaction hash of each log (32 bytes), added as a transaction_hash column.
+
+    Returns:
+        DataFrameType: Raw logs DataFrame (polars or pandas according to the config), ready to be decoded.
+
+    Raises:
+        ValueError: If the fields have different lengths or a value is malformed. The message reports the row and field.
+
+    Example:
+        ```python
+        raw_logs_df = build_logs_df(
+            addresses=["0xee8fecfc2d4ee6ee89e3b9bf11b4d9491d9a3fa7"],
+            topics=[["0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef", topic1, topic2]],
+            data=[log["data"]],
+        )
+        ```
+    """
+    df = _glaciers_python.build_logs_df(addresses, topics, data, block_numbers, tx_hashes)
+    return to_prefered_type(df)
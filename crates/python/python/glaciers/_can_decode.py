@@ -0,0 +1,33 @@
+from ._dataframe_utils import DataFrameType, to_polars
+from . import _glaciers_python
+
+def can_decode(
+    decoder_type: str,
+    df: DataFrameType,
+    abi_df: DataFrameType,
+) -> dict:
+    """
+    Count how many logs/traces of a DataFrame match and decode with an ABI DataFrame, without building the decoded columns.
+    Each matched row is decoded, but the values aren't converted to strings and JSON, so it's cheaper than a full decode.
+    Useful to gate CI on a file decoding cleanly.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
+        abi_df (DataFrameType): DataFrame (polars or pandas) containing the ABI DB items.
+
+    Returns:
+        dict: The "total", "matched", "decoded" and "failed" row counts. "failed" is the number of rows that would
+        have a decoding_error.
+
+    Example:
+        ```python
+        stats = can_decode("log", raw_logs_df, abi_db_df)
+        assert stats["failed"] == 0
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    return _glaciers_python.can_decode(decoder_type, to_polars(df), to_polars(abi_df))
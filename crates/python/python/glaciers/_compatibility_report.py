@@ -0,0 +1,49 @@
+import toml
+from typing import Optional, Union
+from ._dataframe_utils import DataFrameType, to_polars, to_prefered_type
+from . import _glaciers_python
+from glaciers import get_config
+
+def compatibility_report(
+    decoder_type: str,
+    df: DataFrameType,
+    abi_db_path: Optional[Union[str, list[str]]] = None,
+) -> DataFrameType:
+    """
+    Report, without decoding, how the hashes of a raw logs/traces DataFrame are covered by the ABI DB.
+    Each hash (topic0 for logs, 4 bytes selector for traces) is categorized as "matched", "indexed_arg_mismatch"
+    (the hash is in the ABI DB, but only with a different number of indexed args) or "missing".
+
+    Args:
+        decoder_type (str): Type of the raw data. Must be either "log" or "trace".
+        df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+
+    Returns:
+        DataFrameType: DataFrame (polars or pandas according to the config) with one row per category and the
+        category, hash_count, row_count and example_signatures columns.
+
+    Example:
+        ```python
+        report = compatibility_report(
+            "log",
+            raw_logs_df,
+            "ABIs/ethereum__events__abis.parquet"
+        )
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    if abi_db_path is None:
+        if decoder_type == "log":
+            abi_db_path = toml.loads(get_config())["main"]["events_abi_db_file_path"]
+        elif decoder_type == "trace":
+            abi_db_path = toml.loads(get_config())["main"]["functions_abi_db_file_path"]
+    if isinstance(abi_db_path, str):
+        abi_db_path = [abi_db_path]
+
+    df_pl = to_polars(df)
+    result = _glaciers_python.compatibility_report(decoder_type, df_pl, abi_db_path)
+    return to_prefered_type(result)
@@ -0,0 +1,106 @@
+import polars as pl
+from typing import List, Tuple
+from ._dataframe_utils import DataFrameType, to_polars, to_prefered_type
+from . import _glaciers_python
+
+async def async_decode_and_aggregate(
+    decoder_type: str,
+    df: DataFrameType,
+    abi_df: DataFrameType,
+    signature: str,
+    group_by: List[str],
+    agg: List[Tuple[str, str]],
+) -> DataFrameType:
+    """
+    Asynchronously decode the logs/traces of a single signature from a DataFrame and an ABI DataFrame and aggregate its params.
+    The params are pivoted into columns (e.g. from, to, value) and grouped by the group_by columns. Each aggregation
+    is named {column}_{aggregation} (e.g. value_sum). Sums are exact, returned as decimal strings, and mean, min
+    and max cast the values to Float64.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
+        abi_df (DataFrameType): DataFrame (polars or pandas) containing the ABI DB items.
+        signature (str): Full signature to decode, as in the ABI DB full_signature column.
+        group_by (List[str]): Columns to group by, params or raw columns (e.g. address). Empty to aggregate all rows.
+        agg (List[Tuple[str, str]]): List of (column, aggregation) tuples. Allowed aggregations are
+            "sum", "mean", "min", "max", "count", "n_unique", "first" and "last".
+
+    Returns:
+        DataFrameType: Aggregated DataFrame (polars or pandas according to the config) with the results.
+
+    Example:
+        ```python
+        totals_df = await async_decode_and_aggregate(
+            "log",
+            raw_logs_df,
+            abi_db_df,
+            "event Transfer(address indexed from, address indexed to, uint256 value)",
+            ["to"],
+            [("value", "sum")]
+        )
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    df_pl = to_polars(df)
+    abi_df_pl = to_polars(abi_df)
+    result_pl: pl.DataFrame = await _glaciers_python.decode_and_aggregate(decoder_type, df_pl, abi_df_pl, signature, group_by, agg)
+    return to_prefered_type(result_pl)
+
+def decode_and_aggregate(
+    decoder_type: str,
+    df: DataFrameType,
+    abi_df: DataFrameType,
+    signature: str,
+    group_by: List[str],
+    agg: List[Tuple[str, str]],
+) -> DataFrameType:
+    """
+    Decode the logs/traces of a single signature from a DataFrame and an ABI DataFrame and aggregate its params.
+    The params are pivoted into columns (e.g. from, to, value) and grouped by the group_by columns. Each aggregation
+    is named {column}_{aggregation} (e.g. value_sum). Sums are exact, returned as decimal strings, and mean, min
+    and max cast the values to Float64.
+
+    This is a synchronous wrapper around async_decode_and_aggregate.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
+        abi_df (DataFrameType): DataFrame (polars or pandas) containing the ABI DB items.
+        signature (str): Full signature to decode, as in the ABI DB full_signature column.
+        group_by (List[str]): Columns to group by, params or raw columns (e.g. address). Empty to aggregate all rows.
+        agg (List[Tuple[str, str]]): List of (column, aggregation) tuples. Allowed aggregations are
+            "sum", "mean", "min", "max", "count", "n_unique", "first" and "last".
+
+    Returns:
+        DataFrameType: Aggregated DataFrame (polars or pandas according to the config) with the results.
+
+    Example:
+        ```python
+        totals_df = decode_and_aggregate(
+            "log",
+            raw_logs_df,
+            abi_db_df,
+            "event Transfer(address indexed from, address indexed to, uint256 value)",
+            ["to"],
+            [("value", "sum")]
+        )
+        ```
+    """
+    import asyncio
+    coroutine = async_decode_and_aggregate(decoder_type, df, abi_df, signature, group_by, agg)
+
+    try:
+        import concurrent.futures
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        with concurrent.futures.ThreadPoolExecutor() as executor:
+            future = executor.submit(loop.run_until_complete, coroutine)
+            result = future.result()
+    except RuntimeError:
+        result = asyncio.run(coroutine)
+
+    return result
@@ -0,0 +1,101 @@
+import toml
+from glaciers import get_config
+
+async def async_decode_archive(
+    decoder_type: str,
+    archive_path: str,
+    output_dir: str,
+    abi_db_path = None,
+) -> None:
+    """
+    Asynchronously decode the parquet files inside a .tar.gz archive, without unpacking it to disk.
+    Decoded files are saved in output_dir, with "logs" or "traces" in the file name replaced with "decoded_logs" or "decoded_traces".
+    Non parquet members of the archive are skipped.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        archive_path (str): Path to the .tar.gz archive containing raw blockchain data parquet files.
+        output_dir (str): Path to the folder where the decoded files are saved. It's created if it doesn't exist.
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+
+    Returns:
+        None
+
+    Note:
+        This function gets the max_concurrent_files_decoding from the config and uses it to limit the number of archive members decoded at the same time.
+
+    Example:
+        ```python
+        await async_decode_archive(
+            "log",
+            "data/logs.tar.gz",
+            "data/decoded",
+            "ABIs/ethereum__events_abis.parquet"
+        )
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    from . import _glaciers_python
+    if abi_db_path is None:
+        if decoder_type == "log":
+            abi_db_path = toml.loads(get_config())["main"]["events_abi_db_file_path"]
+        elif decoder_type == "trace":
+            abi_db_path = toml.loads(get_config())["main"]["functions_abi_db_file_path"]
+
+    if isinstance(abi_db_path, str):
+        abi_db_path = [abi_db_path]
+
+    await _glaciers_python.decode_archive(decoder_type, archive_path, output_dir, abi_db_path)
+
+def decode_archive(
+    decoder_type: str,
+    archive_path: str,
+    output_dir: str,
+    abi_db_path = None,
+) -> None:
+    """
+    Decode the parquet files inside a .tar.gz archive, without unpacking it to disk.
+    Decoded files are saved in output_dir, with "logs" or "traces" in the file name replaced with "decoded_logs" or "decoded_traces".
+    Non parquet members of the archive are skipped.
+    This is a synchronous wrapper around async_decode_archive.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        archive_path (str): Path to the .tar.gz archive containing raw blockchain data parquet files.
+        output_dir (str): Path to the folder where the decoded files are saved. It's created if it doesn't exist.
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+
+    Returns:
+        None
+
+    Note:
+        This function gets the max_concurrent_files_decoding from the config and uses it to limit the number of archive members decoded at the same time.
+
+    Example:
+        ```python
+        decode_archive(
+            "log",
+            "data/logs.tar.gz",
+            "data/decoded",
+            "ABIs/ethereum__events_abis.parquet"
+        )
+        ```
+    """
+    import asyncio
+    coroutine = async_decode_archive(decoder_type, archive_path, output_dir, abi_db_path)
+
+    try:
+        import concurrent.futures
+
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        with concurrent.futures.ThreadPoolExecutor() as executor:
+            future = executor.submit(loop.run_until_complete, coroutine)
+            result = future.result()
+    except RuntimeError:
+        result = asyncio.run(coroutine)
+
+    return result
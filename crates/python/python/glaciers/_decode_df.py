@@ -17,7 +17,7 @@ async def async_decode_df(
     Args:
         decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
         df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
-        abi_db_path (str, optional): Path to the ABI database file. If None, uses the path set in the config.
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
 
     Returns:
         DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
@@ -42,6 +42,9 @@ async def async_decode_df(
             abi_db_path = toml.loads(get_config())["main"]["functions_abi_db_file_path"]
 
     df_pl = to_polars(df)
+    if isinstance(abi_db_path, str):
+        abi_db_path = [abi_db_path]
+
     result: pl.DataFrame = await _glaciers_python.decode_df(decoder_type, df_pl, abi_db_path)
     return to_prefered_type(result)
 
@@ -58,7 +61,7 @@ def decode_df(
     Args:
         decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
         df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
-        abi_db_path (str, optional): Path to the ABI database file. If None, uses the path set in the config.
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
 
     Returns:
         DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
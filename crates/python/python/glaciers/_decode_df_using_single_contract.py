@@ -15,7 +15,8 @@ async def async_decode_df_using_single_contract(
     Args:
         decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
         df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
-        contract_address (str): Ethereum contract address to use for decoding.
+        contract_address (str): Ethereum contract address to use for decoding, with or without 0x and in any case.
+            A malformed address raises a ValueError before downloading the ABI.
 
     Returns:
         DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
@@ -52,7 +53,8 @@ def decode_df_using_single_contract(
     Args:
         decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
         df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
-        contract_address (str): Ethereum contract address to use for decoding.
+        contract_address (str): Ethereum contract address to use for decoding, with or without 0x and in any case.
+            A malformed address raises a ValueError before downloading the ABI.
 
     Returns:
         DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
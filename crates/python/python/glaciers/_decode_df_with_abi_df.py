@@ -6,7 +6,7 @@ from . import _glaciers_python
 async def async_decode_df_with_abi_df(
     decoder_type: str,  
     df: DataFrameType,
-    abi_df: DataFrameType,
+    abi_df,
 ) -> DataFrameType:
     """
     Asynchronously decode blockchain data from a DataFrame and an ABI DataFrame.
@@ -14,7 +14,7 @@ async def async_decode_df_with_abi_df(
     Args:
         decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
         df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
-        abi_df (DataFrameType): DataFrame (polars or pandas) containing the ABI DB items.
+        abi_df (DataFrameType | pl.LazyFrame | str | list[str]): DataFrame (polars or pandas) containing the ABI DB items, a polars LazyFrame of them, or the path(s) to one or more ABI DB files. LazyFrames and paths are read on each call, so ABI DB files updated on disk are picked up.
 
     Returns:
         DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
@@ -33,14 +33,19 @@ async def async_decode_df_with_abi_df(
         raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
     
     df_pl = to_polars(df)
-    abi_df_pl = to_polars(abi_df)   
-    result_pl: pl.DataFrame = await _glaciers_python.decode_df_with_abi_df(decoder_type, df_pl, abi_df_pl)
+    if isinstance(abi_df, (str, list)):
+        abi_source = abi_df
+    elif isinstance(abi_df, pl.LazyFrame):
+        abi_source = abi_df.collect()
+    else:
+        abi_source = to_polars(abi_df)
+    result_pl: pl.DataFrame = await _glaciers_python.decode_df_with_abi_df(decoder_type, df_pl, abi_source)
     return to_prefered_type(result_pl)
 
 def decode_df_with_abi_df(
     decoder_type: str,
     df: DataFrameType,
-    abi_df: DataFrameType,
+    abi_df,
 ) -> DataFrameType:
     """
     Decode blockchain data from a DataFrame and an ABI DataFrame.
@@ -50,7 +55,7 @@ def decode_df_with_abi_df(
     Args:
         decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
         df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
-        abi_df (DataFrameType): DataFrame (polars or pandas) containing the ABI DB items.
+        abi_df (DataFrameType | pl.LazyFrame | str | list[str]): DataFrame (polars or pandas) containing the ABI DB items, a polars LazyFrame of them, or the path(s) to one or more ABI DB files. LazyFrames and paths are read on each call, so ABI DB files updated on disk are picked up.
 
     Returns:
         DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
@@ -0,0 +1,88 @@
+import polars as pl
+from ._dataframe_utils import DataFrameType, to_polars, to_prefered_type
+from . import _glaciers_python
+
+async def async_decode_df_with_context(
+    decoder_type: str,
+    df: DataFrameType,
+    abi_df: DataFrameType,
+    context: dict[str, str],
+) -> DataFrameType:
+    """
+    Asynchronously decode blockchain data from a DataFrame and an ABI DataFrame, adding context metadata columns.
+    Each context key-value pair is added as a literal column to every decoded row.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
+        abi_df (DataFrameType): DataFrame (polars or pandas) containing the ABI DB items.
+        context (dict[str, str]): Column names and literal values to add to the decoded rows.
+
+    Returns:
+        DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
+
+    Example:
+        ```python
+        decoded_df = await async_decode_df_with_context(
+            "log",
+            raw_logs_df,
+            abi_db_df,
+            {"chain_id": "1", "source_file": "logs_2024.parquet"}
+        )
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    df_pl = to_polars(df)
+    abi_df_pl = to_polars(abi_df)
+    context = {str(key): str(value) for key, value in context.items()}
+    result_pl: pl.DataFrame = await _glaciers_python.decode_df_with_context(decoder_type, df_pl, abi_df_pl, context)
+    return to_prefered_type(result_pl)
+
+def decode_df_with_context(
+    decoder_type: str,
+    df: DataFrameType,
+    abi_df: DataFrameType,
+    context: dict[str, str],
+) -> DataFrameType:
+    """
+    Decode blockchain data from a DataFrame and an ABI DataFrame, adding context metadata columns.
+    Each context key-value pair is added as a literal column to every decoded row.
+
+    This is a synchronous wrapper around async_decode_df_with_context.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
+        abi_df (DataFrameType): DataFrame (polars or pandas) containing the ABI DB items.
+        context (dict[str, str]): Column names and literal values to add to the decoded rows.
+
+    Returns:
+        DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
+
+    Example:
+        ```python
+        decoded_df = decode_df_with_context(
+            "log",
+            raw_logs_df,
+            abi_db_df,
+            {"chain_id": "1", "source_file": "logs_2024.parquet"}
+        )
+        ```
+    """
+    import asyncio
+    coroutine = async_decode_df_with_context(decoder_type, df, abi_df, context)
+
+    try:
+        import concurrent.futures
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        with concurrent.futures.ThreadPoolExecutor() as executor:
+            future = executor.submit(loop.run_until_complete, coroutine)
+            result = future.result()
+    except RuntimeError:
+        result = asyncio.run(coroutine)
+
+    return result
@@ -0,0 +1,97 @@
+import polars as pl
+from ._dataframe_utils import DataFrameType, to_polars, to_prefered_type
+from . import _glaciers_python
+
+def _hash_to_hex(hash: bytes | str) -> str:
+    if isinstance(hash, bytes):
+        return "0x" + hash.hex()
+    return hash
+
+async def async_decode_df_with_overrides(
+    decoder_type: str,
+    df: DataFrameType,
+    abi_df: DataFrameType,
+    overrides: dict[bytes | str, str],
+) -> DataFrameType:
+    """
+    Asynchronously decode blockchain data from a DataFrame and an ABI DataFrame, overriding the matched signature of some hashes.
+    The rows are matched with the algorithm from the config, then the rows of each topic0 (logs) or selector (traces) in the
+    overrides dictionary get its signature, whatever the matcher chose, and are decoded with it.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
+        abi_df (DataFrameType): DataFrame (polars or pandas) containing the ABI DB items.
+        overrides (dict[bytes | str, str]): Topic0s/selectors (bytes or hex strings) and the full signatures to decode them with.
+
+    Returns:
+        DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
+
+    Example:
+        ```python
+        decoded_df = await async_decode_df_with_overrides(
+            "log",
+            raw_logs_df,
+            abi_db_df,
+            {"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef":
+                "event Transfer(address indexed from, address indexed to, uint256 value)"}
+        )
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    df_pl = to_polars(df)
+    abi_df_pl = to_polars(abi_df)
+    overrides = {_hash_to_hex(hash): signature for hash, signature in overrides.items()}
+    result_pl: pl.DataFrame = await _glaciers_python.decode_df_with_overrides(decoder_type, df_pl, abi_df_pl, overrides)
+    return to_prefered_type(result_pl)
+
+def decode_df_with_overrides(
+    decoder_type: str,
+    df: DataFrameType,
+    abi_df: DataFrameType,
+    overrides: dict[bytes | str, str],
+) -> DataFrameType:
+    """
+    Decode blockchain data from a DataFrame and an ABI DataFrame, overriding the matched signature of some hashes.
+    The rows are matched with the algorithm from the config, then the rows of each topic0 (logs) or selector (traces) in the
+    overrides dictionary get its signature, whatever the matcher chose, and are decoded with it.
+
+    This is a synchronous wrapper around async_decode_df_with_overrides.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
+        abi_df (DataFrameType): DataFrame (polars or pandas) containing the ABI DB items.
+        overrides (dict[bytes | str, str]): Topic0s/selectors (bytes or hex strings) and the full signatures to decode them with.
+
+    Returns:
+        DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
+
+    Example:
+        ```python
+        decoded_df = decode_df_with_overrides(
+            "log",
+            raw_logs_df,
+            abi_db_df,
+            {"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef":
+                "event Transfer(address indexed from, address indexed to, uint256 value)"}
+        )
+        ```
+    """
+    import asyncio
+    coroutine = async_decode_df_with_overrides(decoder_type, df, abi_df, overrides)
+
+    try:
+        import concurrent.futures
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        with concurrent.futures.ThreadPoolExecutor() as executor:
+            future = executor.submit(loop.run_until_complete, coroutine)
+            result = future.result()
+    except RuntimeError:
+        result = asyncio.run(coroutine)
+
+    return result
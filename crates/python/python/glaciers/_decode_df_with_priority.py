@@ -0,0 +1,99 @@
+import polars as pl
+from ._dataframe_utils import DataFrameType, to_polars, to_prefered_type
+from . import _glaciers_python
+
+def _hash_to_hex(hash: bytes | str) -> str:
+    if isinstance(hash, bytes):
+        return "0x" + hash.hex()
+    return hash
+
+async def async_decode_df_with_priority(
+    decoder_type: str,
+    df: DataFrameType,
+    abi_df: DataFrameType,
+    priority: dict[bytes | str, list[str]],
+) -> DataFrameType:
+    """
+    Asynchronously decode blockchain data from a DataFrame and an ABI DataFrame, with ordered candidate signatures for some hashes.
+    The rows of each topic0 (logs) or selector (traces) in the priority dictionary are decoded with its candidates in order,
+    keeping the first that decodes without errors. Other rows are matched with the algorithm from the config.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
+        abi_df (DataFrameType): DataFrame (polars or pandas) containing the ABI DB items.
+        priority (dict[bytes | str, list[str]]): Topic0s/selectors (bytes or hex strings) and their candidate full signatures, in priority order.
+
+    Returns:
+        DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
+
+    Example:
+        ```python
+        decoded_df = await async_decode_df_with_priority(
+            "log",
+            raw_logs_df,
+            abi_db_df,
+            {"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef": [
+                "event Transfer(address indexed from, address indexed to, uint256 value)",
+            ]}
+        )
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    df_pl = to_polars(df)
+    abi_df_pl = to_polars(abi_df)
+    priority = {_hash_to_hex(hash): list(candidates) for hash, candidates in priority.items()}
+    result_pl: pl.DataFrame = await _glaciers_python.decode_df_with_priority(decoder_type, df_pl, abi_df_pl, priority)
+    return to_prefered_type(result_pl)
+
+def decode_df_with_priority(
+    decoder_type: str,
+    df: DataFrameType,
+    abi_df: DataFrameType,
+    priority: dict[bytes | str, list[str]],
+) -> DataFrameType:
+    """
+    Decode blockchain data from a DataFrame and an ABI DataFrame, with ordered candidate signatures for some hashes.
+    The rows of each topic0 (logs) or selector (traces) in the priority dictionary are decoded with its candidates in order,
+    keeping the first that decodes without errors. Other rows are matched with the algorithm from the config.
+
+    This is a synchronous wrapper around async_decode_df_with_priority.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        df (DataFrameType): DataFrame (polars or pandas) containing the raw blockchain data.
+        abi_df (DataFrameType): DataFrame (polars or pandas) containing the ABI DB items.
+        priority (dict[bytes | str, list[str]]): Topic0s/selectors (bytes or hex strings) and their candidate full signatures, in priority order.
+
+    Returns:
+        DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
+
+    Example:
+        ```python
+        decoded_df = decode_df_with_priority(
+            "log",
+            raw_logs_df,
+            abi_db_df,
+            {"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef": [
+                "event Transfer(address indexed from, address indexed to, uint256 value)",
+            ]}
+        )
+        ```
+    """
+    import asyncio
+    coroutine = async_decode_df_with_priority(decoder_type, df, abi_df, priority)
+
+    try:
+        import concurrent.futures
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        with concurrent.futures.ThreadPoolExecutor() as executor:
+            future = executor.submit(loop.run_until_complete, coroutine)
+            result = future.result()
+    except RuntimeError:
+        result = asyncio.run(coroutine)
+
+    return result
@@ -1,14 +1,37 @@
-import polars as pl
+from dataclasses import dataclass
+from datetime import timedelta
 import toml
 from ._dataframe_utils import DataFrameType, to_prefered_type
 from . import _glaciers_python
 from glaciers import get_config
 
+@dataclass
+class DecodeFileResult:
+    """
+    Result of decode_file: the decoded DataFrame, with the paths and row counts of the file.
+
+    Attributes:
+        df (DataFrameType): Decoded DataFrame (polars or pandas according to the config), as saved in output_path.
+        input_path (str): Raw file path.
+        output_path (str): Decoded file path.
+        input_rows (int): Raw logs/traces read.
+        output_rows (int): Decoded logs/traces written.
+        matched_rows (int): Written logs/traces matched with an ABI item.
+        duration (timedelta): Duration of reading, decoding and saving the file.
+    """
+    df: DataFrameType
+    input_path: str
+    output_path: str
+    input_rows: int
+    output_rows: int
+    matched_rows: int
+    duration: timedelta
+
 async def async_decode_file(
     decoder_type: str,
     file_path: str,
     abi_db_path = None,
-) -> DataFrameType:
+) -> DecodeFileResult:
     """
     Asynchronously decode blockchain data provided in a file path and the path to the ABI DB file.
     Decoded file is saved in a "decoded" folder, in the parent folder of the raw data.
@@ -17,18 +40,20 @@ async def async_decode_file(
     Args:
         decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
         file_path (str): Path to the file containing raw blockchain data.
-        abi_db_path (str, optional): Path to the ABI database file. If None, uses the path set in the config.
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
 
     Returns:
-        DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
+        DecodeFileResult: The decoded DataFrame (polars or pandas according to the config) in `df`, with the input and
+            output paths, the input, output and matched row counts and the duration.
 
     Example:
         ```python
-        decoded_df = await async_decode_file(
+        result = await async_decode_file(
             "log",
             "data/logs/ethereum__logs__blocks__18426253_to_18426303_example.parquet",
             "ABIs/ethereum__events_abis.parquet"
         )
+        decoded_df = result.df
         ```
     """
     valid_decoder_types = ["log", "trace"]
@@ -41,14 +66,25 @@ async def async_decode_file(
         elif decoder_type == "trace":
             abi_db_path = toml.loads(get_config())["main"]["functions_abi_db_file_path"]
 
-    result: pl.DataFrame = await _glaciers_python.decode_file(decoder_type, file_path, abi_db_path)
-    return to_prefered_type(result)
+    if isinstance(abi_db_path, str):
+        abi_db_path = [abi_db_path]
+
+    df, input_path, output_path, input_rows, output_rows, matched_rows, duration_secs = await _glaciers_python.decode_file(decoder_type, file_path, abi_db_path)
+    return DecodeFileResult(
+        df=to_prefered_type(df),
+        input_path=input_path,
+        output_path=output_path,
+        input_rows=input_rows,
+        output_rows=output_rows,
+        matched_rows=matched_rows,
+        duration=timedelta(seconds=duration_secs),
+    )
 
 def decode_file(
     decoder_type: str,
     file_path: str,
     abi_db_path = None,
-) -> DataFrameType:
+) -> DecodeFileResult:
     """
     Decode blockchain data provided in a file path and the path to the ABI DB file.
     Decoded file is saved in a "decoded" folder, in the parent folder of the raw data.
@@ -59,18 +95,20 @@ def decode_file(
     Args:
         decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
         file_path (str): Path to the file containing raw blockchain data.
-        abi_db_path (str, optional): Path to the ABI database file. If None, uses the path set in the config.
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
 
     Returns:
-        DataFrameType: Decoded DataFrame (polars or pandas according to the config) with the results.
+        DecodeFileResult: The decoded DataFrame (polars or pandas according to the config) in `df`, with the input and
+            output paths, the input, output and matched row counts and the duration.
 
     Example:
         ```python
-        decoded_df = decode_file(
+        result = decode_file(
             "log",
             "data/logs/ethereum__logs__blocks__18426253_to_18426303_example.parquet",
             "ABIs/ethereum__events_abis.parquet"
         )
+        print(result.output_path, result.matched_rows / result.output_rows)
         ```
     """
     if abi_db_path is None:
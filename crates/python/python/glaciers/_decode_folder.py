@@ -1,13 +1,12 @@
-import polars as pl
+import json
 import toml
-from ._dataframe_utils import DataFrameType, to_prefered_type
 from glaciers import get_config
 
 async def async_decode_folder(
     decoder_type: str,
     folder_path = None,   
     abi_db_path = None,
-) -> None:
+) -> dict:
     """
     Asynchronously decode blockchain data from all files in a folder, provided the path to the folder and the path to the ABI DB file.
     Decoded files are saved in a "decoded" folder, in the parent folder of the raw data.
@@ -17,13 +16,16 @@ async def async_decode_folder(
     Args:
         decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
         folder_path (str, optional): Path to folder containing raw blockchain data. If None, uses the path set in the config.
-        abi_db_path (str, optional): Path to the ABI database file. If None, uses the path set in the config.
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
 
     Returns:
-        None
+        dict: Manifest of the run, with the input folder, the ABI DB paths and row count, the config, the results of each file
+            (rows in/out, match rate, duration, output path and status) under "files", and the overall "totals".
+            The ABI DB row count is None unless decoder.write_run_manifest is set.
 
     Note:
         This function gets the max_concurrent_files_decoding from the config and uses it to limit the number of concurrent files that can be decoded at the same time.
+        If decoder.write_run_manifest is set in the config, the manifest is also saved as manifest.json in the "decoded" folder.
 
     Example:
         ```python
@@ -51,14 +53,17 @@ async def async_decode_folder(
         elif decoder_type == "trace":
             abi_db_path = toml.loads(get_config())["main"]["functions_abi_db_file_path"]
 
-    result: pl.DataFrame = await _glaciers_python.decode_folder(decoder_type, folder_path, abi_db_path)
-    return to_prefered_type(result)
+    if isinstance(abi_db_path, str):
+        abi_db_path = [abi_db_path]
+
+    manifest: str = await _glaciers_python.decode_folder(decoder_type, folder_path, abi_db_path)
+    return json.loads(manifest)
 
 def decode_folder(
     decoder_type: str,
     folder_path = None,   
     abi_db_path = None,
-) -> None:
+) -> dict:
     """
     Decode blockchain data from all files in a folder, provided the path to the folder and the path to the ABI DB file.
     Decoded files are saved in a "decoded" folder, in the parent folder of the raw data.
@@ -69,13 +74,16 @@ def decode_folder(
     Args:
         decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
         folder_path (str, optional): Path to folder containing raw blockchain data. If None, uses the path set in the config.
-        abi_db_path (str, optional): Path to the ABI database file. If None, uses the path set in the config.
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
 
     Returns:
-        None
+        dict: Manifest of the run, with the input folder, the ABI DB paths and row count, the config, the results of each file
+            (rows in/out, match rate, duration, output path and status) under "files", and the overall "totals".
+            The ABI DB row count is None unless decoder.write_run_manifest is set.
 
     Note:
         This function gets the max_concurrent_files_decoding from the config and uses it to limit the number of concurrent files that can be decoded at the same time.
+        If decoder.write_run_manifest is set in the config, the manifest is also saved as manifest.json in the "decoded" folder.
 
     Example:
         ```python
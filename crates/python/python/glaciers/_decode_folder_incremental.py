@@ -0,0 +1,118 @@
+import json
+import toml
+from datetime import datetime, timezone
+from glaciers import get_config
+
+async def async_decode_folder_incremental(
+    decoder_type: str,
+    watermark: datetime = None,
+    folder_path = None,
+    abi_db_path = None,
+) -> tuple[dict, datetime]:
+    """
+    Asynchronously decode the files of a folder modified after a watermark, i.e: the files added since the last run of a scheduled pipeline.
+    Files are decoded like in decode_folder, and saved in the same "decoded" folder, in the parent folder of the raw data.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        watermark (datetime, optional): Only the files modified at or after this time are decoded. Naive datetimes are taken as UTC.
+            If None, uses the watermark saved by a previous run (see decoder.write_watermark_file), skipping the files it already decoded
+            at its time, or decodes all files if there is none.
+        folder_path (str, optional): Path to folder containing raw blockchain data. If None, uses the path set in the config.
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+
+    Returns:
+        tuple[dict, datetime]: The manifest of the run, like in decode_folder, and the new watermark: the latest modification time
+            of the decoded files (in UTC), or the same watermark if no file was newer.
+
+    Note:
+        If decoder.write_watermark_file is set in the config, the new watermark is saved as decode_watermark.json in the "decoded" folder
+        when all files are decoded. decoder.dataset_mode is ignored.
+
+    Example:
+        ```python
+        manifest, watermark = await async_decode_folder_incremental(
+            "log",
+            datetime(2024, 1, 1, tzinfo=timezone.utc),
+            "data/logs",
+            "ABIs/ethereum__events_abis.parquet"
+        )
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    from . import _glaciers_python
+    if folder_path is None:
+        if decoder_type == "log":
+            folder_path = toml.loads(get_config())["main"]["raw_logs_folder_path"]
+        elif decoder_type == "trace":
+            folder_path = toml.loads(get_config())["main"]["raw_traces_folder_path"]
+
+    if abi_db_path is None:
+        if decoder_type == "log":
+            abi_db_path = toml.loads(get_config())["main"]["events_abi_db_file_path"]
+        elif decoder_type == "trace":
+            abi_db_path = toml.loads(get_config())["main"]["functions_abi_db_file_path"]
+
+    if isinstance(abi_db_path, str):
+        abi_db_path = [abi_db_path]
+
+    if watermark is not None:
+        if watermark.tzinfo is None:
+            watermark = watermark.replace(tzinfo=timezone.utc)
+        watermark = watermark.isoformat()
+
+    result = json.loads(await _glaciers_python.decode_folder_incremental(decoder_type, folder_path, abi_db_path, watermark))
+    return result["manifest"], datetime.fromisoformat(result["watermark"])
+
+def decode_folder_incremental(
+    decoder_type: str,
+    watermark: datetime = None,
+    folder_path = None,
+    abi_db_path = None,
+) -> tuple[dict, datetime]:
+    """
+    Decode the files of a folder modified after a watermark, i.e: the files added since the last run of a scheduled pipeline.
+    Files are decoded like in decode_folder, and saved in the same "decoded" folder, in the parent folder of the raw data.
+    This is a synchronous wrapper around async_decode_folder_incremental.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        watermark (datetime, optional): Only the files modified at or after this time are decoded. Naive datetimes are taken as UTC.
+            If None, uses the watermark saved by a previous run (see decoder.write_watermark_file), skipping the files it already decoded
+            at its time, or decodes all files if there is none.
+        folder_path (str, optional): Path to folder containing raw blockchain data. If None, uses the path set in the config.
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+
+    Returns:
+        tuple[dict, datetime]: The manifest of the run, like in decode_folder, and the new watermark: the latest modification time
+            of the decoded files (in UTC), or the same watermark if no file was newer.
+
+    Note:
+        If decoder.write_watermark_file is set in the config, the new watermark is saved as decode_watermark.json in the "decoded" folder
+        when all files are decoded. decoder.dataset_mode is ignored.
+
+    Example:
+        ```python
+        # Hourly job, resuming from the watermark saved by the previous run
+        set_config("decoder.write_watermark_file", True)
+        manifest, watermark = decode_folder_incremental("log", None, "data/logs", "ABIs/ethereum__events_abis.parquet")
+        ```
+    """
+    import asyncio
+    coroutine = async_decode_folder_incremental(decoder_type, watermark, folder_path, abi_db_path)
+
+    try:
+        import concurrent.futures
+
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        with concurrent.futures.ThreadPoolExecutor() as executor:
+            future = executor.submit(loop.run_until_complete, coroutine)
+            result = future.result()
+    except RuntimeError:
+        result = asyncio.run(coroutine)
+
+    return result
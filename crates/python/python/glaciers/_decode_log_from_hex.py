@@ -0,0 +1,43 @@
+from typing import Optional
+from . import _glaciers_python
+
+def decode_log_from_hex(
+    topic0: str,
+    data: str,
+    full_signature: str,
+    topic1: Optional[str] = None,
+    topic2: Optional[str] = None,
+    topic3: Optional[str] = None,
+) -> dict:
+    """
+    Decode a single log from hex strings (with or without the 0x prefix), without building a DataFrame.
+    Useful for quick interactive use, i.e: checking a log from a block explorer against a signature.
+
+    Args:
+        topic0 (str): Topic0 of the log.
+        data (str): Data field of the log. Use "" or "0x" for logs without data.
+        full_signature (str): Full event signature to decode the log with.
+        topic1 (str, optional): Topic1 of the log.
+        topic2 (str, optional): Topic2 of the log.
+        topic3 (str, optional): Topic3 of the log.
+
+    Returns:
+        dict: The decoded "event_values" and "event_keys" lists, the "event_json" string and the "string_validity" and
+        "truncated_values" flags, like the columns of a decoded logs DataFrame.
+
+    Note:
+        Topics missing from the signature's indexed params follow log_decoder.missing_topics_mode in the config.
+
+    Example:
+        ```python
+        decoded = decode_log_from_hex(
+            "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+            "0x0000000000000000000000000000000000000000000000000000000000000064",
+            "event Transfer(address indexed from, address indexed to, uint256 value)",
+            topic1="0x000000000000000000000000" + "11" * 20,
+            topic2="0x000000000000000000000000" + "22" * 20,
+        )
+        assert decoded["event_values"][2] == "100"
+        ```
+    """
+    return _glaciers_python.decode_log_from_hex(topic0, data, full_signature, topic1, topic2, topic3)
@@ -0,0 +1,109 @@
+import json
+import toml
+from glaciers import get_config
+
+async def async_decode_partition(
+    decoder_type: str,
+    input_file: str,
+    output_file: str,
+    abi_db_path = None,
+) -> dict:
+    """
+    Asynchronously decode a single partition file into an explicit output path, i.e: for orchestrators (Airflow, Dagster)
+    calling glaciers once per partition.
+    The output is written to a temporary file and renamed, so it's never left half written. If the output already exists
+    with the same config, nothing is decoded and the summary stored in its metadata is returned.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        input_file (str): Path to the raw blockchain data file.
+        output_file (str): Path of the decoded file. Its extension sets the format (csv, parquet or ndjson_pretty).
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+
+    Returns:
+        dict: Summary of the partition: "input_path", "output_path", "glaciers_version", "config_hash", "rows_in", "rows_out",
+            "matched_rows", "match_rate", "duration_secs" and "skipped" (True if the output already existed with the same config).
+
+    Note:
+        Only parquet outputs store the summary, csv and ndjson_pretty outputs are always decoded again.
+        An output written by another config is refused, unless decoder.overwrite_mismatched_outputs is set in the config.
+
+    Example:
+        ```python
+        summary = await async_decode_partition(
+            "log",
+            "raw/logs/2024-01-01.parquet",
+            "decoded/logs/2024-01-01.parquet",
+            "ABIs/ethereum__events_abis.parquet"
+        )
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    from . import _glaciers_python
+    if abi_db_path is None:
+        if decoder_type == "log":
+            abi_db_path = toml.loads(get_config())["main"]["events_abi_db_file_path"]
+        elif decoder_type == "trace":
+            abi_db_path = toml.loads(get_config())["main"]["functions_abi_db_file_path"]
+
+    if isinstance(abi_db_path, str):
+        abi_db_path = [abi_db_path]
+
+    summary: str = await _glaciers_python.decode_partition(decoder_type, input_file, output_file, abi_db_path)
+    return json.loads(summary)
+
+def decode_partition(
+    decoder_type: str,
+    input_file: str,
+    output_file: str,
+    abi_db_path = None,
+) -> dict:
+    """
+    Decode a single partition file into an explicit output path, i.e: for orchestrators (Airflow, Dagster)
+    calling glaciers once per partition.
+    The output is written to a temporary file and renamed, so it's never left half written. If the output already exists
+    with the same config, nothing is decoded and the summary stored in its metadata is returned.
+    This is a synchronous wrapper around async_decode_partition.
+
+    Args:
+        decoder_type (str): Type of decoder to use. Must be either "log" or "trace".
+        input_file (str): Path to the raw blockchain data file.
+        output_file (str): Path of the decoded file. Its extension sets the format (csv, parquet or ndjson_pretty).
+        abi_db_path (str | list[str], optional): Path to the ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+
+    Returns:
+        dict: Summary of the partition: "input_path", "output_path", "glaciers_version", "config_hash", "rows_in", "rows_out",
+            "matched_rows", "match_rate", "duration_secs" and "skipped" (True if the output already existed with the same config).
+
+    Note:
+        Only parquet outputs store the summary, csv and ndjson_pretty outputs are always decoded again.
+        An output written by another config is refused, unless decoder.overwrite_mismatched_outputs is set in the config.
+
+    Example:
+        ```python
+        summary = decode_partition(
+            "log",
+            "raw/logs/2024-01-01.parquet",
+            "decoded/logs/2024-01-01.parquet",
+            "ABIs/ethereum__events_abis.parquet"
+        )
+        ```
+    """
+    import asyncio
+    coroutine = async_decode_partition(decoder_type, input_file, output_file, abi_db_path)
+
+    try:
+        import concurrent.futures
+
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        with concurrent.futures.ThreadPoolExecutor() as executor:
+            future = executor.submit(loop.run_until_complete, coroutine)
+            result = future.result()
+    except RuntimeError:
+        result = asyncio.run(coroutine)
+
+    return result
@@ -0,0 +1,97 @@
+import polars as pl
+import toml
+from typing import Tuple
+from ._dataframe_utils import DataFrameType, to_polars, to_prefered_type
+from . import _glaciers_python
+from glaciers import get_config
+
+async def async_decode_tx_bundle(
+    logs_df: DataFrameType,
+    traces_df: DataFrameType,
+    events_abi_db_path = None,
+    functions_abi_db_path = None,
+) -> Tuple[DataFrameType, DataFrameType]:
+    """
+    Asynchronously decode the logs and traces of a transaction bundle in a single call.
+
+    Both decodings share the same config snapshot and thread budget.
+
+    Args:
+        logs_df (DataFrameType): DataFrame (polars or pandas) containing the raw logs.
+        traces_df (DataFrameType): DataFrame (polars or pandas) containing the raw traces.
+        events_abi_db_path (str | list[str], optional): Path to the events ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+        functions_abi_db_path (str | list[str], optional): Path to the functions ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+
+    Returns:
+        Tuple[DataFrameType, DataFrameType]: Decoded logs and decoded traces DataFrames (polars or pandas according to the config).
+
+    Example:
+        ```python
+        decoded_logs, decoded_traces = await async_decode_tx_bundle(
+            raw_logs_df,
+            raw_traces_df,
+        )
+        ```
+    """
+    if events_abi_db_path is None:
+        events_abi_db_path = toml.loads(get_config())["main"]["events_abi_db_file_path"]
+    if functions_abi_db_path is None:
+        functions_abi_db_path = toml.loads(get_config())["main"]["functions_abi_db_file_path"]
+
+    if isinstance(events_abi_db_path, str):
+        events_abi_db_path = [events_abi_db_path]
+    if isinstance(functions_abi_db_path, str):
+        functions_abi_db_path = [functions_abi_db_path]
+
+    logs_pl = to_polars(logs_df)
+    traces_pl = to_polars(traces_df)
+
+    result: Tuple[pl.DataFrame, pl.DataFrame] = await _glaciers_python.decode_tx_bundle(
+        logs_pl, traces_pl, events_abi_db_path, functions_abi_db_path
+    )
+    decoded_logs, decoded_traces = result
+    return to_prefered_type(decoded_logs), to_prefered_type(decoded_traces)
+
+def decode_tx_bundle(
+    logs_df: DataFrameType,
+    traces_df: DataFrameType,
+    events_abi_db_path = None,
+    functions_abi_db_path = None,
+) -> Tuple[DataFrameType, DataFrameType]:
+    """
+    Decode the logs and traces of a transaction bundle in a single call.
+
+    This is a synchronous wrapper around async_decode_tx_bundle.
+
+    Args:
+        logs_df (DataFrameType): DataFrame (polars or pandas) containing the raw logs.
+        traces_df (DataFrameType): DataFrame (polars or pandas) containing the raw traces.
+        events_abi_db_path (str | list[str], optional): Path to the events ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+        functions_abi_db_path (str | list[str], optional): Path to the functions ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+
+    Returns:
+        Tuple[DataFrameType, DataFrameType]: Decoded logs and decoded traces DataFrames (polars or pandas according to the config).
+
+    Example:
+        ```python
+        decoded_logs, decoded_traces = decode_tx_bundle(
+            raw_logs_df,
+            raw_traces_df,
+        )
+        ```
+    """
+    import asyncio
+    coroutine = async_decode_tx_bundle(logs_df, traces_df, events_abi_db_path, functions_abi_db_path)
+
+    try:
+        import concurrent.futures
+
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        with concurrent.futures.ThreadPoolExecutor() as executor:
+            future = executor.submit(loop.run_until_complete, coroutine)
+            result = future.result()
+    except RuntimeError:
+        result = asyncio.run(coroutine)
+
+    return result
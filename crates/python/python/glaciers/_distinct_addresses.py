@@ -0,0 +1,31 @@
+from ._dataframe_utils import DataFrameType, to_prefered_type
+from . import _glaciers_python
+
+def distinct_addresses(
+    file_path: str,
+    decoder_type: str = "log",
+) -> DataFrameType:
+    """
+    List the distinct contract addresses of a raw logs/traces file, with their number of logs/traces, without decoding.
+    Only the address column (log_decoder.log_schema.log_alias.address for logs, trace_decoder.trace_schema.trace_alias.action_to
+    for traces) is read from the file. Useful to choose the ABIs to fetch before decoding.
+
+    Args:
+        file_path (str): Path to the raw logs/traces file (parquet or csv), or to a folder of files.
+        decoder_type (str): Type of the raw data. Must be either "log" or "trace". Defaults to "log".
+
+    Returns:
+        DataFrameType: DataFrame (polars or pandas according to the config) with the address (0x prefixed hex string)
+        and count columns, sorted by descending count.
+
+    Example:
+        ```python
+        addresses_df = distinct_addresses("data/logs/logs.parquet")
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    result = _glaciers_python.distinct_addresses(file_path, decoder_type)
+    return to_prefered_type(result)
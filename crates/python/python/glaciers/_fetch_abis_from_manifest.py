@@ -0,0 +1,68 @@
+import polars as pl
+import toml
+from ._dataframe_utils import DataFrameType, to_prefered_type
+from . import _glaciers_python
+from glaciers import get_config
+
+async def async_fetch_abis_from_manifest(
+    manifest_path: str,
+    abi_db_path: str | None = None,
+) -> DataFrameType:
+    """
+    Asynchronously download the ABIs listed in a manifest file and append them to the ABI DB.
+    Each ABI is downloaded from the source indicated in the manifest, and all ABIs are appended with a single deduplication pass.
+
+    Args:
+        manifest_path (str): Path to a csv or parquet manifest, with the columns address, chain_id and source ("sourcify" or "etherscan").
+        abi_db_path (str | None, optional): Path to the ABI database file. If None, uses the path set in the config file. Defaults to None.
+
+    Returns:
+        DataFrameType: A report DataFrame, with the columns address, chain_id, source, success, num_items and error.
+
+    Example:
+        ```python
+        report_df = await async_fetch_abis_from_manifest("manifest.csv", "ABIs/ethereum__events__abis.parquet")
+        ```
+    """
+    if abi_db_path is None:
+        abi_db_path = toml.loads(get_config())["main"]["events_abi_db_file_path"]
+
+    result_pl: pl.DataFrame = await _glaciers_python.fetch_abis_from_manifest(manifest_path, abi_db_path)
+    return to_prefered_type(result_pl)
+
+def fetch_abis_from_manifest(
+    manifest_path: str,
+    abi_db_path: str | None = None,
+) -> DataFrameType:
+    """
+    Download the ABIs listed in a manifest file and append them to the ABI DB.
+    Each ABI is downloaded from the source indicated in the manifest, and all ABIs are appended with a single deduplication pass.
+
+    This is a synchronous wrapper around async_fetch_abis_from_manifest.
+
+    Args:
+        manifest_path (str): Path to a csv or parquet manifest, with the columns address, chain_id and source ("sourcify" or "etherscan").
+        abi_db_path (str | None, optional): Path to the ABI database file. If None, uses the path set in the config file. Defaults to None.
+
+    Returns:
+        DataFrameType: A report DataFrame, with the columns address, chain_id, source, success, num_items and error.
+
+    Example:
+        ```python
+        report_df = fetch_abis_from_manifest("manifest.csv", "ABIs/ethereum__events__abis.parquet")
+        ```
+    """
+    import asyncio
+    coroutine = async_fetch_abis_from_manifest(manifest_path, abi_db_path)
+
+    try:
+        import concurrent.futures
+        loop = asyncio.new_event_loop()
+        asyncio.set_event_loop(loop)
+        with concurrent.futures.ThreadPoolExecutor() as executor:
+            future = executor.submit(loop.run_until_complete, coroutine)
+            result = future.result()
+    except RuntimeError:
+        result = asyncio.run(coroutine)
+
+    return result
@@ -0,0 +1,39 @@
+import toml
+from typing import Optional, Union
+from ._dataframe_utils import DataFrameType, to_polars, to_prefered_type
+from . import _glaciers_python
+from glaciers import get_config
+
+def label_selectors(
+    df: DataFrameType,
+    abi_db_path: Optional[Union[str, list[str]]] = None,
+) -> DataFrameType:
+    """
+    Label a DataFrame of 4 bytes function selectors with their names and signatures, without decoding calldata.
+    The selector column name is trace_decoder.trace_schema.trace_alias.selector in the config, and it can be binary or a hex string.
+    Ambiguous selectors are labeled with the most frequent signature in the ABI DB, and candidate_count has
+    the number of distinct signatures for each selector (0 for unknown selectors).
+
+    Args:
+        df (DataFrameType): DataFrame (polars or pandas) containing the selector column.
+        abi_db_path (str | list[str], optional): Path to the functions ABI database file, or a list of paths to multiple ABI database files. If None, uses the path set in the config.
+
+    Returns:
+        DataFrameType: DataFrame (polars or pandas according to the config) with the name, full_signature and candidate_count columns added.
+
+    Example:
+        ```python
+        labeled_df = label_selectors(
+            selectors_df,
+            "ABIs/ethereum__functions__abis.parquet"
+        )
+        ```
+    """
+    if abi_db_path is None:
+        abi_db_path = toml.loads(get_config())["main"]["functions_abi_db_file_path"]
+    if isinstance(abi_db_path, str):
+        abi_db_path = [abi_db_path]
+
+    df_pl = to_polars(df)
+    result = _glaciers_python.label_selectors(df_pl, abi_db_path)
+    return to_prefered_type(result)
@@ -0,0 +1,44 @@
+from datetime import datetime, timezone
+from ._dataframe_utils import DataFrameType, to_prefered_type
+from . import _glaciers_python
+
+def plan_folder(
+    folder_path: str,
+    decoder_type: str = "log",
+    extensions: list[str] = None,
+    modified_since: datetime = None,
+    block_range: tuple[int, int] = None,
+) -> DataFrameType:
+    """
+    List the files of a folder that decode_folder would decode, with their decoded file paths, to inspect them before a run.
+    Subfolders are skipped, and the files are sorted by path.
+
+    Args:
+        folder_path (str): Path to the folder containing the raw logs/traces files.
+        decoder_type (str): Type of the raw data, used to name the decoded files. Must be either "log" or "trace". Defaults to "log".
+        extensions (list[str], optional): Only plan the files with these extensions (i.e: ["parquet"]). If None, all the files are planned.
+        modified_since (datetime, optional): Only plan the files modified after this time. Naive datetimes are taken as UTC.
+        block_range (tuple[int, int], optional): Only plan the files whose block range, inferred from the file name
+            (i.e: "ethereum__logs__00018000000_to_00018099999.parquet"), overlaps this inclusive (from_block, to_block) range.
+            Files without a block range in their name are left out.
+
+    Returns:
+        DataFrameType: DataFrame (polars or pandas according to the config) with the input_path, output_path, modified (UTC datetime),
+        size_bytes, from_block and to_block (null if the file name has no block range) of each planned file.
+
+    Example:
+        ```python
+        plan_df = plan_folder("data/logs", extensions=["parquet"], block_range=(18_000_000, 18_999_999))
+        ```
+    """
+    valid_decoder_types = ["log", "trace"]
+    if decoder_type not in valid_decoder_types:
+        raise ValueError(f"Decoder type must be one of {valid_decoder_types}")
+
+    if modified_since is not None:
+        if modified_since.tzinfo is None:
+            modified_since = modified_since.replace(tzinfo=timezone.utc)
+        modified_since = modified_since.isoformat()
+
+    result = _glaciers_python.plan_folder(folder_path, decoder_type, extensions or [], modified_since, block_range)
+    return to_prefered_type(result)
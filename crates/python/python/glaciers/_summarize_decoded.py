@@ -0,0 +1,23 @@
+from ._dataframe_utils import DataFrameType, to_polars, to_prefered_type
+from . import _glaciers_python
+
+def summarize_decoded(df: DataFrameType) -> DataFrameType:
+    """
+    Summarize a decoded logs/traces DataFrame by contract and event/function name.
+    The address column is the log address or the trace action_to, according to the aliases in the config.
+
+    Args:
+        df (DataFrameType): Decoded DataFrame (polars or pandas).
+
+    Returns:
+        DataFrameType: DataFrame (polars or pandas according to the config) with one row per address, name and
+            match_status ("decoded", "decoding_error" or "unmatched"), with a count column, plus first_block and
+            last_block when the input has a block_number column. Rows are sorted by address and descending count.
+
+    Example:
+        ```python
+        summary = summarize_decoded(decoded_df)
+        ```
+    """
+    result = _glaciers_python.summarize_decoded(to_polars(df))
+    return to_prefered_type(result)
@@ -1,7 +1,9 @@
+import json
 import polars as pl
 import toml
 from glaciers import get_config
 from ._dataframe_utils import DataFrameType, to_polars, to_prefered_type
+from . import _glaciers_python
 
 def unnest_event(
         decoded_log_df: DataFrameType,
@@ -21,7 +23,8 @@ def unnest_event(
         topic0 (str, optional): The topic0 of the event to filter the DataFrame. topic0 must be a hex string.
 
     Returns:
-        DataFrameType: The unnested DataFrame.
+        DataFrameType: The unnested DataFrame. Params named like an existing column (i.e: a param named "address", not to
+            replace the emitter address column) are unnested as "param_<name>".
 
     Example:
         ```python
@@ -65,7 +68,7 @@ def unnest_event(
     elif filtered_df.height == 0:
         raise ValueError("No event found after filtering with the given parameters")
     else:
-        first_row = filtered_df.select(pl.col("event_json").str.json_decode()).row(0)[0]
+        first_row = json.loads(filtered_df["event_json"][0])
         num_fields = len(first_row)
         value_types = []
         field_names = []
@@ -75,6 +78,7 @@ def unnest_event(
                 field_names.append(first_row[i]["name"])
             else:
                 field_names.append(f"field_{i}")
+        field_names = _glaciers_python.param_column_names(field_names, filtered_df.columns)
 
         unnesting_hex_string_encoding = toml.loads(get_config())["glaciers"]["unnesting_hex_string_encoding"]
 
@@ -83,7 +87,7 @@ def unnest_event(
                 filtered_df = filtered_df.with_columns(pl.col("event_values").str.json_decode().list.get(i).replace_strict({"false":False, "true":True}).cast(pl.Boolean).alias(f"{field_names[i]}"))
             elif "int" in type:
                 filtered_df = filtered_df.with_columns(pl.col("event_values").str.json_decode().list.get(i).cast(pl.Float64).alias(f"{field_names[i]}"))
-            elif "bytes" in type:
+            elif "bytes" in type or type == "indexed (hashed tuple)":
                 if unnesting_hex_string_encoding:
                     filtered_df = filtered_df.with_columns(pl.col("event_values").str.json_decode().list.get(i).cast(pl.String).alias(f"{field_names[i]}"))
                 else:
@@ -118,7 +122,8 @@ def unnest_trace(
         full_signature (str, optional): The full signature of the function to filter the DataFrame.
 
     Returns:
-        DataFrameType: The unnested DataFrame.
+        DataFrameType: The unnested DataFrame. Params named like an existing column (i.e: a param named "selector") are
+            unnested as "param_<name>".
 
     Example:
         ```python
@@ -164,7 +169,7 @@ def unnest_trace(
     else:
         unnesting_hex_string_encoding = toml.loads(get_config())["glaciers"]["unnesting_hex_string_encoding"]
 
-        input_first_row = filtered_df.select(pl.col("input_json").str.json_decode()).row(0)[0]
+        input_first_row = json.loads(filtered_df["input_json"][0])
         input_num_fields = len(input_first_row)
         input_value_types = []
         input_field_names = []
@@ -174,6 +179,7 @@ def unnest_trace(
                 input_field_names.append(input_first_row[i]["name"])
             else:
                 input_field_names.append(f"input_{i}")
+        input_field_names = _glaciers_python.param_column_names(input_field_names, filtered_df.columns)
         
         for (i, type) in enumerate(input_value_types):
             if type == "bool":
@@ -193,7 +199,7 @@ def unnest_trace(
             elif type == "string":
                 filtered_df = filtered_df.with_columns(pl.col("input_values").str.json_decode().list.get(i).cast(pl.String).alias(f"{input_field_names[i]}"))
 
-        output_first_row = filtered_df.select(pl.col("output_json").str.json_decode()).row(0)[0]
+        output_first_row = json.loads(filtered_df["output_json"][0])
         output_num_fields = len(output_first_row)
         output_value_types = []
         output_field_names = []
@@ -203,6 +209,7 @@ def unnest_trace(
                 output_field_names.append(output_first_row[i]["name"])
             else:
                 output_field_names.append(f"output_{i}")
+        output_field_names = _glaciers_python.param_column_names(output_field_names, filtered_df.columns + input_field_names)
         
         for (i, type) in enumerate(output_value_types):
             if type == "bool":
@@ -0,0 +1,48 @@
+from ._dataframe_utils import DataFrameType, to_polars, to_prefered_type
+from . import _glaciers_python
+
+def to_hex_columns(df: DataFrameType, columns: list[str]) -> DataFrameType:
+    """Converts the chosen columns to 0x prefixed hex string columns, without using the config.
+
+    The conversion is idempotent: binary columns are hex encoded, string columns only get the 0x prefix if it's missing.
+
+    Args:
+        df (DataFrameType): DataFrame (polars or pandas) with the columns to convert.
+        columns (list[str]): Names of the columns to convert.
+
+    Returns:
+        DataFrameType: A DataFrame with the converted columns.
+
+    Raises:
+        ValueError: If a column is missing or isn't binary or string. The message names the offending column.
+
+    Examples:
+        ```python
+        df = to_hex_columns(decoded_logs_df, ["transaction_hash", "address"])
+        ```
+    """
+    df = _glaciers_python.to_hex_columns(to_polars(df), columns)
+    return to_prefered_type(df)
+
+def to_binary_columns(df: DataFrameType, columns: list[str]) -> DataFrameType:
+    """Converts the chosen columns to binary columns, without using the config.
+
+    The conversion is idempotent: string columns are hex decoded (with or without the 0x prefix), binary columns are kept as they are.
+
+    Args:
+        df (DataFrameType): DataFrame (polars or pandas) with the columns to convert.
+        columns (list[str]): Names of the columns to convert.
+
+    Returns:
+        DataFrameType: A DataFrame with the converted columns.
+
+    Raises:
+        ValueError: If a column is missing, isn't binary or string, or has invalid hex strings. The message names the offending column.
+
+    Examples:
+        ```python
+        df = to_binary_columns(raw_logs_df, ["transaction_hash", "topic0"])
+        ```
+    """
+    df = _glaciers_python.to_binary_columns(to_polars(df), columns)
+    return to_prefered_type(df)
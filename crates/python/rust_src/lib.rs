@@ -1,15 +1,20 @@
-use std::str::FromStr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use chrono::{DateTime, SecondsFormat, Utc};
 use alloy::primitives::Address;
 use alloy::json_abi::JsonAbi;
 use pyo3::prelude::*;
 use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
 use polars::prelude::*;
 use glaciers::abi_reader;
 use glaciers::configger;
 use glaciers::miscellaneous;
-use glaciers::decoder::{self, DecoderType};
+use glaciers::decoder::{self, AggKind, DecoderType};
+use glaciers::log_decoder::{self, RawBytes};
+use glaciers::matcher;
+use glaciers::utils;
 
 /// Register in the Python module the functions tbelow hat can be called in Python
 #[pymodule]
@@ -19,14 +24,43 @@ fn glaciers_python(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(set_config, m)?)?;
     m.add_function(wrap_pyfunction!(set_config_toml, m)?)?;
     m.add_function(wrap_pyfunction!(update_abi_db, m)?)?;
+    m.add_function(wrap_pyfunction!(update_abi_db_with_progress, m)?)?;
     m.add_function(wrap_pyfunction!(read_new_abi_folder, m)?)?;
+    m.add_function(wrap_pyfunction!(read_new_abi_folder_parallel, m)?)?;
     m.add_function(wrap_pyfunction!(read_new_abi_file, m)?)?;
     m.add_function(wrap_pyfunction!(read_new_abi_json, m)?)?;
+    m.add_function(wrap_pyfunction!(deduplicate_abi_df, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_num_indexed_args, m)?)?;
+    m.add_function(wrap_pyfunction!(abi_reader_output_schema, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_abi_db_encoding, m)?)?;
     m.add_function(wrap_pyfunction!(decode_folder, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_folder_incremental, m)?)?;
+    m.add_function(wrap_pyfunction!(plan_folder, m)?)?;
     m.add_function(wrap_pyfunction!(decode_file, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_archive, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_partition, m)?)?;
     m.add_function(wrap_pyfunction!(decode_df, m)?)?;
     m.add_function(wrap_pyfunction!(decode_df_with_abi_df, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_tx_bundle, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_df_with_context, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_df_with_priority, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_df_with_overrides, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_to_wide, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_and_aggregate, m)?)?;
+    m.add_function(wrap_pyfunction!(distinct_addresses, m)?)?;
+    m.add_function(wrap_pyfunction!(benchmark_decode, m)?)?;
+    m.add_function(wrap_pyfunction!(can_decode, m)?)?;
+    m.add_function(wrap_pyfunction!(label_selectors, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_event_count, m)?)?;
+    m.add_function(wrap_pyfunction!(summarize_decoded, m)?)?;
+    m.add_function(wrap_pyfunction!(compatibility_report, m)?)?;
     m.add_function(wrap_pyfunction!(decode_df_using_single_contract, m)?)?;
+    m.add_function(wrap_pyfunction!(fetch_abis_from_manifest, m)?)?;
+    m.add_function(wrap_pyfunction!(build_logs_df, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_log_from_hex, m)?)?;
+    m.add_function(wrap_pyfunction!(to_hex_columns, m)?)?;
+    m.add_function(wrap_pyfunction!(to_binary_columns, m)?)?;
+    m.add_function(wrap_pyfunction!(param_column_names, m)?)?;
     Ok(())
 }
 
@@ -91,7 +125,50 @@ pub fn set_config_toml(path: String) -> PyResult<()> {
 pub fn update_abi_db(abi_db_path: String, abi_folder_path: String) -> PyResult<PyDataFrame> {
     abi_reader::update_abi_db(abi_db_path, abi_folder_path)
         .map_err(|e| PyValueError::new_err(format!("Error reading ABIs: {}", e)))
-        .map(|df| PyDataFrame(df))
+        .map(PyDataFrame)
+}
+
+/// Reads ABIs in a folder and append to the ABI parquet file, reporting the progress and counts of the update
+///
+/// # Arguments
+/// - `abi_db_path`: Path to the parquet file containing the existing DataFrame.
+/// - `abi_folder_path`: Path to the folder containing ABI JSON files
+/// - `progress`: Optional callable, called after each file with the number of files processed and the total number of files
+///
+/// # Returns
+/// A `PyResult` containing a `PyDataFrame` with all unique itens, and a dict with the files_read, files_skipped and rows_added counts
+///
+/// # Errors
+/// Returns a `PyValueError` if there are issues reading or processing the ABIs, or the error raised by the progress callable
+#[pyfunction]
+#[pyo3(signature = (abi_db_path, abi_folder_path, progress=None))]
+pub fn update_abi_db_with_progress(
+    py: Python<'_>,
+    abi_db_path: String,
+    abi_folder_path: String,
+    progress: Option<PyObject>,
+) -> PyResult<(PyDataFrame, HashMap<String, usize>)> {
+    // The first error raised by the callable is returned once the update is done
+    let mut progress_error = None;
+    let result = abi_reader::update_abi_db_with_progress(abi_db_path, abi_folder_path, |done, total| {
+        if let (Some(progress), None) = (&progress, &progress_error) {
+            if let Err(e) = progress.call1(py, (done, total)) {
+                progress_error = Some(e);
+            }
+        }
+    });
+    if let Some(e) = progress_error {
+        return Err(e);
+    }
+    let (abi_df, stats) = result.map_err(|e| PyValueError::new_err(format!("Error reading ABIs: {}", e)))?;
+    Ok((
+        PyDataFrame(abi_df),
+        HashMap::from([
+            ("files_read".to_string(), stats.files_read),
+            ("files_skipped".to_string(), stats.files_skipped),
+            ("rows_added".to_string(), stats.rows_added),
+        ]),
+    ))
 }
 
 /// Reads ABIs (Application Binary Interface) from a folder
@@ -111,7 +188,27 @@ pub fn update_abi_db(abi_db_path: String, abi_folder_path: String) -> PyResult<P
 pub fn read_new_abi_folder(abi_folder_path: String) -> PyResult<PyDataFrame> {
     abi_reader::read_new_abi_folder(&abi_folder_path)
         .map_err(|e| PyValueError::new_err(format!("Error reading ABI folder: {}", e)))
-        .map(|df| PyDataFrame(df))
+        .map(PyDataFrame)
+}
+
+/// Reads ABIs (Application Binary Interface) from the subfolders of a folder in parallel
+///
+/// Each subfolder is read atomically: a subfolder with an ABI file that can't be read
+/// is skipped and its errors are printed, without blocking the other subfolders.
+///
+/// # Arguments
+/// - `root_path`: Path to the folder containing the ABI subfolders
+///
+/// # Returns
+/// A `PyResult` containing a `PyDataFrame` with all functions and events of the subfolders read
+///
+/// # Errors
+/// Returns a `PyValueError` if the root folder can't be read
+#[pyfunction]
+pub fn read_new_abi_folder_parallel(root_path: String) -> PyResult<PyDataFrame> {
+    abi_reader::read_new_abi_folder_parallel(&root_path)
+        .map_err(|e| PyValueError::new_err(format!("Error reading ABI folder: {}", e)))
+        .map(PyDataFrame)
 }
 
 /// Reads an ABI (Application Binary Interface) from a file
@@ -131,7 +228,7 @@ pub fn read_new_abi_folder(abi_folder_path: String) -> PyResult<PyDataFrame> {
 pub fn read_new_abi_file(path: String) -> PyResult<PyDataFrame> {
     abi_reader::read_new_abi_file(PathBuf::from(path))
         .map_err(|e| PyValueError::new_err(format!("Error reading ABI file: {}", e)))
-        .map(|df| PyDataFrame(df))
+        .map(PyDataFrame)
 }
 
 /// Reads functions and events from an ABI JSON (Application Binary Interface)
@@ -142,6 +239,7 @@ pub fn read_new_abi_file(path: String) -> PyResult<PyDataFrame> {
 /// # Arguments
 /// - `abi`: JSON string containing the ABI definition
 /// - `address`: Contract address as a hex string
+/// - `chain_id`: Optional chain where the contract is deployed, stored in the chain_id column
 ///
 /// # Returns
 /// A `PyResult` containing a `PyDataFrame` with all functions and events
@@ -149,16 +247,83 @@ pub fn read_new_abi_file(path: String) -> PyResult<PyDataFrame> {
 /// # Errors
 /// Returns a `PyValueError` if there are issues processing the ABI
 #[pyfunction]
-pub fn read_new_abi_json(abi: String, address: String) -> PyResult<PyDataFrame> {
+#[pyo3(signature = (abi, address, chain_id=None))]
+pub fn read_new_abi_json(abi: String, address: String, chain_id: Option<u64>) -> PyResult<PyDataFrame> {
     let abi: JsonAbi = serde_json::from_str(&abi)
         .map_err(|e| PyValueError::new_err(format!("Invalid ABI JSON: {}", e)))?;
-    let address = Address::from_str(&address)
-        .map_err(|e| PyValueError::new_err(format!("Invalid address: {}", e)))?;
-    abi_reader::read_new_abi_json(abi, address)
+    let address = utils::normalize_hex_string(&address)
+        .map_err(|e| PyValueError::new_err(format!("Invalid address: {}", e)))
+        .and_then(|bytes| Address::try_from(bytes.as_slice())
+            .map_err(|_| PyValueError::new_err(format!("Invalid address: expected 20 bytes, got {}", bytes.len()))))?;
+    abi_reader::read_new_abi_json_with_chain_id(abi, address, chain_id)
         .map_err(|e| PyValueError::new_err(format!("Error processing ABI: {}", e)))
-        .map(|df| PyDataFrame(df))
+        .map(PyDataFrame)
+}
+
+/// Returns the schema of the ABI DataFrames produced by the ABI reader, under the current config
+///
+/// # Returns
+/// A `PyResult` containing an empty `PyDataFrame` with the ABI reader output schema
+#[pyfunction]
+pub fn abi_reader_output_schema() -> PyResult<PyDataFrame> {
+    Ok(PyDataFrame(DataFrame::from(&abi_reader::output_schema())))
 }
 
+/// Deduplicates an ABI DataFrame by its id column
+///
+/// # Arguments
+/// - `df`: A DataFrame containing the ABI items
+/// - `strategy`: Deduplication strategy, allowed values = ["first", "last", "most_specific", "fail"]
+///
+/// # Returns
+/// A `PyResult` containing a `PyDataFrame` with unique ABI items
+///
+/// # Errors
+/// Returns a `PyValueError` if the strategy is invalid, or if using the "fail" strategy and duplicates exist
+#[pyfunction]
+pub fn deduplicate_abi_df(df: PyDataFrame, strategy: String) -> PyResult<PyDataFrame> {
+    let strategy = match strategy.as_str() {
+        "first" => abi_reader::DedupStrategy::First,
+        "last" => abi_reader::DedupStrategy::Last,
+        "most_specific" => abi_reader::DedupStrategy::MostSpecific,
+        "fail" => abi_reader::DedupStrategy::Fail,
+        _ => return Err(PyValueError::new_err("Invalid deduplication strategy")),
+    };
+    abi_reader::deduplicate_abi_df(df.into(), strategy)
+        .map_err(|e| PyValueError::new_err(format!("Error deduplicating ABI DataFrame: {}", e)))
+        .map(PyDataFrame)
+}
+
+/// Checks that the num_indexed_args of each event in an ABI DataFrame matches its full_signature
+///
+/// # Arguments
+/// - `df`: ABI DataFrame, i.e: an ABI DB loaded from an older format
+///
+/// # Errors
+/// Returns a `PyValueError` listing the inconsistent rows, or if the DataFrame has no num_indexed_args or full_signature column
+#[pyfunction]
+pub fn validate_num_indexed_args(df: PyDataFrame) -> PyResult<()> {
+    abi_reader::validate_num_indexed_args(&df.into())
+        .map_err(|e| PyValueError::new_err(format!("Error validating ABI DataFrame: {}", e)))
+}
+
+/// Converts the hash and address columns of an ABI DB file between binary and hex string encodings, rewriting the file
+///
+/// # Arguments
+/// - `abi_db_path`: Path to the ABI database file
+/// - `to_hex`: Convert the columns to 0x prefixed hex strings if true, or to binary if false
+///
+/// # Returns
+/// A `PyResult` containing a `PyDataFrame` with the converted ABI items
+///
+/// # Errors
+/// Returns a `PyValueError` if reading or writing the ABI DB fails, or when converting a csv ABI DB to binary
+#[pyfunction]
+pub fn convert_abi_db_encoding(abi_db_path: String, to_hex: bool) -> PyResult<PyDataFrame> {
+    abi_reader::convert_abi_db_encoding(abi_db_path, to_hex)
+        .map_err(|e| PyValueError::new_err(format!("Error converting ABI DB encoding: {}", e)))
+        .map(PyDataFrame)
+}
 
 /// Decode a folder of logs/traces   in parquet format.
 ///
@@ -168,26 +333,166 @@ pub fn read_new_abi_json(abi: String, address: String) -> PyResult<PyDataFrame>
 /// # Arguments
 /// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
 /// - `folder_path`: Path to a folder containing the logs/traces parquet files
-/// - `abi_db_path`: Path to the abi file containing the topic0 and event signatures
+/// - `abi_db_path`: Paths to one or more abi files containing the topic0 and event signatures
 ///
 /// # Returns
-/// No Return
+/// The run manifest (see `decoder::RunManifest`) as a JSON string
 ///
 /// # Errors
 /// Returns a `PyValueError` if there are issues processing the logs
 #[pyfunction]
-pub fn decode_folder(py: Python<'_>, decoder_type: String, folder_path: String, abi_db_path: String) -> PyResult<&PyAny> {
+pub fn decode_folder(py: Python<'_>, decoder_type: String, folder_path: String, abi_db_path: Vec<String>) -> PyResult<&PyAny> {
     let decoder_type = match decoder_type.as_str() {
         "log" => DecoderType::Log,
         "trace" => DecoderType::Trace,
         _ => return Err(PyValueError::new_err("Invalid decoder type")),
     };
     pyo3_asyncio::tokio::future_into_py(py, async move {
-        decoder::decode_folder(folder_path, abi_db_path, decoder_type).await
+        let manifest = decoder::decode_folder(folder_path, abi_db_path, decoder_type).await
+            .map_err(|e| PyValueError::new_err(format!("Decoding error: {}", e)))?;
+        serde_json::to_string(&manifest)
+            .map_err(|e| PyValueError::new_err(format!("Error serializing the run manifest: {}", e)))
+    })
+}
+
+/// Decode the files of a folder of logs/traces modified after a watermark
+///
+/// # Arguments
+/// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
+/// - `folder_path`: Path to a folder containing the logs/traces parquet files
+/// - `abi_db_path`: Paths to one or more abi files containing the topic0 and event signatures
+/// - `watermark`: RFC 3339 timestamp, only the files modified at or after it are decoded. If None, the watermark saved
+///   in the decoded folder (see `decoder::read_decode_watermark`) is used, skipping the files already decoded at its
+///   time, or all files are decoded if there is none
+///
+/// # Returns
+/// A JSON string with the run `manifest` (see `decoder::RunManifest`) and the new `watermark` as an RFC 3339 timestamp,
+/// rounded up to microseconds (the Python datetime precision), so the newest file isn't decoded again
+///
+/// # Errors
+/// Returns a `PyValueError` if the watermark is invalid, or there are issues processing the logs
+#[pyfunction]
+pub fn decode_folder_incremental(py: Python<'_>, decoder_type: String, folder_path: String, abi_db_path: Vec<String>, watermark: Option<String>) -> PyResult<&PyAny> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    let watermark = match watermark {
+        Some(watermark) => DateTime::parse_from_rfc3339(&watermark)
+            .map_err(|e| PyValueError::new_err(format!("Invalid watermark {}: {}", watermark, e)))?
+            .with_timezone(&Utc)
+            .into(),
+        None => decoder::read_decode_watermark(&folder_path)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?
+            .unwrap_or(DateTime::UNIX_EPOCH.into()),
+    };
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let (manifest, watermark) = decoder::decode_folder_incremental(folder_path, abi_db_path, decoder_type, watermark).await
+            .map_err(|e| PyValueError::new_err(format!("Decoding error: {}", e)))?;
+        let watermark = watermark.time;
+        let sub_micros = watermark.timestamp_subsec_nanos() % 1_000;
+        let watermark = if sub_micros == 0 { watermark } else { watermark + chrono::Duration::nanoseconds((1_000 - sub_micros) as i64) };
+        let result = serde_json::json!({
+            "manifest": manifest,
+            "watermark": watermark.to_rfc3339_opts(SecondsFormat::Micros, false),
+        });
+        Ok(result.to_string())
+    })
+}
+
+/// List the files of a folder of logs/traces that decode_folder would decode, with their decoded file paths
+///
+/// # Arguments
+/// - `folder_path`: Path to a folder containing the logs/traces files
+/// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
+/// - `extensions`: Extensions of the files to plan (i.e: "parquet"). All extensions if empty
+/// - `modified_since`: RFC 3339 timestamp, only the files modified after it are planned
+/// - `block_range`: Inclusive (from_block, to_block) range, only the files whose name block range overlaps it are planned
+///
+/// # Returns
+/// A `PyResult` containing a `PyDataFrame` with the planned files (see `decoder::plan_to_df`)
+///
+/// # Errors
+/// Returns a `PyValueError` if the timestamp is invalid or the folder can't be read
+#[pyfunction]
+pub fn plan_folder(folder_path: String, decoder_type: String, extensions: Vec<String>, modified_since: Option<String>, block_range: Option<(u64, u64)>) -> PyResult<PyDataFrame> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    let modified_since = modified_since
+        .map(|time| DateTime::parse_from_rfc3339(&time)
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|e| PyValueError::new_err(format!("Invalid modified_since {}: {}", time, e))))
+        .transpose()?;
+    let options = decoder::FilePlanOptions { extensions, modified_since, block_range };
+    decoder::plan_folder(Path::new(&folder_path), &decoder_type, &options)
+        .and_then(|plan| decoder::plan_to_df(&plan))
+        .map_err(|e| PyValueError::new_err(format!("Error planning the folder: {}", e)))
+        .map(PyDataFrame)
+}
+
+/// Decode the parquet files inside a .tar.gz archive of logs/traces
+///
+/// This function streams the parquet members out of the archive, without unpacking it to disk,
+/// and saves the decoded logs/traces' files to the output folder
+///
+/// # Arguments
+/// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
+/// - `archive_path`: Path to a .tar.gz archive containing the logs/traces parquet files
+/// - `output_dir`: Path to the folder where the decoded files are saved
+/// - `abi_db_path`: Paths to one or more abi files containing the topic0 and event signatures
+///
+/// # Returns
+/// No Return
+///
+/// # Errors
+/// Returns a `PyValueError` if there are issues reading the archive or processing the logs
+#[pyfunction]
+pub fn decode_archive(py: Python<'_>, decoder_type: String, archive_path: String, output_dir: String, abi_db_path: Vec<String>) -> PyResult<&PyAny> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        decoder::decode_archive(PathBuf::from(archive_path), abi_db_path, decoder_type, PathBuf::from(output_dir)).await
         .map_err(|e| PyValueError::new_err(format!("Decoding error: {}", e)))
     })
 }
 
+/// Decode a single log/trace partition file into an explicit output path
+///
+/// The output is written atomically, and skipped if it already exists with the same config
+///
+/// # Arguments
+/// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
+/// - `input_file`: Path to the raw logs/traces file
+/// - `output_file`: Path of the decoded file, its extension sets the format
+/// - `abi_db_path`: Paths to one or more abi files containing the topic0 and event signatures
+///
+/// # Returns
+/// The partition summary (see `decoder::PartitionSummary`) as a JSON string
+///
+/// # Errors
+/// Returns a `PyValueError` if there are issues processing the file, or the output was written by another config
+#[pyfunction]
+pub fn decode_partition(py: Python<'_>, decoder_type: String, input_file: String, output_file: String, abi_db_path: Vec<String>) -> PyResult<&PyAny> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let summary = decoder::decode_partition(PathBuf::from(input_file), abi_db_path, PathBuf::from(output_file), decoder_type).await
+            .map_err(|e| PyValueError::new_err(format!("Decoding error: {}", e)))?;
+        serde_json::to_string(&summary)
+            .map_err(|e| PyValueError::new_err(format!("Error serializing the partition summary: {}", e)))
+    })
+}
+
 /// Decode a log/trace file
 ///
 /// This function takes a log/trace file path and a abi parquet file path and decode it
@@ -196,15 +501,16 @@ pub fn decode_folder(py: Python<'_>, decoder_type: String, folder_path: String,
 /// # Arguments
 /// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
 /// - `file_path`: Path to the log/trace file
-/// - `abi_db_path`: Path to the abi file containing the topic0 and event signatures
+/// - `abi_db_path`: Paths to one or more abi files containing the topic0 and event signatures
 ///
 /// # Returns
-/// A `PyResult` containing a decoded logs' `PyDataFrame` or an error
+/// A `PyResult` containing a tuple with the decoded logs' `PyDataFrame`, the input and output paths,
+/// the input, output and matched row counts and the duration in seconds, or an error
 ///
 /// # Errors
 /// Returns a `PyValueError` if there are issues processing the logs
 #[pyfunction]
-pub fn decode_file(py: Python<'_>, decoder_type: String, file_path: String, abi_db_path: String) -> PyResult<&PyAny> {
+pub fn decode_file(py: Python<'_>, decoder_type: String, file_path: String, abi_db_path: Vec<String>) -> PyResult<&PyAny> {
     let decoder_type = match decoder_type.as_str() {
         "log" => DecoderType::Log,
         "trace" => DecoderType::Trace,
@@ -213,7 +519,15 @@ pub fn decode_file(py: Python<'_>, decoder_type: String, file_path: String, abi_
     let file_path = PathBuf::from(file_path);
     let result = pyo3_asyncio::tokio::future_into_py(py, async move {
         match decoder::decode_file(file_path, abi_db_path, decoder_type).await {
-            Ok(df) => Ok(PyDataFrame(df)),
+            Ok(result) => Ok((
+                PyDataFrame(result.df),
+                result.input_path.to_string_lossy().into_owned(),
+                result.output_path.to_string_lossy().into_owned(),
+                result.input_rows,
+                result.output_rows,
+                result.matched_rows,
+                result.duration.as_secs_f64(),
+            )),
             Err(e) => Err(PyValueError::new_err(format!("Decoding error: {}", e))),
         }
     })?;
@@ -228,7 +542,7 @@ pub fn decode_file(py: Python<'_>, decoder_type: String, file_path: String, abi_
 /// # Arguments
 /// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
 /// - `df`: A DataFrame containing raw blockchain logs/traces
-/// - `abi_db_path`: Path to the abi file containing the topic0 and event signatures
+/// - `abi_db_path`: Paths to one or more abi files containing the topic0 and event signatures
 ///
 /// # Returns
 /// A `PyResult` containing a decoded logs/traces' `PyDataFrame` or an error
@@ -236,7 +550,7 @@ pub fn decode_file(py: Python<'_>, decoder_type: String, file_path: String, abi_
 /// # Errors
 /// Returns a `PyValueError` if there are issues processing the logs
 #[pyfunction]
-pub fn decode_df(py: Python<'_>, decoder_type: String, df: PyDataFrame, abi_db_path: String) -> PyResult<&PyAny> {
+pub fn decode_df(py: Python<'_>, decoder_type: String, df: PyDataFrame, abi_db_path: Vec<String>) -> PyResult<&PyAny> {
     let decoder_type = match decoder_type.as_str() {
         "log" => DecoderType::Log,
         "trace" => DecoderType::Trace,
@@ -261,7 +575,7 @@ pub fn decode_df(py: Python<'_>, decoder_type: String, df: PyDataFrame, abi_db_p
 /// # Arguments
 /// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
 /// - `df`: A DataFrame containing raw blockchain logs/traces
-/// - `abi_df`: A DataFrame containing:
+/// - `abi_df`: A DataFrame, or the path(s) to one or more ABI DB files, containing:
 ///         - topic0: The topic0 (event signature hash) as bytes
 ///         - full_signature: The full event signature as string (e.g. "Transfer(address indexed from, address indexed to, uint256 value)")
 ///
@@ -269,9 +583,127 @@ pub fn decode_df(py: Python<'_>, decoder_type: String, df: PyDataFrame, abi_db_p
 /// A `PyResult` containing a decoded logs' `PyDataFrame` or an error
 ///
 /// # Errors
+/// Returns a `PyValueError` if `abi_df` isn't a DataFrame, a path or a list of paths, or if there are issues processing the logs
+#[pyfunction]
+pub fn decode_df_with_abi_df<'py>(py: Python<'py>, decoder_type: String, df: PyDataFrame, abi_df: &'py PyAny) -> PyResult<&'py PyAny> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    // Convert PyDataFrame to native polars DataFrame
+    let df:DataFrame = df.into();
+    let abi_input: decoder::AbiInput = if let Ok(path) = abi_df.extract::<String>() {
+        path.into()
+    } else if let Ok(paths) = abi_df.extract::<Vec<String>>() {
+        paths.into()
+    } else if let Ok(abi_df) = abi_df.extract::<PyDataFrame>() {
+        abi_df.0.into()
+    } else {
+        return Err(PyValueError::new_err("abi_df must be a DataFrame, a path or a list of paths"));
+    };
+    let result = pyo3_asyncio::tokio::future_into_py(py, async move {
+        match decoder::decode_df_with_abi_df(df, abi_input, decoder_type).await {
+            Ok(df) => Ok(PyDataFrame(df)),
+            Err(e) => Err(PyValueError::new_err(format!("Decoding error: {}", e))),
+        }
+    })?;
+    Ok(result)
+}
+
+/// Estimate the decoding throughput of this machine with the current config
+///
+/// This function decodes a sample DataFrame of raw logs or traces, measuring the wall time of the decoding.
+///
+/// # Arguments
+/// - `decoder_type`: The type of decoder to use, either "log" or "trace"
+/// - `sample_df`: A DataFrame with a sample of the raw blockchain data to decode
+/// - `abi_df`: A DataFrame containing the ABI DB items, or the path(s) to one or more ABI DB files
+///
+/// # Returns
+/// A `PyResult` containing the decoded rows per second or an error
+///
+/// # Errors
+/// Returns a `PyValueError` if the sample is empty, if `abi_df` isn't a DataFrame, a path or a list of paths, or if there are issues decoding the sample
+#[pyfunction]
+pub fn benchmark_decode<'py>(py: Python<'py>, decoder_type: String, sample_df: PyDataFrame, abi_df: &'py PyAny) -> PyResult<&'py PyAny> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    let sample_df: DataFrame = sample_df.into();
+    let abi_input: decoder::AbiInput = if let Ok(path) = abi_df.extract::<String>() {
+        path.into()
+    } else if let Ok(paths) = abi_df.extract::<Vec<String>>() {
+        paths.into()
+    } else if let Ok(abi_df) = abi_df.extract::<PyDataFrame>() {
+        abi_df.0.into()
+    } else {
+        return Err(PyValueError::new_err("abi_df must be a DataFrame, a path or a list of paths"));
+    };
+    let result = pyo3_asyncio::tokio::future_into_py(py, async move {
+        decoder::benchmark_decode(sample_df, abi_input, decoder_type)
+            .await
+            .map_err(|e| PyValueError::new_err(format!("Decoding error: {}", e)))
+    })?;
+    Ok(result)
+}
+
+/// Decode the logs and traces DataFrames of a transaction bundle
+///
+/// This function takes a raw logs' DataFrame and a raw traces' DataFrame and decodes both,
+/// sharing the config snapshot and the decoding thread budget.
+///
+/// # Arguments
+/// - `logs_df`: A DataFrame containing raw blockchain logs
+/// - `traces_df`: A DataFrame containing raw blockchain traces
+/// - `events_abi_db_path`: Paths to one or more events abi files
+/// - `functions_abi_db_path`: Paths to one or more functions abi files
+///
+/// # Returns
+/// A `PyResult` containing a tuple of the decoded logs' and traces' `PyDataFrame` or an error
+///
+/// # Errors
+/// Returns a `PyValueError` if there are issues processing the logs or traces
+#[pyfunction]
+pub fn decode_tx_bundle(
+    py: Python<'_>,
+    logs_df: PyDataFrame,
+    traces_df: PyDataFrame,
+    events_abi_db_path: Vec<String>,
+    functions_abi_db_path: Vec<String>,
+) -> PyResult<&PyAny> {
+    // Convert PyDataFrame to native polars DataFrame
+    let logs_df: DataFrame = logs_df.into();
+    let traces_df: DataFrame = traces_df.into();
+    let result = pyo3_asyncio::tokio::future_into_py(py, async move {
+        match decoder::decode_tx_bundle(logs_df, traces_df, events_abi_db_path, functions_abi_db_path).await {
+            Ok(bundle) => Ok((PyDataFrame(bundle.logs), PyDataFrame(bundle.traces))),
+            Err(e) => Err(PyValueError::new_err(format!("Decoding error: {}", e))),
+        }
+    })?;
+    Ok(result)
+}
+
+/// Decode a DataFrame of logs/traces using an ABI DataFrame, adding context metadata columns
+///
+/// This function takes a raw logs/traces' DataFrame, an ABI DataFrame and a dictionary of context
+/// values, and decode it to a decoded logs/traces' DataFrame with one literal column per context item.
+///
+/// # Arguments
+/// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
+/// - `df`: A DataFrame containing raw blockchain logs/traces
+/// - `abi_df`: A DataFrame containing the ABI DB items
+/// - `context`: A dictionary of column names to literal values (e.g. {"chain_id": "1"})
+///
+/// # Returns
+/// A `PyResult` containing a decoded logs/traces' `PyDataFrame` or an error
+///
+/// # Errors
 /// Returns a `PyValueError` if there are issues processing the logs
 #[pyfunction]
-pub fn decode_df_with_abi_df(py: Python<'_>, decoder_type: String, df: PyDataFrame, abi_df: PyDataFrame) -> PyResult<&PyAny> {
+pub fn decode_df_with_context(py: Python<'_>, decoder_type: String, df: PyDataFrame, abi_df: PyDataFrame, context: HashMap<String, String>) -> PyResult<&PyAny> {
     let decoder_type = match decoder_type.as_str() {
         "log" => DecoderType::Log,
         "trace" => DecoderType::Trace,
@@ -281,7 +713,7 @@ pub fn decode_df_with_abi_df(py: Python<'_>, decoder_type: String, df: PyDataFra
     let df:DataFrame = df.into();
     let abi_df:DataFrame = abi_df.into();
     let result = pyo3_asyncio::tokio::future_into_py(py, async move {
-        match decoder::decode_df_with_abi_df(df, abi_df, decoder_type).await {
+        match decoder::decode_df_with_context(df, abi_df, decoder_type, context).await {
             Ok(df) => Ok(PyDataFrame(df)),
             Err(e) => Err(PyValueError::new_err(format!("Decoding error: {}", e))),
         }
@@ -289,6 +721,304 @@ pub fn decode_df_with_abi_df(py: Python<'_>, decoder_type: String, df: PyDataFra
     Ok(result)
 }
 
+/// Decode a DataFrame of logs/traces using an ABI DataFrame, with ordered candidate signatures for some hashes
+///
+/// This function takes a raw logs/traces' DataFrame, an ABI DataFrame and a dictionary of candidate
+/// signatures, and decode it trying the candidates of each hash in order, keeping the first that decodes.
+///
+/// # Arguments
+/// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
+/// - `df`: A DataFrame containing raw blockchain logs/traces
+/// - `abi_df`: A DataFrame containing the ABI DB items
+/// - `priority`: A dictionary of 0x prefixed hex topic0s/selectors to lists of full signatures, in priority order
+///
+/// # Returns
+/// A `PyResult` containing a decoded logs/traces' `PyDataFrame` or an error
+///
+/// # Errors
+/// Returns a `PyValueError` if a hash isn't valid hex, or if there are issues processing the logs
+#[pyfunction]
+pub fn decode_df_with_priority(py: Python<'_>, decoder_type: String, df: PyDataFrame, abi_df: PyDataFrame, priority: HashMap<String, Vec<String>>) -> PyResult<&PyAny> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    let priority = priority
+        .into_iter()
+        .map(|(hash, candidates)| Ok((utils::normalize_hex_string(&hash)?, candidates)))
+        .collect::<Result<HashMap<Vec<u8>, Vec<String>>, PolarsError>>()
+        .map_err(|e| PyValueError::new_err(format!("Invalid priority hash: {}", e)))?;
+    // Convert PyDataFrame to native polars DataFrame
+    let df:DataFrame = df.into();
+    let abi_df:DataFrame = abi_df.into();
+    let result = pyo3_asyncio::tokio::future_into_py(py, async move {
+        match decoder::decode_df_with_priority(df, abi_df, priority, decoder_type).await {
+            Ok(df) => Ok(PyDataFrame(df)),
+            Err(e) => Err(PyValueError::new_err(format!("Decoding error: {}", e))),
+        }
+    })?;
+    Ok(result)
+}
+
+/// Decode a DataFrame of logs/traces using an ABI DataFrame, overriding the matched signature of some hashes
+///
+/// This function takes a raw logs/traces' DataFrame, an ABI DataFrame and a dictionary of override
+/// signatures, and decode it forcing the signature of each hash in the dictionary, whatever the matcher chose.
+///
+/// # Arguments
+/// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
+/// - `df`: A DataFrame containing raw blockchain logs/traces
+/// - `abi_df`: A DataFrame containing the ABI DB items
+/// - `overrides`: A dictionary of 0x prefixed hex topic0s/selectors to full signatures
+///
+/// # Returns
+/// A `PyResult` containing a decoded logs/traces' `PyDataFrame` or an error
+///
+/// # Errors
+/// Returns a `PyValueError` if a hash isn't valid hex, a signature can't be parsed, or if there are issues processing the logs
+#[pyfunction]
+pub fn decode_df_with_overrides(py: Python<'_>, decoder_type: String, df: PyDataFrame, abi_df: PyDataFrame, overrides: HashMap<String, String>) -> PyResult<&PyAny> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    let overrides = overrides
+        .into_iter()
+        .map(|(hash, signature)| Ok((utils::normalize_hex_string(&hash)?, signature)))
+        .collect::<Result<HashMap<Vec<u8>, String>, PolarsError>>()
+        .map_err(|e| PyValueError::new_err(format!("Invalid override hash: {}", e)))?;
+    // Convert PyDataFrame to native polars DataFrame
+    let df:DataFrame = df.into();
+    let abi_df:DataFrame = abi_df.into();
+    let result = pyo3_asyncio::tokio::future_into_py(py, async move {
+        match decoder::decode_df_with_overrides(df, abi_df, overrides, decoder_type).await {
+            Ok(df) => Ok(PyDataFrame(df)),
+            Err(e) => Err(PyValueError::new_err(format!("Decoding error: {}", e))),
+        }
+    })?;
+    Ok(result)
+}
+
+/// Decode a DataFrame of logs/traces using an ABI DataFrame into a wide DataFrame
+///
+/// This function takes a raw logs/traces' DataFrame and an ABI DataFrame, decode it and pivot
+/// the params of each signature into prefixed columns (e.g. Transfer_from, Transfer_value).
+///
+/// # Arguments
+/// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
+/// - `df`: A DataFrame containing raw blockchain logs/traces
+/// - `abi_df`: A DataFrame containing the ABI DB items
+///
+/// # Returns
+/// A `PyResult` containing a wide decoded logs/traces' `PyDataFrame` or an error
+///
+/// # Errors
+/// Returns a `PyValueError` if there are issues processing the logs
+#[pyfunction]
+pub fn decode_to_wide(py: Python<'_>, decoder_type: String, df: PyDataFrame, abi_df: PyDataFrame) -> PyResult<&PyAny> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    // Convert PyDataFrame to native polars DataFrame
+    let df:DataFrame = df.into();
+    let abi_df:DataFrame = abi_df.into();
+    let result = pyo3_asyncio::tokio::future_into_py(py, async move {
+        match decoder::decode_to_wide(df, abi_df, decoder_type).await {
+            Ok(df) => Ok(PyDataFrame(df)),
+            Err(e) => Err(PyValueError::new_err(format!("Decoding error: {}", e))),
+        }
+    })?;
+    Ok(result)
+}
+
+/// Decode a DataFrame of logs/traces with a single signature and aggregate its params
+///
+/// This function takes a raw logs/traces' DataFrame and an ABI DataFrame, decode the logs/traces of the signature,
+/// pivot its params into columns (e.g. from, to, value) and apply the group-by/aggregation.
+///
+/// # Arguments
+/// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
+/// - `df`: A DataFrame containing raw blockchain logs/traces
+/// - `abi_df`: A DataFrame containing the ABI DB items
+/// - `signature`: Full signature to decode
+/// - `group_by`: Columns to group by
+/// - `agg`: List of (column, aggregation) tuples, allowed aggregations = ["sum", "mean", "min", "max", "count", "n_unique", "first", "last"]
+///
+/// # Returns
+/// A `PyResult` containing the aggregated `PyDataFrame` or an error
+///
+/// # Errors
+/// Returns a `PyValueError` if an aggregation is invalid, or there are issues processing the logs
+#[pyfunction]
+pub fn decode_and_aggregate(
+    py: Python<'_>,
+    decoder_type: String,
+    df: PyDataFrame,
+    abi_df: PyDataFrame,
+    signature: String,
+    group_by: Vec<String>,
+    agg: Vec<(String, String)>,
+) -> PyResult<&PyAny> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    let agg = agg
+        .into_iter()
+        .map(|(column, kind)| kind.parse::<AggKind>().map(|kind| (column, kind)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| PyValueError::new_err(format!("Decoding error: {}", e)))?;
+    // Convert PyDataFrame to native polars DataFrame
+    let df:DataFrame = df.into();
+    let abi_df:DataFrame = abi_df.into();
+    let result = pyo3_asyncio::tokio::future_into_py(py, async move {
+        match decoder::decode_and_aggregate(df, abi_df, &signature, group_by, agg, decoder_type).await {
+            Ok(df) => Ok(PyDataFrame(df)),
+            Err(e) => Err(PyValueError::new_err(format!("Decoding error: {}", e))),
+        }
+    })?;
+    Ok(result)
+}
+
+/// List the distinct contract addresses of a raw logs/traces file, with their number of logs/traces
+///
+/// Only the address column (action_to for traces) is read, without decoding.
+///
+/// # Arguments
+/// - `file_path`: Path to the raw logs/traces file, or to a folder of files
+/// - `decoder_type`: Type of the raw data, allowed values = ["log", "trace"]
+///
+/// # Returns
+/// A `PyResult` containing a `PyDataFrame` with the address (hex) and count columns
+///
+/// # Errors
+/// Returns a `PyValueError` if the file can't be read or the address column is missing
+#[pyfunction]
+pub fn distinct_addresses(file_path: String, decoder_type: String) -> PyResult<PyDataFrame> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    decoder::distinct_addresses(PathBuf::from(file_path), decoder_type)
+        .map_err(|e| PyValueError::new_err(format!("Error listing addresses: {}", e)))
+        .map(PyDataFrame)
+}
+
+/// Count how many logs/traces of a DataFrame match and decode with an ABI DataFrame, without building the decoded columns
+///
+/// # Arguments
+/// - `decoder_type`: Type of the decoder to use, allowed values = ["log", "trace"]
+/// - `df`: A DataFrame containing raw blockchain logs/traces
+/// - `abi_df`: A DataFrame containing the ABI DB items
+///
+/// # Returns
+/// A `PyResult` containing a dict with the total, matched, decoded and failed counts
+///
+/// # Errors
+/// Returns a `PyValueError` if there are issues matching the logs/traces
+#[pyfunction]
+pub fn can_decode(decoder_type: String, df: PyDataFrame, abi_df: PyDataFrame) -> PyResult<HashMap<String, usize>> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    let stats = decoder::can_decode(df.into(), abi_df.into(), decoder_type)
+        .map_err(|e| PyValueError::new_err(format!("Decoding error: {}", e)))?;
+    Ok(HashMap::from([
+        ("total".to_string(), stats.total),
+        ("matched".to_string(), stats.matched),
+        ("decoded".to_string(), stats.decoded),
+        ("failed".to_string(), stats.failed),
+    ]))
+}
+
+/// Label a DataFrame of 4 bytes function selectors with their names and signatures
+///
+/// This function joins the selector column against the functions ABI DB, without decoding any calldata.
+///
+/// # Arguments
+/// - `df`: A DataFrame with a selector column (binary or hex string)
+/// - `abi_db_path`: Paths to one or more functions ABI DB files
+///
+/// # Returns
+/// A `PyResult` containing a `PyDataFrame` with the name, full_signature and candidate_count columns added
+///
+/// # Errors
+/// Returns a `PyValueError` if the ABI DB can't be read or the selector column is missing
+#[pyfunction]
+pub fn label_selectors(df: PyDataFrame, abi_db_path: Vec<String>) -> PyResult<PyDataFrame> {
+    decoder::label_selectors(df.into(), abi_db_path)
+        .map_err(|e| PyValueError::new_err(format!("Error labeling selectors: {}", e)))
+        .map(PyDataFrame)
+}
+
+/// Check that a decoded DataFrame has the expected number of rows for a signature
+///
+/// # Arguments
+/// - `df`: A decoded logs/traces DataFrame, with a full_signature column
+/// - `signature`: The full signature to count
+/// - `expected`: The reference number of rows for the signature
+///
+/// # Errors
+/// Returns a `PyValueError` if the count doesn't match or the full_signature column is missing
+#[pyfunction]
+pub fn assert_event_count(df: PyDataFrame, signature: String, expected: usize) -> PyResult<()> {
+    decoder::assert_event_count(&df.into(), &signature, expected)
+        .map_err(|e| PyValueError::new_err(format!("Error validating event count: {}", e)))
+}
+
+/// Summarize a decoded logs/traces DataFrame by contract and event/function name
+///
+/// # Arguments
+/// - `df`: A decoded logs/traces DataFrame
+///
+/// # Returns
+/// A `PyResult` containing a `PyDataFrame` with the address, name, match_status and count columns,
+/// plus first_block and last_block when the input has a block_number column
+///
+/// # Errors
+/// Returns a `PyValueError` if the address column is missing
+#[pyfunction]
+pub fn summarize_decoded(df: PyDataFrame) -> PyResult<PyDataFrame> {
+    decoder::summarize_decoded(df.into())
+        .map_err(|e| PyValueError::new_err(format!("Error summarizing decoded DataFrame: {}", e)))
+        .map(PyDataFrame)
+}
+
+/// Report how the hashes of a raw logs/traces DataFrame are covered by the ABI DB, without decoding
+///
+/// # Arguments
+/// - `decoder_type`: Type of the raw data, allowed values = ["log", "trace"]
+/// - `df`: A DataFrame containing raw blockchain logs/traces
+/// - `abi_db_path`: Paths to one or more ABI DB files
+///
+/// # Returns
+/// A `PyResult` containing a `PyDataFrame` with one row per category (matched, indexed_arg_mismatch, missing)
+/// and the hash_count, row_count and example_signatures columns
+///
+/// # Errors
+/// Returns a `PyValueError` if the ABI DB can't be read or the hash columns are missing
+#[pyfunction]
+pub fn compatibility_report(decoder_type: String, df: PyDataFrame, abi_db_path: Vec<String>) -> PyResult<PyDataFrame> {
+    let decoder_type = match decoder_type.as_str() {
+        "log" => DecoderType::Log,
+        "trace" => DecoderType::Trace,
+        _ => return Err(PyValueError::new_err("Invalid decoder type")),
+    };
+    let abi_df = utils::read_abi_db_files(&abi_db_path)
+        .map_err(|e| PyValueError::new_err(format!("Error reading ABI DB: {}", e)))?;
+    matcher::compatibility_report(df.into(), abi_df, decoder_type)
+        .map_err(|e| PyValueError::new_err(format!("Error building compatibility report: {}", e)))
+        .map(PyDataFrame)
+}
+
 /// Decode a DataFrame of logs/traces using a single contract address
 ///
 /// This function takes a raw logs/traces' DataFrame and a contract address, download the ABI from Sourcify
@@ -321,4 +1051,150 @@ pub fn decode_df_using_single_contract(py: Python<'_>, decoder_type: String, df:
     })?;
 
     Ok(result)
-}
\ No newline at end of file
+}
+
+/// Download the ABIs listed in a manifest file and append them to the ABI DB
+///
+/// # Arguments
+/// - `manifest_path`: Path to a csv or parquet manifest, with the columns address, chain_id and source ("sourcify" or "etherscan")
+/// - `abi_db_path`: Path to the existing or new ABI database file
+///
+/// # Returns
+/// A `PyResult` containing a report `PyDataFrame`, with the success or failure of each manifest entry
+///
+/// # Errors
+/// Returns a `PyValueError` if the manifest can't be read or the ABI DB can't be written
+#[pyfunction]
+pub fn fetch_abis_from_manifest(py: Python<'_>, manifest_path: String, abi_db_path: String) -> PyResult<&PyAny> {
+    let result = pyo3_asyncio::tokio::future_into_py(py, async move {
+        match miscellaneous::fetch_abis_from_manifest(manifest_path, abi_db_path).await {
+            Ok(df) => Ok(PyDataFrame(df)),
+            Err(e) => Err(PyValueError::new_err(format!("Error fetching ABIs from manifest: {}", e))),
+        }
+    })?;
+
+    Ok(result)
+}
+
+/// Build a raw logs DataFrame, matching the configured log schema, from lists of fields
+///
+/// # Arguments
+/// - `addresses`: Contract address of each log, as bytes or hex strings (20 bytes)
+/// - `topics`: List of topics of each log, as bytes or hex strings (up to 4 topics of 32 bytes)
+/// - `data`: Data field of each log, as bytes or hex strings
+/// - `block_numbers`: Optional block number of each log
+/// - `tx_hashes`: Optional transaction hash of each log, as bytes or hex strings (32 bytes)
+///
+/// # Returns
+/// A `PyResult` containing a raw logs' `PyDataFrame` or an error
+///
+/// # Errors
+/// Returns a `PyValueError` if the fields have different lengths or a value is malformed, reporting the row and field
+#[pyfunction]
+#[pyo3(signature = (addresses, topics, data, block_numbers=None, tx_hashes=None))]
+pub fn build_logs_df(
+    addresses: Vec<RawBytes>,
+    topics: Vec<Vec<Option<RawBytes>>>,
+    data: Vec<RawBytes>,
+    block_numbers: Option<Vec<u64>>,
+    tx_hashes: Option<Vec<RawBytes>>,
+) -> PyResult<PyDataFrame> {
+    log_decoder::build_logs_df(addresses, topics, data, block_numbers, tx_hashes)
+        .map_err(|e| PyValueError::new_err(format!("Error building logs DataFrame: {}", e)))
+        .map(PyDataFrame)
+}
+
+/// Decode a single log from hex strings, without building a DataFrame
+///
+/// # Arguments
+/// - `topic0`: Topic0 of the log, as a hex string (with or without the 0x prefix)
+/// - `data`: Data field of the log, as a hex string
+/// - `full_signature`: Full event signature to decode the log with
+/// - `topic1`, `topic2`, `topic3`: Optional topics of the log, as hex strings
+///
+/// # Returns
+/// A `PyResult` containing a dict with the event_values, event_keys, event_json, string_validity and truncated_values of the log
+///
+/// # Errors
+/// Returns a `PyValueError` if a field isn't valid hex, or the log doesn't decode with the signature
+#[pyfunction]
+#[pyo3(signature = (topic0, data, full_signature, topic1=None, topic2=None, topic3=None))]
+pub fn decode_log_from_hex<'py>(
+    py: Python<'py>,
+    topic0: String,
+    data: String,
+    full_signature: String,
+    topic1: Option<String>,
+    topic2: Option<String>,
+    topic3: Option<String>,
+) -> PyResult<&'py PyDict> {
+    let topics = [Some(topic0), topic1, topic2, topic3]
+        .into_iter()
+        .flatten()
+        .map(|topic| utils::normalize_hex_string(&topic))
+        .collect::<Result<Vec<Vec<u8>>, PolarsError>>()
+        .map_err(|e| PyValueError::new_err(format!("Invalid topic: {}", e)))?;
+    let data = utils::normalize_hex_string(&data)
+        .map_err(|e| PyValueError::new_err(format!("Invalid data: {}", e)))?;
+    let topics: Vec<&[u8]> = topics.iter().map(Vec::as_slice).collect();
+    let decoded = log_decoder::decode_log_from_bytes(&full_signature, &topics, &data)
+        .map_err(|e| PyValueError::new_err(format!("Decoding error: {}", e)))?;
+
+    let result = PyDict::new(py);
+    result.set_item("event_values", decoded.event_values)?;
+    result.set_item("event_keys", decoded.event_keys)?;
+    result.set_item("event_json", decoded.event_json)?;
+    result.set_item("string_validity", decoded.string_validity)?;
+    result.set_item("truncated_values", decoded.truncated_values)?;
+    Ok(result)
+}
+
+/// Convert the chosen columns to 0x prefixed hex string columns
+///
+/// # Arguments
+/// - `df`: A DataFrame with the columns to convert
+/// - `columns`: Names of the columns to convert
+///
+/// # Returns
+/// A `PyResult` containing a `PyDataFrame` with the converted columns
+///
+/// # Errors
+/// Returns a `PyValueError` naming the offending column, if a column is missing or isn't binary or string
+#[pyfunction]
+pub fn to_hex_columns(df: PyDataFrame, columns: Vec<String>) -> PyResult<PyDataFrame> {
+    utils::to_hex_columns(df.into(), &columns)
+        .map_err(|e| PyValueError::new_err(format!("Error converting columns to hex string: {}", e)))
+        .map(PyDataFrame)
+}
+
+/// Convert the chosen columns to binary columns
+///
+/// # Arguments
+/// - `df`: A DataFrame with the columns to convert
+/// - `columns`: Names of the columns to convert
+///
+/// # Returns
+/// A `PyResult` containing a `PyDataFrame` with the converted columns
+///
+/// # Errors
+/// Returns a `PyValueError` naming the offending column, if a column is missing, isn't binary or string, or has invalid hex strings
+#[pyfunction]
+pub fn to_binary_columns(df: PyDataFrame, columns: Vec<String>) -> PyResult<PyDataFrame> {
+    utils::to_binary_columns(df.into(), &columns)
+        .map_err(|e| PyValueError::new_err(format!("Error converting columns to binary: {}", e)))
+        .map(PyDataFrame)
+}
+
+/// Names the columns of unnested params, so they never replace the other columns of a DataFrame
+///
+/// # Arguments
+/// - `param_names`: Names of the params, in order
+/// - `existing_columns`: Columns of the DataFrame the params are added to
+///
+/// # Returns
+/// A `PyResult` containing one column name per param, named as the params columns of `decode_to_wide`
+#[pyfunction]
+pub fn param_column_names(param_names: Vec<String>, existing_columns: Vec<String>) -> PyResult<Vec<String>> {
+    let existing_columns: Vec<&str> = existing_columns.iter().map(|column| column.as_str()).collect();
+    Ok(decoder::param_column_names(&param_names, "", &existing_columns))
+}
@@ -2,13 +2,26 @@ import pytest
 import polars as pl
 import pandas as pd
 import json
+import toml
 from pathlib import Path
 import os
+import threading
+import urllib.parse
+from http.server import HTTPServer, BaseHTTPRequestHandler
 from glaciers import (
     read_new_abi_json,
     read_new_abi_file,
     read_new_abi_folder,
-    update_abi_db
+    read_new_abi_folder_parallel,
+    update_abi_db,
+    update_abi_db_with_progress,
+    deduplicate_abi_df,
+    validate_num_indexed_args,
+    convert_abi_db_encoding,
+    abi_reader_output_schema,
+    fetch_abis_from_manifest,
+    get_config,
+    set_config
 )
 
 @pytest.fixture
@@ -55,6 +68,110 @@ def test_read_new_abi_json_returns_dataframe(sample_abi, sample_address):
     # Verify Transfer event details
     assert "Transfer" in df["name"].to_list()
 
+def test_read_new_abi_json_address_formats(sample_abi, sample_address):
+    expected = read_new_abi_json(sample_abi, sample_address)["address"].to_list()
+    # Uppercase prefix and no prefix are normalized to the same address
+    for address in ["0X" + sample_address[2:], sample_address[2:]]:
+        df = read_new_abi_json(sample_abi, address)
+        assert df["address"].to_list() == expected
+    with pytest.raises(ValueError, match="invalid character"):
+        read_new_abi_json(sample_abi, "0x" + "zz" * 20)
+    with pytest.raises(ValueError, match="expected 20 bytes"):
+        read_new_abi_json(sample_abi, "0x1234")
+
+def test_read_new_abi_json_chain_id(sample_abi, sample_address):
+    df = read_new_abi_json(sample_abi, sample_address)
+    assert df["chain_id"].dtype == pl.UInt64
+    assert df["chain_id"].to_list() == [None]
+
+    df = read_new_abi_json(sample_abi, sample_address, chain_id=10)
+    assert df["chain_id"].to_list() == [10]
+
+    # With chain_id in the unique_key, the same item in different chains has different ids
+    previous_unique_key = toml.loads(get_config())["abi_reader"]["unique_key"]
+    set_config("abi_reader.unique_key", ["hash", "full_signature", "address", "chain_id"])
+    try:
+        mainnet_id = read_new_abi_json(sample_abi, sample_address, chain_id=1)["id"][0]
+        optimism_id = read_new_abi_json(sample_abi, sample_address, chain_id=10)["id"][0]
+    finally:
+        set_config("abi_reader.unique_key", previous_unique_key)
+    assert mainnet_id != optimism_id
+    assert optimism_id.endswith(" - 10")
+
+def test_abi_reader_output_schema(sample_abi, sample_address):
+    schema = abi_reader_output_schema()
+    assert read_new_abi_json(sample_abi, sample_address).schema == schema
+    assert schema["address"] == pl.Binary
+    assert schema["hash"] == pl.Binary
+
+    previous_encoding = toml.loads(get_config())["abi_reader"]["output_hex_string_encoding"]
+    set_config("abi_reader.output_hex_string_encoding", True)
+    try:
+        hex_schema = abi_reader_output_schema()
+        assert read_new_abi_json(sample_abi, sample_address).schema == hex_schema
+    finally:
+        set_config("abi_reader.output_hex_string_encoding", previous_encoding)
+    assert hex_schema["address"] == pl.String
+    assert hex_schema["hash"] == pl.String
+    assert list(hex_schema.keys()) == list(schema.keys())
+
+def test_read_new_abi_json_receive_and_fallback(sample_address):
+    abi = json.dumps([
+        {"type": "function", "name": "deposit", "inputs": [], "outputs": [], "stateMutability": "payable"},
+        {"type": "receive", "stateMutability": "payable"},
+        {"type": "fallback", "stateMutability": "nonpayable"},
+    ])
+    previous_mode = toml.loads(get_config())["abi_reader"]["abi_read_mode"]
+    set_config("abi_reader.abi_read_mode", "functions")
+    try:
+        df = read_new_abi_json(abi, sample_address)
+    finally:
+        set_config("abi_reader.abi_read_mode", previous_mode)
+    assert sorted(df["name"].to_list()) == ["deposit", "fallback", "receive"]
+    receive = df.filter(pl.col("name") == "receive")
+    assert receive["full_signature"][0] == "receive()"
+    assert receive["state_mutability"][0] == "receive"
+    assert receive["hash"][0] == bytes(4)
+    fallback = df.filter(pl.col("name") == "fallback")
+    assert fallback["state_mutability"][0] == "fallback"
+    assert fallback["hash"][0] == b"\xff" * 4
+
+def test_read_new_abi_json_skip_item_types(sample_address):
+    abi = json.dumps([
+        {"type": "constructor", "inputs": [], "stateMutability": "nonpayable"},
+        {"type": "function", "name": "deposit", "inputs": [], "outputs": [], "stateMutability": "payable"},
+        {"type": "receive", "stateMutability": "payable"},
+        {"type": "fallback", "stateMutability": "nonpayable"},
+        {"type": "event", "name": "Deposit", "anonymous": False, "inputs": [{"name": "dst", "type": "address", "indexed": True}]},
+    ])
+    previous_mode = toml.loads(get_config())["abi_reader"]["abi_read_mode"]
+    set_config("abi_reader.abi_read_mode", "both")
+    set_config("abi_reader.skip_item_types", ["function"])
+    try:
+        df = read_new_abi_json(abi, sample_address)
+    finally:
+        set_config("abi_reader.abi_read_mode", previous_mode)
+        set_config("abi_reader.skip_item_types", [])
+    # The receive() and fallback() functions are skipped with the other functions
+    assert df["name"].to_list() == ["Deposit"]
+
+def test_read_new_abi_json_duplicated_items(sample_abi, sample_address):
+    # Malformed ABI listing the same event twice, and the same function overload twice next to another overload
+    transfer = json.loads(sample_abi)[0]
+    transfer_to = {"type": "function", "name": "transfer", "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}], "outputs": [], "stateMutability": "nonpayable"}
+    transfer_batch = {"type": "function", "name": "transfer", "inputs": [{"name": "to", "type": "address[]"}, {"name": "amount", "type": "uint256"}], "outputs": [], "stateMutability": "nonpayable"}
+    abi = json.dumps([transfer, transfer, transfer_to, transfer_batch, transfer_to])
+
+    previous_mode = toml.loads(get_config())["abi_reader"]["abi_read_mode"]
+    set_config("abi_reader.abi_read_mode", "both")
+    try:
+        df = read_new_abi_json(abi, sample_address)
+    finally:
+        set_config("abi_reader.abi_read_mode", previous_mode)
+    assert len(df) == 3
+    assert len(set(df["id"].to_list())) == 3
+    assert sorted(df["name"].to_list()) == ["Transfer", "transfer", "transfer"]
+
 def test_read_new_abi_file(tmp_path, sample_abi):
     # Create temporary ABI file
     abi_file = tmp_path / "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64.json"
@@ -66,6 +183,62 @@ def test_read_new_abi_file(tmp_path, sample_abi):
     assert not df.is_empty()
     assert "Transfer" in df["name"].to_list()
 
+def test_read_new_abi_file_relaxed_json(tmp_path):
+    # Hand-maintained ABI file, with a comment and trailing commas
+    abi_file = tmp_path / "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64.json"
+    abi_file.write_text("""[
+        // ERC20 Transfer
+        {"type": "event", "name": "Transfer", "anonymous": false, "inputs": [
+            {"name": "from", "type": "address", "indexed": true},
+            {"name": "to", "type": "address", "indexed": true},
+            {"name": "value", "type": "uint256", "indexed": false},
+        ]},
+    ]""")
+
+    with pytest.raises(ValueError):
+        read_new_abi_file(str(abi_file))
+
+    set_config("abi_reader.relaxed_json", True)
+    try:
+        df = read_new_abi_file(str(abi_file))
+    finally:
+        set_config("abi_reader.relaxed_json", False)
+    assert df["full_signature"].to_list() == ["event Transfer(address indexed from, address indexed to, uint256 value)"]
+
+    # Other JSON5 extensions, like unquoted keys and single quoted strings, are accepted too
+    abi_file.write_text("""[{type: 'event', name: 'Approval', anonymous: false, inputs: [
+        {name: 'owner', type: 'address', indexed: true},
+        {name: 'value', type: 'uint256', indexed: false},
+    ]}]""")
+    set_config("abi_reader.relaxed_json", True)
+    try:
+        df = read_new_abi_file(str(abi_file))
+    finally:
+        set_config("abi_reader.relaxed_json", False)
+    assert df["full_signature"].to_list() == ["event Approval(address indexed owner, uint256 value)"]
+
+def test_read_new_abi_file_store_raw_inputs(tmp_path):
+    abi_file = tmp_path / "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64.json"
+    abi_file.write_text(json.dumps([
+        {"type": "event", "name": "Transfer", "anonymous": False, "inputs": [
+            {"name": "from", "type": "address", "indexed": True},
+            {"name": "to", "type": "address", "indexed": True},
+            {"name": "value", "type": "uint256", "indexed": False},
+        ]},
+    ]))
+
+    assert "raw_inputs_json" not in read_new_abi_file(str(abi_file)).columns
+
+    set_config("abi_reader.store_raw_inputs", True)
+    try:
+        df = read_new_abi_file(str(abi_file))
+    finally:
+        set_config("abi_reader.store_raw_inputs", False)
+    inputs = json.loads(df["raw_inputs_json"][0])
+    assert [(i["name"], i["type"], i["indexed"]) for i in inputs] == [
+        ("from", "address", True), ("to", "address", True), ("value", "uint256", False)
+    ]
+
 def test_read_new_abi_folder(tmp_path, sample_abi):
     # Create ABI folder
     abi_folder = tmp_path / "abis"
@@ -89,6 +262,23 @@ def test_read_new_abi_folder(tmp_path, sample_abi):
     assert len(df) == 3
     assert all("Transfer" in name for name in df["name"].to_list())
 
+def test_read_new_abi_folder_parallel(tmp_path, sample_abi):
+    root = tmp_path / "abis"
+    good = root / "good_protocol"
+    bad = root / "bad_protocol"
+    good.mkdir(parents=True)
+    bad.mkdir(parents=True)
+
+    (good / "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64.json").write_text(sample_abi)
+    (good / "0x1234567890123456789012345678901234567890.json").write_text(sample_abi)
+    # the corrupt file makes the whole subfolder be skipped, including its valid ABI
+    (bad / "0xabcdefABCDEF1234567890abcdefABCDEF123456.json").write_text(sample_abi)
+    (bad / "0x0000000000000000000000000000000000000001.json").write_text("{not json")
+
+    df = read_new_abi_folder_parallel(str(root))
+    assert isinstance(df, (pl.DataFrame, pd.DataFrame))
+    assert len(df) == 2
+
 def test_update_abi_db(tmp_path, sample_abi):
     # Create ABI folder and DB path
     abi_folder = tmp_path / "abis"
@@ -104,4 +294,264 @@ def test_update_abi_db(tmp_path, sample_abi):
     assert isinstance(df, (pl.DataFrame, pd.DataFrame))
     assert not df.is_empty()
     assert db_path.exists()
-    assert "Transfer" in df["name"].to_list() 
\ No newline at end of file
+    assert "Transfer" in df["name"].to_list() 
+
+def test_update_abi_db_reports_size(tmp_path, sample_abi, capfd):
+    abi_folder = tmp_path / "abis"
+    abi_folder.mkdir()
+    db_path = tmp_path / "abi_db.parquet"
+    (abi_folder / "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64.json").write_text(sample_abi)
+
+    df = update_abi_db(str(db_path), str(abi_folder))
+    out = capfd.readouterr().out
+    assert f"ABI DB saved to {db_path}: 0 B -> " in out
+    assert f"{len(df)} rows added, {len(df)} rows in total." in out
+
+    # Updating again adds nothing, and the old size is the file written before
+    update_abi_db(str(db_path), str(abi_folder))
+    out = capfd.readouterr().out
+    assert "0 B -> " not in out
+    assert f"0 rows added, {len(df)} rows in total." in out
+
+def test_update_abi_db_with_progress(tmp_path, sample_abi):
+    abi_folder = tmp_path / "abis"
+    abi_folder.mkdir()
+    db_path = tmp_path / "abi_db.parquet"
+    (abi_folder / "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64.json").write_text(sample_abi)
+    (abi_folder / ("0x" + "11" * 20 + ".json")).write_text(sample_abi)
+    # Files whose name isn't an address are skipped
+    non_address_files = ["token.json", "readme.txt", "0x1234.json"]
+    for file_name in non_address_files:
+        (abi_folder / file_name).write_text(sample_abi)
+
+    calls = []
+    df, stats = update_abi_db_with_progress(str(db_path), str(abi_folder), lambda done, total: calls.append((done, total)))
+    assert calls == [(i, 5) for i in range(1, 6)]
+    assert stats["files_read"] == 2
+    assert stats["files_skipped"] == len(non_address_files)
+    assert stats["rows_added"] == len(df) == 2
+
+    # Updating again adds nothing
+    _, stats = update_abi_db_with_progress(str(db_path), str(abi_folder))
+    assert stats["rows_added"] == 0
+
+def test_update_abi_db_csv_round_trip(tmp_path, sample_abi):
+    abi_folder = tmp_path / "abis"
+    abi_folder.mkdir()
+    abi_file = abi_folder / "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64.json"
+    abi_file.write_text(sample_abi)
+    parquet_path = tmp_path / "abi_db.parquet"
+    csv_path = tmp_path / "abi_db.csv"
+
+    parquet_df = update_abi_db(str(parquet_path), str(abi_folder))
+    # The binary hash and address columns are written as hex strings, and read back as binary to update the DB again
+    update_abi_db(str(csv_path), str(abi_folder))
+    csv_df = update_abi_db(str(csv_path), str(abi_folder))
+    assert pl.read_csv(csv_path)["hash"][0].startswith("0x")
+    assert csv_df.equals(parquet_df)
+
+def test_update_abi_db_json_round_trip(tmp_path, sample_abi):
+    abi_folder = tmp_path / "abis"
+    abi_folder.mkdir()
+    abi_file = abi_folder / "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64.json"
+    abi_file.write_text(sample_abi)
+    parquet_path = tmp_path / "abi_db.parquet"
+    json_path = tmp_path / "abi_db.json"
+
+    parquet_df = update_abi_db(str(parquet_path), str(abi_folder))
+    # Saved as a JSON array of objects with hex string hash and address, and read back as binary to update the DB again
+    update_abi_db(str(json_path), str(abi_folder))
+    json_df = update_abi_db(str(json_path), str(abi_folder))
+    items = json.loads(json_path.read_text())
+    assert isinstance(items, list) and len(items) == parquet_df.height
+    assert items[0]["hash"].startswith("0x")
+    assert json_df.equals(parquet_df)
+
+    # Hand-edited hashes and addresses, uppercase or without the 0x prefix, are normalized on read
+    items[0]["hash"] = items[0]["hash"][2:].upper()
+    items[0]["address"] = items[0]["address"].upper().replace("0X", "0x")
+    json_path.write_text(json.dumps(items))
+    assert update_abi_db(str(json_path), str(abi_folder)).equals(parquet_df)
+
+    # An empty JSON array is read as an ABI DB without items
+    json_path.write_text("[]")
+    assert update_abi_db(str(json_path), str(abi_folder)).equals(parquet_df)
+
+    with pytest.raises(ValueError):
+        convert_abi_db_encoding(str(json_path), to_hex=False)
+
+def test_deduplicate_abi_df(sample_abi, sample_address):
+    zero_address = "0x" + "00" * 20
+    specific_df = read_new_abi_json(sample_abi, sample_address)
+    zero_df = read_new_abi_json(sample_abi, zero_address)
+    # Use the same id for both items, to simulate an ABI DB with a unique_key without address
+    zero_df = zero_df.with_columns(pl.lit(specific_df["id"][0]).alias("id"))
+    df = pl.concat([zero_df, specific_df])
+
+    first = deduplicate_abi_df(df, "first")
+    assert len(first) == 1
+    assert first["address"][0] == zero_df["address"][0]
+
+    last = deduplicate_abi_df(df, "last")
+    assert len(last) == 1
+    assert last["address"][0] == specific_df["address"][0]
+
+    most_specific = deduplicate_abi_df(df, "most_specific")
+    assert len(most_specific) == 1
+    assert most_specific["address"][0] == specific_df["address"][0]
+
+    with pytest.raises(ValueError, match="Duplicated ABI items"):
+        deduplicate_abi_df(df, "fail")
+    assert len(deduplicate_abi_df(specific_df, "fail")) == 1
+
+    with pytest.raises(ValueError):
+        deduplicate_abi_df(df, "invalid")
+
+def test_validate_num_indexed_args(sample_abi, sample_address):
+    df = read_new_abi_json(sample_abi, sample_address)
+    validate_num_indexed_args(df)
+
+    # An older format storing only the number of indexed params, without the topic0
+    events = pl.col("num_indexed_args").is_not_null()
+    old_df = df.with_columns(pl.when(events).then(pl.col("num_indexed_args") - 1).otherwise(pl.col("num_indexed_args")).alias("num_indexed_args"))
+    with pytest.raises(ValueError, match="Inconsistent num_indexed_args"):
+        validate_num_indexed_args(old_df)
+
+def test_num_indexed_args_anonymous_events(sample_abi, sample_address):
+    anonymous_abi = json.dumps([{
+        "anonymous": True,
+        "inputs": [
+            {"indexed": True, "name": "sig", "type": "bytes4"},
+            {"indexed": True, "name": "guy", "type": "address"},
+            {"indexed": False, "name": "data", "type": "bytes"},
+        ],
+        "name": "LogNote",
+        "type": "event",
+    }])
+    # The logs of anonymous events have no topic0, only a topic for each indexed param
+    anonymous_df = read_new_abi_json(anonymous_abi, sample_address)
+    assert anonymous_df["anonymous"].to_list() == [True]
+    assert anonymous_df["num_indexed_args"].to_list() == [2]
+    # Transfer has the topic0 and 2 indexed params
+    transfer_df = read_new_abi_json(sample_abi, sample_address)
+    assert transfer_df["num_indexed_args"].to_list() == [3]
+    validate_num_indexed_args(pl.concat([transfer_df, anonymous_df]))
+
+def test_convert_abi_db_encoding(tmp_path, sample_abi, sample_address):
+    abi_df = read_new_abi_json(sample_abi, sample_address)
+    db_path = tmp_path / "abi_db.parquet"
+    abi_df.write_parquet(db_path)
+
+    hex_df = convert_abi_db_encoding(str(db_path), to_hex=True)
+    assert hex_df.schema["hash"] == pl.String
+    assert hex_df.schema["address"] == pl.String
+    assert all(h.startswith("0x") for h in hex_df["hash"].to_list())
+    assert pl.read_parquet(db_path).schema["address"] == pl.String
+
+    binary_df = convert_abi_db_encoding(str(db_path), to_hex=False)
+    assert binary_df.schema["hash"] == pl.Binary
+    assert pl.read_parquet(db_path).equals(abi_df)
+
+    # csv files can't store binary columns
+    csv_path = tmp_path / "abi_db.csv"
+    hex_df.write_csv(csv_path)
+    with pytest.raises(ValueError):
+        convert_abi_db_encoding(str(csv_path), to_hex=False)
+    assert pl.read_csv(csv_path).height == hex_df.height
+
+
+@pytest.fixture
+def mock_abi_server(sample_abi):
+    # Mock server answering both Sourcify metadata.json and Etherscan getabi requests
+    abi = json.loads(sample_abi)
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_GET(self):
+            url = urllib.parse.urlparse(self.path)
+            if url.path.endswith("/metadata.json"):
+                body = {"output": {"abi": abi}}
+            elif url.path == "/api":
+                body = {"status": "1", "message": "OK", "result": json.dumps(abi)}
+            else:
+                self.send_response(404)
+                self.end_headers()
+                return
+            self.send_response(200)
+            self.send_header("Content-Type", "application/json")
+            self.end_headers()
+            self.wfile.write(json.dumps(body).encode())
+
+        def log_message(self, format, *args):
+            pass
+
+    server = HTTPServer(("127.0.0.1", 0), Handler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    base_url = f"http://127.0.0.1:{server.server_port}"
+
+    previous_config = toml.loads(get_config())["abi_reader"]
+    set_config("abi_reader.sourcify_url", base_url)
+    set_config("abi_reader.etherscan_url", base_url + "/api")
+    yield base_url
+    set_config("abi_reader.sourcify_url", previous_config["sourcify_url"])
+    set_config("abi_reader.etherscan_url", previous_config["etherscan_url"])
+    server.shutdown()
+
+def test_fetch_abis_from_manifest(tmp_path, mock_abi_server):
+    manifest_path = tmp_path / "manifest.csv"
+    manifest_path.write_text(
+        "address,chain_id,source\n"
+        "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64,1,sourcify\n"
+        "0x1111111111111111111111111111111111111111,10,etherscan\n"
+    )
+    db_path = tmp_path / "abi_db.parquet"
+
+    report = fetch_abis_from_manifest(str(manifest_path), str(db_path))
+    assert isinstance(report, (pl.DataFrame, pd.DataFrame))
+    assert len(report) == 2
+    assert report["success"].to_list() == [True, True]
+    assert report["source"].to_list() == ["sourcify", "etherscan"]
+    assert db_path.exists()
+    assert len(pl.read_parquet(db_path)) == 2
+    assert sorted(pl.read_parquet(db_path)["chain_id"].to_list()) == [1, 10]
+
+    # Fetching again shouldn't duplicate the ABI DB items
+    fetch_abis_from_manifest(str(manifest_path), str(db_path))
+    assert len(pl.read_parquet(db_path)) == 2
+
+def test_fetch_abis_from_manifest_store_abi_source(tmp_path, mock_abi_server, sample_abi):
+    manifest_path = tmp_path / "manifest.csv"
+    manifest_path.write_text(
+        "address,chain_id,source\n"
+        "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64,1,sourcify\n"
+        "0x1111111111111111111111111111111111111111,10,etherscan\n"
+    )
+    db_path = tmp_path / "abi_db.parquet"
+    abi_folder = tmp_path / "abis"
+    abi_folder.mkdir()
+    abi_file = abi_folder / "0x2222222222222222222222222222222222222222.json"
+    abi_file.write_text(sample_abi)
+
+    set_config("abi_reader.store_abi_source", True)
+    try:
+        fetch_abis_from_manifest(str(manifest_path), str(db_path))
+        abi_db = update_abi_db(str(db_path), str(abi_folder))
+    finally:
+        set_config("abi_reader.store_abi_source", False)
+
+    assert sorted(abi_db["abi_source"].to_list()) == ["etherscan", f"local:{abi_file}", "sourcify"]
+
+def test_fetch_abis_from_manifest_reports_failures(tmp_path, mock_abi_server):
+    manifest_path = tmp_path / "manifest.csv"
+    manifest_path.write_text(
+        "address,chain_id,source\n"
+        "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64,1,sourcify\n"
+        "0x1111111111111111111111111111111111111111,1,unknown_source\n"
+    )
+    db_path = tmp_path / "abi_db.parquet"
+
+    report = fetch_abis_from_manifest(str(manifest_path), str(db_path))
+    assert report["success"].to_list() == [True, False]
+    assert report["error"][0] is None
+    assert "invalid source" in report["error"][1]
+    assert len(pl.read_parquet(db_path)) == 1
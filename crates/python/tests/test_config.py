@@ -17,16 +17,62 @@ def test_default_config():
     assert config["main"]["raw_traces_folder_path"] == "data/traces"
     assert config["abi_reader"]["abi_read_mode"] == "Events"
     assert config["abi_reader"]["unique_key"] == ["hash", "full_signature", "address"]
+    assert config["abi_reader"]["sourcify_url"] == "https://repo.sourcify.dev"
+    assert config["abi_reader"]["etherscan_url"] == "https://api.etherscan.io/v2/api"
+    assert config["abi_reader"]["max_concurrent_abi_downloads"] == 8
+    assert config["abi_reader"]["hash_column_name"] == "hash"
+    assert config["abi_reader"]["max_concurrent_abi_folders"] == config["decoder"]["max_chunk_threads_per_file"]
+    assert config["abi_reader"]["store_abi_source"] == False
+    assert config["abi_reader"]["skip_item_types"] == []
+    assert config["abi_reader"]["relaxed_json"] == False
+    assert config["abi_reader"]["store_raw_inputs"] == False
+    assert config["abi_reader"]["http_timeout_secs"] == 30
+    assert config["abi_reader"]["http_retry_count"] == 2
+    assert config["abi_reader"]["http_retry_backoff_ms"] == 500
+    assert config["abi_reader"]["http_user_agent"].startswith("glaciers/")
     assert config["decoder"]["algorithm"] == "Hash"
     assert config["decoder"]["output_hex_string_encoding"] == False
     assert config["decoder"]["output_file_format"] == "parquet"
-    assert config["decoder"]["max_concurrent_files_decoding"] == 16
-    assert config["decoder"]["max_chunk_threads_per_file"] == 16
+    # concurrency defaults are sized from the available CPU cores
+    assert config["decoder"]["max_chunk_threads_per_file"] >= 1
+    assert config["decoder"]["max_concurrent_files_decoding"] == 2 * config["decoder"]["max_chunk_threads_per_file"]
     assert config["decoder"]["decoded_chunk_size"] == 500000
+    assert config["decoder"]["max_total_chunk_threads"] == 0
+    assert config["decoder"]["max_in_flight_bytes"] == 0
+    assert config["decoder"]["chunk_retry_count"] == 0
+    assert config["decoder"]["io_max_retries"] == 2
+    assert config["io"]["csv_delimiter"] == ","
+    assert config["io"]["csv_has_header"] == True
+    assert config["io"]["csv_quote_char"] == '"'
+    assert config["io"]["csv_alias_columns_as_string"] == False
+    assert config["decoder"]["drop_raw_columns"] == False
+    assert config["decoder"]["dataset_mode"] == False
+    assert config["decoder"]["abi_id_column"] == "id"
+    assert config["decoder"]["abi_passthrough_columns"] == []
+    assert config["decoder"]["overwrite_mismatched_outputs"] == False
+    assert config["decoder"]["write_run_manifest"] == False
+    assert config["decoder"]["write_watermark_file"] == False
+    assert config["decoder"]["add_source_file_column"] == False
+    assert config["decoder"]["positional_names_when_unnamed"] == True
+    assert config["decoder"]["max_value_length"] == 0
+    assert config["decoder"]["bytes_encoding"] == "Hex"
+    assert "timestamp_column" not in config["decoder"]
+    assert config["decoder"]["timestamp_timezone"] == "UTC"
+    assert config["decoder"]["passthrough_columns"] == ["*"]
+    assert config["decoder"]["signature_allowlist"] == []
+    assert config["decoder"]["signature_denylist"] == []
+    assert config["decoder"]["decode_all_candidates"] == False
     assert config["log_decoder"]["log_schema"]["log_alias"] == {"topic0": "topic0", "topic1": "topic1", "topic2": "topic2", "topic3": "topic3", "data": "data", "address": "address"}
     assert config["log_decoder"]["log_schema"]["log_datatype"] == {"topic0": "Binary", "topic1": "Binary", "topic2": "Binary", "topic3": "Binary", "data": "Binary", "address": "Binary"}
-    assert config["trace_decoder"]["trace_schema"]["trace_alias"] == {"selector": "selector", "action_input": "action_input", "result_output": "result_output", "action_to": "action_to"}
+    assert config["log_decoder"]["missing_topics_mode"] == "Compatible"
+    assert config["log_decoder"]["adjust_indexed_args"] == False
+    assert config["log_decoder"]["include_num_indexed_args"] == False
+    assert config["log_decoder"]["topics_list_column"] == ""
+    assert config["log_decoder"]["topics_list_last_is_data"] == False
+    assert config["log_decoder"]["indexed_only"] == False
+    assert config["trace_decoder"]["trace_schema"]["trace_alias"] == {"selector": "selector", "action_input": "action_input", "result_output": "result_output", "action_to": "action_to", "gas": "action_gas", "gas_used": "result_gas_used", "value": "action_value"}
     assert config["trace_decoder"]["trace_schema"]["trace_datatype"] == {"selector": "Binary", "action_input": "Binary", "result_output": "Binary", "action_to": "Binary"}
+    assert config["trace_decoder"]["parse_hex_numeric_columns"] == False
 
 def test_set_config_toml():
     """Test loading configuration from TOML file"""
@@ -82,6 +128,24 @@ def test_set_config():
     set_config("abi_reader.unique_key", "hash")
     set_config("abi_reader.unique_key", "full_signature")
     set_config("abi_reader.unique_key", "address")
+    set_config("abi_reader.unique_key", ["hash", "full_signature", "address", "chain_id"])
+    set_config("abi_reader.sourcify_url", "http://localhost:8000/")
+    set_config("abi_reader.etherscan_url", "http://localhost:8001/api")
+    set_config("abi_reader.max_concurrent_abi_downloads", 2)
+    set_config("abi_reader.hash_column_name", "topic0")
+    set_config("abi_reader.max_concurrent_abi_folders", 2)
+    set_config("abi_reader.store_abi_source", 1)
+    set_config("abi_reader.store_abi_source", False)
+    set_config("abi_reader.skip_item_types", "event")
+    set_config("abi_reader.skip_item_types", ["Function", "event"])
+    set_config("abi_reader.relaxed_json", 1)
+    set_config("abi_reader.relaxed_json", False)
+    set_config("abi_reader.store_raw_inputs", 1)
+    set_config("abi_reader.store_raw_inputs", False)
+    set_config("abi_reader.http_timeout_secs", 10)
+    set_config("abi_reader.http_retry_count", 3)
+    set_config("abi_reader.http_retry_backoff_ms", 100)
+    set_config("abi_reader.http_user_agent", "my-indexer/1.0")
     set_config("decoder.algorithm", "Hash")
     set_config("decoder.algorithm", "Hash_Address")
     set_config("decoder.output_hex_string_encoding", False)
@@ -89,10 +153,47 @@ def test_set_config():
     set_config("decoder.output_hex_string_encoding", 0)
     set_config("decoder.output_hex_string_encoding", 1)
     set_config("decoder.output_file_format", "parquet")
+    set_config("decoder.output_file_format", "ndjson_pretty")
     set_config("decoder.output_file_format", "csv")
     set_config("decoder.max_concurrent_files_decoding", 1)
     set_config("decoder.max_chunk_threads_per_file", 1)
     set_config("decoder.decoded_chunk_size", 1)
+    set_config("decoder.max_total_chunk_threads", 4)
+    set_config("decoder.max_in_flight_bytes", "2g")
+    set_config("decoder.chunk_retry_count", 2)
+    set_config("decoder.io_max_retries", 5)
+    set_config("decoder.drop_raw_columns", 0)
+    set_config("decoder.drop_raw_columns", 1)
+    set_config("decoder.drop_raw_columns", False)
+    set_config("decoder.dataset_mode", 1)
+    set_config("decoder.dataset_mode", False)
+    set_config("decoder.abi_id_column", "abi_id")
+    set_config("decoder.abi_id_column", "id")
+    set_config("decoder.overwrite_mismatched_outputs", 1)
+    set_config("decoder.overwrite_mismatched_outputs", False)
+    set_config("decoder.write_run_manifest", 1)
+    set_config("decoder.write_run_manifest", False)
+    set_config("decoder.write_watermark_file", 1)
+    set_config("decoder.write_watermark_file", False)
+    set_config("decoder.add_source_file_column", 1)
+    set_config("decoder.add_source_file_column", False)
+    set_config("decoder.positional_names_when_unnamed", 0)
+    set_config("decoder.positional_names_when_unnamed", True)
+    set_config("decoder.max_value_length", "1m")
+    set_config("decoder.bytes_encoding", "utf8_lossy")
+    set_config("decoder.bytes_encoding", "Base64")
+    set_config("decoder.timestamp_column", "block_timestamp")
+    set_config("decoder.timestamp_timezone", "America/New_York")
+    set_config("decoder.passthrough_columns", ["block_number", "transaction_hash"])
+    set_config("decoder.passthrough_columns", "*")
+    set_config("decoder.abi_passthrough_columns", ["protocol", "category"])
+    set_config("decoder.abi_passthrough_columns", [])
+    set_config("decoder.signature_allowlist", ["0xa9059cbb", "event Transfer(address indexed from, address indexed to, uint256 value)"])
+    set_config("decoder.signature_allowlist", [])
+    set_config("decoder.signature_denylist", "0xa9059cbb")
+    set_config("decoder.signature_denylist", [])
+    set_config("decoder.decode_all_candidates", 1)
+    set_config("decoder.decode_all_candidates", False)
     set_config("log_decoder.log_schema.log_alias.topic0", "t0")
     set_config("log_decoder.log_schema.log_alias.topic1", "t1")
     set_config("log_decoder.log_schema.log_alias.topic2", "t2")
@@ -105,14 +206,37 @@ def test_set_config():
     set_config("log_decoder.log_schema.log_datatype.topic3", "HexString")
     set_config("log_decoder.log_schema.log_datatype.data", "HexString")
     set_config("log_decoder.log_schema.log_datatype.address", "HexString")
+    set_config("log_decoder.missing_topics_mode", "compatible")
+    set_config("log_decoder.missing_topics_mode", "Strict")
+    set_config("log_decoder.adjust_indexed_args", 1)
+    set_config("log_decoder.adjust_indexed_args", False)
+    set_config("log_decoder.include_num_indexed_args", 0)
+    set_config("log_decoder.include_num_indexed_args", True)
+    set_config("log_decoder.topics_list_column", "topics")
+    set_config("log_decoder.topics_list_column", "")
+    set_config("log_decoder.topics_list_last_is_data", 1)
+    set_config("log_decoder.topics_list_last_is_data", False)
+    set_config("log_decoder.indexed_only", 1)
+    set_config("log_decoder.indexed_only", False)
     set_config("trace_decoder.trace_schema.trace_alias.selector", "4bytes")
     set_config("trace_decoder.trace_schema.trace_alias.action_input", "input")
     set_config("trace_decoder.trace_schema.trace_alias.result_output", "output")
     set_config("trace_decoder.trace_schema.trace_alias.action_to", "to")
+    set_config("trace_decoder.trace_schema.trace_alias.gas", "gas")
+    set_config("trace_decoder.trace_schema.trace_alias.gas_used", "gas_used")
+    set_config("trace_decoder.trace_schema.trace_alias.value", "value")
+    set_config("trace_decoder.trace_schema.trace_alias.trace_address", "trace_address")
     set_config("trace_decoder.trace_schema.trace_datatype.selector", "HexString")
     set_config("trace_decoder.trace_schema.trace_datatype.action_input", "HexString")
     set_config("trace_decoder.trace_schema.trace_datatype.result_output", "HexString")
     set_config("trace_decoder.trace_schema.trace_datatype.action_to", "HexString")
+    set_config("trace_decoder.parse_hex_numeric_columns", 0)
+    set_config("trace_decoder.parse_hex_numeric_columns", True)
+    set_config("io.csv_delimiter", "|")
+    set_config("io.csv_has_header", 0)
+    set_config("io.csv_has_header", False)
+    set_config("io.csv_quote_char", "")
+    set_config("io.csv_alias_columns_as_string", 1)
     expected_config = '''
         [glaciers]
         preferred_dataframe_type = "Pandas"
@@ -129,6 +253,19 @@ def test_set_config():
         abi_read_mode = "Both"
         output_hex_string_encoding = true
         unique_key = ["address"]
+        sourcify_url = "http://localhost:8000"
+        etherscan_url = "http://localhost:8001/api"
+        max_concurrent_abi_downloads = 2
+        hash_column_name = "topic0"
+        max_concurrent_abi_folders = 2
+        store_abi_source = false
+        skip_item_types = ["function"]
+        relaxed_json = false
+        store_raw_inputs = false
+        http_timeout_secs = 10
+        http_retry_count = 3
+        http_retry_backoff_ms = 100
+        http_user_agent = "my-indexer/1.0"
 
         [decoder]
         algorithm = "HashAddress"
@@ -137,14 +274,52 @@ def test_set_config():
         max_concurrent_files_decoding = 1
         max_chunk_threads_per_file = 1
         decoded_chunk_size = 1
+        max_total_chunk_threads = 4
+        max_in_flight_bytes = 2_000_000_000
+        chunk_retry_count = 2
+        io_max_retries = 5
+        drop_raw_columns = false
+        dataset_mode = false
+        abi_id_column = "id"
+        abi_passthrough_columns = []
+        overwrite_mismatched_outputs = false
+        write_run_manifest = false
+        write_watermark_file = false
+        add_source_file_column = false
+        positional_names_when_unnamed = true
+        max_value_length = 1_000_000
+        bytes_encoding = "Base64"
+        timestamp_column = "block_timestamp"
+        timestamp_timezone = "America/New_York"
+        passthrough_columns = ["*"]
+        signature_allowlist = []
+        signature_denylist = []
+        decode_all_candidates = false
+
+        [log_decoder]
+        missing_topics_mode = "Strict"
+        adjust_indexed_args = false
+        include_num_indexed_args = true
+        topics_list_column = ""
+        topics_list_last_is_data = false
+        indexed_only = false
 
         [log_decoder.log_schema]
         log_alias = { topic0 = "t0", topic1 = "t1", topic2 = "t2", topic3 = "t3", data = "d", address = "event_address" }
         log_datatype = { topic0 = "HexString", topic1 = "HexString", topic2 = "HexString", topic3 = "HexString", data = "HexString", address = "HexString" }
 
+        [trace_decoder]
+        parse_hex_numeric_columns = true
+
         [trace_decoder.trace_schema]
-        trace_alias = { selector = "4bytes", action_input = "input", result_output = "output", action_to = "to" }
+        trace_alias = { selector = "4bytes", action_input = "input", result_output = "output", action_to = "to", gas = "gas", gas_used = "gas_used", value = "value", trace_address = "trace_address" }
         trace_datatype = { selector = "HexString", action_input = "HexString", result_output = "HexString", action_to = "HexString" }
+
+        [io]
+        csv_delimiter = "|"
+        csv_has_header = false
+        csv_quote_char = ""
+        csv_alias_columns_as_string = true
     '''
     expected_config = toml.loads(expected_config)
     config = get_config()
@@ -152,6 +327,25 @@ def test_set_config():
     print(config)
     print(expected_config)
     assert config == expected_config
+    # The example config file has no trace_address nor timestamp_column, so they wouldn't be unset by the other tests' config reset
+    set_config("trace_decoder.trace_schema.trace_alias.trace_address", "")
+    set_config("decoder.timestamp_column", "")
+
+def test_set_config_human_readable_numbers(tmp_path):
+    """Test numeric configs set with underscore separators and k/m/g suffixes"""
+    for value, expected in [("1_000_000", 1_000_000), ("500000", 500_000), ("500k", 500_000), ("1M", 1_000_000)]:
+        set_config("decoder.decoded_chunk_size", value)
+        assert toml.loads(get_config())["decoder"]["decoded_chunk_size"] == expected
+
+    # TOML files accept them as strings too
+    config_file = tmp_path / "config.toml"
+    config_file.write_text('[decoder]\ndecoded_chunk_size = "250k"\n')
+    set_config_toml(str(config_file))
+    assert toml.loads(get_config())["decoder"]["decoded_chunk_size"] == 250_000
+
+    with pytest.raises(ValueError):
+        set_config("decoder.decoded_chunk_size", "1.5M")
+    set_config("decoder.decoded_chunk_size", 500_000)
 
 def test_invalid_config():
     """Test error handling for invalid configurations"""
@@ -168,3 +362,25 @@ def test_invalid_config():
         config = get_config()
         config = toml.loads(config)
         assert config["glaciers"]["preferred_dataframe_type"] == "invalid"
+
+    # Test invalid ABI item type
+    with pytest.raises(ValueError):
+        set_config("abi_reader.skip_item_types", ["modifier"])
+    # Constructors, errors, receive() and fallback() can't be skipped on their own
+    with pytest.raises(ValueError):
+        set_config("abi_reader.skip_item_types", ["receive"])
+
+    # Test invalid hex hash in a signature list
+    with pytest.raises(ValueError):
+        set_config("decoder.signature_denylist", ["0xnothex"])
+
+    # Test duplicate column aliases, the config is left unchanged
+    config = toml.loads(get_config())
+    log_alias = config["log_decoder"]["log_schema"]["log_alias"]
+    with pytest.raises(ValueError):
+        set_config("log_decoder.log_schema.log_alias.topic0", log_alias["data"])
+    assert toml.loads(get_config())["log_decoder"]["log_schema"]["log_alias"] == log_alias
+    trace_alias = config["trace_decoder"]["trace_schema"]["trace_alias"]
+    with pytest.raises(ValueError):
+        set_config("trace_decoder.trace_schema.trace_alias.gas", trace_alias["value"])
+    assert toml.loads(get_config())["trace_decoder"]["trace_schema"]["trace_alias"] == trace_alias
@@ -1,5 +1,9 @@
 import os
+import json
+import base64
+import tarfile
 from os.path import dirname
+from datetime import datetime, timedelta, timezone
 import polars as pl
 import pandas as pd
 import toml
@@ -8,11 +12,33 @@ import pytest
 from glaciers import (
     decode_df,
     decode_df_with_abi_df,
+    decode_df_with_context,
+    decode_df_with_priority,
+    decode_df_with_overrides,
+    decode_tx_bundle,
+    decode_to_wide,
+    decode_and_aggregate,
     decode_df_using_single_contract,
+    distinct_addresses,
+    benchmark_decode,
+    can_decode,
+    label_selectors,
+    assert_event_count,
+    summarize_decoded,
+    compatibility_report,
+    build_logs_df,
+    decode_log_from_hex,
+    read_new_abi_json,
     get_config,
+    set_config,
     set_config_toml,
     decode_folder,
-    decode_file
+    decode_folder_incremental,
+    plan_folder,
+    decode_file,
+    DecodeFileResult,
+    decode_archive,
+    decode_partition
 )
 
 @pytest.fixture
@@ -109,15 +135,295 @@ def test_decode_folder(setup_paths):
     assert os.path.exists(decoded_folder)
     assert len(os.listdir(decoded_folder)) > 0
 
+def test_decode_folder_manifest(setup_paths):
+    set_config("decoder.write_run_manifest", True)
+    try:
+        manifest = decode_folder(
+            decoder_type="log",
+            abi_db_path=setup_paths['events_abi_path'],
+            folder_path=setup_paths['logs_folder_path']
+        )
+    finally:
+        set_config("decoder.write_run_manifest", False)
+
+    # The manifest is returned and saved in the decoded folder
+    decoded_folder = os.path.join(os.path.dirname(setup_paths['logs_folder_path']), "decoded")
+    with open(os.path.join(decoded_folder, "manifest.json")) as f:
+        assert json.load(f) == manifest
+
+    assert manifest["input_folder"] == setup_paths['logs_folder_path']
+    assert manifest["decoder_type"] == "log"
+    assert manifest["abi_db_rows"] == pl.read_parquet(setup_paths['events_abi_path']).height
+    assert manifest["config"]["decoder"]["write_run_manifest"] == True
+    assert len(manifest["files"]) == len(os.listdir(setup_paths['logs_folder_path']))
+    for file in manifest["files"]:
+        assert file["status"] == "decoded"
+        assert file["error"] is None
+        assert os.path.exists(file["output_path"])
+        assert file["rows_out"] == pl.read_parquet(file["output_path"]).height
+        assert 0 < file["match_rate"] <= 1
+    totals = manifest["totals"]
+    assert totals["files"] == totals["decoded_files"] == len(manifest["files"])
+    assert totals["failed_files"] == 0
+    assert totals["rows_in"] == sum(file["rows_in"] for file in manifest["files"])
+    assert totals["rows_out"] == sum(file["rows_out"] for file in manifest["files"])
+
+def test_decode_file_retries_transient_read_failure(setup_paths, capfd):
+    import threading
+    logs_folder = setup_paths['logs_folder_path']
+    sample_df = pl.read_parquet(os.path.join(logs_folder, "sample_log.parquet")).head(100)
+    flaky_file = os.path.join(logs_folder, "flaky_log.parquet")
+
+    # The file can't be opened until it's written, like a file of a network filesystem that isn't available yet
+    def write_file():
+        sample_df.write_parquet(flaky_file + ".tmp")
+        os.replace(flaky_file + ".tmp", flaky_file)
+    timer = threading.Timer(0.2, write_file)
+
+    set_config("decoder.io_max_retries", 5)
+    try:
+        capfd.readouterr()
+        timer.start()
+        result = decode_file(decoder_type="log", file_path=flaky_file, abi_db_path=setup_paths['events_abi_path'])
+        out = capfd.readouterr().out
+    finally:
+        timer.join()
+        set_config("decoder.io_max_retries", 2)
+    assert "Retrying reading file" in out
+    assert result.input_rows == 100
+
+def test_decode_file_corrupted_file_isnt_retried(setup_paths, capfd):
+    logs_folder = setup_paths['logs_folder_path']
+    corrupted_file = os.path.join(logs_folder, "corrupted_log.parquet")
+    with open(corrupted_file, "wb") as f:
+        f.write(b"not a parquet file")
+
+    set_config("decoder.io_max_retries", 5)
+    try:
+        capfd.readouterr()
+        with pytest.raises(ValueError):
+            decode_file(decoder_type="log", file_path=corrupted_file, abi_db_path=setup_paths['events_abi_path'])
+        out = capfd.readouterr().out
+    finally:
+        set_config("decoder.io_max_retries", 2)
+        os.remove(corrupted_file)
+    assert "Retrying reading file" not in out
+
+def test_decode_folder_continues_after_failed_file(setup_paths):
+    logs_folder = setup_paths['logs_folder_path']
+    with open(os.path.join(logs_folder, "corrupted_log.parquet"), "wb") as f:
+        f.write(b"not a parquet file")
+    set_config("decoder.io_max_retries", 0)
+    set_config("decoder.write_run_manifest", True)
+    try:
+        with pytest.raises(ValueError):
+            decode_folder(decoder_type="log", abi_db_path=setup_paths['events_abi_path'], folder_path=logs_folder)
+    finally:
+        set_config("decoder.io_max_retries", 2)
+        set_config("decoder.write_run_manifest", False)
+
+    # The corrupted file is recorded as failed, and the other file is still decoded
+    decoded_folder = os.path.join(os.path.dirname(logs_folder), "decoded")
+    with open(os.path.join(decoded_folder, "manifest.json")) as f:
+        manifest = json.load(f)
+    statuses = {os.path.basename(file["input_path"]): file["status"] for file in manifest["files"]}
+    assert statuses == {"sample_log.parquet": "decoded", "corrupted_log.parquet": "failed"}
+    assert manifest["totals"]["failed_files"] == 1
+
+def test_decode_folder_max_in_flight_bytes(setup_paths, capfd):
+    logs_folder = setup_paths['logs_folder_path']
+    sample_df = pl.read_parquet(os.path.join(logs_folder, "sample_log.parquet"))
+    for i in range(3):
+        sample_df.head(100).write_parquet(os.path.join(logs_folder, f"other_log_{i}.parquet"))
+    # Every file is bigger than a 1 byte budget, so they are decoded one at a time, whatever the file count limit
+    max_concurrent_files = get_config()["decoder"]["max_concurrent_files_decoding"]
+    set_config("decoder.max_concurrent_files_decoding", 8)
+    set_config("decoder.max_in_flight_bytes", 1)
+    try:
+        capfd.readouterr()
+        manifest = decode_folder(decoder_type="log", abi_db_path=setup_paths['events_abi_path'], folder_path=logs_folder)
+        out = capfd.readouterr().out
+    finally:
+        set_config("decoder.max_in_flight_bytes", 0)
+        set_config("decoder.max_concurrent_files_decoding", max_concurrent_files)
+    assert manifest["totals"]["decoded_files"] == 4
+    events = [line for line in out.splitlines() if "Starting decoding file" in line or "Saving decoded to" in line]
+    assert len(events) == 8
+    for start, save in zip(events[::2], events[1::2]):
+        assert "Starting decoding file" in start
+        assert "Saving decoded to" in save
+
+def test_decode_folder_incremental(setup_paths):
+    logs_folder = setup_paths['logs_folder_path']
+    decoded_folder = os.path.join(os.path.dirname(logs_folder), "decoded")
+    set_config("decoder.write_watermark_file", True)
+    try:
+        # Without a saved watermark, all the files are decoded
+        manifest, watermark = decode_folder_incremental("log", None, logs_folder, setup_paths['events_abi_path'])
+        assert manifest["totals"]["decoded_files"] == 1
+        sample_mtime = os.path.getmtime(os.path.join(logs_folder, "sample_log.parquet"))
+        assert abs(watermark.timestamp() - sample_mtime) < 1e-5
+        with open(os.path.join(decoded_folder, "decode_watermark.json")) as f:
+            assert "watermark" in json.load(f)
+
+        # The next run resumes from the saved watermark, with no new files
+        manifest, next_watermark = decode_folder_incremental("log", None, logs_folder, setup_paths['events_abi_path'])
+        assert manifest["totals"]["files"] == 0
+        assert next_watermark == watermark
+
+        # A file modified at the watermark time after the run isn't skipped
+        same_time_file = os.path.join(logs_folder, "same_time_log.parquet")
+        pl.read_parquet(os.path.join(logs_folder, "sample_log.parquet")).head(10).write_parquet(same_time_file)
+        sample_mtime_ns = os.stat(os.path.join(logs_folder, "sample_log.parquet")).st_mtime_ns
+        os.utime(same_time_file, ns=(sample_mtime_ns, sample_mtime_ns))
+        manifest, next_watermark = decode_folder_incremental("log", None, logs_folder, setup_paths['events_abi_path'])
+        assert [file["input_path"] for file in manifest["files"]] == [same_time_file]
+        assert next_watermark == watermark
+        with open(os.path.join(decoded_folder, "decode_watermark.json")) as f:
+            assert json.load(f)["files"] == ["sample_log.parquet", "same_time_log.parquet"]
+
+        # Only the new file is decoded
+        new_file = os.path.join(logs_folder, "new_log.parquet")
+        pl.read_parquet(os.path.join(logs_folder, "sample_log.parquet")).head(10).write_parquet(new_file)
+        new_mtime = int(sample_mtime) + 60
+        os.utime(new_file, (new_mtime, new_mtime))
+        manifest, next_watermark = decode_folder_incremental("log", None, logs_folder, setup_paths['events_abi_path'])
+        assert [file["input_path"] for file in manifest["files"]] == [new_file]
+        assert next_watermark == datetime.fromtimestamp(new_mtime, timezone.utc)
+    finally:
+        set_config("decoder.write_watermark_file", False)
+
+    # An explicit watermark is used instead of the saved one, naive datetimes as UTC
+    manifest, _ = decode_folder_incremental("log", datetime(2000, 1, 1), logs_folder, setup_paths['events_abi_path'])
+    assert manifest["totals"]["decoded_files"] == 3
+
+def test_plan_folder(setup_paths, tmp_path):
+    logs_folder = setup_paths['logs_folder_path']
+    sample_df = pl.read_parquet(os.path.join(logs_folder, "sample_log.parquet"))
+    sample_df.head(10).write_parquet(os.path.join(logs_folder, "ethereum__logs__00018000000_to_00018099999.parquet"))
+    sample_df.head(10).write_parquet(os.path.join(logs_folder, "ethereum__logs__00018100000_to_00018199999.parquet"))
+    with open(os.path.join(logs_folder, "notes.txt"), "w") as f:
+        f.write("not a logs file")
+
+    plan = plan_folder(logs_folder, "log")
+    assert plan.columns == ["input_path", "output_path", "modified", "size_bytes", "from_block", "to_block"]
+    assert plan.height == 4
+    decoded_folder = os.path.join(os.path.dirname(logs_folder), "decoded")
+    assert plan["output_path"][0] == os.path.join(decoded_folder, "ethereum__decoded_logs__00018000000_to_00018099999.parquet")
+
+    plan = plan_folder(logs_folder, "log", extensions=["parquet"], block_range=(18_150_000, 18_160_000))
+    assert plan["input_path"].to_list() == [os.path.join(logs_folder, "ethereum__logs__00018100000_to_00018199999.parquet")]
+    assert plan["from_block"].to_list() == [18_100_000]
+    assert plan_folder(logs_folder, "log", modified_since=datetime(2100, 1, 1)).height == 0
+
+    # Symlinked files are planned, broken symlinks are skipped
+    linked_file = tmp_path / "ethereum__logs__00018200000_to_00018299999.parquet"
+    sample_df.head(10).write_parquet(linked_file)
+    os.symlink(linked_file, os.path.join(logs_folder, linked_file.name))
+    os.symlink(tmp_path / "missing.parquet", os.path.join(logs_folder, "broken_log.parquet"))
+    plan = plan_folder(logs_folder, "log", extensions=["parquet"], block_range=(18_200_000, 18_200_000))
+    assert plan["input_path"].to_list() == [os.path.join(logs_folder, linked_file.name)]
+    assert plan["size_bytes"][0] == os.path.getsize(linked_file)
+    os.remove(os.path.join(logs_folder, "broken_log.parquet"))
+
+    # decode_folder decodes the planned files
+    os.remove(os.path.join(logs_folder, "notes.txt"))
+    manifest = decode_folder(decoder_type="log", abi_db_path=setup_paths['events_abi_path'], folder_path=logs_folder)
+    assert [file["input_path"] for file in manifest["files"]] == plan_folder(logs_folder, "log")["input_path"].to_list()
+    # The ABI DB rows are only counted for saved manifests
+    assert manifest["abi_db_rows"] is None
+
+def test_decode_partition(setup_paths, tmp_path):
+    input_file = os.path.join(setup_paths['logs_folder_path'], "sample_log.parquet")
+    output_file = tmp_path / "out" / "date=2024-01-01" / "logs.parquet"
+
+    summary = decode_partition("log", input_file, str(output_file), setup_paths['events_abi_path'])
+    assert summary["skipped"] == False
+    assert summary["input_path"] == input_file
+    assert summary["output_path"] == str(output_file)
+    assert summary["rows_in"] == pl.read_parquet(input_file).height
+    assert summary["rows_out"] == pl.read_parquet(output_file).height
+    assert 0 < summary["match_rate"] <= 1
+    # No temporary file is left next to the output
+    assert os.listdir(output_file.parent) == ["logs.parquet"]
+
+    # Decoding again with the same config is a no-op returning the stored summary
+    modified = os.path.getmtime(output_file)
+    again = decode_partition("log", input_file, str(output_file), setup_paths['events_abi_path'])
+    assert again["skipped"] == True
+    assert os.path.getmtime(output_file) == modified
+    assert {k: v for k, v in again.items() if k != "skipped"} == {k: v for k, v in summary.items() if k != "skipped"}
+
+    # Another config is refused
+    set_config("decoder.drop_raw_columns", True)
+    try:
+        with pytest.raises(ValueError, match="Refusing to overwrite"):
+            decode_partition("log", input_file, str(output_file), setup_paths['events_abi_path'])
+    finally:
+        set_config("decoder.drop_raw_columns", False)
+
+def test_decode_archive(sample_logs_df, setup_paths, tmp_path):
+    # A small tar.gz with two log parquets, one of them in a subfolder, and a non parquet member
+    members_dir = tmp_path / "members"
+    (members_dir / "part2").mkdir(parents=True)
+    sample_logs_df.head(100).write_parquet(members_dir / "logs_part1.parquet")
+    sample_logs_df.slice(100, 100).write_parquet(members_dir / "part2" / "logs_part2.parquet")
+    (members_dir / "README.txt").write_text("not a parquet file")
+    archive_path = tmp_path / "logs.tar.gz"
+    with tarfile.open(archive_path, "w:gz") as archive:
+        archive.add(members_dir, arcname="logs")
+
+    output_dir = tmp_path / "decoded"
+    decode_archive("log", str(archive_path), str(output_dir), abi_db_path=setup_paths['events_abi_path'])
+
+    assert sorted(os.listdir(output_dir)) == ["decoded_logs_part1.parquet", "decoded_logs_part2.parquet"]
+    decoded = pl.read_parquet(output_dir / "decoded_logs_part1.parquet")
+    assert decoded.height == 100
+    for col in ["event_keys", "event_values", "event_json"]:
+        assert col in decoded.columns
+
+def test_decode_folder_dataset_mode(tmp_path, setup_paths, sample_logs_df):
+    # A hive partitioned dataset with two partitions, the first one split in two files
+    dataset_path = tmp_path / "dataset" / "logs"
+    half = sample_logs_df.height // 2
+    quarter = half // 2
+    parts = {
+        "bucket=0/part-0.parquet": sample_logs_df.slice(0, quarter),
+        "bucket=0/part-1.parquet": sample_logs_df.slice(quarter, half - quarter),
+        "bucket=1/part-0.parquet": sample_logs_df.slice(half),
+    }
+    for path, part in parts.items():
+        (dataset_path / path).parent.mkdir(parents=True, exist_ok=True)
+        part.write_parquet(dataset_path / path)
+
+    set_config("decoder.dataset_mode", True)
+    try:
+        decode_folder(decoder_type="log", abi_db_path=setup_paths['events_abi_path'], folder_path=str(dataset_path))
+    finally:
+        set_config("decoder.dataset_mode", False)
+
+    decoded_path = tmp_path / "dataset" / "decoded" / "decoded_logs"
+    # One decoded file per partition, with the partition column kept
+    assert sorted(os.listdir(decoded_path)) == ["bucket=0", "bucket=1"]
+    for bucket, height in [(0, half), (1, sample_logs_df.height - half)]:
+        assert os.listdir(decoded_path / f"bucket={bucket}") == ["decoded_logs.parquet"]
+        decoded = pl.read_parquet(decoded_path / f"bucket={bucket}" / "decoded_logs.parquet")
+        assert decoded.height == height
+        assert decoded["bucket"].unique().to_list() == [bucket]
+        for col in ["event_keys", "event_values", "event_json"]:
+            assert col in decoded.columns
+
 def test_decode_file(setup_paths):
     # Test log file decoding
     log_file = os.path.join(setup_paths['logs_folder_path'], os.listdir(setup_paths['logs_folder_path'])[0])
-    decoded_logs = decode_file(
+    result = decode_file(
         decoder_type="log",
         file_path=log_file,
         abi_db_path=setup_paths['events_abi_path']
     )
+    decoded_logs = result.df
     
+    assert isinstance(result, DecodeFileResult)
     assert isinstance(decoded_logs, pl.DataFrame)
     assert not decoded_logs.is_empty()
     for col in ["event_keys", "event_values", "event_json"]:
@@ -129,13 +435,226 @@ def test_decode_file(setup_paths):
         decoder_type="trace",
         file_path=trace_file,
         abi_db_path=setup_paths['functions_abi_path']
-    )
+    ).df
     
     assert isinstance(decoded_traces, pl.DataFrame)
     assert not decoded_traces.is_empty()
     for col in ["input_keys", "input_values", "input_json", "output_keys", "output_values", "output_json"]:
         assert col in decoded_traces.columns 
 
+def test_decode_file_result(setup_paths):
+    log_file = os.path.join(setup_paths['logs_folder_path'], os.listdir(setup_paths['logs_folder_path'])[0])
+    result = decode_file(decoder_type="log", file_path=log_file, abi_db_path=setup_paths['events_abi_path'])
+
+    assert result.input_path == log_file
+    assert os.path.exists(result.output_path)
+    assert result.input_rows == pl.read_parquet(log_file).height
+    assert result.output_rows == result.df.height == pl.read_parquet(result.output_path).height
+    assert result.matched_rows == result.df["full_signature"].is_not_null().sum()
+    assert 0 < result.matched_rows <= result.output_rows
+    assert result.duration > timedelta(0)
+
+def test_decode_file_passthrough_columns(setup_paths):
+    log_file = os.path.join(setup_paths['logs_folder_path'], os.listdir(setup_paths['logs_folder_path'])[0])
+    set_config("decoder.passthrough_columns", ["block_number", "log_index", "not_a_column"])
+    try:
+        decoded_logs = decode_file(decoder_type="log", file_path=log_file, abi_db_path=setup_paths['events_abi_path']).df
+    finally:
+        set_config("decoder.passthrough_columns", ["*"])
+
+    assert not decoded_logs.is_empty()
+    for col in ["block_number", "log_index", "address", "topic0", "data", "event_json"]:
+        assert col in decoded_logs.columns
+    for col in ["transaction_hash", "transaction_index", "not_a_column"]:
+        assert col not in decoded_logs.columns
+
+
+def test_decode_file_add_source_file_column(setup_paths):
+    logs_folder = setup_paths['logs_folder_path']
+    pl.read_parquet(os.path.join(logs_folder, "sample_log.parquet")).head(10).write_parquet(os.path.join(logs_folder, "other_log.parquet"))
+    set_config("decoder.add_source_file_column", True)
+    try:
+        result = decode_file(decoder_type="log", file_path=os.path.join(logs_folder, "sample_log.parquet"), abi_db_path=setup_paths['events_abi_path'])
+        assert result.df["source_file"].unique().to_list() == ["sample_log.parquet"]
+        assert pl.read_parquet(result.output_path)["source_file"].null_count() == 0
+
+        # the column survives combining the decoded files of a folder
+        decode_folder(decoder_type="log", abi_db_path=setup_paths['events_abi_path'], folder_path=logs_folder)
+    finally:
+        set_config("decoder.add_source_file_column", False)
+    decoded_folder = os.path.join(os.path.dirname(logs_folder), "decoded")
+    combined = pl.read_parquet(os.path.join(decoded_folder, "*.parquet"))
+    counts = dict(combined.group_by("source_file").len().iter_rows())
+    assert counts == {"sample_log.parquet": result.output_rows, "other_log.parquet": 10}
+
+
+def test_decode_file_timestamp_column(setup_paths):
+    logs_folder = setup_paths['logs_folder_path']
+    log_file = os.path.join(logs_folder, "timestamped_log.parquet")
+    (pl.read_parquet(os.path.join(logs_folder, "sample_log.parquet")).head(10)
+        .with_columns(pl.lit(1_700_000_000, dtype=pl.Int64).alias("block_timestamp"))
+        .write_parquet(log_file))
+    set_config("decoder.timestamp_column", "block_timestamp")
+    set_config("decoder.timestamp_timezone", "America/New_York")
+    try:
+        decoded = decode_file(decoder_type="log", file_path=log_file, abi_db_path=setup_paths['events_abi_path']).df
+        with pytest.raises(ValueError):
+            set_config("decoder.timestamp_timezone", "Not/A_Timezone")
+    finally:
+        set_config("decoder.timestamp_column", "")
+        set_config("decoder.timestamp_timezone", "UTC")
+    assert decoded.schema["block_timestamp"] == pl.Datetime("ms", "America/New_York")
+    assert decoded["block_timestamp"][0] == datetime(2023, 11, 14, 22, 13, 20, tzinfo=timezone.utc)
+
+
+def test_decode_file_refuses_mismatched_output(setup_paths):
+    log_file = os.path.join(setup_paths['logs_folder_path'], "sample_log.parquet")
+    decode_file(decoder_type="log", file_path=log_file, abi_db_path=setup_paths['events_abi_path'])
+    # Same config, the output is overwritten
+    decode_file(decoder_type="log", file_path=log_file, abi_db_path=setup_paths['events_abi_path'])
+
+    set_config("decoder.drop_raw_columns", True)
+    try:
+        with pytest.raises(ValueError, match="decoder.drop_raw_columns: false -> true"):
+            decode_file(decoder_type="log", file_path=log_file, abi_db_path=setup_paths['events_abi_path'])
+
+        set_config("decoder.overwrite_mismatched_outputs", True)
+        decoded = decode_file(decoder_type="log", file_path=log_file, abi_db_path=setup_paths['events_abi_path']).df
+    finally:
+        set_config("decoder.drop_raw_columns", False)
+        set_config("decoder.overwrite_mismatched_outputs", False)
+    assert "data" not in decoded.columns
+
+def test_decode_file_csv_options(tmp_path):
+    erc20_abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]'
+    address = "33" * 20
+    abi_df = read_new_abi_json(erc20_abi, "0x" + address)
+    abi_db_path = str(tmp_path / "erc20_abis.parquet")
+    abi_df.write_parquet(abi_db_path)
+
+    # A pipe delimited csv with quoted hex strings without the 0x prefix. The address and topic1/topic2
+    # only have digits, so their dtype is inferred as int64 without the alias columns override
+    topic0 = abi_df["hash"][0].hex()
+    topic1 = "00" * 12 + "11" * 20
+    topic2 = "00" * 12 + "22" * 20
+    data = "00" * 31 + "05"
+    logs_file = tmp_path / "logs" / "pipe_logs.csv"
+    logs_file.parent.mkdir()
+    logs_file.write_text(
+        "address|topic0|topic1|topic2|topic3|data\n"
+        + f'"{address}"|"{topic0}"|"{topic1}"|"{topic2}"||"{data}"\n' * 3
+    )
+
+    set_config("io.csv_delimiter", "|")
+    for field in ["topic0", "topic1", "topic2", "topic3", "data", "address"]:
+        set_config(f"log_decoder.log_schema.log_datatype.{field}", "HexString")
+    try:
+        with pytest.raises(Exception):
+            decode_file(decoder_type="log", file_path=str(logs_file), abi_db_path=abi_db_path)
+
+        set_config("io.csv_alias_columns_as_string", True)
+        decoded = decode_file(decoder_type="log", file_path=str(logs_file), abi_db_path=abi_db_path).df
+        assert decoded.height == 3
+        assert decoded["name"].to_list() == ["Transfer"] * 3
+        assert '"5"' in decoded["event_json"][0]
+    finally:
+        set_config("io.csv_delimiter", ",")
+        set_config("io.csv_alias_columns_as_string", False)
+        for field in ["topic0", "topic1", "topic2", "topic3", "data", "address"]:
+            set_config(f"log_decoder.log_schema.log_datatype.{field}", "Binary")
+
+    # Invalid delimiter
+    with pytest.raises(ValueError):
+        set_config("io.csv_delimiter", "||")
+
+def test_decode_file_mixed_log_and_trace_columns(tmp_path):
+    erc20_abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]'
+    address = "33" * 20
+    abi_df = read_new_abi_json(erc20_abi, "0x" + address)
+    abi_db_path = str(tmp_path / "erc20_abis.parquet")
+    abi_df.write_parquet(abi_db_path)
+
+    # A single csv with the hex string columns of both schemas. The address column is both the log address
+    # and the trace action_to alias, the other columns are only aliases of one of the decoder types
+    topic0 = abi_df["hash"][0].hex()
+    topic1 = "00" * 12 + "11" * 20
+    topic2 = "00" * 12 + "22" * 20
+    data = "00" * 31 + "05"
+    action_input = "a9059cbb" + topic2 + data
+    result_output = "00" * 31 + "01"
+    raw_file = tmp_path / "raw" / "mixed.csv"
+    raw_file.parent.mkdir()
+    raw_file.write_text(
+        "address|topic0|topic1|topic2|topic3|data|selector|action_input|result_output\n"
+        + f'"{address}"|"{topic0}"|"{topic1}"|"{topic2}"||"{data}"|"a9059cbb"|"{action_input}"|"{result_output}"\n' * 3
+    )
+
+    log_fields = ["topic0", "topic1", "topic2", "topic3", "data", "address"]
+    trace_fields = ["selector", "action_input", "result_output", "action_to"]
+    set_config("io.csv_delimiter", "|")
+    set_config("io.csv_alias_columns_as_string", True)
+    set_config("trace_decoder.trace_schema.trace_alias.action_to", "address")
+    for field in log_fields:
+        set_config(f"log_decoder.log_schema.log_datatype.{field}", "HexString")
+    for field in trace_fields:
+        set_config(f"trace_decoder.trace_schema.trace_datatype.{field}", "HexString")
+    try:
+        logs = decode_file(decoder_type="log", file_path=str(raw_file), abi_db_path=abi_db_path).df
+        traces = decode_file(decoder_type="trace", file_path=str(raw_file), abi_db_path=abi_db_path).df
+    finally:
+        set_config("io.csv_delimiter", ",")
+        set_config("io.csv_alias_columns_as_string", False)
+        set_config("trace_decoder.trace_schema.trace_alias.action_to", "action_to")
+        for field in log_fields:
+            set_config(f"log_decoder.log_schema.log_datatype.{field}", "Binary")
+        for field in trace_fields:
+            set_config(f"trace_decoder.trace_schema.trace_datatype.{field}", "Binary")
+
+    # Each decoder type only converts its own alias columns, the other type's columns are kept as strings
+    assert logs["name"].to_list() == ["Transfer"] * 3
+    assert {column: logs[column].dtype for column in ["address", "topic1", "data", "selector", "result_output"]} == {
+        "address": pl.Binary, "topic1": pl.Binary, "data": pl.Binary, "selector": pl.String, "result_output": pl.String
+    }
+    assert {column: traces[column].dtype for column in ["address", "topic1", "data", "selector", "result_output"]} == {
+        "address": pl.Binary, "topic1": pl.String, "data": pl.String, "selector": pl.Binary, "result_output": pl.Binary
+    }
+
+def test_decode_file_csv_output_round_trip(setup_paths):
+    log_file = os.path.join(setup_paths['logs_folder_path'], "sample_log.parquet")
+    set_config("decoder.output_file_format", "csv")
+    try:
+        decoded = decode_file(decoder_type="log", file_path=log_file, abi_db_path=setup_paths['events_abi_path']).df
+    finally:
+        set_config("decoder.output_file_format", "parquet")
+
+    # Binary columns are written to csv as 0x prefixed hex strings, even without decoder.output_hex_string_encoding
+    binary_columns = [name for name, dtype in decoded.schema.items() if dtype == pl.Binary]
+    assert binary_columns
+    expected = decoded.with_columns([("0x" + pl.col(name).bin.encode("hex")).alias(name) for name in binary_columns])
+
+    decoded_file = os.path.join(os.path.dirname(setup_paths['logs_folder_path']), "decoded", "decoded_sample_log.csv")
+    read_back = pl.read_csv(decoded_file).cast(expected.schema)
+    assert read_back.equals(expected)
+
+def test_decode_file_ndjson_pretty_output(setup_paths):
+    log_file = os.path.join(setup_paths['logs_folder_path'], "sample_log.parquet")
+    set_config("decoder.output_file_format", "ndjson_pretty")
+    try:
+        decoded = decode_file(decoder_type="log", file_path=log_file, abi_db_path=setup_paths['events_abi_path']).df
+    finally:
+        set_config("decoder.output_file_format", "parquet")
+
+    decoded_file = os.path.join(os.path.dirname(setup_paths['logs_folder_path']), "decoded", "decoded_sample_log.ndjson_pretty")
+    with open(decoded_file) as f:
+        content = f.read()
+    # one multi-line object per row, separated by blank lines
+    objects = [json.loads(block) for block in content.strip().split("\n\n")]
+    assert len(objects) == decoded.height
+    assert "\n  \"" in content
+    assert list(objects[0].keys()) == decoded.columns
+    assert objects[0]["topic0"] == "0x" + decoded["topic0"][0].hex()
+    assert objects[0]["full_signature"] == decoded["full_signature"][0]
+
 def test_decode_df(sample_logs_df, sample_traces_df, setup_paths):    
     result = decode_df("log", sample_logs_df, abi_db_path=setup_paths['events_abi_path'])
     assert isinstance(result, (pl.DataFrame, pd.DataFrame))
@@ -153,6 +672,40 @@ def test_decode_df(sample_logs_df, sample_traces_df, setup_paths):
     for col in ["input_keys", "input_values", "input_json", "output_keys", "output_values", "output_json"]:
         assert col in result.columns
 
+def test_decode_df_with_multiple_abi_dbs(sample_logs_df, sample_events_abi_df, tmp_path):
+    # Split the ABI DB in two files, the second one with an extra column
+    half = sample_events_abi_df.height // 2
+    first_db_path = str(tmp_path / "events_core.parquet")
+    second_db_path = str(tmp_path / "events_partners.parquet")
+    sample_events_abi_df.head(half).write_parquet(first_db_path)
+    sample_events_abi_df.slice(half - 1).with_columns(pl.lit("partners").alias("source")).write_parquet(second_db_path)
+
+    single_db_result = decode_df("log", sample_logs_df, abi_db_path=[first_db_path])
+    result = decode_df("log", sample_logs_df, abi_db_path=[first_db_path, second_db_path])
+    assert isinstance(result, (pl.DataFrame, pd.DataFrame))
+    assert result.height == sample_logs_df.height
+    assert "source" in result.columns
+    assert result["full_signature"].null_count() <= single_db_result["full_signature"].null_count()
+    for col in ["event_keys", "event_values", "event_json"]:
+        assert col in result.columns
+
+def test_decode_tx_bundle(sample_logs_df, sample_traces_df, setup_paths):
+    decoded_logs, decoded_traces = decode_tx_bundle(
+        sample_logs_df,
+        sample_traces_df,
+        events_abi_db_path=setup_paths['events_abi_path'],
+        functions_abi_db_path=setup_paths['functions_abi_path'],
+    )
+    expected_logs = decode_df("log", sample_logs_df, abi_db_path=setup_paths['events_abi_path'])
+    expected_traces = decode_df("trace", sample_traces_df, abi_db_path=setup_paths['functions_abi_path'])
+
+    assert decoded_logs.columns == expected_logs.columns
+    assert decoded_logs.height == expected_logs.height
+    assert "Transfer" in decoded_logs["name"].to_list()
+    assert decoded_traces.columns == expected_traces.columns
+    assert decoded_traces.height == expected_traces.height
+    assert "transfer" in decoded_traces["name"].to_list()
+
 def test_decode_df_with_abi_df(sample_logs_df, sample_traces_df, sample_events_abi_df, sample_functions_abi_df):
     result = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
     assert isinstance(result, (pl.DataFrame, pd.DataFrame))
@@ -170,6 +723,915 @@ def test_decode_df_with_abi_df(sample_logs_df, sample_traces_df, sample_events_a
     for col in ["input_keys", "input_values", "input_json", "output_keys", "output_values", "output_json"]:
         assert col in result.columns
 
+def test_decode_df_with_abi_df_empty_input(sample_logs_df, sample_traces_df, sample_events_abi_df, sample_functions_abi_df):
+    # an empty input returns an empty DataFrame with the decoded columns, instead of failing to combine zero chunks
+    result = decode_df_with_abi_df("log", sample_logs_df.head(0), sample_events_abi_df)
+    assert result.is_empty()
+    assert result.schema["name"] == pl.String
+    for col in ["event_keys", "event_values", "event_json", "decoding_error"]:
+        assert result.schema[col] == pl.String
+    assert result.schema["string_validity"] == pl.Boolean
+
+    result = decode_df_with_abi_df("trace", sample_traces_df.head(0), sample_functions_abi_df)
+    assert result.is_empty()
+    for col in ["input_keys", "input_values", "input_json", "output_keys", "output_values", "output_json"]:
+        assert result.schema[col] == pl.String
+
+def test_decode_df_with_abi_df_sources(sample_logs_df, sample_events_abi_df, setup_paths):
+    # A path or a LazyFrame over the ABI DB file decode the same as the loaded ABI DataFrame
+    expected = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+    from_path = decode_df_with_abi_df("log", sample_logs_df, setup_paths['events_abi_path'])
+    from_lazy = decode_df_with_abi_df("log", sample_logs_df, pl.scan_parquet(setup_paths['events_abi_path']))
+    for result in [from_path, from_lazy]:
+        assert result.height == expected.height
+        assert result["full_signature"].to_list() == expected["full_signature"].to_list()
+
+def test_decode_df_with_abi_df_path_updated_on_disk(sample_logs_df, sample_events_abi_df, tmp_path):
+    # ABI DB files are cached by path, the cache is invalidated when the file changes on disk
+    abi_db_path = str(tmp_path / "abi_db.parquet")
+    transfer_abi_df = sample_events_abi_df.filter(pl.col("name") == "Transfer")
+    transfer_abi_df.write_parquet(abi_db_path)
+    first = decode_df_with_abi_df("log", sample_logs_df, abi_db_path)
+    assert set(first["name"].drop_nulls().to_list()) == {"Transfer"}
+
+    sample_events_abi_df.write_parquet(abi_db_path)
+    second = decode_df_with_abi_df("log", sample_logs_df, abi_db_path)
+    assert second["full_signature"].null_count() < first["full_signature"].null_count()
+
+def test_decode_df_single_topic0(sample_logs_df, sample_events_abi_df):
+    # A single-event extract takes the fast path, the output must be the same as the general path
+    single_signature_hashes = (
+        sample_events_abi_df.group_by("hash")
+        .agg(pl.col("full_signature").n_unique().alias("signatures"))
+        .filter(pl.col("signatures") == 1)["hash"]
+    )
+    topic0 = (
+        sample_logs_df.filter(pl.col("topic0").is_in(single_signature_hashes))["topic0"]
+        .value_counts(sort=True)["topic0"][0]
+    )
+    single_topic0_df = sample_logs_df.filter(pl.col("topic0") == topic0)
+
+    fast = decode_df_with_abi_df("log", single_topic0_df, sample_events_abi_df)
+    general = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df).filter(pl.col("topic0") == topic0)
+
+    sort_columns = ["block_number", "log_index"]
+    assert fast.height == single_topic0_df.height
+    assert fast["full_signature"].null_count() < fast.height
+    assert fast.sort(sort_columns).equals(general.sort(sort_columns))
+
+def test_decode_df_with_num_indexed_args_as_i64(sample_logs_df, sample_events_abi_df):
+    # Old ABI DBs or csv round-trips can store num_indexed_args as i64
+    abi_df_i64 = sample_events_abi_df.with_columns(pl.col("num_indexed_args").cast(pl.Int64))
+    expected = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+    result = decode_df_with_abi_df("log", sample_logs_df, abi_df_i64)
+    assert result.height == sample_logs_df.height
+    assert "Transfer" in result["name"].to_list()
+    assert result["full_signature"].null_count() == expected["full_signature"].null_count()
+
+def test_decode_df_with_legacy_topic0_abi_db(sample_logs_df, sample_events_abi_df, tmp_path):
+    # Legacy ABI DBs named the hash column "topic0"
+    legacy_db_path = str(tmp_path / "legacy_events.parquet")
+    sample_events_abi_df.rename({"hash": "topic0"}).write_parquet(legacy_db_path)
+    expected = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+
+    set_config("abi_reader.hash_column_name", "topic0")
+    try:
+        result = decode_df("log", sample_logs_df, abi_db_path=legacy_db_path)
+        assert result.height == sample_logs_df.height
+        assert "Transfer" in result["name"].to_list()
+        assert result["full_signature"].null_count() == expected["full_signature"].null_count()
+    finally:
+        set_config("abi_reader.hash_column_name", "hash")
+
+def test_decode_df_with_chain_id():
+    address = "0x" + "11" * 20
+    # The same address has different ABIs in each chain
+    mainnet_abi = '[{"type":"event","name":"Deposit","anonymous":false,"inputs":[{"name":"user","type":"address","indexed":true},{"name":"amount","type":"uint256","indexed":false}]}]'
+    optimism_abi = '[{"type":"event","name":"Staked","anonymous":false,"inputs":[{"name":"user","type":"address","indexed":true},{"name":"amount","type":"uint256","indexed":false}]}]'
+    abi_df = pl.concat([
+        read_new_abi_json(mainnet_abi, address, chain_id=1),
+        read_new_abi_json(optimism_abi, address, chain_id=10),
+    ])
+    user_topic = "0x" + "00" * 12 + "aa" * 20
+    logs_df = build_logs_df(
+        addresses=[address, address, address],
+        topics=[[abi_df["hash"][0], user_topic], [abi_df["hash"][1], user_topic], [abi_df["hash"][0], user_topic]],
+        data=[(100).to_bytes(32, "big")] * 3,
+    ).with_columns(pl.Series("chain_id", [1, 10, 10], dtype=pl.Int64))
+
+    set_config("decoder.algorithm", "hash_address")
+    try:
+        result = decode_df_with_abi_df("log", logs_df, abi_df)
+    finally:
+        set_config("decoder.algorithm", "hash")
+    assert result.height == 3
+    assert result["chain_id"].to_list() == [1, 10, 10]
+    # The last log has the mainnet Deposit topic0, but was emitted in optimism
+    assert result["name"].to_list() == ["Deposit", "Staked", None]
+
+def test_decode_df_with_chain_id_unscoped_abi_db():
+    address = "0x" + "11" * 20
+    other_address = "0x" + "22" * 20
+    deposit_abi = '[{"type":"event","name":"Deposit","anonymous":false,"inputs":[{"name":"user","type":"address","indexed":true},{"name":"amount","type":"uint256","indexed":false}]}]'
+    staked_abi = '[{"type":"event","name":"Staked","anonymous":false,"inputs":[{"name":"user","type":"address","indexed":true},{"name":"amount","type":"uint256","indexed":false}]}]'
+    # The ABI DB has a chain_id column, null unless the ABI is scoped to a chain
+    unscoped_abi_df = read_new_abi_json(deposit_abi, address)
+    assert unscoped_abi_df["chain_id"].null_count() == unscoped_abi_df.height
+    abi_df = pl.concat([
+        unscoped_abi_df,
+        read_new_abi_json(staked_abi, other_address, chain_id=10),
+        read_new_abi_json(deposit_abi, other_address),
+    ])
+    user_topic = "0x" + "00" * 12 + "aa" * 20
+    deposit_topic0 = unscoped_abi_df["hash"][0]
+    staked_topic0 = abi_df["hash"][1]
+    logs_df = build_logs_df(
+        addresses=[address, address, other_address, other_address, other_address],
+        topics=[[deposit_topic0, user_topic], [deposit_topic0, user_topic], [staked_topic0, user_topic], [staked_topic0, user_topic], [deposit_topic0, user_topic]],
+        data=[(100).to_bytes(32, "big")] * 5,
+    ).with_columns(pl.Series("chain_id", [1, 10, 10, 1, 1], dtype=pl.Int64))
+
+    set_config("decoder.algorithm", "hash_address")
+    try:
+        result = decode_df_with_abi_df("log", logs_df, abi_df)
+    finally:
+        set_config("decoder.algorithm", "hash")
+    assert result.height == 5
+    assert result["chain_id"].to_list() == [1, 10, 10, 1, 1]
+    # Unscoped items match any chain, while the optimism Staked item only matches optimism logs
+    assert result["name"].to_list() == ["Deposit", "Deposit", "Staked", None, "Deposit"]
+
+def test_decode_df_with_abi_df_without_address_column(sample_logs_df, sample_events_abi_df):
+    # a protocol-level ABI DataFrame has no addresses, so hash_address falls back to matching by topic0 only
+    abi_df = sample_events_abi_df.drop("address")
+    expected = decode_df_with_abi_df("log", sample_logs_df, abi_df)
+
+    set_config("decoder.algorithm", "hash_address")
+    try:
+        result = decode_df_with_abi_df("log", sample_logs_df, abi_df)
+    finally:
+        set_config("decoder.algorithm", "hash")
+    assert result.height == sample_logs_df.height
+    assert result["full_signature"].null_count() == expected["full_signature"].null_count()
+    assert result["full_signature"].null_count() < result.height
+
+def test_decode_df_with_context(sample_logs_df, sample_events_abi_df):
+    context = {"chain_id": "1", "source_file": "logs_2024.parquet"}
+    result = decode_df_with_context("log", sample_logs_df, sample_events_abi_df, context)
+    assert isinstance(result, (pl.DataFrame, pd.DataFrame))
+    assert result.height == sample_logs_df.height
+    assert result["chain_id"].to_list() == ["1"] * result.height
+    assert result["source_file"].to_list() == ["logs_2024.parquet"] * result.height
+    for col in ["event_keys", "event_values", "event_json"]:
+        assert col in result.columns
+
+def test_decode_df_drop_raw_columns(sample_logs_df, sample_traces_df, sample_events_abi_df, sample_functions_abi_df):
+    set_config("decoder.drop_raw_columns", True)
+    try:
+        result = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+        assert result.height == sample_logs_df.height
+        for col in ["topic0", "topic1", "topic2", "topic3", "data"]:
+            assert col not in result.columns
+        for col in ["address", "block_number", "event_keys", "event_values", "event_json"]:
+            assert col in result.columns
+
+        result = decode_df_with_abi_df("trace", sample_traces_df, sample_functions_abi_df)
+        for col in ["action_input", "result_output"]:
+            assert col not in result.columns
+        for col in ["selector", "action_to", "input_values", "output_values"]:
+            assert col in result.columns
+    finally:
+        set_config("decoder.drop_raw_columns", False)
+
+def test_decode_df_signature_lists(sample_logs_df, sample_events_abi_df):
+    decoded = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+    matched = decoded.filter(pl.col("full_signature").is_not_null())
+    # the most frequent decoded event
+    signature = matched["full_signature"].mode()[0]
+    topic0 = "0x" + matched.filter(pl.col("full_signature") == signature)["topic0"][0].hex()
+
+    try:
+        # a denylisted event is never decoded
+        set_config("decoder.signature_denylist", [signature])
+        result = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+        assert result.height == sample_logs_df.height
+        assert signature not in result["full_signature"].to_list()
+
+        # the allowlist, here by topic0, restricts decoding to the listed events
+        set_config("decoder.signature_denylist", [])
+        set_config("decoder.signature_allowlist", [topic0])
+        result = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+        decoded_topics = result.filter(pl.col("full_signature").is_not_null())["topic0"]
+        assert decoded_topics.len() > 0
+        assert all("0x" + t.hex() == topic0 for t in decoded_topics.to_list())
+
+        # the denylist takes precedence
+        set_config("decoder.signature_denylist", [signature])
+        result = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+        assert result["full_signature"].null_count() == result.height
+    finally:
+        set_config("decoder.signature_allowlist", [])
+        set_config("decoder.signature_denylist", [])
+
+def test_decode_df_hex_output_binary_list(sample_logs_df, sample_events_abi_df):
+    logs_df = sample_logs_df.head(3).with_columns(pl.concat_list(["topic0", "topic1"]).alias("topics"))
+    set_config("decoder.output_hex_string_encoding", True)
+    try:
+        result = decode_df_with_abi_df("log", logs_df, sample_events_abi_df)
+    finally:
+        set_config("decoder.output_hex_string_encoding", False)
+
+    assert result.schema["topics"] == pl.List(pl.String)
+    for topics, topic0 in zip(result["topics"].to_list(), result["topic0"].to_list()):
+        assert len(topics) == 2
+        assert topics[0] == topic0
+        assert all(topic is None or topic.startswith("0x") for topic in topics)
+
+def test_decode_traces_with_hex_gas_fields(sample_traces_df, sample_functions_abi_df):
+    hex_traces_df = sample_traces_df.with_columns(
+        pl.col("action_gas").map_elements(hex, return_dtype=pl.String).alias("action_gas"),
+        pl.lit("0x5208").alias("result_gas_used"),
+        pl.lit("0xde0b6b3a7640000").alias("action_value"),
+    )
+
+    # By default the gas and value columns are passed through untouched
+    result = decode_df_with_abi_df("trace", hex_traces_df, sample_functions_abi_df)
+    assert result.height == hex_traces_df.height
+    assert result["action_gas"].dtype == pl.String
+    assert result["result_gas_used"].unique().to_list() == ["0x5208"]
+
+    set_config("trace_decoder.parse_hex_numeric_columns", True)
+    try:
+        result = decode_df_with_abi_df("trace", hex_traces_df, sample_functions_abi_df)
+        assert result.height == hex_traces_df.height
+        assert result["action_gas"].dtype == pl.UInt64
+        assert sorted(result["action_gas"].to_list()) == sorted(sample_traces_df["action_gas"].to_list())
+        assert result["result_gas_used"].unique().to_list() == [21000]
+        assert result["action_value"].unique().to_list() == ["1000000000000000000"]
+    finally:
+        set_config("trace_decoder.parse_hex_numeric_columns", False)
+
+def test_decode_traces_call_depth(sample_traces_df, sample_functions_abi_df):
+    # Top-level calls, first level and second level subcalls
+    trace_addresses = [[], [0], [0, 1]]
+    traces_df = sample_traces_df.with_columns(
+        pl.Series("trace_address", [trace_addresses[i % 3] for i in range(sample_traces_df.height)], dtype=pl.List(pl.UInt32))
+    )
+
+    # Without the trace_address alias, no call_depth column is added
+    result = decode_df_with_abi_df("trace", traces_df, sample_functions_abi_df)
+    assert "call_depth" not in result.columns
+
+    set_config("trace_decoder.trace_schema.trace_alias.trace_address", "trace_address")
+    try:
+        result = decode_df_with_abi_df("trace", traces_df, sample_functions_abi_df)
+        assert result["call_depth"].dtype == pl.UInt32
+        assert result["call_depth"].to_list() == result["trace_address"].list.len().to_list()
+        assert result.filter(pl.col("call_depth") == 0)["trace_address"].to_list() == [[]] * result.filter(pl.col("call_depth") == 0).height
+
+        # Traces without the column have a null call_depth
+        result = decode_df_with_abi_df("trace", sample_traces_df, sample_functions_abi_df)
+        assert result["call_depth"].null_count() == result.height
+    finally:
+        set_config("trace_decoder.trace_schema.trace_alias.trace_address", "")
+
+def test_decode_to_wide():
+    abi = '''[
+        {"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]},
+        {"type":"event","name":"Approval","anonymous":false,"inputs":[{"name":"owner","type":"address","indexed":true},{"name":"spender","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}
+    ]'''
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(abi, address)
+    transfer_topic0 = abi_df.filter(pl.col("name") == "Transfer")["hash"][0]
+    approval_topic0 = abi_df.filter(pl.col("name") == "Approval")["hash"][0]
+    account_a = "0x" + "00" * 12 + "aa" * 20
+    account_b = "0x" + "00" * 12 + "bb" * 20
+
+    logs_df = build_logs_df(
+        addresses=[address, address, address],
+        topics=[[transfer_topic0, account_a, account_b], [approval_topic0, account_b, account_a], [transfer_topic0, account_b, account_a]],
+        data=[(100).to_bytes(32, "big"), (200).to_bytes(32, "big"), (300).to_bytes(32, "big")],
+    )
+    result = decode_to_wide("log", logs_df, abi_df)
+    assert result.height == 3
+    assert result["event_name"].to_list() == ["Transfer", "Approval", "Transfer"]
+    for col in ["Transfer_from", "Transfer_to", "Transfer_value", "Approval_owner", "Approval_spender", "Approval_value"]:
+        assert col in result.columns
+    assert result["Transfer_value"].to_list() == ["100", None, "300"]
+    assert result["Approval_value"].to_list() == [None, "200", None]
+    assert result["Approval_owner"][1].lower() == "0x" + "bb" * 20
+
+def test_decode_and_aggregate():
+    abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(abi, address)
+    topic0 = abi_df["hash"][0]
+    signature = abi_df["full_signature"][0]
+    account_a = "0x" + "00" * 12 + "aa" * 20
+    account_b = "0x" + "00" * 12 + "bb" * 20
+
+    logs_df = build_logs_df(
+        addresses=[address, address, address],
+        topics=[[topic0, account_a, account_b], [topic0, account_a, account_a], [topic0, account_a, account_b]],
+        data=[(100).to_bytes(32, "big"), (200).to_bytes(32, "big"), (300).to_bytes(32, "big")],
+    )
+    result = decode_and_aggregate("log", logs_df, abi_df, signature, ["to"], [("value", "sum"), ("value", "count")])
+    assert result.columns == ["to", "value_sum", "value_count"]
+    totals = {to.lower(): value for to, value in zip(result["to"].to_list(), result["value_sum"].to_list())}
+    assert totals == {"0x" + "bb" * 20: "400", "0x" + "aa" * 20: "200"}
+    assert sorted(result["value_count"].to_list()) == [1, 2]
+
+    # uint256 values are summed exactly
+    big_value = 2**256 - 1
+    big_df = build_logs_df(
+        addresses=[address, address],
+        topics=[[topic0, account_a, account_b], [topic0, account_a, account_b]],
+        data=[big_value.to_bytes(32, "big"), big_value.to_bytes(32, "big")],
+    )
+    result = decode_and_aggregate("log", big_df, abi_df, signature, [], [("value", "sum")])
+    assert result["value_sum"].to_list() == [str(2 * big_value)]
+
+    with pytest.raises(ValueError):
+        decode_and_aggregate("log", logs_df, abi_df, signature, ["to"], [("value", "median")])
+
+def test_decode_wide_params_named_like_columns():
+    # An event with params named like the raw address and data columns
+    abi = '[{"type":"event","name":"Stored","anonymous":false,"inputs":[{"name":"address","type":"address","indexed":true},{"name":"data","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(abi, address)
+    topic0 = abi_df["hash"][0]
+    signature = abi_df["full_signature"][0]
+    account_a = "0x" + "00" * 12 + "aa" * 20
+    account_b = "0x" + "00" * 12 + "bb" * 20
+    logs_df = build_logs_df(
+        addresses=[address, address, address],
+        topics=[[topic0, account_a], [topic0, account_b], [topic0, account_a]],
+        data=[(100).to_bytes(32, "big"), (200).to_bytes(32, "big"), (300).to_bytes(32, "big")],
+    )
+    decoded = decode_df_with_abi_df("log", logs_df, abi_df)
+
+    # The raw columns are kept, and the params get their own columns
+    result = decode_and_aggregate("log", logs_df, abi_df, signature, ["address", "param_address"], [("param_data", "sum")])
+    assert result.columns == ["address", "param_address", "param_data_sum"]
+    assert result["address"].to_list() == [decoded["address"][0]] * 2
+    totals = {account.lower(): value for account, value in zip(result["param_address"].to_list(), result["param_data_sum"].to_list())}
+    assert totals == {"0x" + "aa" * 20: "400", "0x" + "bb" * 20: "200"}
+
+    # In the wide DataFrame, params are prefixed with the event name, so they don't collide
+    result = decode_to_wide("log", logs_df, abi_df)
+    assert result["data"].to_list() == decoded["data"].to_list()
+    assert result["address"].to_list() == decoded["address"].to_list()
+    assert result["Stored_data"].to_list() == ["100", "200", "300"]
+
+def test_decode_df_with_priority():
+    # Two signatures sharing the Transfer topic0, with different param names. The logs' address has the first one
+    default_abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]'
+    other_abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"sender","type":"address","indexed":true},{"name":"receiver","type":"address","indexed":true},{"name":"amount","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = pl.concat([read_new_abi_json(default_abi, address), read_new_abi_json(other_abi, "0x" + "22" * 20)])
+    topic0 = abi_df["hash"][0]
+    assert abi_df["hash"][1] == topic0
+    other_signature = "event Transfer(address indexed sender, address indexed receiver, uint256 amount)"
+    assert other_signature in abi_df["full_signature"].to_list()
+    account = "0x" + "00" * 12 + "aa" * 20
+
+    logs_df = build_logs_df(
+        addresses=[address, address],
+        topics=[[topic0, account, account], [topic0, account, account]],
+        data=[(100).to_bytes(32, "big"), (200).to_bytes(32, "big")],
+    )
+    default = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert default["event_keys"].to_list() == ['["from", "to", "value"]'] * 2
+
+    # The first candidate isn't in the ABI DataFrame, so the second one is used
+    priority = {topic0: ["event Missing(address indexed from, address indexed to, uint256 value)", other_signature]}
+    result = decode_df_with_priority("log", logs_df, abi_df, priority)
+    assert result.height == 2
+    assert result["full_signature"].to_list() == [other_signature] * 2
+    assert result["event_keys"].to_list() == ['["sender", "receiver", "amount"]'] * 2
+    assert result.columns == default.columns
+
+    # Hashes can also be hex strings
+    result = decode_df_with_priority("log", logs_df, abi_df, {"0x" + topic0.hex(): [other_signature]})
+    assert result["full_signature"].to_list() == [other_signature] * 2
+
+    # Rows of other hashes are matched as usual, keeping the row order
+    mixed_df = build_logs_df(
+        addresses=[address] * 3,
+        topics=[[topic0, account, account], ["0x" + "cd" * 32], [topic0, account, account]],
+        data=[(100).to_bytes(32, "big"), b"", (300).to_bytes(32, "big")],
+    )
+    result = decode_df_with_priority("log", mixed_df, abi_df, priority)
+    assert result["full_signature"].to_list() == [other_signature, None, other_signature]
+    assert result["data"].to_list() == mixed_df["data"].to_list()
+
+def test_decode_df_with_overrides():
+    # The logs' address has the default Transfer ABI, but the events were emitted with other param names
+    default_abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(default_abi, address)
+    topic0 = abi_df["hash"][0]
+    other_signature = "event Transfer(address indexed sender, address indexed receiver, uint256 amount)"
+    account = "0x" + "00" * 12 + "aa" * 20
+
+    logs_df = build_logs_df(
+        addresses=[address, address],
+        topics=[[topic0, account, account], [topic0, account, account]],
+        data=[(100).to_bytes(32, "big"), (200).to_bytes(32, "big")],
+    )
+    default = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert default["event_keys"].to_list() == ['["from", "to", "value"]'] * 2
+
+    # The override corrects the matched signature, even if it isn't in the ABI DataFrame
+    result = decode_df_with_overrides("log", logs_df, abi_df, {topic0: other_signature})
+    assert result.height == 2
+    assert result["full_signature"].to_list() == [other_signature] * 2
+    assert result["event_keys"].to_list() == ['["sender", "receiver", "amount"]'] * 2
+    assert result["decoding_error"].is_null().all()
+    assert result.columns == default.columns
+
+    # Hashes can also be hex strings, and invalid signatures are rejected
+    result = decode_df_with_overrides("log", logs_df, abi_df, {"0x" + topic0.hex(): other_signature})
+    assert result["full_signature"].to_list() == [other_signature] * 2
+    with pytest.raises(ValueError):
+        decode_df_with_overrides("log", logs_df, abi_df, {topic0: "event Transfer(address"})
+
+def test_decode_log_from_hex():
+    transfer = "event Transfer(address indexed from, address indexed to, uint256 value)"
+    topic0 = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+    sender = "0x" + "00" * 12 + "11" * 20
+    receiver = "00" * 12 + "22" * 20
+    data = "0x" + (100).to_bytes(32, "big").hex()
+
+    decoded = decode_log_from_hex(topic0, data, transfer, topic1=sender, topic2=receiver)
+    assert decoded["event_keys"] == ["from", "to", "value"]
+    assert decoded["event_values"] == ["0x" + "11" * 20, "0x" + "22" * 20, "100"]
+    assert json.loads(decoded["event_json"])[2]["value"] == "100"
+    assert decoded["string_validity"] == True
+
+    # Same result as decoding a one row DataFrame
+    logs_df = build_logs_df(addresses=["0x" + "33" * 20], topics=[[topic0, sender, receiver]], data=[data])
+    abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]'
+    abi_df = read_new_abi_json(abi, "0x" + "33" * 20)
+    decoded_df = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert decoded_df["event_json"][0] == decoded["event_json"]
+
+    with pytest.raises(ValueError):
+        decode_log_from_hex(topic0, data, transfer, topic1="0x1234")
+    with pytest.raises(ValueError):
+        decode_log_from_hex(topic0, "0xzz", transfer)
+
+def test_decode_tuple_arrays_json():
+    # OrdersPlaced(Order[] orders), with Order { address maker; Leg[] legs } and Leg { address token; uint256 amount }
+    signature = "event OrdersPlaced(tuple(address maker, tuple(address token, uint256 amount)[] legs)[] orders)"
+    # keccak256("OrdersPlaced((address,(address,uint256)[])[])")
+    topic0 = "0xa717626fa32d651245ee4142ffaf02e01f1d790f47dc4b79a9b8f35196426bfe"
+    word = lambda n: n.to_bytes(32, "big").hex()
+    maker = "00" * 12 + "11" * 20
+    token = "00" * 12 + "22" * 20
+    # One order with two legs: orders offset, length, order offset, maker, legs offset, legs length, legs
+    data = "0x" + word(0x20) + word(1) + word(0x20) + maker + word(0x40) + word(2) + token + word(5) + token + word(7)
+
+    decoded = decode_log_from_hex(topic0, data, signature)
+    event_json = json.loads(decoded["event_json"])
+    assert event_json[0]["value_type"] == "tuple[]"
+    assert event_json[0]["value"] == [{
+        "maker": "0x" + "11" * 20,
+        "legs": [{"token": "0x" + "22" * 20, "amount": "5"}, {"token": "0x" + "22" * 20, "amount": "7"}],
+    }]
+    # The values column keeps the flat string
+    assert decoded["event_values"][0].startswith("[(0x" + "11" * 20)
+
+def test_decode_unnamed_params():
+    # 4byte-sourced signature, without param names
+    transfer = "event Transfer(address indexed, address indexed, uint256)"
+    topic0 = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+    sender = "0x" + "00" * 12 + "11" * 20
+    receiver = "0x" + "00" * 12 + "22" * 20
+    data = "0x" + (100).to_bytes(32, "big").hex()
+
+    decoded = decode_log_from_hex(topic0, data, transfer, topic1=sender, topic2=receiver)
+    assert decoded["event_keys"] == ["arg0", "arg1", "arg2"]
+    assert [param["name"] for param in json.loads(decoded["event_json"])] == ["arg0", "arg1", "arg2"]
+
+    set_config("decoder.positional_names_when_unnamed", False)
+    try:
+        decoded = decode_log_from_hex(topic0, data, transfer, topic1=sender, topic2=receiver)
+        assert decoded["event_keys"] == ["", "", ""]
+    finally:
+        set_config("decoder.positional_names_when_unnamed", True)
+
+def test_decode_unnamed_params_interleaved():
+    # Indexed params are decoded first, but the positional names follow the signature order
+    swap = "event Swap(uint256, address indexed, uint256, address indexed)"
+    topic0 = "0x" + "ab" * 32
+    sender = "0x" + "00" * 12 + "11" * 20
+    receiver = "0x" + "00" * 12 + "22" * 20
+    data = "0x" + (1).to_bytes(32, "big").hex() + (2).to_bytes(32, "big").hex()
+
+    decoded = decode_log_from_hex(topic0, data, swap, topic1=sender, topic2=receiver)
+    assert decoded["event_keys"] == ["arg1", "arg3", "arg0", "arg2"]
+    assert decoded["event_values"][2:] == ["1", "2"]
+    assert [param["name"] for param in json.loads(decoded["event_json"])] == ["arg1", "arg3", "arg0", "arg2"]
+
+def test_decode_max_value_length(sample_logs_df, sample_events_abi_df):
+    # Event with a 1 MiB bytes blob in the data
+    signature = "event BlobPosted(uint256 indexed id, bytes blob)"
+    topic0 = "0x" + "ab" * 32
+    topic1 = "0x" + (1).to_bytes(32, "big").hex()
+    blob = b"\x01" * 1048576
+    data = "0x" + (32).to_bytes(32, "big").hex() + len(blob).to_bytes(32, "big").hex() + blob.hex()
+
+    # Unlimited by default
+    decoded = decode_log_from_hex(topic0, data, signature, topic1=topic1)
+    assert len(decoded["event_values"][1]) == 2 + 2 * len(blob)
+    assert decoded["truncated_values"] == False
+    assert "truncated_values" not in decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df).columns
+
+    set_config("decoder.max_value_length", 1000)
+    try:
+        decoded = decode_log_from_hex(topic0, data, signature, topic1=topic1)
+        assert decoded["event_values"][0] == "1"
+        assert decoded["event_values"][1] == "0x" + "01" * 499 + "\u2026(truncated, 2097154 bytes)"
+        assert json.loads(decoded["event_json"])[1]["value"] == decoded["event_values"][1]
+        assert decoded["truncated_values"] == True
+
+        result = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+        assert result["truncated_values"].dtype == pl.Boolean
+        assert result.filter(pl.col("decoding_error").is_null() & pl.col("full_signature").is_not_null())["truncated_values"].null_count() == 0
+    finally:
+        set_config("decoder.max_value_length", 0)
+
+def test_decode_bytes_encoding():
+    signature = "event Reverted(uint256 indexed id, bytes reason, bytes raw)"
+    topic0 = "0x" + "ab" * 32
+    topic1 = "0x" + (1).to_bytes(32, "big").hex()
+    reason, raw = "Ownable: caller is not the owner".encode(), bytes([0xff, 0xfe, 0x00])
+    padded = lambda b: len(b).to_bytes(32, "big").hex() + b.hex().ljust(64 * ((len(b) + 31) // 32), "0")
+    data = "0x" + (64).to_bytes(32, "big").hex() + (128).to_bytes(32, "big").hex() + padded(reason) + padded(raw)
+
+    # Hex by default
+    decoded = decode_log_from_hex(topic0, data, signature, topic1=topic1)
+    assert decoded["event_values"][1:] == ["0x" + reason.hex(), "0xfffe00"]
+
+    try:
+        set_config("decoder.bytes_encoding", "base64")
+        decoded = decode_log_from_hex(topic0, data, signature, topic1=topic1)
+        assert decoded["event_values"][1:] == [base64.b64encode(reason).decode(), base64.b64encode(raw).decode()]
+        assert json.loads(decoded["event_json"])[1]["value"] == base64.b64encode(reason).decode()
+
+        # Bytes that aren't valid UTF-8 are kept as hex with the "hex:" prefix, instead of replacement characters
+        set_config("decoder.bytes_encoding", "utf8_lossy")
+        decoded = decode_log_from_hex(topic0, data, signature, topic1=topic1)
+        assert decoded["event_values"][1:] == ["Ownable: caller is not the owner", "hex:0xfffe00"]
+        assert json.loads(decoded["event_json"])[2]["value"] == "hex:0xfffe00"
+    finally:
+        set_config("decoder.bytes_encoding", "hex")
+
+def test_decode_negative_int256():
+    signature = "event PositionChanged(int256 indexed delta, int256 amount, int8 small)"
+    topic0 = "0x" + "ab" * 32
+    int256_min = -2**255
+    large_negative = -123456789012345678901234567890
+    topic1 = "0x" + int256_min.to_bytes(32, "big", signed=True).hex()
+    data = "0x" + (-1).to_bytes(32, "big", signed=True).hex() * 2
+
+    # Signed values, not their unsigned two's complement (2**255, 2**256 - 1, 255)
+    decoded = decode_log_from_hex(topic0, data, signature, topic1=topic1)
+    assert decoded["event_values"] == [str(int256_min), "-1", "-1"]
+
+    data = "0x" + large_negative.to_bytes(32, "big", signed=True).hex() + (-128).to_bytes(32, "big", signed=True).hex()
+    decoded = decode_log_from_hex(topic0, data, signature, topic1=topic1)
+    assert decoded["event_values"][1:] == [str(large_negative), "-128"]
+    assert [param["value"] for param in json.loads(decoded["event_json"])] == [str(int256_min), str(large_negative), "-128"]
+
+def test_distinct_addresses(tmp_path):
+    address_a = "0x" + "11" * 20
+    address_b = "0x" + "22" * 20
+    topic0 = "0x" + "ab" * 32
+    logs_df = build_logs_df(
+        addresses=[address_a, address_b, address_a, address_a],
+        topics=[[topic0]] * 4,
+        data=[b""] * 4,
+    )
+    logs_file = tmp_path / "logs.parquet"
+    logs_df.write_parquet(logs_file)
+
+    result = distinct_addresses(str(logs_file))
+    assert result.columns == ["address", "count"]
+    assert result["address"].to_list() == [address_a, address_b]
+    assert result["count"].to_list() == [3, 1]
+
+def test_benchmark_decode(sample_logs_df, sample_events_abi_df, setup_paths):
+    rows_per_sec = benchmark_decode("log", sample_logs_df, sample_events_abi_df)
+    assert isinstance(rows_per_sec, float)
+    assert rows_per_sec > 0
+    assert benchmark_decode("log", sample_logs_df.head(10), setup_paths['events_abi_path']) > 0
+
+    with pytest.raises(ValueError, match="empty"):
+        benchmark_decode("log", sample_logs_df.clear(), sample_events_abi_df)
+
+def test_can_decode(sample_logs_df, sample_traces_df, sample_events_abi_df, sample_functions_abi_df):
+    # The dry decode counts must be the same as the full decode ones
+    for decoder_type, df, abi_df in [
+        ("log", sample_logs_df, sample_events_abi_df),
+        ("trace", sample_traces_df, sample_functions_abi_df),
+    ]:
+        stats = can_decode(decoder_type, df, abi_df)
+        decoded = decode_df_with_abi_df(decoder_type, df, abi_df)
+        assert stats["total"] == decoded.height
+        assert stats["matched"] == decoded["full_signature"].is_not_null().sum()
+        assert stats["failed"] == decoded["decoding_error"].is_not_null().sum()
+        assert stats["decoded"] == decoded.filter(
+            pl.col("full_signature").is_not_null() & pl.col("decoding_error").is_null()
+        ).height
+        assert stats["decoded"] > 0
+
+def test_label_selectors(sample_traces_df, setup_paths):
+    selectors_df = sample_traces_df.select("selector")
+    result = label_selectors(selectors_df, abi_db_path=setup_paths['functions_abi_path'])
+    assert result.height == selectors_df.height
+    for col in ["selector", "name", "full_signature", "candidate_count"]:
+        assert col in result.columns
+    assert "transfer" in result["name"].to_list()
+
+    # Hex string selectors, with an unknown selector
+    hex_df = pl.DataFrame({"selector": ["0xa9059cbb", "0xffffffff"]})
+    result = label_selectors(hex_df, abi_db_path=setup_paths['functions_abi_path'])
+    assert result["name"].to_list() == ["transfer", None]
+    assert result["candidate_count"][0] >= 1
+    assert result["candidate_count"][1] == 0
+
+def test_assert_event_count(sample_logs_df, setup_paths):
+    decoded = decode_df("log", sample_logs_df, abi_db_path=setup_paths['events_abi_path'])
+    signature = decoded["full_signature"].drop_nulls().mode()[0]
+    count = decoded.filter(pl.col("full_signature") == signature).height
+
+    # Match
+    assert_event_count(decoded, signature, count)
+
+    # Mismatch
+    with pytest.raises(ValueError, match="expected"):
+        assert_event_count(decoded, signature, count + 1)
+
+    # Unknown signature counts 0 rows
+    assert_event_count(decoded, "event Unknown()", 0)
+
+def test_summarize_decoded(sample_logs_df, setup_paths):
+    decoded = decode_df("log", sample_logs_df, abi_db_path=setup_paths['events_abi_path'])
+    summary = summarize_decoded(decoded)
+    for col in ["address", "name", "match_status", "count", "first_block", "last_block"]:
+        assert col in summary.columns
+    assert summary["count"].sum() == decoded.height
+    assert set(summary["match_status"].unique().to_list()) <= {"decoded", "decoding_error", "unmatched"}
+    assert (summary["first_block"] <= summary["last_block"]).all()
+
+    # Without a block column, first_block and last_block are not added
+    summary = summarize_decoded(decoded.drop("block_number"))
+    assert "first_block" not in summary.columns
+
+def test_compatibility_report(sample_logs_df, setup_paths):
+    report = compatibility_report("log", sample_logs_df, abi_db_path=setup_paths['events_abi_path'])
+    assert report["category"].to_list() == ["matched", "indexed_arg_mismatch", "missing"]
+    assert report["row_count"].sum() == sample_logs_df.height
+    matched = report.filter(pl.col("category") == "matched")
+    decoded = decode_df("log", sample_logs_df, abi_db_path=setup_paths['events_abi_path'])
+    assert matched["row_count"][0] == decoded["full_signature"].is_not_null().sum()
+    assert len(matched["example_signatures"][0]) > 0
+
+def test_compatibility_report_indexed_arg_mismatch(tmp_path):
+    erc20_abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(erc20_abi, address)
+    abi_db_path = str(tmp_path / "erc20_abis.parquet")
+    abi_df.write_parquet(abi_db_path)
+    topic0 = abi_df["hash"][0]
+    account_a = "0x" + "00" * 12 + "aa" * 20
+    account_b = "0x" + "00" * 12 + "bb" * 20
+
+    # an ERC-20 Transfer, two ERC-721 Transfers (tokenId indexed) and an unknown event
+    logs_df = build_logs_df(
+        addresses=[address] * 4,
+        topics=[
+            [topic0, account_a, account_b],
+            [topic0, account_a, account_b, "0x" + "00" * 31 + "01"],
+            [topic0, account_b, account_a, "0x" + "00" * 31 + "02"],
+            ["0x" + "ff" * 32],
+        ],
+        data=[(100).to_bytes(32, "big"), b"", b"", b""],
+    )
+
+    report = compatibility_report("log", logs_df, abi_db_path=abi_db_path)
+    assert report["category"].to_list() == ["matched", "indexed_arg_mismatch", "missing"]
+    assert report["hash_count"].to_list() == [1, 1, 1]
+    assert report["row_count"].to_list() == [1, 2, 1]
+    assert report["example_signatures"][1].to_list() == [abi_df["full_signature"][0]]
+    assert report["example_signatures"][2] is None
+
+def test_decode_abi_id_column(sample_logs_df, sample_events_abi_df):
+    result = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+    matched = result.filter(pl.col("full_signature").is_not_null())
+    assert matched["id"].null_count() == 0
+    assert set(matched["id"].to_list()) <= set(sample_events_abi_df["id"].to_list())
+
+    # raw data with its own id column keeps it, and the ABI item id is renamed
+    logs_df = sample_logs_df.with_columns(pl.lit("raw").alias("id"))
+    set_config("decoder.abi_id_column", "abi_id")
+    try:
+        result = decode_df_with_abi_df("log", logs_df, sample_events_abi_df)
+        assert result["id"].to_list() == ["raw"] * result.height
+        assert sorted(result.filter(pl.col("full_signature").is_not_null())["abi_id"].to_list()) == sorted(matched["id"].to_list())
+
+        # An id column name colliding with an ABI column is a config error
+        set_config("decoder.abi_id_column", "full_signature")
+        with pytest.raises(ValueError, match="abi_id_column = 'full_signature' collides"):
+            decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+        set_config("decoder.abi_id_column", "abi_id")
+        with pytest.raises(ValueError, match="abi_id_column = 'abi_id' collides"):
+            decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df.with_columns(pl.lit("other").alias("abi_id")))
+    finally:
+        set_config("decoder.abi_id_column", "id")
+
+def test_decode_include_num_indexed_args(sample_logs_df, sample_events_abi_df):
+    result = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+    assert "num_indexed_args" not in result.columns
+
+    set_config("log_decoder.include_num_indexed_args", True)
+    try:
+        result = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+        assert result.height == sample_logs_df.height
+        assert "num_indexed_args" in result.columns
+        expected = sample_logs_df.select(
+            (1 + pl.col("topic1").is_not_null().cast(pl.UInt32) + pl.col("topic2").is_not_null().cast(pl.UInt32) + pl.col("topic3").is_not_null().cast(pl.UInt32)).alias("num_indexed_args")
+        )
+        assert sorted(result["num_indexed_args"].to_list()) == sorted(expected["num_indexed_args"].to_list())
+    finally:
+        set_config("log_decoder.include_num_indexed_args", False)
+
+def test_decode_adjust_indexed_args():
+    erc20_abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(erc20_abi, address)
+    topic0 = abi_df["hash"][0]
+    # a nonstandard Transfer, with the value indexed in topic3 and no data
+    logs_df = build_logs_df(
+        addresses=[address],
+        topics=[[topic0, "0x" + "00" * 12 + "aa" * 20, "0x" + "00" * 12 + "bb" * 20, (100).to_bytes(32, "big")]],
+        data=[b""],
+    )
+
+    result = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert result["full_signature"][0] is None
+    assert "matched_by" not in result.columns
+
+    set_config("log_decoder.adjust_indexed_args", True)
+    try:
+        result = decode_df_with_abi_df("log", logs_df, abi_df)
+        assert result["matched_by"].to_list() == ["indexed_args_adjusted"]
+        assert result["full_signature"][0] == "event Transfer(address indexed from, address indexed to, uint256 indexed value)"
+        assert result["event_keys"][0] == '["from", "to", "value"]'
+        assert '"100"' in result["event_values"][0]
+
+        # rows matched by each step keep their original order
+        mixed_df = build_logs_df(
+            addresses=[address] * 3,
+            topics=[
+                [topic0, "0x" + "00" * 12 + "aa" * 20, "0x" + "00" * 12 + "bb" * 20, (100).to_bytes(32, "big")],
+                ["0x" + "cd" * 32],
+                [topic0, "0x" + "00" * 12 + "aa" * 20, "0x" + "00" * 12 + "bb" * 20],
+            ],
+            data=[b"", b"", (200).to_bytes(32, "big")],
+            block_numbers=[1, 2, 3],
+        )
+        result = decode_df_with_abi_df("log", mixed_df, abi_df)
+        assert result["block_number"].to_list() == [1, 2, 3]
+        assert result["matched_by"].to_list() == ["indexed_args_adjusted", None, "exact"]
+    finally:
+        set_config("log_decoder.adjust_indexed_args", False)
+
+def test_decode_missing_topics_mode():
+    abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"tokenId","type":"uint256","indexed":true}]}]'
+    address = "0x" + "11" * 20
+    # Simulate a mispaired ABI item: a 3 indexed params signature matched with a log without topic3
+    abi_df = read_new_abi_json(abi, address).with_columns(pl.lit(3, dtype=pl.UInt32).alias("num_indexed_args"))
+    topic0 = abi_df["hash"][0]
+    logs_df = build_logs_df(
+        addresses=[address],
+        topics=[[topic0, "0x" + "00" * 12 + "aa" * 20, "0x" + "00" * 12 + "bb" * 20]],
+        data=[(100).to_bytes(32, "big")],
+    )
+
+    # compatible mode zero-fills topic3, producing a bogus tokenId
+    result = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert result["event_values"][0] is not None
+    assert '"0"]' in result["event_values"][0]
+
+    set_config("log_decoder.missing_topics_mode", "strict")
+    try:
+        result = decode_df_with_abi_df("log", logs_df, abi_df)
+        assert result["full_signature"][0] is not None
+        assert result["event_values"][0] is None
+    finally:
+        set_config("log_decoder.missing_topics_mode", "compatible")
+
+def test_decode_topics_list_column():
+    weth_abi = '[{"type":"event","name":"Deposit","anonymous":false,"inputs":[{"name":"dst","type":"address","indexed":true},{"name":"wad","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(weth_abi, address)
+    topic0 = abi_df["hash"][0]
+    logs_df = build_logs_df(
+        addresses=[address],
+        topics=[[topic0, "0x" + "00" * 12 + "aa" * 20]],
+        data=[(100).to_bytes(32, "big")],
+    )
+    expected = decode_df_with_abi_df("log", logs_df, abi_df)
+
+    # a 3 elements list: topic0, topic1 and the data as the last element
+    list_logs_df = logs_df.with_columns(
+        pl.concat_list([pl.col("topic0"), pl.col("topic1"), pl.col("data")]).alias("topics")
+    ).drop(["topic0", "topic1", "topic2", "topic3", "data"])
+    set_config("log_decoder.topics_list_column", "topics")
+    set_config("log_decoder.topics_list_last_is_data", True)
+    try:
+        result = decode_df_with_abi_df("log", list_logs_df, abi_df)
+        assert "topics" not in result.columns
+        assert result["full_signature"][0] == "event Deposit(address indexed dst, uint256 wad)"
+        assert result["topic2"][0] is None
+        assert result["event_values"].to_list() == expected["event_values"].to_list()
+        assert result["data"].to_list() == expected["data"].to_list()
+    finally:
+        set_config("log_decoder.topics_list_column", "")
+        set_config("log_decoder.topics_list_last_is_data", False)
+
+def test_decode_indexed_only():
+    erc20_abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(erc20_abi, address)
+    topic0 = abi_df["hash"][0]
+    sender = "0x" + "00" * 12 + "aa" * 20
+    receiver = "0x" + "00" * 12 + "bb" * 20
+    # the second log has a truncated data, that only fails to decode if the data is decoded
+    logs_df = build_logs_df(
+        addresses=[address, address],
+        topics=[[topic0, sender, receiver], [topic0, sender, receiver]],
+        data=[(100).to_bytes(32, "big"), b"\x01"],
+    )
+
+    set_config("log_decoder.indexed_only", True)
+    try:
+        result = decode_df_with_abi_df("log", logs_df, abi_df)
+        assert result["event_keys"].to_list() == ['["from", "to"]', '["from", "to"]']
+        assert result["event_values"][0] == '["0x' + "aa" * 20 + '", "0x' + "bb" * 20 + '"]'
+        assert [param["name"] for param in json.loads(result["event_json"][0])] == ["from", "to"]
+        assert result["decoding_error"].null_count() == 2
+
+        decoded = decode_log_from_hex("0x" + topic0.hex(), "0x", abi_df["full_signature"][0], topic1=sender, topic2=receiver)
+        assert decoded["event_keys"] == ["from", "to"]
+    finally:
+        set_config("log_decoder.indexed_only", False)
+
+    result = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert result["event_keys"][0] == '["from", "to", "value"]'
+    assert result["event_values"][1] is None
+
+def test_decode_indexed_tuple():
+    abi = '[{"type":"event","name":"OrderFilled","anonymous":false,"inputs":[{"name":"order","type":"tuple","indexed":true,"components":[{"name":"maker","type":"address"},{"name":"amount","type":"uint256"}]},{"name":"amount","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(abi, address)
+    order_hash = "0x" + "ab" * 32
+    logs_df = build_logs_df(
+        addresses=[address],
+        topics=[[abi_df["hash"][0], order_hash]],
+        data=[(100).to_bytes(32, "big")],
+    )
+
+    result = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert result["name"][0] == "OrderFilled"
+    # The indexed tuple keeps the 32 bytes hash stored in the topic, and the non-indexed params decode normally
+    event_json = pl.Series([result["event_json"][0]]).str.json_decode()[0]
+    assert event_json[0]["name"] == "order"
+    assert event_json[0]["value_type"] == "indexed (hashed tuple)"
+    assert event_json[0]["value"] == order_hash
+    assert event_json[1]["name"] == "amount"
+    assert event_json[1]["value"] == "100"
+
+def test_decode_malformed_topic(sample_logs_df, sample_events_abi_df):
+    expected = decode_df_with_abi_df("log", sample_logs_df, sample_events_abi_df)
+    assert expected["decoding_error"].null_count() == expected.height
+    # Corrupt the topic1 of a decoded log, truncating it to 20 bytes
+    bad_row = expected.with_row_index().filter(pl.col("event_values").is_not_null() & pl.col("topic1").is_not_null())["index"][0]
+    topic1 = sample_logs_df["topic1"].to_list()
+    topic1[bad_row] = topic1[bad_row][12:]
+    malformed_logs_df = sample_logs_df.with_columns(pl.Series("topic1", topic1, dtype=pl.Binary))
+
+    result = decode_df_with_abi_df("log", malformed_logs_df, sample_events_abi_df)
+    assert result.height == sample_logs_df.height
+    assert result["event_values"][bad_row] is None
+    assert "topic1 has 20 bytes" in result["decoding_error"][bad_row]
+    # The rest of the logs decode fine
+    assert result["decoding_error"].null_count() == result.height - 1
+    assert result["event_values"].null_count() == expected["event_values"].null_count() + 1
+
 def test_decode_df_using_single_contract(sample_logs_df, sample_traces_df):
     result = decode_df_using_single_contract(
         "log",
@@ -191,6 +1653,187 @@ def test_decode_df_using_single_contract(sample_logs_df, sample_traces_df):
     for col in ["input_keys", "input_values", "input_json", "output_keys", "output_values", "output_json"]:
         assert col in result.columns
 
+def test_decode_df_using_single_contract_invalid_address(sample_logs_df):
+    # Malformed and short addresses fail before downloading the ABI, reporting the address
+    for address in ["0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa6z", "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa", "not an address"]:
+        with pytest.raises(ValueError, match="Invalid address") as error:
+            decode_df_using_single_contract("log", sample_logs_df, address)
+        assert address in str(error.value)
+
+    # Checksummed, lowercase, uppercase and unprefixed addresses are all accepted
+    expected = decode_df_using_single_contract("log", sample_logs_df, "0xE672E0E0101A7F58d728751E2a5e6Da5Ff1FDa64")
+    for address in ["0xe672e0e0101a7f58d728751e2a5e6da5ff1fda64", "E672E0E0101A7F58D728751E2A5E6DA5FF1FDA64"]:
+        result = decode_df_using_single_contract("log", sample_logs_df, address)
+        assert result.height == expected.height
+
 def test_invalid_decoder_type(sample_logs_df):
     with pytest.raises(ValueError, match="Decoder type must be one of"):
-        decode_df("invalid_type", sample_logs_df)
\ No newline at end of file
+        decode_df("invalid_type", sample_logs_df)
+
+def test_build_logs_df(sample_logs_df, sample_events_abi_df):
+    sample = sample_logs_df.head(10)
+    topics = [
+        [row["topic0"], row["topic1"], row["topic2"], row["topic3"]]
+        for row in sample.iter_rows(named=True)
+    ]
+    # Mix bytes and hex strings inputs
+    addresses = ["0x" + address.hex() for address in sample["address"].to_list()]
+    result = build_logs_df(
+        addresses=addresses,
+        topics=topics,
+        data=sample["data"].to_list(),
+        block_numbers=sample["block_number"].to_list(),
+    )
+    assert isinstance(result, (pl.DataFrame, pd.DataFrame))
+    assert result.height == sample.height
+    for col in ["address", "topic0", "topic1", "topic2", "topic3", "data", "block_number"]:
+        assert col in result.columns
+    assert result["address"].to_list() == sample["address"].to_list()
+    assert result["topic0"].to_list() == sample["topic0"].to_list()
+
+    decoded = decode_df_with_abi_df("log", result, sample_events_abi_df)
+    assert decoded.height == sample.height
+
+def test_build_logs_df_invalid_field():
+    topic0 = bytes.fromhex("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")
+    with pytest.raises(ValueError, match="'address' at row 1"):
+        build_logs_df(
+            addresses=[b"\x11" * 20, b"\x22" * 19],
+            topics=[[topic0], [topic0]],
+            data=[b"", b""],
+        )
+    with pytest.raises(ValueError, match="'topic0' at row 0"):
+        build_logs_df(
+            addresses=[b"\x11" * 20],
+            topics=[["0x1234"]],
+            data=[b""],
+        )
+
+def test_decode_string_validity():
+    abi = '[{"type":"event","name":"Note","anonymous":false,"inputs":[{"name":"text","type":"string","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(abi, address)
+    topic0 = abi_df["hash"][0]
+
+    def encode_string(raw: bytes) -> bytes:
+        # ABI encoding of a single dynamic string: offset, length and right padded content
+        offset = (32).to_bytes(32, "big")
+        length = len(raw).to_bytes(32, "big")
+        return offset + length + raw.ljust(32, b"\x00")
+
+    logs_df = build_logs_df(
+        addresses=[address, address, address],
+        topics=[[topic0], [topic0], [topic0]],
+        # 0xff 0xfe is not valid UTF-8, while the replacement character itself is valid UTF-8
+        data=[encode_string(b"\xff\xfe"), encode_string(b"hi"), encode_string("\ufffd".encode())],
+    )
+    result = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert "string_validity" in result.columns
+    assert result["string_validity"].to_list() == [False, True, True]
+    assert "\ufffd" in result["event_values"][0]
+
+def test_decode_string_with_semicolon():
+    abi = '[{"type":"event","name":"Note","anonymous":false,"inputs":[{"name":"text","type":"string","indexed":false},{"name":"value","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(abi, address)
+    topic0 = abi_df["hash"][0]
+
+    # ABI encoding of (string, uint256): string offset, value, string length and right padded content
+    raw = b"hello;world"
+    data = (64).to_bytes(32, "big") + (7).to_bytes(32, "big") + len(raw).to_bytes(32, "big") + raw.ljust(32, b"\x00")
+    logs_df = build_logs_df(addresses=[address], topics=[[topic0]], data=[data])
+
+    result = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert result["event_values"][0] == '["hello;world", "7"]'
+    assert result["event_keys"][0] == '["text", "value"]'
+    assert '"value":"hello;world"' in result["event_json"][0]
+    assert result["string_validity"][0] is True
+
+def test_decode_abi_passthrough_columns():
+    erc20_abi = '[{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(erc20_abi, address).with_columns(
+        pl.lit("erc20").alias("protocol"),
+        pl.lit("token").alias("category"),
+    )
+    topic0 = abi_df["hash"][0]
+    logs_df = build_logs_df(
+        addresses=[address, "0x" + "22" * 20],
+        topics=[[topic0, "0x" + "00" * 12 + "aa" * 20, "0x" + "00" * 12 + "bb" * 20]] * 2,
+        data=[(100).to_bytes(32, "big")] * 2,
+    )
+
+    # custom ABI DB columns are dropped by default
+    result = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert "protocol" not in result.columns
+    assert "category" not in result.columns
+
+    # the second log is matched by the hash only, in the second matching step
+    set_config("decoder.abi_passthrough_columns", ["protocol", "category", "missing_column"])
+    try:
+        result = decode_df_with_abi_df("log", logs_df, abi_df)
+        assert result["protocol"].to_list() == ["erc20", "erc20"]
+        assert result["category"].to_list() == ["token", "token"]
+        assert "missing_column" not in result.columns
+
+        result = decode_df_with_abi_df("log", logs_df, abi_df.lazy())
+        assert result["protocol"].to_list() == ["erc20", "erc20"]
+    finally:
+        set_config("decoder.abi_passthrough_columns", [])
+
+def test_decode_all_candidates():
+    # Both events have the same topic0 and number of topics, but index a different param
+    text_indexed_abi = '[{"type":"event","name":"Message","anonymous":false,"inputs":[{"name":"text","type":"string","indexed":true},{"name":"id","type":"uint256","indexed":false}]}]'
+    id_indexed_abi = '[{"type":"event","name":"Message","anonymous":false,"inputs":[{"name":"text","type":"string","indexed":false},{"name":"id","type":"uint256","indexed":true}]}]'
+    # the id indexed event is the most frequent, so it's the matcher's choice for other addresses
+    abi_df = pl.concat([
+        read_new_abi_json(id_indexed_abi, "0x" + "11" * 20),
+        read_new_abi_json(id_indexed_abi, "0x" + "22" * 20),
+        read_new_abi_json(text_indexed_abi, "0x" + "33" * 20),
+    ])
+    assert abi_df["hash"].n_unique() == 1
+    logs_df = build_logs_df(
+        addresses=["0x" + "44" * 20],
+        topics=[[abi_df["hash"][0], "0x" + "ab" * 32]],
+        data=[(5).to_bytes(32, "big")],
+    )
+
+    result = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert result["full_signature"][0] == "event Message(string text, uint256 indexed id)"
+    assert result["decoding_error"][0] is not None
+
+    set_config("decoder.decode_all_candidates", True)
+    try:
+        result = decode_df_with_abi_df("log", logs_df, abi_df)
+    finally:
+        set_config("decoder.decode_all_candidates", False)
+    assert result.height == 1
+    assert result["full_signature"][0] == "event Message(string indexed text, uint256 id)"
+    assert result["decoding_error"][0] is None
+    assert result["event_keys"][0] == '["text", "id"]'
+
+def test_decode_logs_without_topic2_topic3(tmp_path):
+    # minimal exporters only emit the topics their events use
+    deposit_abi = '[{"type":"event","name":"Deposit","anonymous":false,"inputs":[{"name":"user","type":"address","indexed":true},{"name":"amount","type":"uint256","indexed":false}]}]'
+    address = "0x" + "11" * 20
+    abi_df = read_new_abi_json(deposit_abi, address)
+    logs_df = pl.DataFrame({
+        "address": [bytes.fromhex("11" * 20)] * 2,
+        "topic0": [abi_df["hash"][0]] * 2,
+        "topic1": [bytes.fromhex("00" * 12 + "aa" * 20), bytes.fromhex("00" * 12 + "bb" * 20)],
+        "data": [(7).to_bytes(32, "big"), (8).to_bytes(32, "big")],
+    })
+
+    result = decode_df_with_abi_df("log", logs_df, abi_df)
+    assert result["decoding_error"].null_count() == 2
+    assert result["event_keys"].to_list() == ['["user", "amount"]'] * 2
+    assert json.loads(result["event_values"][1])[1] == "8"
+
+    abi_db_path = str(tmp_path / "deposit_abis.parquet")
+    abi_df.write_parquet(abi_db_path)
+    logs_file = tmp_path / "logs" / "deposit_logs.parquet"
+    logs_file.parent.mkdir()
+    logs_df.write_parquet(logs_file)
+    decoded = decode_file(decoder_type="log", file_path=str(logs_file), abi_db_path=abi_db_path).df
+    assert decoded["decoding_error"].null_count() == 2
+    assert decoded["name"].to_list() == ["Deposit", "Deposit"]
@@ -89,6 +89,30 @@ def test_unnest_event_with_address(sample_decoded_df):
     )
     assert not result.is_empty()
 
+def test_unnest_event_keeps_emitter_address(sample_decoded_df):
+    # The emitter address column and the decoded from/to params are distinct columns
+    result = unnest_event(sample_decoded_df, event_name="Transfer")
+    assert result["address"].to_list() == sample_decoded_df["address"].to_list()
+    for row in result.iter_rows(named=True):
+        assert len({row["address"], row["from"], row["to"]}) == 3
+
+def test_unnest_event_param_named_like_a_column(sample_decoded_df):
+    # A param named address doesn't replace the emitter address column
+    signature = "event Registered(address indexed address, bytes data)"
+    registered = "0x" + "11" * 20
+    df = sample_decoded_df.head(1).with_columns(
+        pl.lit(signature).alias("full_signature"),
+        pl.lit("Registered").alias("name"),
+        pl.lit(f'["{registered}", "0x01"]').alias("event_values"),
+        pl.lit('["address", "data"]').alias("event_keys"),
+        pl.lit(f'[{{"name":"address","index":0,"value_type":"address","value":"{registered}"}},{{"name":"data","index":1,"value_type":"bytes","value":"0x01"}}]').alias("event_json"),
+    )
+    result = unnest_event(df, event_name="Registered")
+    assert result["address"].to_list() == sample_decoded_df.head(1)["address"].to_list()
+    assert result["data"].to_list() == sample_decoded_df.head(1)["data"].to_list()
+    assert "param_address" in result.columns
+    assert "param_data" in result.columns
+
 def test_unnest_trace_with_full_signature(sample_decoded_trace_df):
     result = unnest_trace(
         decoded_trace_df=sample_decoded_trace_df,
@@ -97,6 +121,23 @@ def test_unnest_trace_with_full_signature(sample_decoded_trace_df):
     assert not result.is_empty()
     assert all(col in result.columns for col in ["recipient", "amount", "output_0"])
 
+def test_unnest_trace_output_named_like_an_input(sample_decoded_trace_df):
+    # An output named like an input doesn't replace the input column
+    signature = "function swap(uint256 amount) returns (uint256 amount)"
+    df = sample_decoded_trace_df.head(1).with_columns(
+        pl.lit(signature).alias("full_signature"),
+        pl.lit("swap").alias("name"),
+        pl.lit('["1"]').alias("input_values"),
+        pl.lit('["amount"]').alias("input_keys"),
+        pl.lit('[{"name":"amount","index":0,"value_type":"uint256","value":"1"}]').alias("input_json"),
+        pl.lit('["2"]').alias("output_values"),
+        pl.lit('["amount"]').alias("output_keys"),
+        pl.lit('[{"name":"amount","index":0,"value_type":"uint256","value":"2"}]').alias("output_json"),
+    )
+    result = unnest_trace(df, function_name="swap")
+    assert result["amount"].to_list() == [1.0]
+    assert result["param_amount"].to_list() == [2.0]
+
 def test_unnest_trace_with_function_name_and_action_to(sample_decoded_trace_df):
     result = unnest_trace(
         sample_decoded_trace_df,
@@ -0,0 +1,38 @@
+import pytest
+import polars as pl
+from glaciers import to_hex_columns, to_binary_columns
+
+@pytest.fixture
+def sample_df():
+    return pl.DataFrame({
+        "transaction_hash": [bytes.fromhex("abcd"), bytes.fromhex("01")],
+        "address": ["0x12", "34"],
+        "block_number": [1, 2],
+    })
+
+def test_to_hex_columns(sample_df):
+    result = to_hex_columns(sample_df, ["transaction_hash", "address"])
+    assert result["transaction_hash"].to_list() == ["0xabcd", "0x01"]
+    assert result["address"].to_list() == ["0x12", "0x34"]
+    assert result["block_number"].to_list() == [1, 2]
+    # Idempotent, no double 0x
+    assert to_hex_columns(result, ["transaction_hash", "address"]).equals(result)
+
+def test_to_binary_columns(sample_df):
+    result = to_binary_columns(sample_df, ["transaction_hash", "address"])
+    assert result["transaction_hash"].to_list() == [bytes.fromhex("abcd"), bytes.fromhex("01")]
+    assert result["address"].to_list() == [bytes.fromhex("12"), bytes.fromhex("34")]
+    # Idempotent, no double decode
+    assert to_binary_columns(result, ["transaction_hash", "address"]).equals(result)
+
+def test_hex_binary_round_trip(sample_df):
+    binary_df = to_binary_columns(sample_df, ["address"])
+    assert to_binary_columns(to_hex_columns(binary_df, ["address"]), ["address"]).equals(binary_df)
+
+def test_invalid_columns(sample_df):
+    with pytest.raises(ValueError, match="'missing'"):
+        to_hex_columns(sample_df, ["missing"])
+    with pytest.raises(ValueError, match="'block_number'"):
+        to_binary_columns(sample_df, ["block_number"])
+    with pytest.raises(ValueError, match="'bad'"):
+        to_binary_columns(pl.DataFrame({"bad": ["0xzz"]}), ["bad"])